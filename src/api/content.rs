@@ -0,0 +1,364 @@
+//! Formats user provided content like task names before it gets displayed.
+//!
+//! Content can contain emoji, line breaks and all kinds of other characters that don't work well
+//! in logs or narrow terminals. A [`Pipeline`] of [`Sanitizer`]s is applied in order to all content
+//! that is displayed through [`Content`].
+use std::{borrow::Cow, sync::OnceLock};
+
+use owo_colors::{OwoColorize, Stream};
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::t, output};
+
+/// A single step of a [`Pipeline`] that transforms content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Sanitizer {
+    /// Removes all emoji and pictographs.
+    StripEmoji,
+    /// Replaces all whitespace including line breaks with a single space, and trims the ends.
+    CollapseWhitespace,
+    /// Transliterates non-ASCII characters to their closest ASCII representation.
+    Ascii,
+    /// Replaces every word with lorem ipsum of the same length, for sharing screenshots without
+    /// giving away what the tasks and projects are about.
+    Redact,
+}
+
+impl Sanitizer {
+    fn apply<'a>(&self, text: Cow<'a, str>) -> Cow<'a, str> {
+        match self {
+            Sanitizer::StripEmoji => {
+                if text.chars().any(is_emoji) {
+                    text.chars()
+                        .filter(|c| !is_emoji(*c))
+                        .collect::<String>()
+                        .into()
+                } else {
+                    text
+                }
+            }
+            Sanitizer::CollapseWhitespace => {
+                text.split_whitespace().collect::<Vec<_>>().join(" ").into()
+            }
+            Sanitizer::Ascii => {
+                if text.is_ascii() {
+                    text
+                } else {
+                    unidecode::unidecode(&text).into()
+                }
+            }
+            Sanitizer::Redact => redact(&text).into(),
+        }
+    }
+}
+
+/// Words that [`Sanitizer::Redact`] replaces words with, picking the ones of the same length.
+const LOREM: &[&str] = &[
+    "a",
+    "e",
+    "do",
+    "ut",
+    "et",
+    "ad",
+    "ex",
+    "ea",
+    "sit",
+    "sed",
+    "amet",
+    "elit",
+    "enim",
+    "quis",
+    "nisi",
+    "lorem",
+    "ipsum",
+    "dolor",
+    "magna",
+    "minim",
+    "tempor",
+    "labore",
+    "dolore",
+    "aliqua",
+    "veniam",
+    "eiusmod",
+    "nostrud",
+    "ullamco",
+    "laboris",
+    "aliquip",
+    "commodo",
+    "pariatur",
+    "occaecat",
+    "consequat",
+    "cupidatat",
+    "adipiscing",
+    "incididunt",
+    "consectetur",
+    "exercitation",
+    "reprehenderit",
+];
+
+/// Replaces every run of letters and digits with lorem ipsum of the same number of characters,
+/// keeping everything else like whitespace and punctuation. Words that start with an uppercase
+/// letter stay capitalized.
+fn redact(text: &str) -> String {
+    let longest = LOREM.iter().map(|w| w.len()).max().unwrap_or(1);
+    let mut out = String::with_capacity(text.len());
+    let mut word = Vec::new();
+    let mut count = 0;
+    let mut flush = |word: &mut Vec<char>, out: &mut String| {
+        if word.is_empty() {
+            return;
+        }
+        let mut lorem = String::new();
+        let mut left = word.len();
+        while left > 0 {
+            let len = left.min(longest);
+            let words = LOREM.iter().filter(|w| w.len() == len).collect::<Vec<_>>();
+            lorem.push_str(words[count % words.len()]);
+            count += 1;
+            left -= len;
+        }
+        if word[0].is_uppercase() {
+            lorem[..1].make_ascii_uppercase();
+        }
+        out.push_str(&lorem);
+        word.clear();
+    };
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+/// Returns true for characters that are emoji or are used to compose emoji.
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF // Pictographs, emoticons, flags, transport and other symbols.
+        | 0x2600..=0x27BF // Miscellaneous symbols and dingbats.
+        | 0x2B00..=0x2BFF // Arrows and stars.
+        | 0x200D // Zero width joiner.
+        | 0x20E3 // Combining keycap.
+        | 0xFE00..=0xFE0F // Variation selectors.
+        | 0xE0020..=0xE007F // Tags used for subdivision flags.
+    )
+}
+
+/// A Pipeline applies a list of [`Sanitizer`]s to content, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pipeline(Vec<Sanitizer>);
+
+static PIPELINE: OnceLock<Pipeline> = OnceLock::new();
+
+impl Pipeline {
+    /// Creates a new pipeline that applies the sanitizers in the given order.
+    pub fn new(sanitizers: Vec<Sanitizer>) -> Pipeline {
+        Pipeline(sanitizers)
+    }
+
+    /// Applies all sanitizers on the text.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        self.0
+            .iter()
+            .fold(Cow::Borrowed(text), |text, sanitizer| sanitizer.apply(text))
+    }
+
+    /// Uses this pipeline for all [`Content`] that is displayed from now on. Can only be done once,
+    /// later calls are ignored.
+    pub fn install(self) {
+        let _ = PIPELINE.set(self);
+    }
+}
+
+/// Content displays user provided text through the installed [`Pipeline`].
+pub struct Content<'a>(pub &'a str);
+
+impl std::fmt::Display for Content<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match PIPELINE.get() {
+            Some(pipeline) => pipeline.apply(self.0).fmt(f),
+            None => self.0.fmt(f),
+        }
+    }
+}
+
+/// Markdown displays user provided text like [`Content`], but renders the Markdown that Todoist
+/// supports in descriptions, like headings, lists, emphasis, code and links.
+pub struct Markdown<'a>(pub &'a str);
+
+impl std::fmt::Display for Markdown<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Only styles the text where the rest of the output is colored as well.
+        let styled = !""
+            .if_supports_color(Stream::Stdout, |text| text.bold())
+            .to_string()
+            .is_empty();
+        render(&Content(self.0).to_string(), styled).fmt(f)
+    }
+}
+
+/// Renders the Markdown of the text line by line, styling it with colors if `styled` is set.
+fn render(text: &str, styled: bool) -> String {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+        let heading = trimmed[level..]
+            .strip_prefix(' ')
+            .filter(|_| (1..=6).contains(&level));
+        let rendered = if let Some(heading) = heading {
+            style(&inline(heading, styled), styled, |t| t.bold().to_string())
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            format!("• {}", inline(item, styled))
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            // Screen readers would read out the line that marks quotes.
+            let marker = match output::a11y() {
+                true => format!("{}:", t!("quote")),
+                false => "│".to_string(),
+            };
+            format!("{} {}", marker, inline(quote.trim_start(), styled))
+        } else {
+            inline(trimmed, styled)
+        };
+        out.push(format!("{}{}", indent, rendered));
+    }
+    out.join("\n")
+}
+
+/// Renders emphasis, code and links within a line.
+fn inline(text: &str, styled: bool) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    let mut prev = ' ';
+    while let Some(c) = rest.chars().next() {
+        // Underscores within words, like in snake_case, are no emphasis.
+        let boundary = !prev.is_alphanumeric();
+        if let Some((code, after)) = enclosed(rest, "`", true) {
+            out.push_str(&style(code, styled, |t| t.yellow().to_string()));
+            rest = after;
+        } else if let Some((inner, after)) =
+            enclosed(rest, "**", false).or_else(|| enclosed(rest, "__", false).filter(|_| boundary))
+        {
+            out.push_str(&style(&inline(inner, styled), styled, |t| {
+                t.bold().to_string()
+            }));
+            rest = after;
+        } else if let Some((inner, after)) =
+            enclosed(rest, "*", false).or_else(|| enclosed(rest, "_", false).filter(|_| boundary))
+        {
+            out.push_str(&style(&inline(inner, styled), styled, |t| {
+                t.italic().to_string()
+            }));
+            rest = after;
+        } else if let Some((label, url, after)) = link(rest) {
+            out.push_str(&style(&inline(label, styled), styled, |t| {
+                t.underline().to_string()
+            }));
+            out.push_str(&format!(" ({})", url));
+            rest = after;
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            prev = c;
+            continue;
+        }
+        prev = ' ';
+    }
+    out
+}
+
+/// Splits off the text between the marker at the start and its next occurrence. Emphasis needs
+/// to hug its text, so `2 * 3 * 4` stays as it is.
+fn enclosed<'t>(text: &'t str, marker: &str, code: bool) -> Option<(&'t str, &'t str)> {
+    let inner = text.strip_prefix(marker)?;
+    let end = inner.find(marker)?;
+    let content = &inner[..end];
+    let hugs = !content.starts_with(char::is_whitespace) && !content.ends_with(char::is_whitespace);
+    (!content.is_empty() && (code || hugs)).then(|| (content, &inner[end + marker.len()..]))
+}
+
+/// Splits off a link like `[label](url)`, or an image like `![alt](url)`, at the start.
+fn link(text: &str) -> Option<(&str, &str, &str)> {
+    let inner = text.strip_prefix("![").or_else(|| text.strip_prefix('['))?;
+    let (label, rest) = inner.split_once("](")?;
+    let (url, after) = rest.split_once(')')?;
+    Some((label, url, after))
+}
+
+fn style(text: &str, styled: bool, f: impl Fn(&str) -> String) -> String {
+    if styled {
+        f(text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sanitizes() {
+        let text = "🎉 Party   at  Café\n⭐️ bring 👨‍👩‍👧 ";
+        assert_eq!(Pipeline::default().apply(text), text);
+        assert_eq!(
+            Pipeline::new(vec![Sanitizer::StripEmoji]).apply(text),
+            " Party   at  Café\n bring  "
+        );
+        assert_eq!(
+            Pipeline::new(vec![Sanitizer::StripEmoji, Sanitizer::CollapseWhitespace]).apply(text),
+            "Party at Café bring"
+        );
+        assert_eq!(
+            Pipeline::new(vec![
+                Sanitizer::StripEmoji,
+                Sanitizer::CollapseWhitespace,
+                Sanitizer::Ascii
+            ])
+            .apply(text),
+            "Party at Cafe bring"
+        );
+        assert_eq!(
+            Pipeline::new(vec![Sanitizer::Ascii]).apply("Привет"),
+            "Privet"
+        );
+        assert!(matches!(
+            Pipeline::new(vec![Sanitizer::StripEmoji, Sanitizer::Ascii]).apply("plain"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn redacts() {
+        let text = "Call Anna at 555-0123, bring 🎉 and the Überraschungsgeschenk!";
+        let redacted = Pipeline::new(vec![Sanitizer::Redact]).apply(text);
+        assert_eq!(
+            redacted,
+            "Amet Elit et sed-nisi, lorem 🎉 sit sed Reprehenderitoccaecat!"
+        );
+        assert_eq!(redacted.chars().count(), text.chars().count());
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let text = "## Plan\n- **Book** the `train`\n  * see [the site](https://example.com)\n> 2 * 3 * 4 in snake_case_names\n_done_";
+        assert_eq!(
+            render(text, false),
+            "Plan\n• Book the train\n  • see the site (https://example.com)\n│ 2 * 3 * 4 in snake_case_names\ndone"
+        );
+        assert_eq!(
+            render("**bold**", true),
+            "**bold**".replace("**", "").bold().to_string()
+        );
+        assert_eq!(render("#hashtag", false), "#hashtag");
+    }
+}
@@ -1,6 +1,7 @@
 //! Provides various lower-level mechanisms to interact with the Todoist API.
+pub mod content;
 pub mod rest;
+pub mod sync;
 pub mod tree;
 
 mod serialize;
-mod sync;
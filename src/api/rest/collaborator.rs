@@ -0,0 +1,44 @@
+use crate::api::content::Content;
+use owo_colors::{OwoColorize, Stream};
+use serde::{Deserialize, Serialize};
+
+use super::UserID;
+
+/// Collaborator is a user that a shared project is shared with, who tasks in it can be assigned
+/// to.
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/rest/v2/#get-all-collaborators).
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Collaborator {
+    /// Unique ID of the user.
+    pub id: UserID,
+    /// Full name of the user.
+    pub name: String,
+    /// Email address of the user.
+    pub email: String,
+}
+
+impl std::fmt::Display for Collaborator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        format!("+{}", Content(&self.name))
+            .if_supports_color(Stream::Stdout, |text| text.bright_cyan())
+            .fmt(f)
+    }
+}
+
+/// Used to display a [`Collaborator`] together with their email address.
+pub struct FullCollaborator<'a>(pub &'a Collaborator);
+
+impl std::fmt::Display for FullCollaborator<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} <{}>",
+            self.0
+                .id
+                .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+            self.0,
+            self.0.email
+        )
+    }
+}
@@ -43,9 +43,37 @@ pub struct Comment {
 }
 
 /// An optional attachment file attached to a comment.
-/// TODO: empty for now, so it acts as a marker.
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Attachment {}
+///
+/// All fields are optional, as the contents depend on the kind of attachment.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Attachment {
+    /// The name of the attached file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    /// The size of the attached file in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+    /// The MIME type of the attached file, for example `image/png`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_type: Option<String>,
+    /// The URL where the attached file can be downloaded from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_url: Option<String>,
+    /// The kind of attachment, for example `file` or `image`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+}
+
+impl Attachment {
+    /// Returns true if the attachment is an image that could be previewed.
+    pub fn is_image(&self) -> bool {
+        self.resource_type.as_deref() == Some("image")
+            || self
+                .file_type
+                .as_deref()
+                .is_some_and(|t| t.starts_with("image/"))
+    }
+}
 
 /// CreateComment allows to create a new comment through the API.
 #[derive(Debug, Serialize)]
@@ -55,5 +83,62 @@ pub struct CreateComment {
     pub thread: ThreadID,
     /// The text of the comment. Supports markdown.
     pub content: String,
-    // TODO: pub attachment: Option<Attachment>,
+    /// A file that was uploaded with [`super::Gateway::upload`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<Attachment>,
+}
+
+/// Upload is a file that is uploaded to Todoist, so that it can be attached to a comment.
+#[derive(Debug, Clone)]
+pub struct Upload {
+    /// The name the file is shown with.
+    pub file_name: String,
+    /// The contents of the file.
+    pub content: Vec<u8>,
+}
+
+impl Upload {
+    /// Guesses the MIME type from the extension of the file name. Todoist looks at the contents
+    /// as well, so this only needs to cover what it previews.
+    pub fn file_type(&self) -> &'static str {
+        let extension = self
+            .file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "webp" => "image/webp",
+            "svg" => "image/svg+xml",
+            "pdf" => "application/pdf",
+            "txt" => "text/plain",
+            "md" => "text/markdown",
+            "csv" => "text/csv",
+            "json" => "application/json",
+            "zip" => "application/zip",
+            _ => "application/octet-stream",
+        }
+    }
+
+    /// Encodes the file as a `multipart/form-data` body with the given boundary. The body is
+    /// built in memory instead of streamed, so that failed uploads can be retried.
+    pub(super) fn multipart(&self, boundary: &str) -> Vec<u8> {
+        // Quotes would end the file name early, as the header has no escapes for them.
+        let file_name = self.file_name.replace(['"', '\r', '\n'], "_");
+        let mut body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"file_name\"\r\n\r\n\
+             {file_name}\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\n\
+             Content-Type: {}\r\n\r\n",
+            self.file_type()
+        )
+        .into_bytes();
+        body.extend_from_slice(&self.content);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        body
+    }
 }
@@ -1,6 +1,14 @@
-use crate::{api::tree::Tree, config::Config};
+use crate::{
+    api::{
+        content::{Content, Markdown},
+        tree::Tree,
+    },
+    config::Config,
+    i18n::t,
+    output,
+};
 
-use super::{Comment, DueDateFormatter, Label, Project, Section, Task};
+use super::{Collaborator, Comment, DueDateFormatter, Label, Project, Section, Task};
 use chrono::Utc;
 use owo_colors::{OwoColorize, Stream};
 
@@ -18,16 +26,24 @@ impl std::fmt::Display for FullComment<'_> {
                 .if_supports_color(Stream::Stdout, |text| text.bright_yellow())
         )?;
         writeln!(f, "Posted: {}", comment.posted_at)?;
-        writeln!(
-            f,
-            "Attachment: {}",
-            if comment.attachment.is_some() {
-                "Yes"
-            } else {
-                "No"
+        match &comment.attachment {
+            Some(attachment) => {
+                let info = [
+                    attachment.file_name.as_deref(),
+                    attachment.file_url.as_deref(),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+                if info.is_empty() {
+                    writeln!(f, "Attachment: Yes")?
+                } else {
+                    writeln!(f, "Attachment: {}", info.join(" - "))?
+                }
             }
-        )?;
-        write!(f, "Content: {}", comment.content)?;
+            None => writeln!(f, "Attachment: No")?,
+        }
+        write!(f, "Content: {}", Content(&comment.content))?;
         Ok(())
     }
 }
@@ -48,33 +64,49 @@ impl<'a> std::fmt::Display for FullLabel<'a> {
     }
 }
 
-/// Used to display full information about a Task.
+/// Used to display full information about a Task, together with the chain of its parents from the
+/// top one down.
 pub struct FullTask<'a>(
     pub &'a Task,
     pub Option<&'a Project>,
     pub Option<&'a Section>,
     pub Vec<&'a Label>,
+    pub Vec<&'a Task>,
     pub &'a Config,
 );
 
 impl std::fmt::Display for FullTask<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let FullTask::<'_>(task, project, section, labels, config) = self;
+        let FullTask::<'_>(task, project, section, labels, parents, config) = self;
         write!(
             f,
-            "ID: {}\nPriority: {}\nContent: {}\nDescription: {}",
+            "ID: {}\nPriority: {}\nContent: {}",
             task.id
                 .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
             task.priority,
-            task.content,
-            task.description,
+            Content(&task.content),
         )?;
+        if !parents.is_empty() {
+            write!(
+                f,
+                "\nParents: {}",
+                parents
+                    .iter()
+                    .map(|p| Content(&p.content).to_string())
+                    .collect::<Vec<_>>()
+                    .join(" > ")
+            )?;
+        }
+        write!(f, "\nDescription: {}", Markdown(&task.description))?;
         if let Some(due) = &task.due {
             write!(
                 f,
                 "\nDue: {}",
                 DueDateFormatter(due, &config.override_time.unwrap_or_else(Utc::now))
             )?;
+            if let Some(exact) = &due.exact {
+                write!(f, " ({})", exact.timezone)?;
+            }
         }
         if !labels.is_empty() {
             write!(
@@ -94,6 +126,8 @@ impl std::fmt::Display for FullTask<'_> {
             write!(f, "\nSection: {}", section)?;
         }
         write!(f, "\nComments: {}", task.comment_count)?;
+        write!(f, "\nURL: {}", task.url)?;
+        write!(f, "\nCreated: {}", task.created_at)?;
         Ok(())
     }
 }
@@ -104,6 +138,7 @@ pub struct TableTask<'a>(
     pub Option<&'a Project>,
     pub Option<&'a Section>,
     pub Vec<&'a Label>,
+    pub Option<&'a Collaborator>,
     pub &'a Config,
 );
 
@@ -111,17 +146,18 @@ impl TableTask<'_> {
     /// Initializes a TableTask item that only displays data that is directly available from a
     /// [`Task`].
     pub fn from_task<'a>(task: &'a Tree<Task>, config: &'a Config) -> TableTask<'a> {
-        TableTask(task, None, None, vec![], config)
+        TableTask(task, None, None, vec![], None, config)
     }
 }
 
 impl std::fmt::Display for TableTask<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let TableTask::<'_>(task, project, section, labels, config) = self;
-        let subtask_padding = if task.depth > 0 {
-            format!("{}⌞ ", "  ".repeat(task.depth))
-        } else {
-            "".to_string()
+        let TableTask::<'_>(task, project, section, labels, assignee, config) = self;
+        let subtask_padding = match (task.depth, output::a11y()) {
+            (0, _) => "".to_string(),
+            // Screen readers skip the indentation, so the nesting is told in words.
+            (depth, true) => format!("{}: ", t!("subtask-level", level = depth)),
+            (depth, false) => format!("{}⌞ ", "  ".repeat(depth)),
         };
         write!(
             f,
@@ -130,7 +166,7 @@ impl std::fmt::Display for TableTask<'_> {
             task.id
                 .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
             task.priority,
-            task.content,
+            Content(&task.content),
         )?;
         if let Some(due) = &task.due {
             write!(
@@ -150,10 +186,34 @@ impl std::fmt::Display for TableTask<'_> {
                     .join(" ")
             )?;
         }
+        if let Some(assignee) = &assignee {
+            write!(f, " {}", assignee)?;
+        }
+        let now = config.override_time.unwrap_or_else(Utc::now);
+        let age = (now - task.created_at).num_days();
+        let old = format!("{}d old", age);
+        match config.theme.age_level(age) {
+            0 => {}
+            1 => write!(
+                f,
+                " {}",
+                old.if_supports_color(Stream::Stdout, |text| text.dimmed())
+            )?,
+            2 => write!(
+                f,
+                " {}",
+                old.if_supports_color(Stream::Stdout, |text| text.yellow())
+            )?,
+            _ => write!(
+                f,
+                " {}",
+                old.if_supports_color(Stream::Stdout, |text| text.bright_red())
+            )?,
+        }
         if let Some(p) = &project {
-            write!(f, " [{}", p.name)?;
+            write!(f, " [{}", Content(&p.name))?;
             if let Some(s) = &section {
-                write!(f, "/{}", s.name)?;
+                write!(f, "/{}", Content(&s.name))?;
             }
             write!(f, "]")?;
         }
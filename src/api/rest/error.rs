@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+/// ApiError is an unsuccessful response of the Todoist API, with the explanation that Todoist
+/// gave in its body.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    /// The HTTP status of the response.
+    pub status: StatusCode,
+    /// The explanation of the error as Todoist wrote it, if there was one.
+    pub message: Option<String>,
+    /// The machine readable kind of error, like `INVALID_DATE_FORMAT`, if Todoist sent one.
+    pub tag: Option<String>,
+    /// The argument of the request that was rejected, if Todoist named it.
+    pub argument: Option<String>,
+    /// How long Todoist asked to wait before trying again, if it did.
+    pub retry_after: Option<Duration>,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Todoist answered {}", self.status)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// The JSON body of errors of the Sync API, and of some REST endpoints.
+#[derive(Deserialize)]
+struct Body {
+    error: Option<String>,
+    error_tag: Option<String>,
+    #[serde(default)]
+    error_extra: Extra,
+}
+
+#[derive(Deserialize, Default)]
+struct Extra {
+    argument: Option<String>,
+}
+
+impl ApiError {
+    /// Reads the error from the body of the response, which is JSON for the Sync API and plain
+    /// text for the REST API.
+    pub fn new(status: StatusCode, body: &str) -> Self {
+        let body = body.trim();
+        match serde_json::from_str::<Body>(body) {
+            Ok(parsed) => ApiError {
+                status,
+                message: parsed.error.or_else(|| Some(body.to_string())),
+                tag: parsed.error_tag,
+                argument: parsed.error_extra.argument,
+                retry_after: None,
+            },
+            Err(_) => ApiError {
+                status,
+                message: Some(body.to_string()).filter(|m| !m.is_empty()),
+                tag: None,
+                argument: None,
+                retry_after: None,
+            },
+        }
+    }
+
+    /// Returns advice for the errors that are common, on what to do about them.
+    pub fn hint(&self) -> Option<String> {
+        let message = self.message.as_deref().unwrap_or_default().to_lowercase();
+        let tag = self.tag.as_deref().unwrap_or_default();
+        let argument = self.argument.as_deref().unwrap_or_default();
+        if tag == "INVALID_DATE_FORMAT"
+            || argument.starts_with("due")
+            || (message.contains("date")
+                && ["invalid", "format", "parse"]
+                    .iter()
+                    .any(|w| message.contains(w)))
+        {
+            return Some(
+                "Todoist didn't understand the due date. Try an exact one like `--due 2022-09-01` \
+                 or `--due \"2022-09-01 17:00\"`, or phrase it in the `language` of the config."
+                    .to_string(),
+            );
+        }
+        if self.status == StatusCode::TOO_MANY_REQUESTS {
+            let when = match self.retry_after {
+                Some(wait) => format!("Try again at {}", super::limit::retry_at(wait)),
+                None => "Try again in a few minutes".to_string(),
+            };
+            return Some(format!(
+                "Todoist limits how many requests can be made within 15 minutes. {}, or allow \
+                 longer waits with `max_delay` in the `[retry]` section of the config.",
+                when
+            ));
+        }
+        if tag == "MAX_FILE_SIZE_EXCEEDED"
+            || self.status == StatusCode::PAYLOAD_TOO_LARGE
+            || message.contains("file size")
+        {
+            return Some(
+                "The file is larger than uploads can be with your Todoist plan.".to_string(),
+            );
+        }
+        if self.status == StatusCode::NOT_FOUND || message.contains("not found") {
+            return Some(
+                "Check the ID, the item may have been deleted or closed in the meantime. `doist \
+                 list` shows the IDs of tasks, `doist projects list` those of projects."
+                    .to_string(),
+            );
+        }
+        if self.status == StatusCode::FORBIDDEN {
+            return Some(
+                "The account has no access to this, like to a project that isn't shared with it \
+                 or to a feature of Todoist Pro."
+                    .to_string(),
+            );
+        }
+        if !argument.is_empty() {
+            return Some(format!(
+                "Todoist rejected the value of `{}`, check what was given for it.",
+                argument
+            ));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_bodies() {
+        let error = ApiError::new(StatusCode::BAD_REQUEST, "Date is invalid\n");
+        assert_eq!(
+            error.to_string(),
+            "Todoist answered 400 Bad Request: Date is invalid"
+        );
+        assert!(error.hint().unwrap().contains("--due 2022-09-01"));
+
+        let error = ApiError::new(
+            StatusCode::BAD_REQUEST,
+            r#"{"error": "Invalid argument value", "error_code": 20, "error_tag": "INVALID_ARGUMENT_VALUE", "error_extra": {"argument": "priority"}}"#,
+        );
+        assert_eq!(
+            error.to_string(),
+            "Todoist answered 400 Bad Request: Invalid argument value"
+        );
+        assert_eq!(
+            error.hint().as_deref(),
+            Some("Todoist rejected the value of `priority`, check what was given for it.")
+        );
+
+        let error = ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "");
+        assert_eq!(
+            error.to_string(),
+            "Todoist answered 500 Internal Server Error"
+        );
+        assert_eq!(error.hint(), None);
+
+        let error = ApiError::new(StatusCode::TOO_MANY_REQUESTS, "");
+        assert!(error.hint().unwrap().contains("within 15 minutes"));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::api::content::Content;
+use owo_colors::{OwoColorize, Stream};
+use serde::{Deserialize, Serialize};
+use serde_aux::prelude::deserialize_string_from_number;
+
+/// FilterID specifies the unique ID of a [`Filter`].
+pub type FilterID = String;
+
+/// Filter is a saved filter query, shown as a view of its own in the UI. Filters are only
+/// available through the Sync API.
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/sync/v9/#filters).
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Filter {
+    /// Unique ID of a filter.
+    #[serde(deserialize_with = "deserialize_string_from_number")]
+    pub id: FilterID,
+    /// Name of the filter.
+    pub name: String,
+    /// The filter query, like `today & @work`.
+    pub query: String,
+    /// The display color of the filter as given from the API.
+    pub color: String,
+    /// The order among filters if we were to sort them.
+    pub item_order: isize,
+    /// Toggle for marking a filter as a favorite.
+    pub is_favorite: bool,
+    /// Set for filters that were deleted, which the Sync API still returns.
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+impl Ord for Filter {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.item_order.cmp(&other.item_order) {
+            core::cmp::Ordering::Equal => {}
+            ord => return ord,
+        }
+        self.id.cmp(&other.id)
+    }
+}
+
+impl PartialOrd for Filter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            Content(&self.name).if_supports_color(Stream::Stdout, |text| text.bright_blue()),
+            Content(&self.query).if_supports_color(Stream::Stdout, |text| text.bright_black())
+        )
+    }
+}
+
+/// Command used with [`super::Gateway::create_filter`] to create a new [`Filter`].
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct CreateFilter {
+    /// Name of the filter to create.
+    pub name: String,
+    /// The filter query.
+    pub query: String,
+    /// Color of the filter icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+}
+
+/// Command used with [`super::Gateway::update_filter`] to update a [`Filter`].
+///
+/// Each field is optional, so if something exists, that part of the [`Filter`] will get
+/// overwritten.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateFilter {
+    /// Renames the filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Replaces the filter query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Color of the filter icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+}
+
+/// FiltersResponse is the part of a Sync API read that contains the filters.
+#[derive(Debug, Deserialize)]
+pub(super) struct FiltersResponse {
+    pub filters: Vec<Filter>,
+}
@@ -1,27 +1,191 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use color_eyre::{
-    eyre::{eyre, WrapErr},
-    Result,
+    eyre::{eyre, Report, WrapErr},
+    Result, Section as _,
 };
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use lazy_static::lazy_static;
-use reqwest::{Client, StatusCode};
+use reqwest::{Client, Response, StatusCode};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder};
-use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
+use crate::{
+    api::sync::{SyncCommand, SyncResponse},
+    audit::AuditLog,
+    cache::{self, Cache},
+    cancel,
+    operations::Journal,
+    usage,
+};
+
 use super::{
-    Comment, CreateComment, CreateLabel, CreateProject, CreateSection, CreateTask, Label, LabelID,
-    Project, ProjectID, Section, SectionID, Task, TaskDue, TaskID, UpdateTask,
+    limit::{Quota, Throttling},
+    retry::{self, Retrying},
+    ActivityEvent, ActivityResponse, ApiError, ArchivedProject, Attachment, Collaborator, Comment,
+    CompletedResponse, CompletedTask, CreateComment, CreateFilter, CreateLabel, CreateProject,
+    CreateSection, CreateTask, Filter, FilterID, FiltersResponse, Label, LabelID, MoveTarget,
+    Project, ProjectID, Retry, Section, SectionID, Task, TaskDue, TaskID, UpdateFilter,
+    UpdateLabel, UpdateProject, UpdateTask, Upload, ACTIVITY_PAGE_SIZE, COMPLETED_PAGE_SIZE,
 };
 
 /// Makes network calls to the Todoist API and returns structs that can then be worked with.
 pub struct Gateway {
-    client: ClientWithMiddleware,
+    /// Built on the first request, as loading the TLS certificates slows down the start of
+    /// commands that are answered from the cache.
+    client: OnceLock<ClientWithMiddleware>,
     token: String,
     url: url::Url,
+    read_only: bool,
+    retry: Retry,
+    /// The requests left until Todoist resets its rate limit, shared by all requests.
+    quota: Arc<std::sync::Mutex<Quota>>,
+    audit: Option<AuditLog>,
+    journal: Option<Arc<Journal>>,
+    cache: Option<Cache>,
+    projects: Memo<Vec<Project>>,
+    sections: Memo<Vec<Section>>,
+    labels: Memo<Vec<Label>>,
+}
+
+/// Memo remembers the result of a request for the lifetime of the [`Gateway`], so that commands
+/// resolving names over and over again only fetch the same list once per invocation.
+struct Memo<T> {
+    /// The name of the result in the [`Cache`].
+    name: &'static str,
+    value: Mutex<Option<T>>,
+}
+
+impl<T: Clone> Memo<T> {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            value: Mutex::new(None),
+        }
+    }
+
+    /// Returns the remembered value, or calls `fetch` to get it if there is none yet.
+    async fn get_or_fetch<F, Fut>(&self, fetch: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut value = self.value.lock().await;
+        if let Some(value) = value.as_ref() {
+            return Ok(value.clone());
+        }
+        let fetched = fetch().await?;
+        *value = Some(fetched.clone());
+        Ok(fetched)
+    }
+
+    /// Forgets the remembered value, so the next call fetches it again.
+    async fn invalidate(&self) {
+        *self.value.lock().await = None;
+    }
+}
+
+/// The item returned by quick add, of which only the ID is needed to look up the task.
+#[derive(Deserialize)]
+struct QuickAdded {
+    id: TaskID,
+}
+
+/// Pagination is how an endpoint splits long lists into pages.
+#[derive(Debug, Clone, Copy)]
+enum Pagination {
+    /// Each page names the cursor of the next one, until there is none.
+    Cursor,
+    /// Pages are requested by their offset, until a page has less than `limit` items.
+    Offset { limit: usize },
+}
+
+/// Page is the response of a list endpoint, which is either the whole list or one page of it with
+/// the cursor of the next page.
+struct Page<T> {
+    results: Vec<T>,
+    next_cursor: Option<String>,
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Page<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Part<T> {
+            results: Vec<T>,
+            next_cursor: Option<String>,
+        }
+        // Unlike an untagged enum, this keeps the error of the items that don't parse.
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let page = match value.is_array() {
+            true => serde_json::from_value(value).map(|results| Page {
+                results,
+                next_cursor: None,
+            }),
+            false => serde_json::from_value(value).map(|part: Part<T>| Page {
+                results: part.results,
+                next_cursor: part.next_cursor,
+            }),
+        };
+        page.map_err(serde::de::Error::custom)
+    }
+}
+
+/// Paginated responses contain the items of one page, and maybe the cursor of the next page.
+trait Paginated<T>: DeserializeOwned {
+    fn split(self) -> (Vec<T>, Option<String>);
+}
+
+impl<T: DeserializeOwned> Paginated<T> for Page<T> {
+    fn split(self) -> (Vec<T>, Option<String>) {
+        (self.results, self.next_cursor)
+    }
+}
+
+impl Paginated<CompletedTask> for CompletedResponse {
+    fn split(self) -> (Vec<CompletedTask>, Option<String>) {
+        (self.items, None)
+    }
+}
+
+impl Paginated<ActivityEvent> for ActivityResponse {
+    fn split(self) -> (Vec<ActivityEvent>, Option<String>) {
+        (self.events, None)
+    }
+}
+
+tokio::task_local! {
+    /// The operation that the current requests are part of, see [`Gateway::operation`].
+    static OPERATION: Operation;
+}
+
+/// Operation derives the idempotency keys of the modifying requests that are sent as part of it.
+struct Operation {
+    key: Uuid,
+    /// How often each request was sent so far, so that sending the same one twice, like adding
+    /// two tasks with the same content, still makes two requests.
+    sent: std::sync::Mutex<HashMap<Uuid, u32>>,
+}
+
+impl Operation {
+    /// Returns the key of the next request with the content.
+    fn key(&self, content: &str) -> Uuid {
+        let key = Uuid::new_v5(&self.key, content.as_bytes());
+        let mut sent = self.sent.lock().unwrap();
+        let count = sent.entry(key).or_default();
+        *count += 1;
+        match count {
+            1 => key,
+            n => Uuid::new_v5(&key, &n.to_be_bytes()),
+        }
+    }
 }
 
 lazy_static! {
@@ -37,14 +201,83 @@ impl Gateway {
     /// * `token` - the API token used for network calls.
     /// * `url` - the base URL to call. See [`struct@TODOIST_API_URL`]
     pub fn new(token: &str, url: &url::Url) -> Gateway {
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
-        let client = ClientBuilder::new(Client::new())
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
         Gateway {
-            client,
+            client: OnceLock::new(),
             token: token.to_string(),
             url: url.clone(),
+            read_only: false,
+            retry: Retry::default(),
+            quota: Arc::default(),
+            audit: None,
+            journal: None,
+            cache: None,
+            projects: Memo::new("projects"),
+            sections: Memo::new("sections"),
+            labels: Memo::new("labels"),
+        }
+    }
+
+    /// Returns the HTTP client, building it if this is the first request.
+    fn client(&self) -> &ClientWithMiddleware {
+        self.client.get_or_init(|| {
+            ClientBuilder::new(Client::new())
+                .with(Retrying::new(self.retry))
+                .with(Throttling::new(self.quota.clone()))
+                .build()
+        })
+    }
+
+    /// Makes the gateway refuse all requests that would modify data, so that an account can be
+    /// explored safely.
+    pub fn read_only(mut self, read_only: bool) -> Gateway {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets how requests that failed for a moment are tried again.
+    pub fn retry(mut self, retry: Retry) -> Gateway {
+        self.retry = retry;
+        self
+    }
+
+    /// Records all requests that would modify data into the audit log.
+    pub(crate) fn audit(mut self, log: AuditLog) -> Gateway {
+        self.audit = Some(log);
+        self
+    }
+
+    /// Remembers the operation in the journal once it modifies data, so that it is continued if it
+    /// doesn't finish.
+    pub(crate) fn journal(mut self, journal: Arc<Journal>) -> Gateway {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Reads projects, sections, labels and tasks from the cache if they were fetched recently,
+    /// and caches them otherwise.
+    pub(crate) fn cache(mut self, cache: Cache) -> Gateway {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Runs `operation` with modifying requests whose idempotency keys are derived from `key`, so
+    /// that Todoist ignores them if the same operation ran before, even in an earlier process
+    /// whose responses never arrived. Outside of an operation every request gets its own key.
+    ///
+    /// This covers the [`SyncCommand`]s of [`Gateway::sync_command`] as well.
+    pub async fn operation<F: Future>(key: Uuid, operation: F) -> F::Output {
+        let sent = Default::default();
+        OPERATION.scope(Operation { key, sent }, operation).await
+    }
+
+    /// Forgets the projects, sections, labels and tasks that were remembered or cached, so that
+    /// commands that keep running see the changes that were made elsewhere in the meantime.
+    pub async fn refresh(&self) {
+        self.invalidate(&self.projects).await;
+        self.invalidate(&self.sections).await;
+        self.invalidate(&self.labels).await;
+        if let Some(cache) = &self.cache {
+            cache.remove(cache::TASKS);
         }
     }
 
@@ -61,12 +294,146 @@ impl Gateway {
     ///
     /// * `filter` - a filter query as described in the [documentation](https://todoist.com/help/articles/205248842).
     pub async fn tasks(&self, filter: Option<&str>) -> Result<Vec<Task>> {
-        self.get(
-            "rest/v2/tasks",
-            filter.map(|filter| vec![("filter", filter)]),
+        // All tasks are cached like a filter that is empty.
+        let key = filter.unwrap_or_default();
+        if let Some(tasks) = self.cache.as_ref().and_then(|c| c.tasks(key)) {
+            return Ok(tasks);
+        }
+        let tasks = self
+            .tasks_pages(filter)
+            .try_concat()
+            .await
+            .wrap_err("unable to get tasks")?;
+        if let Some(cache) = &self.cache {
+            cache.put_tasks(key, &tasks);
+        }
+        Ok(tasks)
+    }
+
+    /// Same as [`Gateway::tasks`], but yields the tasks page by page as they arrive, for
+    /// endpoints that paginate. Doesn't use the cache.
+    pub fn tasks_pages<'a>(
+        &'a self,
+        filter: Option<&str>,
+    ) -> impl Stream<Item = Result<Vec<Task>>> + 'a {
+        let query = filter
+            .map(|filter| vec![("filter", filter.to_string())])
+            .unwrap_or_default();
+        self.pages::<_, Page<Task>>("rest/v2/tasks", query, Pagination::Cursor)
+    }
+
+    /// Returns the active tasks with the given IDs. Tasks that are completed or deleted are left
+    /// out.
+    pub async fn tasks_by_ids(&self, ids: &[TaskID]) -> Result<Vec<Task>> {
+        self.all("rest/v2/tasks", vec![("ids", ids.join(","))])
+            .await
+            .wrap_err("unable to get tasks")
+    }
+
+    /// Returns the tasks that were completed since the given time, most recently completed first.
+    pub async fn completed(&self, since: &DateTime<Utc>) -> Result<Vec<CompletedTask>> {
+        self.completed_pages(since).try_concat().await
+    }
+
+    /// Same as [`Gateway::completed`], but yields the completed tasks page by page as they
+    /// arrive, so long histories can be shown before all of them are fetched.
+    pub fn completed_pages<'a>(
+        &'a self,
+        since: &DateTime<Utc>,
+    ) -> impl Stream<Item = Result<Vec<CompletedTask>>> + 'a {
+        self.completed_in(vec![("since", sync_since(since))])
+    }
+
+    /// Returns all tasks that were ever completed in the project, most recently completed first.
+    pub async fn completed_in_project(&self, id: &ProjectID) -> Result<Vec<CompletedTask>> {
+        self.completed_in(vec![("project_id", id.clone())])
+            .try_concat()
+            .await
+    }
+
+    /// Returns all tasks that were completed in the project since the given time, most recently
+    /// completed first.
+    pub async fn completed_in_project_since(
+        &self,
+        id: &ProjectID,
+        since: &DateTime<Utc>,
+    ) -> Result<Vec<CompletedTask>> {
+        self.completed_in_project_pages(id, since)
+            .try_concat()
+            .await
+    }
+
+    /// Same as [`Gateway::completed_in_project_since`], but yields the completed tasks page by
+    /// page as they arrive.
+    pub fn completed_in_project_pages<'a>(
+        &'a self,
+        id: &ProjectID,
+        since: &DateTime<Utc>,
+    ) -> impl Stream<Item = Result<Vec<CompletedTask>>> + 'a {
+        self.completed_in(vec![
+            ("project_id", id.clone()),
+            ("since", sync_since(since)),
+        ])
+    }
+
+    /// Fetches the completed tasks that match the filters page by page.
+    fn completed_in<'a>(
+        &'a self,
+        mut filters: Vec<(&'static str, String)>,
+    ) -> impl Stream<Item = Result<Vec<CompletedTask>>> + 'a {
+        filters.push(("annotate_items", "true".to_string()));
+        self.pages::<_, CompletedResponse>(
+            "sync/v9/completed/get_all",
+            filters,
+            Pagination::Offset {
+                limit: COMPLETED_PAGE_SIZE,
+            },
         )
+        .map(|page| page.wrap_err("unable to get completed tasks"))
+    }
+
+    /// Returns the events of the activity log since the given time, most recent first. The
+    /// activity log is only available to Todoist Pro accounts.
+    pub async fn activity(&self, since: &DateTime<Utc>) -> Result<Vec<ActivityEvent>> {
+        self.activity_pages(since).try_concat().await
+    }
+
+    /// Same as [`Gateway::activity`], but yields the events page by page as they arrive.
+    pub fn activity_pages<'a>(
+        &'a self,
+        since: &DateTime<Utc>,
+    ) -> impl Stream<Item = Result<Vec<ActivityEvent>>> + 'a {
+        self.activity_in(vec![("since", sync_since(since))])
+    }
+
+    /// Returns the events of the activity log in the project since the given time, most recent
+    /// first.
+    pub async fn activity_in_project(
+        &self,
+        id: &ProjectID,
+        since: &DateTime<Utc>,
+    ) -> Result<Vec<ActivityEvent>> {
+        self.activity_in(vec![
+            ("parent_project_id", id.clone()),
+            ("since", sync_since(since)),
+        ])
+        .try_concat()
         .await
-        .wrap_err("unable to get tasks")
+    }
+
+    /// Fetches the events of the activity log that match the filters page by page.
+    fn activity_in<'a>(
+        &'a self,
+        filters: Vec<(&'static str, String)>,
+    ) -> impl Stream<Item = Result<Vec<ActivityEvent>>> + 'a {
+        self.pages::<_, ActivityResponse>(
+            "sync/v9/activity/get",
+            filters,
+            Pagination::Offset {
+                limit: ACTIVITY_PAGE_SIZE,
+            },
+        )
+        .map(|page| page.wrap_err("unable to get activity"))
     }
 
     /// Closes a task.
@@ -82,6 +449,19 @@ impl Gateway {
         Ok(())
     }
 
+    /// Reopens a closed task.
+    ///
+    /// Equivalent to uncompleting it in the UI.
+    pub async fn reopen(&self, id: &TaskID) -> Result<()> {
+        self.post_empty(
+            &format!("rest/v2/tasks/{}/reopen", id),
+            &serde_json::Map::new(),
+        )
+        .await
+        .wrap_err("unable to reopen task")?;
+        Ok(())
+    }
+
     /// Complete will complete a task by first updating the due date to today, so if it's
     /// recurring, it will stop doing that.
     /// This is a bit hacky, but the REST API does not support completely closing tasks without
@@ -100,6 +480,13 @@ impl Gateway {
         Ok(())
     }
 
+    /// Deletes a task and all of its subtasks.
+    pub async fn delete_task(&self, id: &TaskID) -> Result<()> {
+        self.delete(&format!("rest/v2/tasks/{}", id))
+            .await
+            .wrap_err("unable to delete task")
+    }
+
     /// Creates a task by calling the Todoist API.
     pub async fn create(&self, task: &CreateTask) -> Result<Task> {
         self.post("rest/v2/tasks", task)
@@ -108,6 +495,17 @@ impl Gateway {
             .ok_or_else(|| eyre!("unable to create task"))
     }
 
+    /// Creates a task from a single line like the quick add box of the Todoist apps, which parses
+    /// the project, labels, priority and due date out of the text.
+    pub async fn quick_add(&self, text: &str) -> Result<Task> {
+        let added: QuickAdded = self
+            .post("sync/v9/quick/add", &serde_json::json!({ "text": text }))
+            .await
+            .wrap_err("unable to quick add task")?
+            .ok_or_else(|| eyre!("unable to quick add task"))?;
+        self.task(&added.id).await
+    }
+
     /// Updates a task with the data as specified in UpdateTask.
     pub async fn update(&self, id: &TaskID, task: &UpdateTask) -> Result<()> {
         self.post_empty(&format!("rest/v2/tasks/{}", id), &task)
@@ -116,37 +514,66 @@ impl Gateway {
         Ok(())
     }
 
+    /// Moves a task to another project, section or parent through the Sync API, as the REST API
+    /// can't.
+    pub async fn move_task(&self, id: &TaskID, to: &MoveTarget) -> Result<()> {
+        let mut args = serde_json::to_value(to)?;
+        args["id"] = serde_json::json!(id);
+        let command = self.sync_command("item_move", args);
+        let resp = self.sync(std::slice::from_ref(&command)).await?;
+        match resp.error(&command) {
+            Some(error) => Err(eyre!("unable to move task: {}", error)),
+            None => Ok(()),
+        }
+    }
+
     /// Returns the list of Projects.
+    ///
+    /// The list is only fetched once per [`Gateway`], unless projects get created or deleted.
     pub async fn projects(&self) -> Result<Vec<Project>> {
-        self.get::<(), _>("rest/v2/projects", None)
+        self.memoized(&self.projects, "rest/v2/projects")
             .await
             .wrap_err("unable to get projects")
     }
 
     /// Returns the list of all Sections.
+    ///
+    /// The list is only fetched once per [`Gateway`], unless sections get created or deleted.
     pub async fn sections(&self) -> Result<Vec<Section>> {
-        self.get::<(), _>("rest/v2/sections", None)
+        self.memoized(&self.sections, "rest/v2/sections")
             .await
             .wrap_err("unable to get sections")
     }
 
     /// Returns the list of all Labels.
+    ///
+    /// The list is only fetched once per [`Gateway`], unless labels get created or deleted.
     pub async fn labels(&self) -> Result<Vec<Label>> {
-        self.get::<(), _>("rest/v2/labels", None)
+        self.memoized(&self.labels, "rest/v2/labels")
             .await
             .wrap_err("unable to get labels")
     }
 
+    /// Returns the users that the project is shared with, including the user of the token.
+    pub async fn collaborators(&self, id: &ProjectID) -> Result<Vec<Collaborator>> {
+        self.all(
+            &format!("rest/v2/projects/{}/collaborators", id),
+            Vec::new(),
+        )
+        .await
+        .wrap_err("unable to get collaborators")
+    }
+
     /// Returns the list of all comments attached to the given Project.
     pub async fn project_comments(&self, id: &ProjectID) -> Result<Vec<Comment>> {
-        self.get("rest/v2/comments", Some(&[("project_id", id)]))
+        self.all("rest/v2/comments", vec![("project_id", id.clone())])
             .await
             .wrap_err("unable to get comments")
     }
 
     /// Returns the list of all comments attached to the given Task.
     pub async fn task_comments(&self, id: &TaskID) -> Result<Vec<Comment>> {
-        self.get("rest/v2/comments", Some(&[("task_id", id)]))
+        self.all("rest/v2/comments", vec![("task_id", id.clone())])
             .await
             .wrap_err("unable to get comments")
     }
@@ -159,6 +586,44 @@ impl Gateway {
             .ok_or_else(|| eyre!("unable to create comment"))
     }
 
+    /// Uploads a file, returning the attachment to add to a comment.
+    pub async fn upload(&self, file: &Upload) -> Result<Attachment> {
+        const PATH: &str = "sync/v9/uploads/add";
+        self.ensure_writable("POST", PATH)?;
+        let boundary = format!("doist-{}", Uuid::new_v4().simple());
+        let body = file.multipart(&boundary);
+        let resp = send(
+            "POST",
+            PATH,
+            self.client()
+                .post(self.url.join(PATH)?)
+                .bearer_auth(&self.token)
+                .header(
+                    "X-Request-Id",
+                    self.request_id("POST", PATH, &format!("{:?}", file)),
+                )
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .body(body),
+        )
+        .await;
+        self.record(
+            "POST",
+            PATH,
+            Some(serde_json::json!({
+                "file_name": file.file_name,
+                "file_size": file.content.len(),
+            })),
+            &resp,
+        );
+        parse(resp?)
+            .await
+            .wrap_err_with(|| format!("unable to upload {}", file.file_name))?
+            .ok_or_else(|| eyre!("unable to upload {}", file.file_name))
+    }
+
     /// Returns details about a single project.
     ///
     /// * `id` - the ID as used by the Todoist API.
@@ -170,14 +635,58 @@ impl Gateway {
 
     /// Creates a project by calling the Todoist API.
     pub async fn create_project(&self, project: &CreateProject) -> Result<Project> {
+        self.invalidate(&self.projects).await;
         self.post("rest/v2/projects", project)
             .await
             .wrap_err("unable to create project")?
             .ok_or_else(|| eyre!("unable to create project"))
     }
 
+    /// Updates a project with the data as specified in UpdateProject.
+    pub async fn update_project(&self, id: &ProjectID, project: &UpdateProject) -> Result<()> {
+        self.invalidate(&self.projects).await;
+        self.post_empty(&format!("rest/v2/projects/{}", id), &project)
+            .await
+            .wrap_err("unable to update project")?;
+        Ok(())
+    }
+
+    /// Archives a project and its child projects through the Sync API, as the REST API can't.
+    pub async fn archive_project(&self, id: &ProjectID) -> Result<()> {
+        self.project_command("project_archive", id)
+            .await
+            .wrap_err("unable to archive project")
+    }
+
+    /// Unarchives a project through the Sync API, so it and its tasks show up again.
+    pub async fn unarchive_project(&self, id: &ProjectID) -> Result<()> {
+        self.project_command("project_unarchive", id)
+            .await
+            .wrap_err("unable to unarchive project")
+    }
+
+    /// Runs a Sync API command that only takes the ID of the project.
+    async fn project_command(&self, kind: &str, id: &ProjectID) -> Result<()> {
+        self.invalidate(&self.projects).await;
+        let command = self.sync_command(kind, serde_json::json!({ "id": id }));
+        let resp = self.sync(std::slice::from_ref(&command)).await?;
+        match resp.error(&command) {
+            Some(error) => Err(eyre!("{}", error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the archived projects, which the REST API leaves out of [`Gateway::projects`].
+    pub async fn archived_projects(&self) -> Result<Vec<ArchivedProject>> {
+        self.get::<(), _>("sync/v9/projects/get_archived", None)
+            .await
+            .wrap_err("unable to get archived projects")
+    }
+
     /// Deletes a project by calling the Todoist API.
     pub async fn delete_project(&self, project: &ProjectID) -> Result<()> {
+        self.invalidate(&self.projects).await;
+        self.invalidate(&self.sections).await;
         self.delete(&format!("rest/v2/projects/{}", project))
             .await
             .wrap_err("unable to delete project")
@@ -194,14 +703,28 @@ impl Gateway {
 
     /// Creates a section by calling the Todoist API.
     pub async fn create_section(&self, section: &CreateSection) -> Result<Section> {
+        self.invalidate(&self.sections).await;
         self.post("rest/v2/sections", section)
             .await
             .wrap_err("unable to create section")?
             .ok_or_else(|| eyre!("unable to create section"))
     }
 
+    /// Renames a section, which is the only thing the Todoist API can change about it.
+    pub async fn rename_section(&self, id: &SectionID, name: &str) -> Result<()> {
+        self.invalidate(&self.sections).await;
+        self.post_empty(
+            &format!("rest/v2/sections/{}", id),
+            &serde_json::json!({ "name": name }),
+        )
+        .await
+        .wrap_err("unable to rename section")?;
+        Ok(())
+    }
+
     /// Deletes a section by calling the Todoist API.
     pub async fn delete_section(&self, section: &SectionID) -> Result<()> {
+        self.invalidate(&self.sections).await;
         self.delete(&format!("rest/v2/sections/{}", section))
             .await
             .wrap_err("unable to delete section")
@@ -218,19 +741,157 @@ impl Gateway {
 
     /// Creates a label by calling the Todoist API.
     pub async fn create_label(&self, label: &CreateLabel) -> Result<Label> {
+        self.invalidate(&self.labels).await;
         self.post("rest/v2/labels", label)
             .await
             .wrap_err("unable to create label")?
             .ok_or_else(|| eyre!("unable to create label"))
     }
 
+    /// Updates a label by calling the Todoist API.
+    pub async fn update_label(&self, id: &LabelID, label: &UpdateLabel) -> Result<Label> {
+        self.invalidate(&self.labels).await;
+        self.post(&format!("rest/v2/labels/{}", id), label)
+            .await
+            .wrap_err("unable to update label")?
+            .ok_or_else(|| eyre!("unable to update label"))
+    }
+
     /// Deletes a label by calling the Todoist API.
     pub async fn delete_label(&self, label: &LabelID) -> Result<()> {
+        self.invalidate(&self.labels).await;
         self.delete(&format!("rest/v2/labels/{}", label))
             .await
             .wrap_err("unable to delete label")
     }
 
+    /// Returns the names of the shared labels, which are the labels on the tasks of shared
+    /// projects.
+    ///
+    /// * `omit_personal` - leaves out the names that are personal labels as well.
+    pub async fn shared_labels(&self, omit_personal: bool) -> Result<Vec<String>> {
+        self.get(
+            "rest/v2/labels/shared",
+            Some(&[("omit_personal", omit_personal)]),
+        )
+        .await
+        .wrap_err("unable to get shared labels")
+    }
+
+    /// Renames a shared label on every task that carries it.
+    pub async fn rename_shared_label(&self, name: &str, new_name: &str) -> Result<()> {
+        self.post_empty(
+            "rest/v2/labels/shared/rename",
+            &serde_json::json!({ "name": name, "new_name": new_name }),
+        )
+        .await
+        .wrap_err("unable to rename shared label")
+    }
+
+    /// Removes a shared label from every task that carries it.
+    pub async fn remove_shared_label(&self, name: &str) -> Result<()> {
+        self.post_empty(
+            "rest/v2/labels/shared/remove",
+            &serde_json::json!({ "name": name }),
+        )
+        .await
+        .wrap_err("unable to remove shared label")
+    }
+
+    /// Returns the saved filters, which only the Sync API knows about.
+    pub async fn filters(&self) -> Result<Vec<Filter>> {
+        let resp: FiltersResponse = self
+            .get(
+                "sync/v9/sync",
+                Some(&[("sync_token", "*"), ("resource_types", r#"["filters"]"#)]),
+            )
+            .await
+            .wrap_err("unable to get filters")?;
+        let mut filters = resp
+            .filters
+            .into_iter()
+            .filter(|f| !f.is_deleted)
+            .collect::<Vec<_>>();
+        filters.sort();
+        Ok(filters)
+    }
+
+    /// Creates a saved filter through the Sync API and returns its ID.
+    pub async fn create_filter(&self, filter: &CreateFilter) -> Result<FilterID> {
+        let mut command = self.sync_command("filter_add", serde_json::to_value(filter)?);
+        let temp_id = command.uuid.to_string();
+        command.temp_id = Some(temp_id.clone());
+        let resp = self.sync(std::slice::from_ref(&command)).await?;
+        if let Some(error) = resp.error(&command) {
+            return Err(eyre!("unable to create filter: {}", error));
+        }
+        resp.temp_id_mapping
+            .get(&temp_id)
+            .cloned()
+            .ok_or_else(|| eyre!("unable to create filter: no ID was returned"))
+    }
+
+    /// Updates a saved filter through the Sync API.
+    pub async fn update_filter(&self, id: &FilterID, filter: &UpdateFilter) -> Result<()> {
+        let mut args = serde_json::to_value(filter)?;
+        args["id"] = serde_json::json!(id);
+        let command = self.sync_command("filter_update", args);
+        let resp = self.sync(std::slice::from_ref(&command)).await?;
+        match resp.error(&command) {
+            Some(error) => Err(eyre!("unable to update filter: {}", error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Deletes a saved filter through the Sync API.
+    pub async fn delete_filter(&self, id: &FilterID) -> Result<()> {
+        let command = self.sync_command("filter_delete", serde_json::json!({ "id": id }));
+        let resp = self.sync(std::slice::from_ref(&command)).await?;
+        match resp.error(&command) {
+            Some(error) => Err(eyre!("unable to delete filter: {}", error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns a write command for [`Gateway::sync`], whose UUID is derived from the current
+    /// [`Gateway::operation`] like the idempotency keys of other requests are, as the API ignores
+    /// commands with a UUID it has already seen.
+    pub fn sync_command(&self, kind: &str, args: serde_json::Value) -> SyncCommand {
+        SyncCommand {
+            kind: kind.to_string(),
+            uuid: self.idempotency_key(&format!("{}\n{}", kind, args)),
+            temp_id: None,
+            args,
+        }
+    }
+
+    /// Runs a batch of write commands through the Sync API in a single request.
+    ///
+    /// Each command can fail on its own, so check [`SyncResponse::error`] for every command.
+    pub async fn sync(&self, commands: &[SyncCommand]) -> Result<SyncResponse> {
+        const PATH: &str = "sync/v9/sync";
+        self.ensure_writable("POST", PATH)?;
+        let commands_json = serde_json::to_string(commands)?;
+        let resp = send(
+            "POST",
+            PATH,
+            self.client()
+                .post(self.url.join(PATH)?)
+                .bearer_auth(&self.token)
+                .header(
+                    "X-Request-Id",
+                    self.request_id("POST", PATH, &commands_json),
+                )
+                .form(&[("commands", commands_json)]),
+        )
+        .await;
+        self.record("POST", PATH, Some(serde_json::to_value(commands)?), &resp);
+        parse(resp?)
+            .await
+            .wrap_err("unable to sync commands")?
+            .ok_or_else(|| eyre!("unable to sync commands"))
+    }
+
     /// Makes a GET request to the Todoist API with an optional query.
     async fn get<'a, T: 'a + Serialize, R: DeserializeOwned>(
         &self,
@@ -238,7 +899,7 @@ impl Gateway {
         query: Option<T>,
     ) -> Result<R> {
         let req = self
-            .client
+            .client()
             .get(self.url.join(path)?)
             .bearer_auth(&self.token);
         let req = if let Some(q) = query {
@@ -246,37 +907,179 @@ impl Gateway {
         } else {
             req
         };
-        handle_req(req)
+        handle_req("GET", path, req)
             .await?
             .ok_or_else(|| eyre!("Invalid response from API"))
     }
 
+    /// Fetches a list from the Todoist API, following the cursors of endpoints that paginate.
+    async fn all<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: Vec<(&'static str, String)>,
+    ) -> Result<Vec<T>> {
+        self.pages::<T, Page<T>>(path, query, Pagination::Cursor)
+            .try_concat()
+            .await
+    }
+
+    /// Fetches a paginated list from the Todoist API, yielding each page as it arrives. The next
+    /// page is only requested once the previous one was taken from the stream.
+    fn pages<'a, T, P>(
+        &'a self,
+        path: &'a str,
+        query: Vec<(&'static str, String)>,
+        pagination: Pagination,
+    ) -> impl Stream<Item = Result<Vec<T>>> + 'a
+    where
+        T: 'a,
+        P: Paginated<T> + 'a,
+    {
+        // The state is the number of items so far and the cursor of the next page, or nothing
+        // after the last page.
+        stream::try_unfold(Some((0, None)), move |state| {
+            let mut query = query.clone();
+            async move {
+                let Some((fetched, cursor)) = state else {
+                    return Ok(None);
+                };
+                match pagination {
+                    Pagination::Cursor => query.extend(cursor.map(|c| ("cursor", c))),
+                    Pagination::Offset { limit } => query.extend([
+                        ("limit", limit.to_string()),
+                        ("offset", fetched.to_string()),
+                    ]),
+                }
+                let (items, next_cursor) = self.get::<_, P>(path, Some(query)).await?.split();
+                let fetched = fetched + items.len();
+                let next = match pagination {
+                    Pagination::Cursor => next_cursor.map(|c| (fetched, Some(c))),
+                    Pagination::Offset { limit } => {
+                        (items.len() >= limit).then_some((fetched, None))
+                    }
+                };
+                Ok(Some((items, next)))
+            }
+        })
+    }
+
     /// Sends a POST request to the Todoist API with the given content.
     async fn post<T: Serialize, R: DeserializeOwned>(
         &self,
         path: &str,
         content: &T,
     ) -> Result<Option<R>> {
-        let uuid = Uuid::new_v4();
-        handle_req(
-            self.client
+        self.ensure_writable("POST", path)?;
+        let body = serde_json::to_string(&content)?;
+        let resp = send(
+            "POST",
+            path,
+            self.client()
                 .post(self.url.join(path)?)
                 .bearer_auth(&self.token)
-                .body(serde_json::to_string(&content)?)
+                .header("X-Request-Id", self.request_id("POST", path, &body))
                 .header(reqwest::header::CONTENT_TYPE, "application/json")
-                .header("X-Request-Id", uuid.to_string()),
+                .body(body),
         )
-        .await
+        .await;
+        self.record("POST", path, Some(serde_json::to_value(content)?), &resp);
+        parse(resp?).await
     }
 
     /// Sends a DELETE request to the Todoist API.
     async fn delete(&self, path: &str) -> Result<()> {
-        handle_req::<()>(
-            self.client
+        self.ensure_writable("DELETE", path)?;
+        let resp = send(
+            "DELETE",
+            path,
+            self.client()
                 .delete(self.url.join(path)?)
-                .bearer_auth(&self.token),
+                .bearer_auth(&self.token)
+                .header("X-Request-Id", self.request_id("DELETE", path, "")),
         )
-        .await?;
+        .await;
+        self.record("DELETE", path, None, &resp);
+        parse::<()>(resp?).await?;
+        Ok(())
+    }
+
+    /// Returns the remembered list, which is read from the cache or fetched if there is none.
+    async fn memoized<T>(&self, memo: &Memo<Vec<T>>, path: &str) -> Result<Vec<T>>
+    where
+        T: Clone + Serialize + DeserializeOwned,
+    {
+        memo.get_or_fetch(|| async {
+            if let Some(value) = self.cache.as_ref().and_then(|c| c.get(memo.name)) {
+                return Ok(value);
+            }
+            let value = self.all(path, Vec::new()).await?;
+            if let Some(cache) = &self.cache {
+                cache.put(memo.name, &value);
+            }
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Forgets the remembered list, also in the cache.
+    async fn invalidate<T: Clone>(&self, memo: &Memo<T>) {
+        memo.invalidate().await;
+        if let Some(cache) = &self.cache {
+            cache.remove(memo.name);
+        }
+    }
+
+    /// Returns the idempotency key of a modifying request. Retries send the same key, as they
+    /// clone the request.
+    fn request_id(&self, method: &str, path: &str, body: &str) -> String {
+        self.idempotency_key(&format!("{} {}\n{}", method, path, body))
+            .to_string()
+    }
+
+    /// Returns the idempotency key of the content. Within an [`Gateway::operation`] the key is
+    /// derived from the operation and the content, so that running the operation again sends the
+    /// same keys in the same order.
+    fn idempotency_key(&self, content: &str) -> Uuid {
+        OPERATION
+            .try_with(|operation| operation.key(content))
+            .unwrap_or_else(|_| Uuid::new_v4())
+    }
+
+    /// Records a request that modifies data in the audit log, if there is one.
+    fn record(
+        &self,
+        method: &str,
+        path: &str,
+        request: Option<serde_json::Value>,
+        resp: &Result<Response>,
+    ) {
+        if let Some(audit) = &self.audit {
+            let request = request.filter(|r| match r {
+                serde_json::Value::Null => false,
+                serde_json::Value::Object(o) => !o.is_empty(),
+                _ => true,
+            });
+            let status = resp.as_ref().ok().map(|r| r.status().as_u16());
+            audit.record(method, path, request, status);
+        }
+    }
+
+    /// Returns an error if the gateway is in read-only mode. Otherwise forgets the cached tasks,
+    /// as any change can affect them.
+    fn ensure_writable(&self, method: &str, path: &str) -> Result<()> {
+        if self.read_only {
+            return Err(eyre!(
+                "refusing to send {} {} in read-only mode",
+                method,
+                path
+            ));
+        }
+        if let Some(journal) = &self.journal {
+            journal.modifies();
+        }
+        if let Some(cache) = &self.cache {
+            cache.remove(cache::TASKS);
+        }
         Ok(())
     }
 
@@ -287,21 +1090,53 @@ impl Gateway {
     }
 }
 
+/// Formats the start of a range of completed tasks or events the way the Sync API expects it.
+fn sync_since(since: &DateTime<Utc>) -> String {
+    since.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
 /// Does the actual call to the Todoist API and handles error handling.
-async fn handle_req<R: DeserializeOwned>(req: RequestBuilder) -> Result<Option<R>> {
-    // TODO: implement retries/backoffs
-    let resp = req
-        .timeout(Duration::from_secs(30))
-        .send()
-        .await
-        .wrap_err("unable to send request")?;
+async fn handle_req<R: DeserializeOwned>(
+    method: &str,
+    path: &str,
+    req: RequestBuilder,
+) -> Result<Option<R>> {
+    parse(send(method, path, req).await?).await
+}
+
+/// Sends the request to the Todoist API, adding how long it took to the usage statistics.
+async fn send(method: &str, path: &str, req: RequestBuilder) -> Result<Response> {
+    let started = Instant::now();
+    let resp = cancel::or_cancelled(async {
+        req.timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .wrap_err("unable to send request")
+    })
+    .await;
+    usage::record_request(method, path, started.elapsed());
+    resp
+}
+
+/// Parses the response of the Todoist API, turning unsuccessful responses into errors.
+async fn parse<R: DeserializeOwned>(resp: Response) -> Result<Option<R>> {
     let status = resp.status();
     if status == StatusCode::NO_CONTENT {
         return Ok(None);
     }
+    let retry_after = retry::retry_after(&resp);
     let text = resp.text().await.wrap_err("unable to read response")?;
     if !status.is_success() {
-        return Err(eyre!("Bad response from API: {} - {}", status, text));
+        let error = ApiError {
+            retry_after,
+            ..ApiError::new(status, &text)
+        };
+        let hint = error.hint();
+        let report = Report::new(error);
+        return Err(match hint {
+            Some(hint) => report.suggestion(hint),
+            None => report,
+        });
     }
     let result = serde_json::from_str(&text).wrap_err("unable to parse API response")?;
     Ok(Some(result))
@@ -310,7 +1145,7 @@ async fn handle_req<R: DeserializeOwned>(req: RequestBuilder) -> Result<Option<R
 #[cfg(test)]
 mod test {
     use wiremock::{
-        matchers::{bearer_token, method, path, query_param},
+        matchers::{bearer_token, body_json, method, path, query_param, query_param_is_missing},
         Mock, MockServer, ResponseTemplate,
     };
 
@@ -369,6 +1204,151 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn follows_cursors() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/v2/tasks"))
+            .and(query_param("filter", "today"))
+            .and(query_param_is_missing("cursor"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [create_task("123", "456", "hello there")],
+                "next_cursor": "abc",
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rest/v2/tasks"))
+            .and(query_param("filter", "today"))
+            .and(query_param("cursor", "abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [create_task("234", "567", "general kenobi")],
+                "next_cursor": null,
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let tasks = gw.tasks(Some("today")).await?;
+        assert_eq!(
+            tasks.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(),
+            ["123", "234"]
+        );
+        let pages = gw
+            .tasks_pages(Some("today"))
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), [1, 1]);
+        mock_server.verify().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn throttles_when_out_of_requests() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/v2/tasks/123"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(create_task("123", "456", "hello"))
+                    .insert_header("X-RateLimit-Remaining", "0")
+                    .insert_header("X-RateLimit-Reset", "2"),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        gw.task(&"123".to_string()).await?;
+        let started = Instant::now();
+        gw.task(&"123".to_string()).await?;
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        mock_server.verify().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn throttles_concurrent_requests() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/v2/tasks/123"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(create_task("123", "456", "hello"))
+                    .insert_header("X-RateLimit-Remaining", "0")
+                    .insert_header("X-RateLimit-Reset", "2"),
+            )
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let id = "123".to_string();
+        gw.task(&id).await?;
+        let timed = || async {
+            let started = Instant::now();
+            gw.task(&id).await?;
+            Ok::<_, color_eyre::Report>(started.elapsed())
+        };
+        let (first, second) = tokio::try_join!(timed(), timed())?;
+        assert!(first >= Duration::from_secs(1), "{:?}", first);
+        assert!(second >= Duration::from_secs(1), "{:?}", second);
+        mock_server.verify().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn completed_pages() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        let completed = |id: usize| CompletedTask {
+            id: id.to_string(),
+            task_id: "123".to_string(),
+            content: "hello there".to_string(),
+            project_id: "456".to_string(),
+            section_id: None,
+            completed_at: Utc::now(),
+            task: None,
+        };
+        Mock::given(method("GET"))
+            .and(path("/sync/v9/completed/get_all"))
+            .and(query_param("since", "2022-08-22T00:00:00"))
+            .and(query_param("offset", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": (0..COMPLETED_PAGE_SIZE).map(completed).collect::<Vec<_>>(),
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/sync/v9/completed/get_all"))
+            .and(query_param("offset", COMPLETED_PAGE_SIZE.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [completed(COMPLETED_PAGE_SIZE)],
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let since = "2022-08-22T00:00:00Z".parse::<DateTime<Utc>>()?;
+        let completed = gw.completed(&since).await?;
+        mock_server.verify().await;
+        assert_eq!(completed.len(), COMPLETED_PAGE_SIZE + 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_task() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/rest/v2/tasks/123"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        assert!(gw.delete_task(&"123".to_string()).await.is_ok());
+        mock_server.verify().await;
+    }
+
     #[tokio::test]
     async fn close_task() {
         let mock_server = MockServer::start().await;
@@ -446,6 +1426,58 @@ mod test {
         assert_eq!(task.id, "123");
     }
 
+    #[tokio::test]
+    async fn derives_request_ids_from_operations() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/v2/tasks"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(create_task("123", "456", "hello")),
+            )
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let task = CreateTask {
+            content: "hello".to_string(),
+            ..Default::default()
+        };
+        let operation = Uuid::new_v4();
+        let twice = || async {
+            gw.create(&task).await.unwrap();
+            gw.create(&task).await.unwrap();
+        };
+        twice().await;
+        Gateway::operation(operation, twice()).await;
+        Gateway::operation(operation, twice()).await;
+        Gateway::operation(Uuid::new_v4(), twice()).await;
+        let ids = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.headers["X-Request-Id"].to_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        // The same request twice is two requests, also within an operation, but running the same
+        // operation again repeats its requests.
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[2], ids[3]);
+        assert_eq!(ids[2..4], ids[4..6]);
+        assert_ne!(ids[2], ids[6]);
+        assert_ne!(ids[0], ids[2]);
+    }
+
+    #[tokio::test]
+    async fn derives_sync_commands_from_operations() {
+        let gw = Gateway::new("", &TODOIST_API_URL);
+        let args = || serde_json::json!({ "id": "1" });
+        let operation = Uuid::new_v4();
+        let command = |gw: &Gateway| gw.sync_command("item_close", args()).uuid;
+        assert_ne!(command(&gw), command(&gw));
+        let first = Gateway::operation(operation, async { command(&gw) }).await;
+        let again = Gateway::operation(operation, async { command(&gw) }).await;
+        assert_eq!(first, again);
+    }
+
     #[tokio::test]
     async fn lists_projects() {
         let mock_server = MockServer::start().await;
@@ -495,6 +1527,37 @@ mod test {
         assert_eq!(labels.len(), 2);
     }
 
+    #[tokio::test]
+    async fn fetches_lists_once() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/v2/labels"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(vec![Label::new("123", "one"), Label::new("456", "two")]),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/v2/labels"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Label::new("789", "three")))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let (first, second) = tokio::try_join!(gw.labels(), gw.labels()).unwrap();
+        assert_eq!(first, second);
+        gw.labels().await.unwrap();
+        gw.create_label(&CreateLabel {
+            name: "three".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        gw.labels().await.unwrap();
+        mock_server.verify().await;
+    }
+
     #[tokio::test]
     async fn show_label() {
         let mock_server = MockServer::start().await;
@@ -563,6 +1626,7 @@ mod test {
                     project_id: "123".to_string(),
                 },
                 content: "hello".to_string(),
+                attachment: None,
             })
             .await
             .unwrap();
@@ -592,6 +1656,7 @@ mod test {
                     task_id: "123".to_string(),
                 },
                 content: "hello".to_string(),
+                attachment: None,
             })
             .await
             .unwrap();
@@ -675,6 +1740,34 @@ mod test {
         assert_eq!(label.id, "123");
     }
 
+    #[tokio::test]
+    async fn updates_label() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/v2/labels/123"))
+            .and(body_json(
+                serde_json::json!({ "name": "hi", "color": "red" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Label::new("123", "hi")))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let label = gw
+            .update_label(
+                &"123".to_string(),
+                &UpdateLabel {
+                    name: Some("hi".to_string()),
+                    color: Some("red".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(label.name, "hi");
+    }
+
     #[tokio::test]
     async fn delete_label() {
         let mock_server = MockServer::start().await;
@@ -688,6 +1781,39 @@ mod test {
         assert!(closed.is_ok());
     }
 
+    #[tokio::test]
+    async fn shared_labels() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/v2/labels/shared"))
+            .and(query_param("omit_personal", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(["one", "two"]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/v2/labels/shared/rename"))
+            .and(body_json(
+                serde_json::json!({ "name": "one", "new_name": "uno" }),
+            ))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/v2/labels/shared/remove"))
+            .and(body_json(serde_json::json!({ "name": "two" })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        assert_eq!(gw.shared_labels(true).await.unwrap(), ["one", "two"]);
+        gw.rename_shared_label("one", "uno").await.unwrap();
+        gw.remove_shared_label("two").await.unwrap();
+        mock_server.verify().await;
+    }
+
     #[tokio::test]
     async fn creates_project() {
         let mock_server = MockServer::start().await;
@@ -1,3 +1,4 @@
+use crate::api::content::Content;
 use owo_colors::{OwoColorize, Stream};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -41,7 +42,7 @@ impl PartialOrd for Label {
 
 impl std::fmt::Display for Label {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        format!("@{}", self.name)
+        format!("@{}", Content(&self.name))
             .if_supports_color(Stream::Stdout, |text| text.bright_blue())
             .fmt(f)
     }
@@ -63,6 +64,26 @@ pub struct CreateLabel {
     pub is_favorite: Option<bool>,
 }
 
+/// Command used with [`super::Gateway::update_label`] to update a [`Label`].
+///
+/// Each field is optional, so if something exists, that part of the [`Label`] will get
+/// overwritten.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateLabel {
+    /// Renames the label.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Order of the label in lists.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<isize>,
+    /// Color of the label icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+}
+
 #[cfg(test)]
 impl Label {
     /// This is initializer is used for tests, as in general the tool relies on the API and not
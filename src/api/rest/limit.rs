@@ -0,0 +1,151 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::Extensions;
+use reqwest::{header::HeaderMap, Request, Response};
+use reqwest_middleware::{Middleware, Next};
+
+/// The number of requests left in the window below which requests are spread out over the rest
+/// of it, instead of running into the limit.
+const RESERVE: u32 = 20;
+
+/// The window that Todoist counts the requests in. Resets further away than this are not trusted.
+const WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Quota is what is known about the requests that are left until Todoist resets its limit, as it
+/// told in the headers of the last response.
+#[derive(Debug, Default)]
+pub(super) struct Quota {
+    remaining: Option<u32>,
+    reset: Option<Instant>,
+}
+
+impl Quota {
+    /// Remembers the quota from the `X-RateLimit-Remaining` and `X-RateLimit-Reset` headers of a
+    /// response, if it sent them.
+    fn update(&mut self, headers: &HeaderMap, now: Instant) {
+        let header = |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+        if let Some(remaining) = header("x-ratelimit-remaining") {
+            self.remaining = Some(remaining.try_into().unwrap_or(u32::MAX));
+        }
+        if let Some(reset) = header("x-ratelimit-reset") {
+            self.reset = Some(now + reset_in(reset));
+        }
+    }
+
+    /// Returns how long to wait before the next request, and takes it from the quota. Without
+    /// any requests left this waits for the reset, and with only a few left they are spread out
+    /// evenly until the reset.
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        let reset = self.reset.filter(|reset| *reset > now);
+        let (remaining, reset) = self.remaining.zip(reset)?;
+        let left = reset - now;
+        if left > WINDOW {
+            return None;
+        }
+        if remaining == 0 {
+            // Every request waits for the reset, not only the first one. After it, the reset is
+            // in the past and requests go out until the next response tells the quota again.
+            return Some(left);
+        }
+        self.remaining = Some(remaining - 1);
+        (remaining < RESERVE).then(|| left / (remaining + 1))
+    }
+}
+
+/// Returns the time until the reset from the `X-RateLimit-Reset` header, which is either the
+/// seconds until it or the Unix timestamp of it.
+fn reset_in(reset: u64) -> Duration {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    match reset > now / 2 {
+        true => Duration::from_secs(reset.saturating_sub(now)),
+        false => Duration::from_secs(reset),
+    }
+}
+
+/// Throttling is the middleware that keeps requests within the rate limit of Todoist, so that
+/// bulk operations slow down instead of failing.
+pub(super) struct Throttling {
+    quota: Arc<Mutex<Quota>>,
+}
+
+impl Throttling {
+    pub(super) fn new(quota: Arc<Mutex<Quota>>) -> Self {
+        Self { quota }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for Throttling {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let wait = self.quota.lock().unwrap().take(Instant::now());
+        if let Some(wait) = wait {
+            if wait >= Duration::from_secs(1) {
+                eprintln!("rate limited, retrying at {}", retry_at(wait));
+            }
+            tokio::time::sleep(wait).await;
+        }
+        let result = next.run(req, extensions).await;
+        if let Ok(response) = &result {
+            self.quota
+                .lock()
+                .unwrap()
+                .update(response.headers(), Instant::now());
+        }
+        result
+    }
+}
+
+/// Formats the local time after the wait, like `14:05`.
+pub(super) fn retry_at(wait: Duration) -> String {
+    let at = chrono::Local::now() + chrono::Duration::from_std(wait).unwrap_or_default();
+    at.format("%H:%M").to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn headers(remaining: &str, reset: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-RateLimit-Remaining", remaining.parse().unwrap());
+        headers.insert("X-RateLimit-Reset", reset.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn spreads_out_the_last_requests() {
+        let now = Instant::now();
+        let mut quota = Quota::default();
+        assert_eq!(quota.take(now), None);
+
+        quota.update(&headers("400", "600"), now);
+        assert_eq!(quota.take(now), None);
+        assert_eq!(quota.remaining, Some(399));
+
+        quota.update(&headers("9", "100"), now);
+        assert_eq!(quota.take(now), Some(Duration::from_secs(10)));
+        assert_eq!(quota.remaining, Some(8));
+
+        quota.update(&headers("0", "30"), now);
+        assert_eq!(quota.take(now), Some(Duration::from_secs(30)));
+        let later = now + Duration::from_secs(10);
+        assert_eq!(quota.take(later), Some(Duration::from_secs(20)));
+        assert_eq!(quota.take(now + Duration::from_secs(30)), None);
+    }
+
+    #[test]
+    fn reads_resets_as_timestamps() {
+        let now = chrono::Utc::now().timestamp() as u64;
+        assert!(reset_in(now + 60) >= Duration::from_secs(59));
+        assert_eq!(reset_in(now - 60), Duration::ZERO);
+        assert_eq!(reset_in(60), Duration::from_secs(60));
+    }
+}
@@ -6,18 +6,29 @@
 //! serialization/deserialization..
 //!
 //! To get started, take a look at [`Gateway`].
+mod collaborator;
 mod comment;
 mod display;
+mod error;
+mod filter;
 mod gateway;
 mod label;
+mod limit;
 mod project;
+mod retry;
 mod section;
+mod sync;
 mod task;
 
+pub use collaborator::*;
 pub use comment::*;
 pub use display::*;
+pub use error::*;
+pub use filter::*;
 pub use gateway::*;
 pub use label::*;
 pub use project::*;
+pub use retry::Retry;
 pub use section::*;
+pub use sync::*;
 pub use task::*;
@@ -1,4 +1,4 @@
-use crate::api::tree::Treeable;
+use crate::api::{content::Content, tree::Treeable};
 use owo_colors::{OwoColorize, Stream};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
@@ -44,10 +44,11 @@ pub struct Project {
 /// ViewStyle for viewing of the project in different clients.
 ///
 /// Taken from the [Developer Documentation](https://developer.todoist.com/rest/v2/#projects).
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum ViewStyle {
     /// Project as list view (default).
+    #[default]
     List,
     /// Project as board view.
     Board,
@@ -55,12 +56,6 @@ pub enum ViewStyle {
     Calendar,
 }
 
-impl Default for ViewStyle {
-    fn default() -> Self {
-        Self::List
-    }
-}
-
 impl Treeable for Project {
     type ID = ProjectID;
 
@@ -84,7 +79,35 @@ impl std::fmt::Display for Project {
             "{} {}",
             self.id
                 .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
-            self.name
+            Content(&self.name)
+        )
+    }
+}
+
+/// ArchivedProject is a [`Project`] that was archived. The REST API leaves them out, so they come
+/// from the Sync API, which describes them with fewer fields.
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/sync/v9/#get-archived-projects).
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct ArchivedProject {
+    /// ID of the Project.
+    pub id: ProjectID,
+    /// The direct parent of the project if it exists.
+    pub parent_id: Option<ProjectID>,
+    /// The name of the Project.
+    pub name: String,
+    /// Color as used by the Todoist UI.
+    pub color: String,
+}
+
+impl std::fmt::Display for ArchivedProject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.id
+                .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+            Content(&self.name)
         )
     }
 }
@@ -108,6 +131,26 @@ pub struct CreateProject {
     pub view_style: Option<ViewStyle>,
 }
 
+/// Command used with [`super::Gateway::update_project`] to update a [`Project`].
+///
+/// Each field is optional, so if something exists, that part of the [`Project`] will get
+/// overwritten.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateProject {
+    /// Renames the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Color of the project icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+    /// Sets the view style of the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_style: Option<ViewStyle>,
+}
+
 #[cfg(test)]
 impl Project {
     /// This is initializer is used for tests, as in general the tool relies on the API and not
@@ -0,0 +1,154 @@
+use std::time::{Duration, SystemTime};
+
+use http::Extensions;
+use reqwest::{header::RETRY_AFTER, Request, Response, StatusCode};
+use reqwest_middleware::{Middleware, Next};
+use reqwest_retry::{
+    policies::ExponentialBackoff, DefaultRetryableStrategy, Jitter, RetryDecision, RetryPolicy,
+    Retryable, RetryableStrategy,
+};
+
+/// Retry decides how often and how long the [`super::Gateway`] waits before it tries a request
+/// again that failed for a moment, like after a network error, a rate limit or an error of the
+/// servers of Todoist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retry {
+    /// How often a request is tried again before giving up. 0 never tries again.
+    pub max_retries: u32,
+    /// The wait before the first retry, which doubles with every retry after it.
+    pub min_delay: Duration,
+    /// The longest wait between two tries. Todoist asking to wait longer than this with
+    /// `Retry-After` gives up instead.
+    pub max_delay: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            min_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Retrying is the middleware that tries transient failures again. Unlike
+/// [`reqwest_retry::RetryTransientMiddleware`] it waits as long as `Retry-After` asks for.
+pub(super) struct Retrying {
+    retry: Retry,
+    backoff: ExponentialBackoff,
+}
+
+impl Retrying {
+    pub(super) fn new(retry: Retry) -> Self {
+        let backoff = ExponentialBackoff::builder()
+            .retry_bounds(retry.min_delay, retry.max_delay.max(retry.min_delay))
+            // Spreads out the retries of many requests that failed at the same time, like those
+            // of bulk operations.
+            .jitter(Jitter::Bounded)
+            .build_with_max_retries(retry.max_retries);
+        Self { retry, backoff }
+    }
+
+    /// Returns how long to wait before trying again, or nothing if the request should not be
+    /// tried again.
+    fn delay(
+        &self,
+        result: &reqwest_middleware::Result<Response>,
+        start: SystemTime,
+        retries: u32,
+    ) -> Option<Duration> {
+        if !matches!(
+            DefaultRetryableStrategy.handle(result),
+            Some(Retryable::Transient)
+        ) {
+            return None;
+        }
+        let RetryDecision::Retry { execute_after } = self.backoff.should_retry(start, retries)
+        else {
+            return None;
+        };
+        let backoff = execute_after
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+        match result.as_ref().ok().and_then(retry_after) {
+            Some(wait) if wait > self.retry.max_delay => None,
+            Some(wait) => Some(wait.max(backoff)),
+            None => Some(backoff),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for Retrying {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let start = SystemTime::now();
+        let mut retries = 0;
+        loop {
+            let Some(attempt) = req.try_clone() else {
+                // Streamed bodies can only be sent once.
+                return next.run(req, extensions).await;
+            };
+            let result = next.clone().run(attempt, extensions).await;
+            let Some(delay) = self.delay(&result, start, retries) else {
+                return result;
+            };
+            if matches!(&result, Ok(r) if r.status() == StatusCode::TOO_MANY_REQUESTS) {
+                eprintln!(
+                    "rate limited, retrying at {}",
+                    super::limit::retry_at(delay)
+                );
+            }
+            tokio::time::sleep(delay).await;
+            retries += 1;
+        }
+    }
+}
+
+/// Returns how long the response asks to wait with `Retry-After`, either in seconds or until a
+/// date.
+pub(super) fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+        .or(Some(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn response(retry_after: &str) -> Response {
+        http::Response::builder()
+            .status(429)
+            .header(RETRY_AFTER, retry_after)
+            .body("")
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn reads_retry_after() {
+        assert_eq!(
+            retry_after(&response("120")),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            retry_after(&response("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+        let later = (chrono::Utc::now() + chrono::Duration::minutes(10)).to_rfc2822();
+        assert!(retry_after(&response(&later)).unwrap() > Duration::from_secs(9 * 60));
+        assert_eq!(retry_after(&response("soon")), None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::api::content::Content;
 use owo_colors::{OwoColorize, Stream};
 use serde::{Deserialize, Serialize};
 
@@ -44,7 +45,7 @@ impl std::fmt::Display for Section {
             "{} {}",
             self.id
                 .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
-            self.name
+            Content(&self.name)
         )
     }
 }
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_aux::prelude::deserialize_string_from_number;
+
+use super::{ProjectID, SectionID, TaskDuration, TaskID};
+
+/// How many completed tasks the Sync API returns at most per request.
+pub const COMPLETED_PAGE_SIZE: usize = 200;
+
+/// CompletedTask is a task that was completed, as returned by [`super::Gateway::completed`].
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/sync/v9/#get-all-completed-items)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CompletedTask {
+    /// The ID of the completion, which differs for every time a recurring task is completed.
+    pub id: String,
+    /// The ID of the task that was completed.
+    pub task_id: TaskID,
+    /// The content of the task at the time it was completed.
+    pub content: String,
+    /// The project the task was in.
+    pub project_id: ProjectID,
+    /// The section the task was in, if any.
+    #[serde(default)]
+    pub section_id: Option<SectionID>,
+    /// When the task was completed.
+    pub completed_at: DateTime<Utc>,
+    /// Details of the task at the time it was completed.
+    #[serde(default, rename = "item_object")]
+    pub task: Option<CompletedTaskDetails>,
+}
+
+impl CompletedTask {
+    /// Returns the labels the task had when it was completed.
+    pub fn labels(&self) -> &[String] {
+        self.task.as_ref().map_or(&[], |t| &t.labels)
+    }
+}
+
+/// CompletedTaskDetails are the parts of the task that the API returns alongside a
+/// [`CompletedTask`], when asked to annotate them.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct CompletedTaskDetails {
+    /// The labels of the task.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// How long the task was estimated to take.
+    #[serde(default)]
+    pub duration: Option<TaskDuration>,
+}
+
+/// CompletedResponse is a single page of completed tasks.
+#[derive(Debug, Deserialize)]
+pub(super) struct CompletedResponse {
+    pub items: Vec<CompletedTask>,
+}
+
+/// How many activity events the Sync API returns at most per request.
+pub const ACTIVITY_PAGE_SIZE: usize = 100;
+
+/// ActivityEvent is a change to the account, as returned by [`super::Gateway::activity`].
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/sync/v9/#activity)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ActivityEvent {
+    /// The ID of the event.
+    #[serde(deserialize_with = "deserialize_string_from_number")]
+    pub id: String,
+    /// What kind of object changed, like `item`, `note` or `project`.
+    pub object_type: String,
+    /// The ID of the object that changed.
+    pub object_id: String,
+    /// What happened to the object, like `added`, `updated` or `completed`.
+    pub event_type: String,
+    /// When the event happened.
+    pub event_date: DateTime<Utc>,
+    /// The project of the object, if any.
+    #[serde(default)]
+    pub parent_project_id: Option<ProjectID>,
+    /// The task of the object, if it is a comment.
+    #[serde(default)]
+    pub parent_item_id: Option<TaskID>,
+    /// The user that caused the event, which is empty if it was the user of the token.
+    #[serde(default)]
+    pub initiator_id: Option<String>,
+    /// Details of the event, like the content or name of the object.
+    #[serde(default)]
+    pub extra_data: serde_json::Map<String, serde_json::Value>,
+}
+
+/// ActivityResponse is a single page of activity events.
+#[derive(Debug, Deserialize)]
+pub(super) struct ActivityResponse {
+    pub events: Vec<ActivityEvent>,
+}
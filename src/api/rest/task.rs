@@ -3,6 +3,7 @@ use std::fmt::Display;
 
 use crate::api::serialize::todoist_rfc3339;
 use crate::api::tree::Treeable;
+use crate::output;
 use chrono::{DateTime, FixedOffset, Utc};
 use owo_colors::{OwoColorize, Stream};
 use reqwest::Url;
@@ -43,6 +44,9 @@ pub struct Task {
     pub priority: Priority,
     /// The due date of the Task.
     pub due: Option<DueDate>,
+    /// How long the Task is estimated to take.
+    #[serde(default)]
+    pub duration: Option<TaskDuration>,
     /// Links the Task to a URL in the Todoist UI.
     pub url: Url,
     /// How many comments are written for this Task.
@@ -160,6 +164,38 @@ impl Display for Priority {
     }
 }
 
+/// TaskDuration is the estimate of how long a [`Task`] takes.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct TaskDuration {
+    /// The amount of units.
+    pub amount: u32,
+    /// The unit of the amount.
+    pub unit: DurationUnit,
+}
+
+/// DurationUnit is the unit of a [`TaskDuration`].
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationUnit {
+    /// The amount is in minutes.
+    Minute,
+    /// The amount is in whole days.
+    Day,
+}
+
+impl TaskDuration {
+    /// How many minutes of work a day of duration counts as.
+    pub const WORKDAY_MINUTES: u32 = 8 * 60;
+
+    /// Returns the duration in minutes, counting days as workdays.
+    pub fn minutes(&self) -> u32 {
+        match self.unit {
+            DurationUnit::Minute => self.amount,
+            DurationUnit::Day => self.amount * Self::WORKDAY_MINUTES,
+        }
+    }
+}
+
 /// ExactTime exists in DueDate if this is an exact DueDate.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct ExactTime {
@@ -196,6 +232,17 @@ pub struct DueDate {
     pub exact: Option<ExactTime>,
 }
 
+impl DueDate {
+    /// Returns true if the task is due before the given time, or on an earlier day if it has no
+    /// exact time.
+    pub fn is_overdue(&self, now: &DateTime<Utc>) -> bool {
+        match &self.exact {
+            Some(exact) => exact.datetime < *now,
+            None => self.date < now.date_naive(),
+        }
+    }
+}
+
 /// Formats a [`DueDate`] using the given [`DateTime`], by coloring the output based on if it's
 /// too late or too soon.
 pub struct DueDateFormatter<'a>(pub &'a DueDate, pub &'a DateTime<Utc>);
@@ -203,46 +250,43 @@ pub struct DueDateFormatter<'a>(pub &'a DueDate, pub &'a DateTime<Utc>);
 impl<'a> Display for DueDateFormatter<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.0.is_recurring {
-            write!(
-                f,
-                "{}",
-                "[REPEAT] ".if_supports_color(Stream::Stdout, |_| "🔁 ")
-            )?;
+            write!(f, "{}", output::symbol("[REPEAT] ", "🔁 "))?;
         }
-        if let Some(exact) = &self.0.exact {
-            if exact.datetime >= *self.1 {
-                write!(
-                    f,
-                    "{}",
-                    exact.if_supports_color(Stream::Stdout, |text| text.bright_green())
-                )
-            } else {
-                write!(
-                    f,
-                    "{}",
-                    exact.if_supports_color(Stream::Stdout, |text| text.bright_red())
-                )
-            }
-        } else if self.0.date >= self.1.date_naive() {
+        let overdue = self.0.is_overdue(self.1);
+        let text = match &self.0.exact {
+            Some(exact) => exact.to_string(),
+            None => self.0.string.clone(),
+        };
+        if overdue {
             write!(
                 f,
                 "{}",
-                self.0
-                    .string
-                    .if_supports_color(Stream::Stdout, |text| text.bright_green())
+                text.if_supports_color(Stream::Stdout, |text| text.bright_red())
             )
         } else {
             write!(
                 f,
                 "{}",
-                self.0
-                    .string
-                    .if_supports_color(Stream::Stdout, |text| text.bright_red())
+                text.if_supports_color(Stream::Stdout, |text| text.bright_green())
             )
         }
     }
 }
 
+/// MoveTarget is where [`super::Gateway::move_task`] moves a [`Task`] to.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum MoveTarget {
+    /// To the top of a project, outside of any section.
+    #[serde(rename = "project_id")]
+    Project(ProjectID),
+    /// To a section, in the project of the section.
+    #[serde(rename = "section_id")]
+    Section(SectionID),
+    /// Under another task, as its subtask.
+    #[serde(rename = "parent_id")]
+    Parent(TaskID),
+}
+
 /// Human representation of the due date.
 #[derive(Debug, Serialize, Deserialize)]
 pub enum TaskDue {
@@ -280,7 +324,8 @@ pub struct CreateTask {
     pub due: Option<TaskDue>,
     /// If due is [TaskDue::String], this two-letter code optionally specifies the language if it's not english.
     pub due_lang: Option<String>,
-    /// Sets the [`Task::assignee`] on the new [`Task`].
+    /// Sets the [`Task::assignee_id`] on the new [`Task`].
+    #[serde(rename = "assignee_id", skip_serializing_if = "Option::is_none")]
     pub assignee: Option<UserID>,
 }
 
@@ -307,9 +352,9 @@ pub struct UpdateTask {
     /// If due is [TaskDue::String], this two-letter code optionally specifies the language if it's not english.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub due_lang: Option<String>,
-    /// Overwrites [`Task::assignee`] if set.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub assignee: Option<UserID>,
+    /// Overwrites [`Task::assignee_id`] if set, and unassigns the task if set to `Some(None)`.
+    #[serde(rename = "assignee_id", skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<Option<UserID>>,
 }
 
 #[cfg(test)]
@@ -329,6 +374,7 @@ impl Task {
             order: 0,
             priority: Priority::default(),
             due: None,
+            duration: None,
             url: "http://localhost".to_string().parse().unwrap(),
             comment_count: 0,
             creator_id: "0".to_string(),
@@ -0,0 +1,67 @@
+//! The write commands of the Sync API, which [`crate::api::rest::Gateway::sync`] sends in batches.
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How many commands the Sync API accepts in a single request.
+pub const SYNC_BATCH_SIZE: usize = 100;
+
+/// SyncCommand is a single write command that is sent in a batch with
+/// [`crate::api::rest::Gateway::sync`].
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/sync/v9/#write-resources)
+#[derive(Debug, Serialize, Clone)]
+pub struct SyncCommand {
+    /// The type of command, like `item_add`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Identifies the command. The API ignores commands with a UUID it has already seen, so
+    /// sending the same command twice is safe.
+    pub uuid: Uuid,
+    /// Temporary ID of the created resource, which other commands in the same batch can use in
+    /// place of its real ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_id: Option<String>,
+    /// The arguments of the command.
+    pub args: serde_json::Value,
+}
+
+/// SyncResponse is returned by the API after running a batch of [`SyncCommand`]s.
+#[derive(Debug, Deserialize, Default)]
+pub struct SyncResponse {
+    /// The result of each command, keyed by [`SyncCommand::uuid`].
+    #[serde(default)]
+    pub sync_status: HashMap<Uuid, SyncStatus>,
+    /// Maps [`SyncCommand::temp_id`]s to the real IDs of the created resources.
+    #[serde(default)]
+    pub temp_id_mapping: HashMap<String, String>,
+}
+
+/// SyncStatus describes if a [`SyncCommand`] was successful.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SyncStatus {
+    /// The command ran successfully, contains `ok`.
+    Ok(String),
+    /// The command failed.
+    Error {
+        /// Code of the error.
+        error_code: i64,
+        /// Description of what went wrong.
+        error: String,
+    },
+}
+
+impl SyncResponse {
+    /// Returns the error of the command, or none if it ran successfully.
+    pub fn error(&self, command: &SyncCommand) -> Option<String> {
+        match self.sync_status.get(&command.uuid) {
+            Some(SyncStatus::Ok(_)) => None,
+            Some(SyncStatus::Error { error_code, error }) => {
+                Some(format!("{} (code {})", error, error_code))
+            }
+            None => Some("command was not processed".to_string()),
+        }
+    }
+}
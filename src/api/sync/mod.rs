@@ -1,5 +1,10 @@
 //! This was an initial attempt on writing the sync API, but eventually I got too lazy to actually
 //! come through with it, given the REST API was enough for my needs. I'm leaving the code here for
 //! potential future use.
+//!
+//! The write commands are in use though, for changes that are made in batches.
+mod command;
 mod data;
 mod state;
+
+pub use command::*;
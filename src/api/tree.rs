@@ -52,7 +52,7 @@ impl<T: Treeable> Ord for Tree<T> {
 
 impl<T: Treeable + std::cmp::PartialEq> PartialOrd for Tree<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.item.cmp(&other.item))
+        Some(self.cmp(other))
     }
 }
 
@@ -220,6 +220,10 @@ pub trait TreeFlattenExt<T: Treeable> {
     fn keep_trees(self, filter_items: &[T::ID]) -> Self
     where
         Self: Sized;
+    /// Removes the trees of the given items at any depth, together with everything under them.
+    fn drop_trees(self, items: &HashSet<T::ID>) -> Self
+    where
+        Self: Sized;
 }
 
 impl<T: Treeable> TreeFlattenExt<T> for Vec<Tree<T>> {
@@ -275,6 +279,14 @@ impl<T: Treeable> TreeFlattenExt<T> for Vec<Tree<T>> {
         }
         tree
     }
+
+    fn drop_trees(mut self, items: &HashSet<T::ID>) -> Self {
+        self.retain(|t| !items.contains(&t.id()));
+        for t in self.iter_mut() {
+            t.subitems = std::mem::take(&mut t.subitems).drop_trees(items);
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -400,4 +412,30 @@ mod tests {
         assert_eq!(trees[0].subitems[0].subitems[0].item.id, "3");
         assert_eq!(trees[1].item.id, "4");
     }
+
+    #[test]
+    fn drop_trees() {
+        let tasks = vec![
+            Task::new("1", "one"),
+            Task {
+                parent_id: Some("1".to_string()),
+                ..Task::new("2", "two")
+            },
+            Task {
+                parent_id: Some("2".to_string()),
+                ..Task::new("3", "three")
+            },
+            Task {
+                parent_id: Some("1".to_string()),
+                ..Task::new("4", "four")
+            },
+            Task::new("5", "five"),
+        ];
+        let trees = Tree::from_items(tasks).unwrap();
+        let trees = trees.drop_trees(&HashSet::from(["2".to_string(), "5".to_string()]));
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees[0].item.id, "1");
+        assert_eq!(trees[0].subitems.len(), 1);
+        assert_eq!(trees[0].subitems[0].item.id, "4");
+    }
 }
@@ -0,0 +1,75 @@
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::Entry;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Shows only the given number of most recent entries.
+    #[arg(short = 'n', long = "limit", default_value_t = 20)]
+    limit: usize,
+    /// Shows only requests that were not accepted by the API.
+    #[arg(long = "failed")]
+    failed: bool,
+    /// Shows only entries where the command line or API path contains the given text.
+    #[arg(short = 'g', long = "grep")]
+    grep: Option<String>,
+}
+
+/// Lists the most recent changes the app made, oldest first.
+pub fn list(params: Params, cfg: &Config) -> Result<()> {
+    let entries = super::entries(&cfg.store()?)?;
+    let entries = entries
+        .iter()
+        .filter(|e| !params.failed || !e.succeeded())
+        .filter(|e| match &params.grep {
+            Some(text) => e.command.contains(text) || e.path.contains(text),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+    let skip = entries.len().saturating_sub(params.limit);
+    for entry in &entries[skip..] {
+        print_entry(entry);
+    }
+    Ok(())
+}
+
+fn print_entry(entry: &Entry) {
+    let status = entry
+        .status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "---".to_string());
+    let status = if entry.succeeded() {
+        format!(
+            "{}",
+            status.if_supports_color(Stream::Stdout, |text| text.bright_green())
+        )
+    } else {
+        format!(
+            "{}",
+            status.if_supports_color(Stream::Stdout, |text| text.bright_red())
+        )
+    };
+    println!(
+        "{} {} {} {}",
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        status,
+        entry.method,
+        entry.path
+    );
+    println!("    {}", entry.command);
+    if let Some(request) = &entry.request {
+        println!("    {}", request);
+    }
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.cfg)
+    }
+}
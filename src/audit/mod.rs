@@ -0,0 +1,80 @@
+//! Keeps an append-only local log of all changes the app makes to the Todoist account.
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{history, store::Store};
+
+pub mod list;
+
+/// The file in the [`Store`] that contains the audit log.
+pub(crate) const AUDIT_FILE: &str = "audit.jsonl";
+
+/// Entry describes a single request that tried to change something in the Todoist account.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    /// When the request was sent.
+    pub timestamp: DateTime<Utc>,
+    /// The command line of the invocation that sent the request, without secrets.
+    pub command: String,
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The API path of the request.
+    pub path: String,
+    /// The content that was sent with the request, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+    /// The HTTP status code of the response, or none if no response was received.
+    pub status: Option<u16>,
+}
+
+impl Entry {
+    /// Returns true if the API accepted the change.
+    pub fn succeeded(&self) -> bool {
+        self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
+
+/// AuditLog records all changes made by this invocation of the app.
+#[derive(Debug)]
+pub(crate) struct AuditLog {
+    store: Store,
+    command: String,
+}
+
+impl AuditLog {
+    /// Creates a log that writes into the store, attributing all entries to the current command
+    /// line.
+    pub fn new(store: Store) -> AuditLog {
+        AuditLog {
+            store,
+            command: history::invocation(),
+        }
+    }
+
+    /// Records a request. Failing to do so only warns, as the change was already made.
+    pub fn record(
+        &self,
+        method: &str,
+        path: &str,
+        request: Option<serde_json::Value>,
+        status: Option<u16>,
+    ) {
+        let entry = Entry {
+            timestamp: Utc::now(),
+            command: self.command.clone(),
+            method: method.to_string(),
+            path: path.to_string(),
+            request,
+            status,
+        };
+        if let Err(e) = self.store.append(AUDIT_FILE, &entry) {
+            eprintln!("unable to write audit log: {:#}", e);
+        }
+    }
+}
+
+/// Returns all entries that were recorded in the store, oldest first.
+pub(crate) fn entries(store: &Store) -> Result<Vec<Entry>> {
+    store.lines(AUDIT_FILE)
+}
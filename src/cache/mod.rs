@@ -0,0 +1,100 @@
+//! Keeps recent API responses on disk, so that commands can start without waiting for the API.
+//! Only used if a maximum age is set in the config, as cached data can be outdated.
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::store::Store;
+
+pub mod prewarm;
+
+/// The prefix of the files in the [`Store`] that hold cached responses.
+const PREFIX: &str = "cache-";
+/// The name of the cached tasks, which are kept by the filter they were fetched with.
+pub(crate) const TASKS: &str = "tasks";
+
+/// Entry is a cached response, together with when it was fetched.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry<T> {
+    fetched: DateTime<Utc>,
+    value: T,
+}
+
+/// Cache reads and writes cached responses by their name, like `projects`.
+#[derive(Debug)]
+pub(crate) struct Cache {
+    store: Store,
+    max_age: Duration,
+}
+
+impl Cache {
+    /// Creates a cache whose responses are used for the given time after they were fetched.
+    pub fn new(store: Store, max_age: Duration) -> Cache {
+        Cache { store, max_age }
+    }
+
+    /// Returns the cached value if it is recent enough. Unreadable caches are treated like missing
+    /// ones, as the value can always be fetched again.
+    pub fn get<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let entry: Option<Entry<T>> = self.store.load(&file(name)).ok()?;
+        entry.filter(|e| self.is_fresh(e.fetched)).map(|e| e.value)
+    }
+
+    /// Caches the value. Failing to do so only warns, as the value was fetched already.
+    pub fn put<T: Serialize>(&self, name: &str, value: &T) {
+        let entry = Entry {
+            fetched: Utc::now(),
+            value,
+        };
+        if let Err(e) = self.store.save(&file(name), &Some(entry)) {
+            eprintln!("unable to write cache: {:#}", e);
+        }
+    }
+
+    /// Returns the cached tasks of the filter, if they are recent enough.
+    pub fn tasks<T: DeserializeOwned>(&self, filter: &str) -> Option<T> {
+        let mut tasks: BTreeMap<String, Entry<T>> = self.store.load(&file(TASKS)).ok()?;
+        tasks
+            .remove(filter)
+            .filter(|e| self.is_fresh(e.fetched))
+            .map(|e| e.value)
+    }
+
+    /// Caches the tasks of the filter, next to the tasks of other filters that are still fresh.
+    pub fn put_tasks<T: Serialize>(&self, filter: &str, tasks: &T) {
+        let mut cached: BTreeMap<String, Entry<serde_json::Value>> =
+            self.store.load(&file(TASKS)).unwrap_or_default();
+        cached.retain(|_, e| self.is_fresh(e.fetched));
+        let result = serde_json::to_value(tasks)
+            .map_err(Into::into)
+            .and_then(|value| {
+                cached.insert(
+                    filter.to_string(),
+                    Entry {
+                        fetched: Utc::now(),
+                        value,
+                    },
+                );
+                self.store.save(&file(TASKS), &cached)
+            });
+        if let Err(e) = result {
+            eprintln!("unable to write cache: {:#}", e);
+        }
+    }
+
+    /// Forgets the cached value, so that it is fetched again the next time.
+    pub fn remove(&self, name: &str) {
+        if let Err(e) = self.store.remove(&file(name)) {
+            eprintln!("unable to clear cache: {:#}", e);
+        }
+    }
+
+    fn is_fresh(&self, fetched: DateTime<Utc>) -> bool {
+        Utc::now() - fetched < self.max_age
+    }
+}
+
+fn file(name: &str) -> String {
+    format!("{}{}.json", PREFIX, name)
+}
@@ -0,0 +1,66 @@
+use std::process::Stdio;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+/// The lists that are cached besides the tasks.
+const LISTS: [&str; 3] = ["projects", "sections", "labels"];
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Refreshes the cache before returning, instead of in a background process.
+    #[arg(long = "foreground")]
+    foreground: bool,
+}
+
+/// Refreshes the cache if any of it is stale. Meant to be run when a shell starts, so it returns
+/// right away and leaves the refresh to a background process.
+pub async fn prewarm(params: Params, ctx: &Context) -> Result<()> {
+    let cache = ctx.cfg.response_cache()?.ok_or_else(|| {
+        eyre!("the cache is off, set `max_age` in the `[cache]` section of the config")
+    })?;
+    let filter = &ctx.cfg.default_filter;
+    let fresh = LISTS
+        .iter()
+        .all(|name| cache.get::<serde_json::Value>(name).is_some())
+        && cache.tasks::<serde_json::Value>(filter).is_some();
+    if fresh {
+        return Ok(());
+    }
+    if !params.foreground {
+        return spawn(&ctx.cfg);
+    }
+    // Fetches what is missing from the cache, which caches it.
+    tokio::try_join!(ctx.gw.projects(), ctx.gw.sections(), ctx.gw.labels())?;
+    ctx.gw.tasks(Some(filter)).await?;
+    Ok(())
+}
+
+/// Runs the refresh in a process of its own, which keeps running after the shell went on.
+fn spawn(cfg: &Config) -> Result<()> {
+    let exe = std::env::current_exe().wrap_err("unable to find the doist executable")?;
+    let mut cmd = std::process::Command::new(exe);
+    if let Some(prefix) = &cfg.prefix {
+        cmd.arg(format!("--config_prefix={}", prefix.display()));
+    }
+    cmd.args(["prewarm", "--foreground"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .wrap_err("unable to start the refresh")?;
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        prewarm(self, ctx).await
+    }
+}
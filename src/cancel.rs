@@ -0,0 +1,118 @@
+//! Handles graceful cancellation of the app when the user presses Ctrl-C.
+//!
+//! The first Ctrl-C marks the app as cancelled, which fails the requests that are still in-flight
+//! and lets the running command stop by itself, so that it can save what it did so far. Long-running
+//! loops can check [`is_cancelled`] to stop cleanly between operations. Commands that don't stop
+//! within a short [`grace_period`] are stopped anyway, and a second Ctrl-C exits immediately.
+//!
+//! A [`deadline`] stops the command right away once it ran for too long, so that scripts can't
+//! hang on a slow network.
+use std::{
+    future::Future,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use color_eyre::{eyre::eyre, Result};
+use lazy_static::lazy_static;
+use tokio::sync::Notify;
+
+/// The exit code that is conventionally used when a process is terminated by SIGINT.
+pub const EXIT_CODE: i32 = 130;
+
+/// The exit code of a command that ran past its deadline, the same as `timeout` uses.
+pub const DEADLINE_EXIT_CODE: i32 = 124;
+
+/// How long a cancelled command gets to stop by itself, before it is stopped anyway.
+const GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref NOTIFY: Notify = Notify::new();
+}
+
+/// Installs the Ctrl-C handler. Needs to be called from within the tokio runtime.
+pub fn install() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if CANCELLED.swap(true, Ordering::SeqCst) {
+                restore_terminal();
+                std::process::exit(EXIT_CODE);
+            }
+            NOTIFY.notify_waiters();
+        }
+    });
+}
+
+/// Marks the app as cancelled, as if the user pressed Ctrl-C.
+pub fn cancel() {
+    CANCELLED.store(true, Ordering::SeqCst);
+    NOTIFY.notify_waiters();
+}
+
+/// Checks if an interactive prompt was interrupted by Ctrl-C, which puts the terminal in raw mode
+/// and hence does not raise a signal, and marks the app as cancelled if so.
+pub fn interrupted(err: dialoguer::Error) -> dialoguer::Error {
+    let dialoguer::Error::IO(io) = &err;
+    if io.kind() == std::io::ErrorKind::Interrupted {
+        cancel();
+    }
+    err
+}
+
+/// Returns true if the user requested to cancel the current operation.
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Returns an error if the user requested to cancel the current operation. Useful to bail out
+/// between steps of a bulk operation.
+pub fn check() -> Result<()> {
+    if is_cancelled() {
+        return Err(eyre!("operation was cancelled"));
+    }
+    Ok(())
+}
+
+/// Runs the future until it is done, or fails it like [`check`] once the user requested to cancel
+/// the current operation.
+pub async fn or_cancelled<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::select! {
+        result = future => result,
+        _ = cancelled() => Err(eyre!("operation was cancelled")),
+    }
+}
+
+/// Resolves once the user requested to cancel the current operation.
+pub async fn cancelled() {
+    let notified = NOTIFY.notified();
+    if is_cancelled() {
+        return;
+    }
+    notified.await
+}
+
+/// Resolves a [`GRACE_PERIOD`] after the user requested to cancel the current operation, for
+/// commands that did not stop by themselves, like ones that never wait for the API.
+pub async fn grace_period() {
+    cancelled().await;
+    tokio::time::sleep(GRACE_PERIOD).await;
+}
+
+/// Resolves once the deadline passed, or never if there is none.
+pub async fn deadline(after: Option<chrono::Duration>) {
+    match after {
+        Some(after) => tokio::time::sleep(after.to_std().unwrap_or_default()).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Restores the terminal into a usable state, as interactive prompts might have hidden the
+/// cursor before being interrupted.
+pub fn restore_terminal() {
+    let term = dialoguer::console::Term::stderr();
+    if term.is_term() {
+        let _ = term.show_cursor();
+    }
+}
@@ -0,0 +1,126 @@
+//! Draws the bars and timelines of charts with block characters, or with plain ASCII for
+//! terminals whose locale can't show them.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether charts are drawn with ASCII only, see [`install`].
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Glyphs are the characters that charts are drawn with.
+struct Glyphs {
+    /// The part of a bar that is filled.
+    filled: &'static str,
+    /// The part of a bar that is empty.
+    empty: &'static str,
+    /// The time a row of a timeline spans.
+    span: &'static str,
+    /// The start and end of a timeline.
+    edge: &'static str,
+    /// The characters of progress bars, as `indicatif` takes them.
+    #[cfg(feature = "importers")]
+    progress: &'static str,
+}
+
+const BLOCKS: Glyphs = Glyphs {
+    filled: "█",
+    empty: "░",
+    span: "━",
+    edge: "│",
+    #[cfg(feature = "importers")]
+    progress: "█░",
+};
+
+const PLAIN: Glyphs = Glyphs {
+    filled: "#",
+    empty: "-",
+    span: "=",
+    edge: "|",
+    #[cfg(feature = "importers")]
+    progress: "#-",
+};
+
+/// Draws all charts from now on with ASCII if asked to, or if the locale of the system isn't
+/// UTF-8.
+pub fn install(ascii: bool) {
+    ASCII.store(ascii || !utf8_locale(), Ordering::Relaxed);
+}
+
+/// Returns true if the locale of the system uses UTF-8. Without any locale, programs run in the
+/// `C` locale, which is ASCII. Windows terminals show UTF-8 regardless of the locale.
+fn utf8_locale() -> bool {
+    if cfg!(windows) {
+        return true;
+    }
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|locale| locale.to_lowercase())
+        .is_some_and(|locale| locale.contains("utf-8") || locale.contains("utf8"))
+}
+
+fn glyphs() -> &'static Glyphs {
+    match ASCII.load(Ordering::Relaxed) {
+        true => &PLAIN,
+        false => &BLOCKS,
+    }
+}
+
+/// Draws a bar of the given width with `filled` parts filled, like `[███░░]`.
+pub fn bar(filled: usize, width: usize) -> String {
+    draw_bar(glyphs(), filled, width)
+}
+
+fn draw_bar(glyphs: &Glyphs, filled: usize, width: usize) -> String {
+    let filled = filled.min(width);
+    format!(
+        "[{}{}]",
+        glyphs.filled.repeat(filled),
+        glyphs.empty.repeat(width - filled)
+    )
+}
+
+/// Draws a row of a timeline of the given width that spans the columns from `from` to `to`, like
+/// `│  ━━━ │`. Rows without a span are empty.
+pub fn span(columns: Option<(usize, usize)>, width: usize) -> String {
+    draw_span(glyphs(), columns, width)
+}
+
+fn draw_span(glyphs: &Glyphs, columns: Option<(usize, usize)>, width: usize) -> String {
+    let inner = match columns {
+        Some((from, to)) => {
+            let to = to.min(width - 1);
+            let from = from.min(to);
+            format!(
+                "{}{}{}",
+                " ".repeat(from),
+                glyphs.span.repeat(to - from + 1),
+                " ".repeat(width - 1 - to)
+            )
+        }
+        None => " ".repeat(width),
+    };
+    format!("{}{}{}", glyphs.edge, inner, glyphs.edge)
+}
+
+/// Returns the characters for the bars of `indicatif` progress bars.
+#[cfg(feature = "importers")]
+pub fn progress_chars() -> &'static str {
+    glyphs().progress
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn draws_with_either_glyphs() {
+        assert_eq!(draw_bar(&BLOCKS, 2, 5), "[██░░░]");
+        assert_eq!(draw_bar(&PLAIN, 2, 5), "[##---]");
+        assert_eq!(draw_bar(&PLAIN, 7, 5), "[#####]");
+        assert_eq!(draw_span(&BLOCKS, Some((1, 3)), 5), "│ ━━━ │");
+        assert_eq!(draw_span(&PLAIN, Some((1, 3)), 5), "| === |");
+        assert_eq!(draw_span(&PLAIN, None, 3), "|   |");
+        #[cfg(feature = "importers")]
+        assert!(PLAIN.progress.is_ascii());
+    }
+}
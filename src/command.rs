@@ -1,11 +1,29 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, process::ExitCode, sync::Arc};
 
+#[cfg(feature = "importers")]
+use crate::import;
+#[cfg(feature = "tray")]
+use crate::tray;
 use crate::{
+    api::{
+        content::{Pipeline, Sanitizer},
+        rest::Gateway,
+    },
+    audit, cache, cancel, chart,
     config::Config,
-    labels, projects, sections,
-    tasks::{add, close, comment, create, edit, list, view},
+    debug, digest, duration, events, failure, filters, goals, history, i18n, labels, notify,
+    operations::Journal,
+    output, projects, recurrence, report, sections,
+    tasks::{
+        add, agenda, balance, check, close, comment, comments, completed, create, defer, delete,
+        done, edit, inbox, list, merge, move_task, note, pick, pin, postpone, quick, reopen,
+        search, someday, split, today, undo, upcoming, view,
+    },
+    timer, usage, waiting,
 };
-use clap::{Args, Parser, Subcommand};
+#[cfg(feature = "integrations")]
+use crate::{export, homeassistant, hooks, obsidian, remote, serve, worklog};
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use color_eyre::Result;
 
 /// Args are the main entry point struct of the CLI app.
@@ -15,6 +33,32 @@ pub struct Arguments {
     /// Overrides the config directory location.
     #[arg(long = "config_prefix")]
     config_prefix: Option<PathBuf>,
+    /// Refuses to make any changes to the Todoist account. Can also be enabled with the
+    /// `read_only` option of the config.
+    #[arg(long = "read-only", global = true)]
+    read_only: bool,
+    /// Sanitizes displayed content with the given comma separated steps, in order. Overrides the
+    /// `sanitize` option of the config.
+    #[arg(long = "sanitize", value_delimiter = ',', global = true)]
+    sanitize: Vec<Sanitizer>,
+    /// Replaces the names of tasks, projects and everything else that is displayed with lorem
+    /// ipsum of the same length, for sharing screenshots. Same as adding `redact` to the
+    /// sanitizers.
+    #[arg(long = "redact", global = true)]
+    redact: bool,
+    /// Makes the output friendly to screen readers, with words instead of symbols and drawings,
+    /// numbered tasks and prompts that ask for numbers. Can also be enabled with the `a11y` option
+    /// of the config.
+    #[arg(long = "a11y", global = true)]
+    a11y: bool,
+    /// Draws charts and progress bars with plain ASCII instead of block characters. Is chosen
+    /// automatically if the locale of the system isn't UTF-8.
+    #[arg(long = "ascii", global = true)]
+    ascii: bool,
+    /// Stops the command with exit code 124 if it doesn't finish in time, like `10s` or `2m`.
+    /// Requests that are still waiting for the API are dropped.
+    #[arg(long = "deadline", value_parser = duration::parse, global = true)]
+    deadline: Option<chrono::Duration>,
 
     #[command(subcommand)]
     command: Option<Commands>,
@@ -23,6 +67,8 @@ pub struct Arguments {
 }
 
 /// All commands available for the CLI app.
+// Only parsed once at startup, so the size difference between variants does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Authenticates with the Todoist API.
@@ -32,6 +78,14 @@ enum Commands {
         /// Settings -> Integrations -> API token
         token: String,
     },
+    /// Collects details for bug reports, like the version and the config without secrets.
+    Debug(DebugArgs),
+    /// Lists the commands that were run, with the arguments that script interactive choices.
+    History(history::Params),
+    /// Runs a command from the history again.
+    Rerun(history::RerunParams),
+    /// Adds a private note to a task, which is only kept locally.
+    Note(note::Params),
     /// Authenticated commands are commands that require a token to be set up via the Auth command
     /// before executing.
     #[command(flatten)]
@@ -46,28 +100,143 @@ enum AuthCommands {
     /// Creates a task interactively.
     #[command(visible_alias = "A")]
     Create(create::Params),
+    /// Adds a task from one line that Todoist parses, like its quick add box.
+    #[command(visible_alias = "q")]
+    Quick(quick::Params),
     /// Lists tasks. This is the default if no subcommand is specified.
     #[command(visible_alias = "l")]
     List(list::Params),
+    /// Searches tasks for text in their content or description.
+    #[command(visible_alias = "s")]
+    Search(search::Params),
+    /// Shows the tasks that are due today or overdue, grouped by project.
+    Today(today::Params),
+    /// Shows the tasks of the coming days as an agenda, day by day.
+    Upcoming(upcoming::Params),
+    /// Lists the tasks of the Inbox, or triages them one by one.
+    Inbox(inbox::Params),
     /// Edits a task.
     #[command(visible_alias = "e")]
     Edit(edit::Params),
+    /// Postpones tasks, to tomorrow unless told otherwise.
+    Postpone(postpone::Params),
     /// Closes a task.
     #[command(visible_alias = "c")]
     Close(close::Params),
+    /// Closes a task, or ends the recurrence of a recurring one.
+    Done(done::Params),
+    /// Reopens a closed task.
+    Reopen(reopen::Params),
+    /// Reverses the last close, delete, edit or move.
+    Undo(undo::Params),
+    /// Lists the tasks that were completed recently, like for a review of the week.
+    Completed(completed::Params),
+    /// Deletes a task and its subtasks for good.
+    Delete(delete::Params),
+    /// Splits a task into smaller ones, written line by line in $EDITOR.
+    Split(split::Params),
+    /// Merges duplicate tasks into the first one and closes the others.
+    Merge(merge::Params),
+    /// Moves tasks to another project, section or parent task.
+    #[command(visible_alias = "m")]
+    Move(move_task::Params),
     /// View details of a single task.
     #[command(visible_alias = "v")]
     View(view::Params),
     /// Add a comment on a task.
     #[command(visible_alias = "C")]
     Comment(comment::Params),
+    /// Lists the comments on a task.
+    Comments(comments::Params),
+    /// Pins a task, so it is always shown at the top of task lists.
+    Pin(pin::Params),
+    /// Lists the pinned tasks.
+    Pins(pin::ListParams),
+    /// Hides a task from task lists until another task is done.
+    Defer(defer::Params),
+    /// Fails if too many tasks are due soon, for use in scripts.
+    Check(check::Params),
+    /// Prints the open tasks of a label as the agenda of a meeting.
+    Agenda(agenda::Params),
+    /// Lets a picker like fzf choose a task and prints it, for building shell bindings.
+    Pick(pick::Params),
+    /// Lists and reviews the backlog of tasks to do someday, maybe.
+    Someday(someday::Params),
+    /// Proposes to move tasks from overloaded days to lighter ones in the coming days.
+    Balance(balance::Params),
+    /// Works with the recurring due dates of tasks.
+    Recurrence(RecurrenceArgs),
 
     /// Manages projects.
     #[command(visible_alias = "p")]
     Projects(ProjectArgs),
     /// Manages labels.
-    #[command(visible_alias = "lbl")]
+    #[command(visible_aliases = ["lbl", "label"])]
     Labels(LabelArgs),
+    /// Manages saved filters.
+    #[command(visible_alias = "filter")]
+    Filters(FilterArgs),
+    /// Shows the progress of a project, treating its sections as milestones.
+    Milestones(projects::milestones::Params),
+
+    /// Imports projects, sections and tasks in bulk from a file.
+    #[cfg(feature = "importers")]
+    Import(ImportArgs),
+    /// Exports tasks to other services, like a Notion database.
+    #[cfg(feature = "integrations")]
+    Export(ExportArgs),
+    /// Creates made up data for trying things out, or scrubs data for sharing it.
+    #[cfg(feature = "importers")]
+    Demo(DemoArgs),
+    /// Exports projects as CSV templates in the format of Todoist, and imports them back.
+    #[cfg(feature = "importers")]
+    Template(TemplateArgs),
+    /// Shows the log of all changes that were made to the Todoist account.
+    Audit(audit::list::Params),
+    /// Shows how often commands and flags were used, from the local usage statistics.
+    Usage(usage::report::Params),
+    /// Refreshes the cache in the background if it is stale, for running when a shell starts.
+    Prewarm(cache::prewarm::Params),
+    /// Tracks the time spent on tasks.
+    Timer(TimerArgs),
+    /// Reports on the tracked time and the tasks.
+    Report(ReportArgs),
+    /// Tracks tasks that wait on someone else, and lists the ones that waited for too long.
+    Waiting(WaitingArgs),
+    /// Summarizes completed, added and upcoming tasks, like for a weekly review.
+    Digest(digest::Params),
+    /// Shows the progress and streaks of the goals in the config, like 5 tasks per week.
+    Goals(goals::Params),
+    /// Reminds of tasks that are due soon, following the notification rules of the config.
+    Notify(notify::Params),
+    /// Posts completed tasks to the webhooks of the config, like Slack or Discord.
+    #[cfg(feature = "integrations")]
+    Hooks(HooksArgs),
+    /// Shows who added, completed or updated what recently, like in shared projects.
+    Log(events::log::Params),
+    /// Exports the changes to the account as events, for automation platforms.
+    Events(EventsArgs),
+    /// Reports the tasks to Home Assistant.
+    #[cfg(feature = "integrations")]
+    #[command(name = "ha")]
+    HomeAssistant(HomeAssistantArgs),
+    /// Keeps the tasks of the day in the daily note of an Obsidian vault.
+    #[cfg(feature = "integrations")]
+    Obsidian(ObsidianArgs),
+    /// Serves an API for editor plugins on stdin and stdout, to list, add and toggle tasks.
+    #[cfg(feature = "integrations")]
+    Serve(serve::Params),
+    /// Shares local state like notes, pins, timers and templates with other machines, through the
+    /// sync location of the config.
+    #[cfg(feature = "integrations")]
+    Sync(remote::sync::Params),
+    /// Comments on the tasks that commits link to with a `Todoist: <id>` trailer.
+    #[cfg(feature = "integrations")]
+    Worklog(worklog::Params),
+    /// Shows the number of tasks due today in the system tray, with a menu to open them and to
+    /// quick add tasks.
+    #[cfg(feature = "tray")]
+    Tray(tray::Params),
 }
 
 #[derive(Args, Debug)]
@@ -90,12 +259,22 @@ enum ProjectCommands {
     /// Add a comment on a project.
     #[command(visible_alias = "C")]
     Comment(projects::comment::Params),
+    /// Lists the comments on a project.
+    Comments(projects::comments::Params),
     /// Adds (creates) a new project.
     #[command(visible_alias = "a")]
     Add(projects::add::Params),
     /// Deletes a project
     #[command(visible_alias = "d")]
     Delete(projects::delete::Params),
+    /// Renames a project.
+    Rename(projects::rename::Params),
+    /// Archives a project, hiding it together with its tasks.
+    Archive(projects::archive::Params),
+    /// Unarchives an archived project, see `projects list --archived`.
+    Unarchive(projects::archive::UnarchiveParams),
+    /// Lists the collaborators of a shared project, who tasks can be assigned to.
+    Collaborators(projects::collaborators::Params),
 
     /// Manages sections.
     #[command(visible_alias = "s")]
@@ -119,9 +298,41 @@ enum LabelCommands {
     /// Adds (creates) a new label.
     #[command(visible_alias = "a")]
     Add(labels::add::Params),
+    /// Renames a label or changes its color.
+    #[command(visible_alias = "u")]
+    Update(labels::update::Params),
     /// Deletes a label.
     #[command(visible_alias = "d")]
     Delete(labels::delete::Params),
+    /// Renames a shared label on every task that carries it.
+    RenameShared(labels::shared::RenameParams),
+    /// Removes a shared label from every task that carries it.
+    RemoveShared(labels::shared::RemoveParams),
+}
+
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct FilterArgs {
+    #[command(subcommand)]
+    command: Option<FilterCommands>,
+    #[command(flatten)]
+    params: filters::list::Params,
+}
+
+#[derive(Subcommand, Debug)]
+enum FilterCommands {
+    /// Lists the saved filters with their queries.
+    #[command(visible_alias = "l")]
+    List(filters::list::Params),
+    /// Saves a filter query under a name.
+    #[command(visible_alias = "a")]
+    Add(filters::add::Params),
+    /// Renames a saved filter or changes its query.
+    #[command(visible_alias = "e")]
+    Edit(filters::edit::Params),
+    /// Deletes a saved filter.
+    #[command(visible_alias = "d")]
+    Delete(filters::delete::Params),
 }
 
 #[derive(Args, Debug)]
@@ -144,76 +355,634 @@ enum SectionCommands {
     /// Deletes a section in a project.
     #[command(visible_alias = "d")]
     Delete(sections::delete::Params),
+    /// Renames a section.
+    Rename(sections::rename::Params),
+}
+
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct TimerArgs {
+    #[command(subcommand)]
+    command: Option<TimerCommands>,
+    #[command(flatten)]
+    params: timer::status::Params,
+}
+
+#[derive(Subcommand, Debug)]
+enum TimerCommands {
+    /// Starts a timer for a task, stopping the one that is running.
+    Start(timer::start::Params),
+    /// Stops the running timer.
+    Stop(timer::stop::Params),
+    /// Shows the running timer. This is the default view.
+    Status(timer::status::Params),
+    /// Exports the stopped timers as CSV.
+    Export(timer::export::Params),
+}
+
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct WaitingArgs {
+    #[command(subcommand)]
+    command: Option<WaitingCommands>,
+    #[command(flatten)]
+    params: waiting::list::Params,
+}
+
+#[derive(Subcommand, Debug)]
+enum WaitingCommands {
+    /// Lists the tasks that waited for longer than their nag interval. This is the default view.
+    #[command(visible_alias = "l")]
+    List(waiting::list::Params),
+    /// Marks a task as waiting on someone else.
+    #[command(visible_alias = "a")]
+    Add(waiting::add::Params),
+    /// Stops waiting on a task.
+    Done(waiting::done::Params),
+}
+
+#[derive(Args, Debug)]
+struct ReportArgs {
+    #[command(subcommand)]
+    command: ReportCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum ReportCommands {
+    /// Compares the estimates of tasks with the time tracked on them, per project or label.
+    Accuracy(report::accuracy::Params),
+}
+
+#[derive(Args, Debug)]
+struct RecurrenceArgs {
+    #[command(subcommand)]
+    command: RecurrenceCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum RecurrenceCommands {
+    /// Edits the recurrence of a task, previewing its next occurrences before submitting it.
+    Fix(recurrence::fix::Params),
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Args, Debug)]
+struct HooksArgs {
+    #[command(subcommand)]
+    command: HooksCommands,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Subcommand, Debug)]
+enum HooksCommands {
+    /// Posts the tasks that were completed since the last run, once or as a daemon.
+    Run(hooks::run::Params),
+}
+
+#[derive(Args, Debug)]
+struct EventsArgs {
+    #[command(subcommand)]
+    command: EventsCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum EventsCommands {
+    /// Prints the events of the activity log in a stable format, like for IFTTT or Zapier.
+    Export(events::export::Params),
+}
+
+#[cfg(feature = "importers")]
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct ImportArgs {
+    #[command(subcommand)]
+    command: Option<ImportCommands>,
+    #[command(flatten)]
+    params: import::Params,
+}
+
+#[cfg(feature = "importers")]
+#[derive(Subcommand, Debug)]
+enum ImportCommands {
+    /// Imports the lists of Google Tasks as projects.
+    Gtasks(import::gtasks::Params),
+    /// Imports the lists of Apple Reminders as projects, on macOS.
+    #[cfg(feature = "reminders")]
+    Reminders(import::reminders::Params),
+    /// Imports the lists of a Remember The Milk export as projects.
+    Rtm(import::rtm::Params),
+    /// Imports the lists of a Wunderlist export as projects.
+    Wunderlist(import::wunderlist::Params),
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Args, Debug)]
+struct ExportArgs {
+    #[command(subcommand)]
+    command: ExportCommands,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Subcommand, Debug)]
+enum ExportCommands {
+    /// Adds the tasks as pages to a Notion database, or updates the pages of earlier exports.
+    Notion(export::notion::Params),
+}
+
+#[derive(Args, Debug)]
+struct DebugArgs {
+    #[command(subcommand)]
+    command: DebugCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum DebugCommands {
+    /// Writes a zip file to attach to bug reports, with the version, the config without secrets
+    /// and the redacted audit log.
+    Bundle(debug::Params),
+}
+
+#[cfg(feature = "importers")]
+#[derive(Args, Debug)]
+struct TemplateArgs {
+    #[command(subcommand)]
+    command: TemplateCommands,
+}
+
+#[cfg(feature = "importers")]
+#[derive(Subcommand, Debug)]
+enum TemplateCommands {
+    /// Writes the sections, tasks and comments of a project as a CSV template.
+    Export(import::template::ExportParams),
+    /// Adds the sections, tasks and comments of a CSV template to a project.
+    Import(import::template::ImportParams),
+}
+
+#[cfg(feature = "importers")]
+#[derive(Args, Debug)]
+struct DemoArgs {
+    #[command(subcommand)]
+    command: DemoCommands,
+}
+
+#[cfg(feature = "importers")]
+#[derive(Subcommand, Debug)]
+enum DemoCommands {
+    /// Creates realistic, but made up projects, sections, labels and tasks, like in a test account.
+    Seed(import::seed::Params),
+    /// Replaces all names and texts of an import file, so it can be shared in bug reports.
+    Anonymize(import::anonymize::Params),
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Args, Debug)]
+struct HomeAssistantArgs {
+    #[command(subcommand)]
+    command: HomeAssistantCommands,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Subcommand, Debug)]
+enum HomeAssistantCommands {
+    /// Prints how many tasks are due and which is next as JSON for a sensor, or publishes it to
+    /// MQTT.
+    Sensor(homeassistant::sensor::Params),
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Args, Debug)]
+struct ObsidianArgs {
+    #[command(subcommand)]
+    command: ObsidianCommands,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Subcommand, Debug)]
+enum ObsidianCommands {
+    /// Closes the tasks that were checked off in the daily note, and writes the open tasks of the
+    /// day into it.
+    Sync(obsidian::sync::Params),
+}
+
+/// Context holds everything that a [`Command`] needs to execute.
+pub(crate) struct Context {
+    /// The gateway to use for all API calls.
+    pub gw: Gateway,
+    /// The loaded configuration of the app.
+    pub cfg: Config,
+}
+
+impl Context {
+    /// Initializes a new context with a gateway based on the given configuration, which notes
+    /// in the journal when the operation of the command modifies data.
+    pub fn new(cfg: Config, journal: Arc<Journal>) -> Result<Context> {
+        Ok(Context {
+            gw: cfg.gateway()?.journal(journal),
+            cfg,
+        })
+    }
+}
+
+/// Command is implemented by all subcommands that need an authenticated gateway to execute.
+///
+/// After clap parses the arguments, a command is first [`Command::validate`]d and then
+/// [`Command::execute`]d, which is what [`Command::run`] does. This allows cross-cutting
+/// behaviour to be added in one place instead of in every single subcommand.
+pub(crate) trait Command: Sized {
+    /// Checks that the given parameters make sense before any API calls are made.
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Executes the command and renders its output.
+    async fn execute(self, ctx: &Context) -> Result<()>;
+
+    /// Validates and executes the command.
+    async fn run(self, ctx: &Context) -> Result<()> {
+        self.validate()?;
+        self.execute(ctx).await
+    }
+}
+
+impl Command for AuthCommands {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self {
+            AuthCommands::Add(p) => p.run(ctx).await,
+            AuthCommands::Create(p) => p.run(ctx).await,
+            AuthCommands::Quick(p) => p.run(ctx).await,
+            AuthCommands::List(p) => p.run(ctx).await,
+            AuthCommands::Search(p) => p.run(ctx).await,
+            AuthCommands::Today(p) => p.run(ctx).await,
+            AuthCommands::Upcoming(p) => p.run(ctx).await,
+            AuthCommands::Inbox(p) => p.run(ctx).await,
+            AuthCommands::Edit(p) => p.run(ctx).await,
+            AuthCommands::Postpone(p) => p.run(ctx).await,
+            AuthCommands::Close(p) => p.run(ctx).await,
+            AuthCommands::Done(p) => p.run(ctx).await,
+            AuthCommands::Reopen(p) => p.run(ctx).await,
+            AuthCommands::Undo(p) => p.run(ctx).await,
+            AuthCommands::Completed(p) => p.run(ctx).await,
+            AuthCommands::Delete(p) => p.run(ctx).await,
+            AuthCommands::Split(p) => p.run(ctx).await,
+            AuthCommands::Merge(p) => p.run(ctx).await,
+            AuthCommands::Move(p) => p.run(ctx).await,
+            AuthCommands::View(p) => p.run(ctx).await,
+            AuthCommands::Comment(p) => p.run(ctx).await,
+            AuthCommands::Comments(p) => p.run(ctx).await,
+            AuthCommands::Pin(p) => p.run(ctx).await,
+            AuthCommands::Pins(p) => p.run(ctx).await,
+            AuthCommands::Defer(p) => p.run(ctx).await,
+            AuthCommands::Check(p) => p.run(ctx).await,
+            AuthCommands::Agenda(p) => p.run(ctx).await,
+            AuthCommands::Pick(p) => p.run(ctx).await,
+            AuthCommands::Someday(p) => p.run(ctx).await,
+            AuthCommands::Balance(p) => p.run(ctx).await,
+            AuthCommands::Recurrence(p) => p.run(ctx).await,
+            AuthCommands::Projects(p) => p.run(ctx).await,
+            AuthCommands::Labels(p) => p.run(ctx).await,
+            AuthCommands::Filters(p) => p.run(ctx).await,
+            AuthCommands::Milestones(p) => p.run(ctx).await,
+            #[cfg(feature = "importers")]
+            AuthCommands::Import(p) => p.run(ctx).await,
+            #[cfg(feature = "integrations")]
+            AuthCommands::Export(p) => p.run(ctx).await,
+            #[cfg(feature = "importers")]
+            AuthCommands::Demo(p) => p.run(ctx).await,
+            #[cfg(feature = "importers")]
+            AuthCommands::Template(p) => p.run(ctx).await,
+            AuthCommands::Audit(p) => p.run(ctx).await,
+            AuthCommands::Usage(p) => p.run(ctx).await,
+            AuthCommands::Prewarm(p) => p.run(ctx).await,
+            AuthCommands::Timer(p) => p.run(ctx).await,
+            AuthCommands::Report(p) => p.run(ctx).await,
+            AuthCommands::Waiting(p) => p.run(ctx).await,
+            AuthCommands::Digest(p) => p.run(ctx).await,
+            AuthCommands::Goals(p) => p.run(ctx).await,
+            AuthCommands::Notify(p) => p.run(ctx).await,
+            #[cfg(feature = "integrations")]
+            AuthCommands::Hooks(p) => p.run(ctx).await,
+            AuthCommands::Log(p) => p.run(ctx).await,
+            AuthCommands::Events(p) => p.run(ctx).await,
+            #[cfg(feature = "integrations")]
+            AuthCommands::HomeAssistant(p) => p.run(ctx).await,
+            #[cfg(feature = "integrations")]
+            AuthCommands::Obsidian(p) => p.run(ctx).await,
+            #[cfg(feature = "integrations")]
+            AuthCommands::Serve(p) => p.run(ctx).await,
+            #[cfg(feature = "integrations")]
+            AuthCommands::Sync(p) => p.run(ctx).await,
+            #[cfg(feature = "integrations")]
+            AuthCommands::Worklog(p) => p.run(ctx).await,
+            #[cfg(feature = "tray")]
+            AuthCommands::Tray(p) => p.run(ctx).await,
+        }
+    }
+}
+
+impl Command for ProjectArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            Some(ProjectCommands::List(p)) => p.run(ctx).await,
+            Some(ProjectCommands::View(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Comment(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Comments(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Add(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Delete(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Rename(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Archive(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Unarchive(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Collaborators(p)) => p.run(ctx).await,
+            Some(ProjectCommands::Sections(p)) => p.run(ctx).await,
+            None => self.params.run(ctx).await,
+        }
+    }
+}
+
+impl Command for LabelArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            Some(LabelCommands::List(p)) => p.run(ctx).await,
+            Some(LabelCommands::Add(p)) => p.run(ctx).await,
+            Some(LabelCommands::Update(p)) => p.run(ctx).await,
+            Some(LabelCommands::Delete(p)) => p.run(ctx).await,
+            Some(LabelCommands::RenameShared(p)) => p.run(ctx).await,
+            Some(LabelCommands::RemoveShared(p)) => p.run(ctx).await,
+            None => self.params.run(ctx).await,
+        }
+    }
+}
+
+impl Command for FilterArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            Some(FilterCommands::List(p)) => p.run(ctx).await,
+            Some(FilterCommands::Add(p)) => p.run(ctx).await,
+            Some(FilterCommands::Edit(p)) => p.run(ctx).await,
+            Some(FilterCommands::Delete(p)) => p.run(ctx).await,
+            None => self.params.run(ctx).await,
+        }
+    }
+}
+
+impl Command for SectionArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            Some(SectionCommands::List(p)) => p.run(ctx).await,
+            Some(SectionCommands::Add(p)) => p.run(ctx).await,
+            Some(SectionCommands::Delete(p)) => p.run(ctx).await,
+            Some(SectionCommands::Rename(p)) => p.run(ctx).await,
+            None => self.params.run(ctx).await,
+        }
+    }
+}
+
+impl Command for TimerArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            Some(TimerCommands::Start(p)) => p.run(ctx).await,
+            Some(TimerCommands::Stop(p)) => p.run(ctx).await,
+            Some(TimerCommands::Status(p)) => p.run(ctx).await,
+            Some(TimerCommands::Export(p)) => p.run(ctx).await,
+            None => self.params.run(ctx).await,
+        }
+    }
+}
+
+impl Command for WaitingArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            Some(WaitingCommands::List(p)) => p.run(ctx).await,
+            Some(WaitingCommands::Add(p)) => p.run(ctx).await,
+            Some(WaitingCommands::Done(p)) => p.run(ctx).await,
+            None => self.params.run(ctx).await,
+        }
+    }
+}
+
+impl Command for ReportArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            ReportCommands::Accuracy(p) => p.run(ctx).await,
+        }
+    }
+}
+
+impl Command for RecurrenceArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            RecurrenceCommands::Fix(p) => p.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(feature = "integrations")]
+impl Command for HooksArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            HooksCommands::Run(p) => p.run(ctx).await,
+        }
+    }
+}
+
+impl Command for EventsArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            EventsCommands::Export(p) => p.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(feature = "importers")]
+impl Command for ImportArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            Some(ImportCommands::Gtasks(p)) => p.run(ctx).await,
+            #[cfg(feature = "reminders")]
+            Some(ImportCommands::Reminders(p)) => p.run(ctx).await,
+            Some(ImportCommands::Rtm(p)) => p.run(ctx).await,
+            Some(ImportCommands::Wunderlist(p)) => p.run(ctx).await,
+            None => self.params.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(feature = "importers")]
+impl Command for DemoArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            DemoCommands::Seed(p) => p.run(ctx).await,
+            DemoCommands::Anonymize(p) => p.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(feature = "importers")]
+impl Command for TemplateArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            TemplateCommands::Export(p) => p.run(ctx).await,
+            TemplateCommands::Import(p) => p.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(feature = "integrations")]
+impl Command for ExportArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            ExportCommands::Notion(p) => p.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(feature = "integrations")]
+impl Command for HomeAssistantArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            HomeAssistantCommands::Sensor(p) => p.run(ctx).await,
+        }
+    }
+}
+
+#[cfg(feature = "integrations")]
+impl Command for ObsidianArgs {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        match self.command {
+            ObsidianCommands::Sync(p) => p.run(ctx).await,
+        }
+    }
 }
 
 impl Arguments {
     /// Runs the CLI app.
     pub async fn exec(self) -> Result<()> {
-        let mut cfg = match self.config_prefix {
-            Some(p) => Config::load_prefix(&p),
+        self.exec_args(std::env::args().skip(1).collect()).await
+    }
+
+    /// Runs the CLI app and shows the error it may end with, returning the exit code of the
+    /// process.
+    pub async fn run(self) -> ExitCode {
+        let prefix = self.config_prefix.clone();
+        match self.exec().await {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => failure::report(error, prefix.as_deref()).await,
+        }
+    }
+
+    /// Executes the parsed arguments, which are the given command line.
+    async fn exec_args(self, args: Vec<String>) -> Result<()> {
+        let cfg = match self.config_prefix {
+            Some(ref p) => Config::load_prefix(p),
             None => Config::load(),
         }?;
-        match self.command {
-            Some(command) => match command {
-                Commands::Auth { token } => {
-                    cfg.token = Some(token);
-                    cfg.save()?;
-                    println!("Token successfully saved")
-                }
-                Commands::Authenticated(command) => {
-                    let gw = cfg.gateway()?;
-                    match command {
-                        AuthCommands::Add(p) => add::add(p, &gw, &cfg).await?,
-                        AuthCommands::Create(p) => create::create(p, &gw, &cfg).await?,
-                        AuthCommands::List(p) => list::list(p, &gw, &cfg).await?,
-                        AuthCommands::Edit(p) => edit::edit(p, &gw, &cfg).await?,
-                        AuthCommands::Close(p) => close::close(p, &gw, &cfg).await?,
-                        AuthCommands::View(p) => view::view(p, &gw, &cfg).await?,
-                        AuthCommands::Comment(p) => comment::comment(p, &gw, &cfg).await?,
-                        AuthCommands::Projects(p) => match p.command {
-                            Some(p) => match p {
-                                ProjectCommands::List(p) => projects::list::list(p, &gw).await?,
-                                ProjectCommands::View(p) => projects::view::view(p, &gw).await?,
-                                ProjectCommands::Comment(p) => {
-                                    projects::comment::comment(p, &gw).await?
-                                }
-                                ProjectCommands::Add(p) => projects::add::add(p, &gw).await?,
-                                ProjectCommands::Delete(p) => {
-                                    projects::delete::delete(p, &gw).await?
-                                }
-                                ProjectCommands::Sections(s) => match s.command {
-                                    Some(s) => match s {
-                                        SectionCommands::List(p) => {
-                                            sections::list::list(p, &gw).await?
-                                        }
-                                        SectionCommands::Add(p) => {
-                                            sections::add::add(p, &gw).await?
-                                        }
-                                        SectionCommands::Delete(p) => {
-                                            sections::delete::delete(p, &gw).await?
-                                        }
-                                    },
-                                    None => sections::list::list(s.params, &gw).await?,
-                                },
-                            },
-                            None => projects::list::list(p.params, &gw).await?,
-                        },
-                        AuthCommands::Labels(p) => match p.command {
-                            Some(p) => match p {
-                                LabelCommands::List(p) => labels::list::list(p, &gw).await?,
-                                LabelCommands::Add(p) => labels::add::add(p, &gw).await?,
-                                LabelCommands::Delete(p) => labels::delete::delete(p, &gw).await?,
-                            },
-                            None => labels::list::list(p.params, &gw).await?,
-                        },
-                    }
+        if let Some(Commands::Rerun(params)) = &self.command {
+            let args = history::rerun(params, &cfg)?;
+            let mut rerun = Arguments::try_parse_from(
+                std::iter::once("doist".to_string()).chain(args.iter().cloned()),
+            )?;
+            rerun.config_prefix = rerun.config_prefix.or(self.config_prefix);
+            return Box::pin(rerun.exec_args(args)).await;
+        }
+        let mut sanitizers = if self.sanitize.is_empty() {
+            cfg.sanitize.clone()
+        } else {
+            self.sanitize.clone()
+        };
+        if self.redact && !sanitizers.contains(&Sanitizer::Redact) {
+            sanitizers.push(Sanitizer::Redact);
+        }
+        Pipeline::new(sanitizers).install();
+        i18n::install(cfg.language.as_deref());
+        if self.a11y || cfg.a11y {
+            output::install_a11y();
+        }
+        chart::install(self.ascii);
+        if cfg.crash_hint {
+            debug::install_crash_hint();
+        }
+        let usage = match cfg.usage_stats {
+            true => {
+                usage::install();
+                Some(cfg.store()?)
+            }
+            false => None,
+        };
+        // Neither the token nor looking at the history itself are recorded.
+        let history = match (&self.command, cfg.history) {
+            (Some(Commands::Auth { .. } | Commands::History(_)), _) | (_, false) => None,
+            (_, true) => Some(cfg.store()?),
+        };
+        cancel::install();
+        let deadline = self.deadline;
+        // Cancelling lets the command stop by itself, so that it can save what it did so far.
+        let result = tokio::select! {
+            result = self.dispatch(cfg, &args) => result,
+            // Commands that don't stop by themselves are dropped, and reported as cancelled below.
+            _ = cancel::grace_period() => Ok(()),
+            _ = cancel::deadline(deadline) => {
+                cancel::restore_terminal();
+                eprintln!(
+                    "the command did not finish within its deadline of {}",
+                    duration::format(deadline.unwrap_or_default())
+                );
+                std::process::exit(cancel::DEADLINE_EXIT_CODE);
+            }
+        };
+        if cancel::is_cancelled() {
+            cancel::restore_terminal();
+            // Tells how far commands got that stopped part way, like imports.
+            if let Err(e) = &result {
+                if e.chain().count() > 1 {
+                    eprintln!("{}", e);
                 }
-            },
-            None => {
-                list::list(self.params, &cfg.gateway()?, &cfg).await?;
             }
+            eprintln!("cancelled");
+            std::process::exit(cancel::EXIT_CODE);
         }
-        Ok(())
+        if let Some(store) = usage {
+            usage::save(&store, &Self::command(), &args);
+        }
+        if let Some(store) = history {
+            history::save(&store, &args);
+        }
+        result
+    }
+
+    async fn dispatch(self, mut cfg: Config, args: &[String]) -> Result<()> {
+        let command = match self.command {
+            Some(Commands::Auth { token }) => {
+                cfg.token = Some(token);
+                cfg.save()?;
+                println!("Token successfully saved");
+                return Ok(());
+            }
+            // Works without a token, as it may be needed to report problems with it.
+            Some(Commands::Debug(DebugArgs {
+                command: DebugCommands::Bundle(params),
+            })) => return debug::bundle(params, &cfg),
+            Some(Commands::History(params)) => return history::history(params, &cfg),
+            // Notes are only kept locally, so they work without a token too.
+            Some(Commands::Note(params)) => return note::note(params, &cfg),
+            Some(Commands::Rerun(_)) => unreachable!("reruns are resolved before dispatching"),
+            Some(Commands::Authenticated(command)) => Some(command),
+            None => None,
+        };
+        // Only applied here, so that the flag never ends up being saved into the config.
+        cfg.read_only |= self.read_only;
+        // Running the same command line again continues its operation if it did not finish.
+        let journal = Arc::new(Journal::open(cfg.store()?, args));
+        let ctx = Context::new(cfg, journal.clone())?;
+        let result = Gateway::operation(journal.key(), async {
+            match command {
+                Some(command) => command.run(&ctx).await,
+                None => self.params.run(&ctx).await,
+            }
+        })
+        .await;
+        if result.is_ok() {
+            journal.finish();
+        }
+        result
     }
 }
 #[cfg(test)]
@@ -1,9 +1,13 @@
+use std::fmt::Write;
+
 use crate::api::rest::{Comment, FullComment};
 
-pub fn list(comments: &[Comment]) {
-    println!("Comments:");
+/// Renders the comments as a list, to be displayed after the entity they belong to.
+pub fn list(comments: &[Comment]) -> String {
+    let mut out = String::from("Comments:");
     for comment in comments {
-        println!("-----");
-        println!("{}", FullComment(comment));
+        // Writing into a String can not fail.
+        let _ = write!(out, "\n-----\n{}", FullComment(comment));
     }
+    out
 }
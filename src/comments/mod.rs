@@ -2,4 +2,25 @@
 //! have comments associated with them.
 mod list;
 
+use std::path::Path;
+
+use color_eyre::{eyre::WrapErr, Result};
+
+use crate::api::rest::{Attachment, Gateway, Upload};
+
 pub use list::list;
+
+/// Uploads the file to attach to a comment, if one was given.
+pub async fn attach(file: Option<&Path>, gw: &Gateway) -> Result<Option<Attachment>> {
+    let Some(file) = file else {
+        return Ok(None);
+    };
+    let content =
+        std::fs::read(file).wrap_err_with(|| format!("unable to read {}", file.display()))?;
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "attachment".to_string());
+    let attachment = gw.upload(&Upload { file_name, content }).await?;
+    Ok(Some(attachment))
+}
@@ -1,5 +1,6 @@
 //! Describes everything related to configuration of the binary.
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -9,7 +10,18 @@ use color_eyre::{eyre::eyre, Result};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::api::rest::{Gateway, TODOIST_API_URL};
+#[cfg(feature = "sqlite")]
+use crate::store::Sqlite;
+use crate::{
+    api::{
+        content::Sanitizer,
+        rest::{Gateway, Retry, TODOIST_API_URL},
+    },
+    audit::AuditLog,
+    cache::Cache,
+    duration,
+    store::{JsonFiles, Store},
+};
 
 /// Stores configuration used by the application.
 #[derive(Serialize, Deserialize, Default)]
@@ -28,12 +40,397 @@ pub struct Config {
     /// Override the current time for various display options in the CLI.
     #[serde(default)]
     pub override_time: Option<DateTime<Utc>>,
+    /// Refuses all changes to the Todoist account, so it can be explored safely.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Sanitizers that are applied in order to all displayed content, like task names.
+    #[serde(default)]
+    pub sanitize: Vec<Sanitizer>,
+    /// Suggests labels for new tasks based on their content, using the `label_rules` and the
+    /// labels of tasks that were added before.
+    #[serde(default)]
+    pub suggest_labels: bool,
+    /// Keywords that suggest a label when they appear in the content of a new task.
+    #[serde(default)]
+    pub label_rules: Vec<LabelRule>,
+    /// Templates for new tasks by their name, which can be used with `add --template`.
+    #[serde(default)]
+    pub templates: BTreeMap<String, Template>,
+    /// Projects by the name of a git repository, for tasks that are added with `add --here`.
+    #[serde(default)]
+    pub repos: BTreeMap<String, String>,
+    /// Goals of how many tasks to complete, whose progress is shown by `goals`.
+    #[serde(default)]
+    pub goals: Vec<Goal>,
+    /// Holidays that are skipped when scheduling in business days.
+    #[serde(default)]
+    pub holidays: Holidays,
+    /// How many hours of work fit in a day, which `today` and `upcoming` compare the estimates of
+    /// the tasks of each day with.
+    #[serde(default)]
+    pub work_hours: Option<f64>,
+    /// When and how `notify` reminds of tasks that are due soon.
+    #[serde(default)]
+    pub notifications: Notifications,
+    /// Webhooks that `hooks run` posts completed tasks to.
+    #[serde(default)]
+    pub hooks: Vec<Hook>,
+    /// The command that `pick` runs to choose a task, like `["fzf", "--delimiter=\t"]`. It gets
+    /// the candidates on its input and prints the chosen one. Defaults to fzf.
+    #[serde(default)]
+    pub picker: Vec<String>,
+    /// The Notion integration that `export notion` exports tasks with.
+    #[serde(default)]
+    pub notion: Notion,
+    /// Points to `debug bundle` when doist crashes, so the crash can be reported with the details
+    /// that are needed to look into it.
+    #[serde(default)]
+    pub crash_hint: bool,
+    /// Keeps local statistics of which commands and flags are used and how long API calls take,
+    /// which `usage` shows. They never leave the machine.
+    #[serde(default)]
+    pub usage_stats: bool,
+    /// Keeps a history of the commands that are run, which `history` lists and `rerun` runs again.
+    /// It never leaves the machine.
+    #[serde(default)]
+    pub history: bool,
+    /// How often and how long requests that failed for a moment are tried again.
+    #[serde(default)]
+    pub retry: Retries,
+    /// How long projects, sections, labels and tasks are read from disk before they are fetched
+    /// again, which `prewarm` keeps fresh.
+    #[serde(default)]
+    pub cache: Caching,
+    /// Where local state like the audit log, notes and the cache is kept.
+    #[serde(default)]
+    pub storage: StorageBackend,
+    /// The location that `sync` shares local state like notes and pins through, so it follows
+    /// along to other machines.
+    #[serde(default)]
+    pub sync: Option<SyncRemote>,
+    /// How tasks are displayed, like marking the ones that linger for too long.
+    #[serde(default)]
+    pub theme: Theme,
+    /// The language of messages and of due dates that are typed in, like `de`. Messages follow
+    /// `LC_ALL`, `LC_MESSAGES` or `LANG` if it is not set, and due dates are typed in English.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Makes the output friendly to screen readers, like `--a11y`.
+    #[serde(default)]
+    pub a11y: bool,
 
     /// Sets a particular config location prefix. Mostly used for testing.
     #[serde(skip)]
     pub prefix: Option<PathBuf>,
 }
 
+/// LabelRule suggests a label for new tasks that contain a keyword.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LabelRule {
+    /// The word or phrase to look for in the content of the task, ignoring case.
+    pub keyword: String,
+    /// The name of the label to suggest.
+    pub label: String,
+}
+
+/// Template describes the content and description of new tasks. Both can contain placeholders
+/// like `{{date}}`, which are filled in when the task is added.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Template {
+    /// The content of the task. Uses the name given on the command line if not set, which is also
+    /// available as the `{{name}}` placeholder.
+    #[serde(default)]
+    pub content: Option<String>,
+    /// The description of the task.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Goal is a number of tasks to complete in every period, like 5 tasks with a label per week.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Goal {
+    /// The name that is shown for the goal.
+    pub name: String,
+    /// Only counts tasks with this label.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Only counts tasks in the project with this name.
+    #[serde(default)]
+    pub project: Option<String>,
+    /// How many tasks to complete in every period.
+    pub count: usize,
+    /// The period in which the tasks should be completed.
+    #[serde(default)]
+    pub period: Period,
+}
+
+/// Period is a repeating span of time of a [`Goal`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    /// From midnight to midnight.
+    Day,
+    /// From Monday to Sunday.
+    #[default]
+    Week,
+    /// From the first to the last day of a month.
+    Month,
+}
+
+/// Holidays are where the days off come from, which are skipped when scheduling due dates.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Holidays {
+    /// The two letter code of the country whose public holidays are used, like `DE`.
+    #[serde(default)]
+    pub country: Option<String>,
+    /// The URL or file path of an ICS calendar whose events are holidays.
+    #[serde(default)]
+    pub ics: Option<String>,
+}
+
+/// Notifications configure the reminders of `notify` for tasks with an exact due time.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notifications {
+    /// How long before a task is due it is notified about, like `15m`. Without it, tasks are
+    /// notified about when they are due.
+    #[serde(default)]
+    pub before: Option<String>,
+    /// The command that is run for every notification, with its text added as last argument,
+    /// like `["notify-send", "doist"]`. Without it, notifications are printed.
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// Rules for tasks of certain labels or projects. The first rule that matches a task is used.
+    #[serde(default)]
+    pub rules: Vec<NotificationRule>,
+    /// Times of the day without notifications, like `22:00-07:00`. Notifications are held back
+    /// and delivered once the quiet hours end.
+    #[serde(default)]
+    pub quiet_hours: Vec<String>,
+}
+
+/// NotificationRule changes the notifications of the tasks that match all of its criteria.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotificationRule {
+    /// Matches tasks with this label.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Matches tasks in the project with this name.
+    #[serde(default)]
+    pub project: Option<String>,
+    /// How long before the task is due it is notified about, instead of the default.
+    #[serde(default)]
+    pub before: Option<String>,
+    /// Never notifies about the task.
+    #[serde(default)]
+    pub never: bool,
+}
+
+/// Hook posts a message to a webhook for every completed task that matches all of its criteria.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hook {
+    /// The URL of the webhook.
+    pub url: String,
+    /// The service behind the webhook, which decides the shape of the message.
+    #[serde(default)]
+    pub kind: HookKind,
+    /// Matches tasks with this label.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Matches tasks in the project with this name.
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+/// HookKind is the service behind a [`Hook`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookKind {
+    /// A Slack incoming webhook.
+    Slack,
+    /// A Discord channel webhook.
+    Discord,
+    /// Any other endpoint, which gets the completed task as JSON.
+    #[default]
+    Generic,
+}
+
+/// Notion is the integration that tasks are exported to a Notion database with.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notion {
+    /// The secret of an internal Notion integration that the database is shared with.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Which properties of the database the fields of a task are written to.
+    #[serde(default)]
+    pub properties: NotionProperties,
+}
+
+/// Caching keeps recent API responses on disk, so that commands start without waiting for the API.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Caching {
+    /// How long cached responses are used, like `10m`. Without it, nothing is cached.
+    #[serde(default)]
+    pub max_age: Option<String>,
+}
+
+/// Retries configures how requests are tried again after network errors, rate limits and errors
+/// of the servers of Todoist. Unset values keep their defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Retries {
+    /// How often a request is tried again, 3 unless set. 0 turns retries off.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// The wait before the first retry like `2s`, which doubles with every retry after it.
+    #[serde(default)]
+    pub min_delay: Option<String>,
+    /// The longest wait between two tries like `1m`, also for rate limits that ask to wait.
+    #[serde(default)]
+    pub max_delay: Option<String>,
+}
+
+impl Retries {
+    /// Returns the retry policy of the gateway, with the defaults filled in.
+    fn policy(&self) -> Result<Retry> {
+        let delay = |value: &Option<String>, name: &str, default: std::time::Duration| {
+            value.as_deref().map_or(Ok(default), |value| {
+                duration::parse(value)
+                    .and_then(|d| d.to_std().map_err(|e| e.to_string()))
+                    .map_err(|e| eyre!("invalid retry {}: {}", name, e))
+            })
+        };
+        let default = Retry::default();
+        Ok(Retry {
+            max_retries: self.max_retries.unwrap_or(default.max_retries),
+            min_delay: delay(&self.min_delay, "min_delay", default.min_delay)?,
+            max_delay: delay(&self.max_delay, "max_delay", default.max_delay)?,
+        })
+    }
+}
+
+/// Theme changes how tasks are displayed in lists.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    /// Ages in days after which tasks are marked with how long ago they were created, like
+    /// `[30, 90, 180]`. Each threshold that a task passes makes the mark stand out more. Tasks are
+    /// not marked without any.
+    #[serde(default)]
+    pub aging: Vec<i64>,
+}
+
+impl Theme {
+    /// Returns how many of the aging thresholds a task of this age in days has passed.
+    pub fn age_level(&self, days: i64) -> usize {
+        self.aging.iter().filter(|t| days >= **t).count()
+    }
+}
+
+/// StorageBackend is where the local state of the app is kept.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// A JSON file for every kind of state in the state directory.
+    #[default]
+    Json,
+    /// A single SQLite database, which writes every change in a transaction. Needs the `sqlite`
+    /// feature.
+    Sqlite,
+}
+
+/// SyncRemote is a location that `sync` keeps a copy of the local state in.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncRemote {
+    /// The kind of location.
+    pub kind: RemoteKind,
+    /// The folder, the folder of a git clone, the URL of a WebDAV folder or the URL of an S3
+    /// bucket with an optional prefix, like `https://s3.eu-central-1.amazonaws.com/bucket/doist`.
+    pub location: String,
+    /// The user name for WebDAV.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// The password for WebDAV.
+    #[serde(default)]
+    pub password: Option<String>,
+    /// The region of the S3 bucket. Defaults to `us-east-1`.
+    #[serde(default)]
+    pub region: Option<String>,
+    /// The access key ID for S3.
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    /// The secret access key for S3.
+    #[serde(default)]
+    pub secret_access_key: Option<String>,
+}
+
+/// RemoteKind is how a [`SyncRemote`] is reached.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteKind {
+    /// A folder, like one that Dropbox or Syncthing keep in sync.
+    #[default]
+    Dir,
+    /// A clone of a git repository, which is pulled before and pushed after syncing.
+    Git,
+    /// A folder on a WebDAV server, like Nextcloud.
+    WebDav,
+    /// A bucket of S3 or a compatible service, like MinIO.
+    S3,
+}
+
+/// The database in the state directory that the SQLite backend keeps its state in.
+#[cfg(feature = "sqlite")]
+const SQLITE_FILE: &str = "state.sqlite3";
+
+/// NotionProperties are the names of the database properties for the fields of a task. Fields
+/// without a property are not exported.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NotionProperties {
+    /// The title property that gets the content of the task.
+    #[serde(default = "default_notion_title")]
+    pub title: String,
+    /// A text property for the ID of the task. With it, tasks that were exported before update
+    /// their page instead of adding another one.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// A text property for the description.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// A date property for the due date.
+    #[serde(default)]
+    pub due: Option<String>,
+    /// A select property for the priority, like `p1`.
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// A multi-select property for the labels.
+    #[serde(default)]
+    pub labels: Option<String>,
+    /// A select property for the name of the project.
+    #[serde(default)]
+    pub project: Option<String>,
+    /// A URL property for the link to the task in Todoist.
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl Default for NotionProperties {
+    fn default() -> Self {
+        NotionProperties {
+            title: default_notion_title(),
+            id: None,
+            description: None,
+            due: None,
+            priority: None,
+            labels: None,
+            project: None,
+            url: None,
+        }
+    }
+}
+
+/// The title property of new Notion databases.
+fn default_notion_title() -> String {
+    "Name".to_string()
+}
+
 /// Returns the default URL to be used for calling the Todoist API.
 fn default_url() -> Option<url::Url> {
     Some(TODOIST_API_URL.clone())
@@ -133,9 +530,48 @@ impl Config {
         let token = self.token.as_deref().ok_or_else(|| {
             eyre!("No token in config specified. Use `doist auth` to register your token.")
         })?;
-        Ok(Gateway::new(
+        let gw = Gateway::new(
             token,
             &self.url.clone().unwrap_or_else(|| default_url().unwrap()),
-        ))
+        )
+        .read_only(self.read_only)
+        .retry(self.retry.policy()?)
+        .audit(AuditLog::new(self.store()?));
+        Ok(match self.response_cache()? {
+            Some(cache) => gw.cache(cache),
+            None => gw,
+        })
+    }
+
+    /// Returns the cache of API responses, if it is enabled.
+    pub(crate) fn response_cache(&self) -> Result<Option<Cache>> {
+        let Some(max_age) = &self.cache.max_age else {
+            return Ok(None);
+        };
+        let max_age =
+            duration::parse(max_age).map_err(|e| eyre!("invalid cache max_age: {}", e))?;
+        Ok(Some(Cache::new(self.store()?, max_age)))
+    }
+
+    /// Returns the store that keeps local state of the app, like the audit log.
+    pub(crate) fn store(&self) -> Result<Store> {
+        let dir = Self::config_dir(self.prefix.as_deref())?;
+        match self.storage {
+            StorageBackend::Json => Ok(Store::new(JsonFiles::new(dir))),
+            #[cfg(feature = "sqlite")]
+            StorageBackend::Sqlite => {
+                let path = dir
+                    .place_state_file(SQLITE_FILE)
+                    .map_err(|io| ConfigError::File {
+                        file: SQLITE_FILE.into(),
+                        io,
+                    })?;
+                Ok(Store::new(Sqlite::open(&path)?))
+            }
+            #[cfg(not(feature = "sqlite"))]
+            StorageBackend::Sqlite => Err(eyre!(
+                "storage = \"sqlite\" needs doist to be built with the `sqlite` feature"
+            )),
+        }
     }
 }
@@ -0,0 +1,230 @@
+//! Collects the details that are needed to look into a bug report, without giving away the tasks
+//! or secrets of the user.
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use color_eyre::{eyre::WrapErr, Result};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    api::content::{Pipeline, Sanitizer},
+    audit,
+    config::Config,
+};
+
+/// How many of the most recent audit log entries end up in the bundle.
+const AUDIT_ENTRIES: usize = 200;
+
+/// Keys of the audit log entries and their requests whose values are kept, as they don't say
+/// anything about the content of the account.
+const KEPT_KEYS: &[&str] = &[
+    "timestamp",
+    "method",
+    "path",
+    "id",
+    "type",
+    "uuid",
+    "temp_id",
+];
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Where to write the bundle. Defaults to a file with the current time in its name, in the
+    /// current directory.
+    #[arg(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+}
+
+/// Writes a zip file with the version, the config without secrets, the files of the local state
+/// and the redacted audit log.
+pub fn bundle(params: Params, cfg: &Config) -> Result<()> {
+    let output = params.output.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "doist-debug-{}.zip",
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    let store = cfg.store()?;
+    let mut files = vec![("version.txt", version()), ("config.toml", config(cfg)?)];
+    let state = store
+        .files()
+        .iter()
+        .map(|(name, size)| format!("{} {} bytes\n", name, size))
+        .collect::<String>();
+    files.push(("state.txt", state));
+    let entries = audit::entries(&store)?;
+    let audit = entries[entries.len().saturating_sub(AUDIT_ENTRIES)..]
+        .iter()
+        .map(|entry| {
+            let mut entry = serde_json::to_value(entry)?;
+            redact_json(&mut entry, None);
+            Ok(entry.to_string() + "\n")
+        })
+        .collect::<Result<String>>()?;
+    files.push(("audit.jsonl", audit));
+    write(&output, &files).wrap_err_with(|| format!("unable to write {}", output.display()))?;
+    println!("wrote debug bundle to {}", output.display());
+    Ok(())
+}
+
+fn write(output: &Path, files: &[(&str, String)]) -> Result<()> {
+    let mut zip = ZipWriter::new(File::create(output)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in files {
+        zip.start_file(*name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn version() -> String {
+    format!(
+        "doist {}\nos: {}\narch: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+/// Returns the config as TOML, without tokens, passwords and the paths of URLs, which often
+/// contain secrets like for webhooks.
+fn config(cfg: &Config) -> Result<String> {
+    let mut value = toml::Value::try_from(cfg)?;
+    redact_toml(&mut value, None);
+    Ok(toml::to_string_pretty(&value)?)
+}
+
+fn redact_toml(value: &mut toml::Value, key: Option<&str>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                redact_toml(value, Some(key));
+            }
+        }
+        toml::Value::Array(values) => {
+            for value in values {
+                redact_toml(value, key);
+            }
+        }
+        toml::Value::String(text) => {
+            if key.is_some_and(is_secret) {
+                *text = "<redacted>".to_string();
+            } else if let Ok(mut url) = url::Url::parse(text) {
+                if url.has_host() && (url.path() != "/" || url.query().is_some()) {
+                    url.set_path("redacted");
+                    url.set_query(None);
+                    let _ = url.set_username("");
+                    let _ = url.set_password(None);
+                    *text = url.to_string();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["token", "secret", "password", "key"]
+        .iter()
+        .any(|s| key.contains(s))
+}
+
+/// Replaces all texts with lorem ipsum, except for the values of [`KEPT_KEYS`] and IDs.
+fn redact_json(value: &mut serde_json::Value, key: Option<&str>) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                redact_json(value, Some(key));
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                redact_json(value, key);
+            }
+        }
+        serde_json::Value::String(text) => {
+            let redact = Pipeline::new(vec![Sanitizer::Redact]);
+            match key {
+                Some(k) if KEPT_KEYS.contains(&k) || k.ends_with("_id") => {}
+                // Keeps the subcommand and the flags, which tell what was run.
+                Some("command") => {
+                    *text = text
+                        .split(' ')
+                        .enumerate()
+                        .map(|(i, word)| match i < 2 || word.starts_with('-') {
+                            true => word.split('=').next().unwrap_or_default().into(),
+                            false => redact.apply(word),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                }
+                _ => *text = redact.apply(text).into_owned(),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Points to `debug bundle` after the report of a panic.
+pub fn install_crash_hint() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        hook(info);
+        eprintln!(
+            "Run `doist debug bundle` and attach the file it writes to the bug report. It \
+             contains no tasks or secrets."
+        );
+    }));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn redacts_config() {
+        let mut value: toml::Value = toml::from_str(
+            r#"
+            token = "abc"
+            url = "https://api.todoist.com/"
+            [notion]
+            token = "secret"
+            [[hooks]]
+            url = "https://hooks.slack.com/services/T000/B000/XXXX"
+            "#,
+        )
+        .unwrap();
+        redact_toml(&mut value, None);
+        assert_eq!(value["token"].as_str(), Some("<redacted>"));
+        assert_eq!(value["url"].as_str(), Some("https://api.todoist.com/"));
+        assert_eq!(value["notion"]["token"].as_str(), Some("<redacted>"));
+        assert_eq!(
+            value["hooks"][0]["url"].as_str(),
+            Some("https://hooks.slack.com/redacted")
+        );
+    }
+
+    #[test]
+    fn redacts_requests() {
+        let mut value = serde_json::json!({
+            "method": "POST",
+            "command": "doist add \"Call Anna\" --priority=1 -P Work",
+            "request": {"content": "Call Anna", "project_id": "123", "priority": 4},
+        });
+        redact_json(&mut value, None);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "method": "POST",
+                "command": "doist add \"Amet Amet\" --priority -P Amet",
+                "request": {"content": "Amet Elit", "project_id": "123", "priority": 4},
+            })
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Summarizes what happened in a time range, to be pasted into a weekly review or sent by mail.
+use chrono::{DateTime, Duration, Local, Utc};
+use color_eyre::Result;
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, Project, ProjectID, Task},
+    },
+    command::{Command, Context},
+    config::Config,
+    duration::{self, Since},
+};
+
+/// The formats a digest can be rendered in.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Markdown, for notes and chat messages.
+    Md,
+    /// HTML, for mails.
+    Html,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Where the digest starts, like `monday`, `yesterday`, `2022-08-01` or `7d`.
+    #[arg(long = "since", default_value = "monday")]
+    since: Since,
+    /// How far ahead from now deadlines are listed as upcoming, like `7d`.
+    #[arg(long = "upcoming", default_value = "7d", value_parser = duration::parse)]
+    upcoming: Duration,
+    /// The format of the digest.
+    #[arg(long = "format", value_enum, default_value_t = Format::Md)]
+    format: Format,
+}
+
+/// A single part of the digest, with its items grouped by project.
+struct Section {
+    title: &'static str,
+    projects: Vec<(String, Vec<String>)>,
+}
+
+/// Prints a summary of completed tasks, newly added tasks and upcoming deadlines.
+pub async fn digest(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let since = params
+        .since
+        .resolve(now.with_timezone(&Local))
+        .with_timezone(&Utc);
+    let until = now + params.upcoming;
+    let (completed, tasks, projects) =
+        tokio::try_join!(gw.completed(&since), gw.tasks(None), gw.projects())?;
+
+    let mut completed = completed;
+    completed.sort_by_key(|c| c.completed_at);
+    let mut added = tasks
+        .iter()
+        .filter(|t| t.created_at >= since)
+        .collect::<Vec<_>>();
+    added.sort_by_key(|t| t.created_at);
+    let mut upcoming = tasks
+        .iter()
+        .filter(|t| due_between(t, &now, &until))
+        .collect::<Vec<_>>();
+    upcoming.sort();
+
+    let sections = [
+        Section {
+            title: "Completed",
+            projects: group(
+                &projects,
+                completed
+                    .iter()
+                    .map(|c| (&c.project_id, Content(&c.content).to_string())),
+            ),
+        },
+        Section {
+            title: "Added",
+            projects: group(
+                &projects,
+                added
+                    .iter()
+                    .map(|t| (&t.project_id, Content(&t.content).to_string())),
+            ),
+        },
+        Section {
+            title: "Upcoming",
+            projects: group(
+                &projects,
+                upcoming.iter().map(|t| {
+                    (
+                        &t.project_id,
+                        format!("{} ({})", Content(&t.content), due(t)),
+                    )
+                }),
+            ),
+        },
+    ];
+    let title = format!(
+        "Digest from {} to {}",
+        since.with_timezone(&Local).format("%Y-%m-%d"),
+        now.with_timezone(&Local).format("%Y-%m-%d")
+    );
+    match params.format {
+        Format::Md => print!("{}", markdown(&title, &sections)),
+        Format::Html => print!("{}", html(&title, &sections)),
+    }
+    Ok(())
+}
+
+/// Returns true if the task is due from now until the given time. Tasks without a time count for
+/// the whole day they are due on.
+fn due_between(task: &Task, now: &DateTime<Utc>, until: &DateTime<Utc>) -> bool {
+    match &task.due {
+        Some(due) => match &due.exact {
+            Some(exact) => exact.datetime >= *now && exact.datetime <= *until,
+            None => {
+                due.date >= now.with_timezone(&Local).date_naive()
+                    && due.date <= until.with_timezone(&Local).date_naive()
+            }
+        },
+        None => false,
+    }
+}
+
+/// Formats when the task is due in local time, without any colors.
+fn due(task: &Task) -> String {
+    match &task.due {
+        Some(due) => match &due.exact {
+            Some(exact) => exact
+                .datetime
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            None => due.date.format("%Y-%m-%d").to_string(),
+        },
+        None => String::new(),
+    }
+}
+
+/// Groups the items by the name of their project, in the order the projects are shown in the UI.
+/// Items of unknown projects are grouped last.
+fn group<'a>(
+    projects: &[Project],
+    items: impl Iterator<Item = (&'a ProjectID, String)>,
+) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(Option<&Project>, Vec<String>)> = Vec::new();
+    for (project_id, item) in items {
+        let project = projects.iter().find(|p| &p.id == project_id);
+        match groups
+            .iter_mut()
+            .find(|(p, _)| p.map(|p| &p.id) == project.map(|p| &p.id))
+        {
+            Some((_, items)) => items.push(item),
+            None => groups.push((project, vec![item])),
+        }
+    }
+    groups.sort_by_key(|(p, _)| p.map_or(isize::MAX, |p| p.order));
+    groups
+        .into_iter()
+        .map(|(p, items)| {
+            (
+                p.map_or_else(
+                    || "Unknown project".to_string(),
+                    |p| Content(&p.name).to_string(),
+                ),
+                items,
+            )
+        })
+        .collect()
+}
+
+fn markdown(title: &str, sections: &[Section]) -> String {
+    let mut out = format!("# {}\n", title);
+    for section in sections {
+        out.push_str(&format!("\n## {}\n", section.title));
+        if section.projects.is_empty() {
+            out.push_str("\nNothing\n");
+        }
+        for (project, items) in &section.projects {
+            out.push_str(&format!("\n### {}\n\n", project));
+            for item in items {
+                out.push_str(&format!("- {}\n", item));
+            }
+        }
+    }
+    out
+}
+
+fn html(title: &str, sections: &[Section]) -> String {
+    let mut out = format!("<h1>{}</h1>\n", escape(title));
+    for section in sections {
+        out.push_str(&format!("<h2>{}</h2>\n", escape(section.title)));
+        if section.projects.is_empty() {
+            out.push_str("<p>Nothing</p>\n");
+        }
+        for (project, items) in &section.projects {
+            out.push_str(&format!("<h3>{}</h3>\n<ul>\n", escape(project)));
+            for item in items {
+                out.push_str(&format!("<li>{}</li>\n", escape(item)));
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+    out
+}
+
+/// Escapes the characters that have a meaning in HTML text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        digest(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_html() {
+        let sections = [
+            Section {
+                title: "Completed",
+                projects: vec![("<Work>".to_string(), vec!["Fix \"it\" & ship".to_string()])],
+            },
+            Section {
+                title: "Added",
+                projects: vec![],
+            },
+        ];
+        assert_eq!(
+            html("Digest", &sections),
+            "<h1>Digest</h1>\n<h2>Completed</h2>\n<h3>&lt;Work&gt;</h3>\n<ul>\n\
+             <li>Fix &quot;it&quot; &amp; ship</li>\n</ul>\n<h2>Added</h2>\n<p>Nothing</p>\n"
+        );
+    }
+}
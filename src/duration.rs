@@ -0,0 +1,189 @@
+//! Parses short durations like `90s`, `5m` or `1h30m`, and points in time relative to now like
+//! `monday`, as they are given on the command line.
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc, Weekday};
+
+/// Units that can be used in a duration, with how many seconds they last.
+const UNITS: [(char, i64); 5] = [
+    ('w', 7 * 24 * 60 * 60),
+    ('d', 24 * 60 * 60),
+    ('h', 60 * 60),
+    ('m', 60),
+    ('s', 1),
+];
+
+/// Parses a duration made up of numbers followed by one of the units `w`, `d`, `h`, `m` or `s`,
+/// like `1h30m`. A number without a unit is in seconds.
+///
+/// Returns a plain error message, so it can be used as a clap value parser.
+pub fn parse(s: &str) -> Result<Duration, String> {
+    let invalid = || {
+        format!(
+            "invalid duration {:?}, expected something like 30s, 5m or 1h30m",
+            s
+        )
+    };
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(seconds) = s.parse::<u32>() {
+        return Ok(Duration::seconds(seconds.into()));
+    }
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = UNITS
+            .iter()
+            .find(|(unit, _)| *unit == c)
+            .map(|(_, seconds)| *seconds)
+            .ok_or_else(invalid)?;
+        let n: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        seconds = n
+            .checked_mul(unit)
+            .and_then(|n| seconds.checked_add(n))
+            .ok_or_else(invalid)?;
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+    Duration::try_seconds(seconds).ok_or_else(invalid)
+}
+
+/// Formats the duration the same way as it would be given to [`parse`], like `1h30m`.
+pub fn format(duration: Duration) -> String {
+    let mut seconds = duration.num_seconds().max(0);
+    if seconds == 0 {
+        return "0s".to_string();
+    }
+    let mut result = String::new();
+    for (unit, length) in UNITS {
+        if seconds >= length {
+            result.push_str(&format!("{}{}", seconds / length, unit));
+            seconds %= length;
+        }
+    }
+    result
+}
+
+/// Since is the start of a time range that ends now, as given on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Since {
+    /// The start of the given day.
+    Date(NaiveDate),
+    /// The start of the most recent given weekday, which is today if today is that weekday.
+    Weekday(Weekday),
+    /// The start of the day the given number of days ago, with `today` being 0.
+    DaysAgo(u32),
+    /// The given duration before now.
+    Ago(Duration),
+    /// An exact point in time.
+    Time(DateTime<Utc>),
+}
+
+impl FromStr for Since {
+    type Err = String;
+
+    /// Parses `today`, `yesterday`, weekdays like `monday`, dates like `2022-08-01`, durations
+    /// like `7d`, or timestamps like `2022-08-01T12:00:00Z`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "today" => return Ok(Since::DaysAgo(0)),
+            "yesterday" => return Ok(Since::DaysAgo(1)),
+            _ => {}
+        }
+        if let Ok(weekday) = s.parse::<Weekday>() {
+            return Ok(Since::Weekday(weekday));
+        }
+        if let Ok(date) = s.parse::<NaiveDate>() {
+            return Ok(Since::Date(date));
+        }
+        if let Ok(time) = DateTime::parse_from_rfc3339(&s) {
+            return Ok(Since::Time(time.with_timezone(&Utc)));
+        }
+        parse(&s).map(Since::Ago).map_err(|_| {
+            format!(
+                "invalid time {:?}, expected something like today, monday, 2022-08-01, 7d or 2022-08-01T12:00:00Z",
+                s
+            )
+        })
+    }
+}
+
+impl Since {
+    /// Returns the point in time, relative to `now`.
+    pub fn resolve(&self, now: DateTime<Local>) -> DateTime<Local> {
+        let today = now.date_naive();
+        let day = match *self {
+            Since::Ago(duration) => return now - duration,
+            Since::Time(time) => return time.with_timezone(&Local),
+            Since::Date(date) => date,
+            Since::DaysAgo(days) => today - Duration::days(days.into()),
+            Since::Weekday(weekday) => {
+                let days = (7 + today.weekday().num_days_from_monday()
+                    - weekday.num_days_from_monday())
+                    % 7;
+                today - Duration::days(days.into())
+            }
+        };
+        let midnight = day.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+        // Midnight might not exist when the clocks change, in which case the day starts later.
+        Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .unwrap_or_else(|| Local.from_utc_datetime(&midnight))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolves_since() {
+        // A Friday.
+        let now = Local.with_ymd_and_hms(2022, 8, 26, 19, 33, 20).unwrap();
+        let day = |d| Local.with_ymd_and_hms(2022, 8, d, 0, 0, 0).unwrap();
+        let since = |s: &str| s.parse::<Since>().unwrap().resolve(now);
+        assert_eq!(since("today"), day(26));
+        assert_eq!(since("Yesterday"), day(25));
+        assert_eq!(since("monday"), day(22));
+        assert_eq!(since("fri"), day(26));
+        assert_eq!(since("saturday"), day(20));
+        assert_eq!(since("2022-08-01"), day(1));
+        assert_eq!(since("2h"), now - Duration::hours(2));
+        assert_eq!(
+            since("2022-08-26T10:00:00Z"),
+            Utc.with_ymd_and_hms(2022, 8, 26, 10, 0, 0).unwrap()
+        );
+        assert!("someday".parse::<Since>().is_err());
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse("90"), Ok(Duration::seconds(90)));
+        assert_eq!(parse("30s"), Ok(Duration::seconds(30)));
+        assert_eq!(parse("5m"), Ok(Duration::minutes(5)));
+        assert_eq!(parse("1h30m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse("2d"), Ok(Duration::days(2)));
+        assert_eq!(parse("1w"), Ok(Duration::weeks(1)));
+        for invalid in ["", "h", "5x", "1h30", "-5m", "99999999999999999999w"] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format(Duration::seconds(90)), "1m30s");
+        assert_eq!(format(Duration::hours(2)), "2h");
+        assert_eq!(format(Duration::days(8)), "1w1d");
+        assert_eq!(format(Duration::zero()), "0s");
+    }
+}
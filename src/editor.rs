@@ -0,0 +1,44 @@
+//! Lets the user edit text in their editor, like git does for commit messages.
+use std::{fs, process::Command};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+
+/// The editor that is used if neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Opens the text in `$VISUAL` or `$EDITOR` and returns the text once the editor is closed.
+///
+/// Lines starting with `#` are left out of the result, so they can explain what to do.
+pub fn edit(text: &str) -> Result<String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    let path = std::env::temp_dir().join(format!("doist-{}.txt", uuid::Uuid::new_v4()));
+    fs::write(&path, text).wrap_err("unable to write the file to edit")?;
+    let mut args = editor.split_whitespace();
+    let status = args
+        .next()
+        .map(|program| Command::new(program).args(args).arg(&path).status());
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    match status {
+        Some(status) => {
+            let status = status.wrap_err_with(|| format!("unable to start editor {}", editor))?;
+            if !status.success() {
+                return Err(eyre!("editor {} exited with {}", editor, status));
+            }
+        }
+        None => return Err(eyre!("no editor is set in $VISUAL or $EDITOR")),
+    }
+    Ok(edited
+        .wrap_err("unable to read the edited file")?
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
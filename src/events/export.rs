@@ -0,0 +1,61 @@
+use chrono::{Local, Utc};
+use color_eyre::Result;
+
+use crate::{
+    api::rest::Gateway,
+    command::{Command, Context},
+    config::Config,
+    duration::Since,
+};
+
+use super::Event;
+
+/// How the events are written.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A single JSON array.
+    Json,
+    /// One JSON object per line, to be processed as a stream.
+    Jsonl,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Exports the events since then, like `2022-08-01T12:00:00Z`, `yesterday` or `2h`.
+    #[arg(long = "since", default_value = "today")]
+    since: Since,
+    /// How the events are written.
+    #[arg(long = "format", value_enum, default_value_t = Format::Json)]
+    format: Format,
+}
+
+/// Prints the events of the activity log since the given time, oldest first.
+pub async fn export(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let since = params
+        .since
+        .resolve(now.with_timezone(&Local))
+        .with_timezone(&Utc);
+    let mut events = gw
+        .activity(&since)
+        .await?
+        .into_iter()
+        .map(Event::from)
+        .collect::<Vec<_>>();
+    events.reverse();
+    match params.format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&events)?),
+        Format::Jsonl => {
+            for event in events {
+                println!("{}", serde_json::to_string(&event)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        export(self, &ctx.gw, &ctx.cfg).await
+    }
+}
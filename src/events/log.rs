@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use chrono::{Local, Utc};
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Project, ProjectID},
+    },
+    command::{Command, Context},
+    duration::Since,
+    interactive, output,
+};
+
+use super::Event;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Shows the events since then, like `today`, `monday`, `2h` or `2022-08-01`.
+    #[arg(short = 's', long = "since", default_value = "today")]
+    since: Since,
+    /// Only shows events of this type, like `completed`, `comment` or `task.added`. Can be given
+    /// multiple times or separated by commas.
+    #[arg(short = 't', long = "type", value_delimiter = ',')]
+    types: Vec<String>,
+    /// Only shows the events in this project.
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    #[clap(flatten)]
+    output: output::Json,
+}
+
+/// Returns whether the event has one of the types, which either name the object, the action or
+/// both.
+fn matches(event: &Event, types: &[String]) -> bool {
+    types.is_empty()
+        || types.iter().any(|t| {
+            let (object, action) = event.kind.split_once('.').unwrap_or((&event.kind, ""));
+            t == &event.kind || t == object || t == action
+        })
+}
+
+/// Describes who caused the event. The activity log leaves out the initiator for the user of the
+/// token.
+fn initiator(event: &Event) -> String {
+    match &event.initiator_id {
+        Some(id) => format!("user {}", id),
+        None => "you".to_string(),
+    }
+}
+
+/// Renders one line per event, most recent first.
+fn render(events: &[Event], projects: &HashMap<&ProjectID, &Project>) -> String {
+    let mut out = String::new();
+    for event in events {
+        let (object, action) = event.kind.split_once('.').unwrap_or((&event.kind, ""));
+        out.push_str(&format!(
+            "{} {} {} {}",
+            event
+                .occurred_at
+                .with_timezone(&Local)
+                .format("%a %-d %b %H:%M")
+                .if_supports_color(Stream::Stdout, |t| t.bright_black()),
+            initiator(event).if_supports_color(Stream::Stdout, |t| t.bold()),
+            action,
+            object,
+        ));
+        if let Some(title) = &event.title {
+            out.push_str(&format!(" {}", Content(title)));
+        }
+        if let Some(project) = event.project_id.as_ref().and_then(|id| projects.get(id)) {
+            out.push_str(&format!(
+                " [{}]",
+                Content(&project.name).if_supports_color(Stream::Stdout, |t| t.blue())
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        let now = ctx.cfg.override_time.unwrap_or_else(Utc::now);
+        let since = self
+            .since
+            .resolve(now.with_timezone(&Local))
+            .with_timezone(&Utc);
+        let projects = ctx.gw.projects().await?;
+        let events = match self.project.optional(&projects)? {
+            Some(project) => ctx.gw.activity_in_project(&project.id, &since).await?,
+            None => ctx.gw.activity(&since).await?,
+        };
+        let events = events
+            .into_iter()
+            .map(Event::from)
+            .filter(|event| matches(event, &self.types))
+            .collect::<Vec<_>>();
+        if self.output.json {
+            return self.output.print(&events);
+        }
+        if events.is_empty() {
+            println!("nothing happened since {}", since.with_timezone(&Local));
+            return Ok(());
+        }
+        let projects = projects.iter().map(|p| (&p.id, p)).collect();
+        print!("{}", render(&events, &projects));
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! Turns the activity log into change events of a stable shape, for automation platforms like
+//! IFTTT or Zapier that shouldn't break when the Todoist API changes.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::api::rest::{ActivityEvent, ProjectID, TaskID};
+
+pub mod export;
+pub mod log;
+
+/// Event is a normalized change to the account.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct Event {
+    /// Unique for every event, so consumers can skip events they have seen.
+    id: String,
+    /// What happened, as `<object>.<action>` like `task.completed` or `comment.added`.
+    #[serde(rename = "type")]
+    kind: String,
+    /// When it happened.
+    occurred_at: DateTime<Utc>,
+    /// The ID of the object that changed.
+    object_id: String,
+    /// The project the object is in, if any.
+    project_id: Option<ProjectID>,
+    /// The task the object belongs to, which is the object itself for tasks.
+    task_id: Option<TaskID>,
+    /// The content or name of the object, if the activity log knows it.
+    title: Option<String>,
+    /// The user that caused the event, which is left out for the user of the token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    initiator_id: Option<String>,
+}
+
+impl From<ActivityEvent> for Event {
+    fn from(event: ActivityEvent) -> Self {
+        let object = match event.object_type.as_str() {
+            "item" => "task",
+            "note" => "comment",
+            other => other,
+        };
+        let task_id = match object {
+            "task" => Some(event.object_id.clone()),
+            _ => event.parent_item_id,
+        };
+        let title = ["content", "name"]
+            .iter()
+            .find_map(|key| event.extra_data.get(*key)?.as_str())
+            .map(str::to_string);
+        Event {
+            id: event.id,
+            kind: format!("{}.{}", object, event.event_type),
+            occurred_at: event.event_date,
+            object_id: event.object_id,
+            project_id: event.parent_project_id,
+            task_id,
+            title,
+            initiator_id: event.initiator_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalizes_events() {
+        let event: ActivityEvent = serde_json::from_str(
+            r#"{
+                "id": 955333384,
+                "object_type": "note",
+                "object_id": "2995104589",
+                "event_type": "added",
+                "event_date": "2022-08-26T14:24:59Z",
+                "parent_project_id": "2203306141",
+                "parent_item_id": "2995104339",
+                "extra_data": {"content": "Looks good"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Event::from(event),
+            Event {
+                id: "955333384".to_string(),
+                kind: "comment.added".to_string(),
+                occurred_at: "2022-08-26T14:24:59Z".parse().unwrap(),
+                object_id: "2995104589".to_string(),
+                project_id: Some("2203306141".to_string()),
+                task_id: Some("2995104339".to_string()),
+                title: Some("Looks good".to_string()),
+                initiator_id: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,2 @@
+//! Exports tasks to other services, for reporting that lives outside of Todoist.
+pub mod notion;
@@ -0,0 +1,253 @@
+//! Exports tasks into a Notion database through the Notion API.
+use std::collections::HashMap;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    api::rest::{Gateway, Project, Task},
+    command::{Command, Context},
+    config::{Config, NotionProperties},
+};
+
+/// The version of the Notion API that requests are made for.
+const NOTION_VERSION: &str = "2022-06-28";
+
+/// The most characters Notion accepts in a single piece of text.
+const MAX_TEXT: usize = 2000;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The ID of the Notion database, which is part of its URL.
+    #[arg(long = "database")]
+    database: String,
+    /// Only exports tasks that match this filter, instead of all tasks.
+    #[arg(short = 'f', long = "filter")]
+    filter: Option<String>,
+    /// The address of the Notion API.
+    #[arg(
+        long = "api-url",
+        default_value = "https://api.notion.com/v1/",
+        hide = true
+    )]
+    api_url: url::Url,
+}
+
+/// Client for the Notion API.
+struct Client {
+    http: reqwest::Client,
+    url: url::Url,
+    token: String,
+}
+
+impl Client {
+    /// Sends a JSON body to the Notion API and returns its response.
+    async fn send(&self, method: reqwest::Method, path: &str, body: &Value) -> Result<Value> {
+        let body = self
+            .http
+            .request(method, self.url.join(path)?)
+            .bearer_auth(&self.token)
+            .header("Notion-Version", NOTION_VERSION)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| eyre!("{}", e.without_url()))
+            .wrap_err("unable to talk to Notion")?
+            .bytes()
+            .await?;
+        serde_json::from_slice(&body).wrap_err("unable to parse the response of Notion")
+    }
+
+    /// Returns the pages of the database by the task ID in their `property`.
+    async fn pages(&self, database: &str, property: &str) -> Result<HashMap<String, String>> {
+        let mut pages = HashMap::new();
+        let mut cursor = None;
+        loop {
+            let mut body = json!({ "page_size": 100 });
+            if let Some(cursor) = cursor {
+                body["start_cursor"] = cursor;
+            }
+            let response = self
+                .send(
+                    reqwest::Method::POST,
+                    &format!("databases/{}/query", database),
+                    &body,
+                )
+                .await?;
+            for page in response["results"].as_array().into_iter().flatten() {
+                let id = page["properties"][property]["rich_text"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|t| t["plain_text"].as_str())
+                    .collect::<String>();
+                if let (false, Some(page)) = (id.is_empty(), page["id"].as_str()) {
+                    pages.insert(id, page.to_string());
+                }
+            }
+            match response["has_more"].as_bool() {
+                Some(true) => cursor = Some(response["next_cursor"].clone()),
+                _ => return Ok(pages),
+            }
+        }
+    }
+}
+
+/// Adds a page to the database for every task, or updates the page of a task that was exported
+/// before.
+pub async fn notion(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let token = cfg.notion.token.clone().ok_or_else(|| {
+        eyre!("no Notion token, set `token` in the `[notion]` part of the config")
+    })?;
+    let client = Client {
+        http: reqwest::Client::new(),
+        url: params.api_url,
+        token,
+    };
+    let mapping = &cfg.notion.properties;
+    let (tasks, projects) = tokio::try_join!(gw.tasks(params.filter.as_deref()), gw.projects())?;
+    let pages = match &mapping.id {
+        Some(property) => client.pages(&params.database, property).await?,
+        None => HashMap::new(),
+    };
+    let (mut added, mut updated) = (0, 0);
+    for task in &tasks {
+        let project = projects.iter().find(|p| p.id == task.project_id);
+        let properties = properties(task, project, mapping);
+        match pages.get(&task.id) {
+            Some(page) => {
+                client
+                    .send(
+                        reqwest::Method::PATCH,
+                        &format!("pages/{}", page),
+                        &json!({ "properties": properties }),
+                    )
+                    .await
+                    .wrap_err_with(|| format!("unable to update the page of task {}", task.id))?;
+                updated += 1;
+            }
+            None => {
+                client
+                    .send(
+                        reqwest::Method::POST,
+                        "pages",
+                        &json!({
+                            "parent": { "database_id": params.database },
+                            "properties": properties,
+                        }),
+                    )
+                    .await
+                    .wrap_err_with(|| format!("unable to add a page for task {}", task.id))?;
+                added += 1;
+            }
+        }
+    }
+    println!("added {} and updated {} pages in Notion", added, updated);
+    Ok(())
+}
+
+/// Builds the values of the mapped database properties for the task.
+fn properties(task: &Task, project: Option<&Project>, mapping: &NotionProperties) -> Value {
+    let mut properties = Map::new();
+    properties.insert(
+        mapping.title.clone(),
+        json!({ "title": text(&task.content) }),
+    );
+    let mut set = |property: &Option<String>, value: Value| {
+        if let Some(property) = property {
+            properties.insert(property.clone(), value);
+        }
+    };
+    set(&mapping.id, json!({ "rich_text": text(&task.id) }));
+    set(
+        &mapping.description,
+        json!({ "rich_text": text(&task.description) }),
+    );
+    set(
+        &mapping.due,
+        json!({
+            "date": task.due.as_ref().map(|due| match &due.exact {
+                Some(exact) => json!({ "start": exact.datetime.to_rfc3339() }),
+                None => json!({ "start": due.date.format("%Y-%m-%d").to_string() }),
+            }),
+        }),
+    );
+    // The API counts priorities the other way around than the UI.
+    set(
+        &mapping.priority,
+        json!({ "select": { "name": format!("p{}", 5 - task.priority as u8) } }),
+    );
+    set(
+        &mapping.labels,
+        json!({
+            "multi_select": task.labels.iter().map(|l| json!({ "name": l })).collect::<Vec<_>>(),
+        }),
+    );
+    set(
+        &mapping.project,
+        json!({ "select": project.map(|p| json!({ "name": p.name })) }),
+    );
+    set(&mapping.url, json!({ "url": task.url.as_str() }));
+    Value::Object(properties)
+}
+
+/// Returns the rich text of the content, cut to the length that Notion accepts.
+fn text(content: &str) -> Value {
+    if content.is_empty() {
+        return json!([]);
+    }
+    let content = content.chars().take(MAX_TEXT).collect::<String>();
+    json!([{ "text": { "content": content } }])
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        notion(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maps_properties() {
+        let task: Task = serde_json::from_value(json!({
+            "id": "7000001", "project_id": "1", "section_id": null, "content": "Ship it",
+            "description": "", "is_completed": false, "labels": ["release"], "parent_id": null,
+            "order": 1, "priority": 4, "comment_count": 0, "creator_id": "1",
+            "created_at": "2022-04-28T03:09:47Z", "url": "https://todoist.com/showTask?id=7000001",
+            "due": {"is_recurring": false, "string": "today", "date": "2022-08-26"},
+        }))
+        .unwrap();
+        let project = Project::new("1", "Website");
+        let mapping = NotionProperties {
+            id: Some("ID".to_string()),
+            due: Some("Due".to_string()),
+            priority: Some("Priority".to_string()),
+            labels: Some("Labels".to_string()),
+            project: Some("Project".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            properties(&task, Some(&project), &mapping),
+            json!({
+                "Name": { "title": [{ "text": { "content": "Ship it" } }] },
+                "ID": { "rich_text": [{ "text": { "content": "7000001" } }] },
+                "Due": { "date": { "start": "2022-08-26" } },
+                "Priority": { "select": { "name": "p1" } },
+                "Labels": { "multi_select": [{ "name": "release" }] },
+                "Project": { "select": { "name": "Website" } },
+            })
+        );
+        assert_eq!(
+            properties(&task, None, &NotionProperties::default()),
+            json!({ "Name": { "title": [{ "text": { "content": "Ship it" } }] } })
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Renders the error that a command ended with, and picks the exit code for it.
+//!
+//! Most errors are shown as they are, with their context and suggestions. A token that Todoist
+//! doesn't accept is common enough to be explained instead, without the noise of the error chain.
+use std::{io::IsTerminal, path::Path, process::ExitCode};
+
+use color_eyre::{Report, Result};
+use reqwest::StatusCode;
+
+use crate::{api::rest::ApiError, config::Config};
+
+/// The exit code when Todoist does not accept the token, the same as `EX_NOPERM` of sysexits.
+pub const UNAUTHORIZED_EXIT_CODE: u8 = 77;
+
+/// Shows the error on stderr and returns the exit code for it. For an unaccepted token it offers
+/// to save a new one, if there is someone at the terminal to type it in.
+pub async fn report(error: Report, prefix: Option<&Path>) -> ExitCode {
+    if !unauthorized(&error) {
+        eprintln!("Error: {:?}", error);
+        return ExitCode::FAILURE;
+    }
+    eprintln!("Error: {}", error.root_cause());
+    eprintln!();
+    eprintln!("Todoist did not accept the API token. This usually means that");
+    eprintln!("  - the token was revoked or regenerated in the Todoist settings,");
+    eprintln!("  - the token was mistyped or only partially copied,");
+    eprintln!("  - the `url` of the config points to a server that expects another token.");
+    eprintln!();
+    eprintln!(
+        "Copy the current token from Settings > Integrations > Developer in Todoist and save it \
+         with `doist auth <TOKEN>`."
+    );
+    if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() {
+        if let Err(error) = reauthenticate(prefix).await {
+            eprintln!("Error: {:?}", error);
+        }
+    }
+    ExitCode::from(UNAUTHORIZED_EXIT_CODE)
+}
+
+/// Returns true if the error was caused by Todoist rejecting the token.
+fn unauthorized(error: &Report) -> bool {
+    error
+        .chain()
+        .filter_map(|e| e.downcast_ref::<ApiError>())
+        .any(|e| e.status == StatusCode::UNAUTHORIZED)
+}
+
+/// Asks for a new token, and saves it once Todoist accepted it.
+async fn reauthenticate(prefix: Option<&Path>) -> Result<()> {
+    eprintln!();
+    let token = dialoguer::Password::new()
+        .with_prompt("Paste a new token to save it, or leave it empty to skip")
+        .allow_empty_password(true)
+        .interact()?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Ok(());
+    }
+    let mut cfg = match prefix {
+        Some(p) => Config::load_prefix(p),
+        None => Config::load(),
+    }?;
+    cfg.token = Some(token.to_string());
+    match cfg.gateway()?.projects().await {
+        Ok(_) => {
+            cfg.save()?;
+            eprintln!("Token successfully saved, run the command again.");
+        }
+        Err(error) if unauthorized(&error) => {
+            eprintln!("Todoist did not accept this token either, so it was not saved.")
+        }
+        Err(error) => return Err(error.wrap_err("unable to check the new token")),
+    }
+    Ok(())
+}
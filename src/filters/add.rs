@@ -0,0 +1,40 @@
+use crate::{
+    api::rest::{CreateFilter, Gateway},
+    command::{Command, Context},
+};
+use color_eyre::{eyre::eyre, Result};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Name of the filter to create.
+    name: String,
+    /// The filter query, like `today & @work`.
+    query: String,
+}
+
+pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
+    gw.create_filter(&CreateFilter {
+        name: params.name.clone(),
+        query: params.query,
+        ..Default::default()
+    })
+    .await?;
+    println!("created filter: {}", params.name);
+    Ok(())
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(eyre!("filter name can not be empty"));
+        }
+        if self.query.trim().is_empty() {
+            return Err(eyre!("filter query can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        add(self, &ctx.gw).await
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{
+    api::rest::Gateway,
+    command::{Command, Context},
+};
+use color_eyre::Result;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Name of the filter to delete.
+    filter: String,
+}
+
+pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
+    let filters = gw.filters().await?;
+    let filter = super::find(&filters, &params.filter)?;
+    gw.delete_filter(&filter.id).await?;
+    println!("deleted filter: {}", filter.name);
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        delete(self, &ctx.gw).await
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    api::rest::{Gateway, UpdateFilter},
+    command::{Command, Context},
+};
+use color_eyre::{eyre::eyre, Result};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Name of the filter to edit.
+    filter: String,
+    /// Renames the filter.
+    #[arg(short = 'n', long = "name")]
+    name: Option<String>,
+    /// Replaces the filter query.
+    #[arg(short = 'q', long = "query")]
+    query: Option<String>,
+    /// Sets the color of the filter, like `red` or `sky_blue`.
+    #[arg(short = 'c', long = "color")]
+    color: Option<String>,
+    /// Marks the filter as a favorite, or not with `--favorite=false`.
+    #[arg(long = "favorite", num_args = 0..=1, default_missing_value = "true")]
+    favorite: Option<bool>,
+}
+
+pub async fn edit(params: Params, gw: &Gateway) -> Result<()> {
+    let filters = gw.filters().await?;
+    let filter = super::find(&filters, &params.filter)?;
+    gw.update_filter(
+        &filter.id,
+        &UpdateFilter {
+            name: params.name.clone(),
+            query: params.query,
+            color: params.color,
+            is_favorite: params.favorite,
+        },
+    )
+    .await?;
+    println!(
+        "updated filter: {}",
+        params.name.as_deref().unwrap_or(&filter.name)
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.is_none()
+            && self.query.is_none()
+            && self.color.is_none()
+            && self.favorite.is_none()
+        {
+            return Err(eyre!(
+                "nothing to update, use --name, --query, --color or --favorite"
+            ));
+        }
+        if self.name.as_ref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(eyre!("filter name can not be empty"));
+        }
+        if self.query.as_ref().is_some_and(|q| q.trim().is_empty()) {
+            return Err(eyre!("filter query can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        edit(self, &ctx.gw).await
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{
+    api::rest::Gateway,
+    command::{Command, Context},
+    output,
+};
+use color_eyre::Result;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    records: output::Records,
+}
+
+pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
+    for filter in gw.filters().await? {
+        params.records.print(filter);
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.gw).await
+    }
+}
@@ -0,0 +1,17 @@
+//! Manages saved filters, so that their queries can be listed by name with `list --saved`.
+use color_eyre::{eyre::eyre, Result};
+
+use crate::api::rest::Filter;
+
+pub mod add;
+pub mod delete;
+pub mod edit;
+pub mod list;
+
+/// Finds the saved filter with the given name, ignoring case.
+pub fn find<'a>(filters: &'a [Filter], name: &str) -> Result<&'a Filter> {
+    filters
+        .iter()
+        .find(|f| f.name.to_lowercase() == name.to_lowercase())
+        .ok_or_else(|| eyre!("no saved filter is named {}", name))
+}
@@ -0,0 +1,234 @@
+//! Turns the completed tasks into habit metrics, by tracking the goals of the config and how many
+//! periods in a row they were reached.
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, Utc};
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    api::rest::{CompletedTask, Gateway, Project},
+    chart,
+    command::{Command, Context},
+    config::{Config, Goal, Period},
+    duration::Since,
+};
+
+/// How many periods, including the current one, are looked at to find streaks.
+const HISTORY: u32 = 12;
+
+/// The width of the progress bar of the current period.
+const BAR_WIDTH: usize = 10;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Progress is how a goal did in its recent periods.
+#[derive(Debug, PartialEq, Eq)]
+struct Progress {
+    /// The tasks completed in the current period.
+    current: usize,
+    /// The periods in a row that the goal was reached, up to now.
+    streak: usize,
+    /// The longest streak in the history.
+    best: usize,
+}
+
+/// Shows the progress of every goal in the config.
+pub async fn goals(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    if cfg.goals.is_empty() {
+        return Err(eyre!(
+            "no goals are set in the config, add them as [[goals]] entries"
+        ));
+    }
+    let now = cfg
+        .override_time
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local);
+    let today = now.date_naive();
+    let since = cfg
+        .goals
+        .iter()
+        .map(|g| period_start(g.period, today, HISTORY - 1))
+        .min()
+        .unwrap_or(today);
+    let since = Since::Date(since).resolve(now).with_timezone(&Utc);
+    let (completed, projects) = tokio::try_join!(gw.completed(&since), gw.projects())?;
+    for goal in &cfg.goals {
+        let matching = matches(goal, &projects)?;
+        let days = completed
+            .iter()
+            .filter(|c| matching(c))
+            .map(|c| c.completed_at.with_timezone(&Local).date_naive())
+            .collect::<Vec<_>>();
+        let progress = progress(goal, today, &days);
+        println!("{}", render(goal, &progress));
+    }
+    Ok(())
+}
+
+/// Returns a check for whether a completed task counts towards the goal.
+fn matches<'a>(
+    goal: &'a Goal,
+    projects: &[Project],
+) -> Result<impl Fn(&CompletedTask) -> bool + 'a> {
+    let project = goal
+        .project
+        .as_ref()
+        .map(|name| {
+            projects
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .map(|p| p.id.clone())
+                .ok_or_else(|| eyre!("goal {:?} has an unknown project {:?}", goal.name, name))
+        })
+        .transpose()?;
+    Ok(move |c: &CompletedTask| {
+        project.as_ref().is_none_or(|p| c.project_id == *p)
+            && goal
+                .label
+                .as_ref()
+                .is_none_or(|l| c.labels().iter().any(|cl| cl == l))
+    })
+}
+
+/// Returns the first day of the period that is `back` periods before the one containing `day`.
+fn period_start(period: Period, day: NaiveDate, back: u32) -> NaiveDate {
+    match period {
+        Period::Day => day - Duration::days(back.into()),
+        Period::Week => {
+            day - Duration::days(day.weekday().num_days_from_monday() as i64 + 7 * i64::from(back))
+        }
+        Period::Month => day.with_day(1).unwrap_or(day) - Months::new(back),
+    }
+}
+
+/// Counts the completions of every period and finds the streaks in them.
+fn progress(goal: &Goal, today: NaiveDate, days: &[NaiveDate]) -> Progress {
+    let mut counts = vec![0; HISTORY as usize];
+    for day in days {
+        if let Some(i) = (0..HISTORY).find(|&i| *day >= period_start(goal.period, today, i)) {
+            counts[i as usize] += 1;
+        }
+    }
+    let reached = counts.iter().map(|&c| c >= goal.count).collect::<Vec<_>>();
+    // The current period is still running, so not having reached it yet doesn't break the streak.
+    let streak = usize::from(reached[0]) + reached[1..].iter().take_while(|&&r| r).count();
+    let best = reached
+        .split(|&r| !r)
+        .map(|run| run.len())
+        .max()
+        .unwrap_or_default();
+    Progress {
+        current: counts[0],
+        streak,
+        best,
+    }
+}
+
+fn render(goal: &Goal, progress: &Progress) -> String {
+    let (current, unit) = match goal.period {
+        Period::Day => ("today", "day"),
+        Period::Week => ("this week", "week"),
+        Period::Month => ("this month", "month"),
+    };
+    let filled = (progress.current.min(goal.count) * BAR_WIDTH)
+        .checked_div(goal.count)
+        .unwrap_or(BAR_WIDTH);
+    format!(
+        "{}: {}/{} {} {} streak of {} {}{}, best {}",
+        goal.name,
+        progress.current,
+        goal.count,
+        current,
+        chart::bar(filled, BAR_WIDTH),
+        progress.streak,
+        unit,
+        if progress.streak == 1 { "" } else { "s" },
+        progress.best,
+    )
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        goals(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn goal(count: usize, period: Period) -> Goal {
+        Goal {
+            name: "Deep work".to_string(),
+            label: None,
+            project: None,
+            count,
+            period,
+        }
+    }
+
+    #[test]
+    fn starts_periods() {
+        // A Friday.
+        let day = NaiveDate::from_ymd_opt(2022, 8, 26).unwrap();
+        let date = |m, d| NaiveDate::from_ymd_opt(2022, m, d).unwrap();
+        assert_eq!(period_start(Period::Day, day, 0), day);
+        assert_eq!(period_start(Period::Day, day, 3), date(8, 23));
+        assert_eq!(period_start(Period::Week, day, 0), date(8, 22));
+        assert_eq!(period_start(Period::Week, day, 2), date(8, 8));
+        assert_eq!(period_start(Period::Month, day, 0), date(8, 1));
+        assert_eq!(
+            period_start(Period::Month, day, 8),
+            NaiveDate::from_ymd_opt(2021, 12, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn counts_streaks() {
+        let today = NaiveDate::from_ymd_opt(2022, 8, 26).unwrap();
+        let ago = |days| today - Duration::days(days);
+        // Reached in the last two weeks, missed the one before, and reached three before that.
+        let mut days = vec![ago(0), ago(7), ago(8), ago(14), ago(15)];
+        days.extend([ago(28), ago(29), ago(35), ago(36), ago(42), ago(43)]);
+        assert_eq!(
+            progress(&goal(2, Period::Week), today, &days),
+            Progress {
+                current: 1,
+                streak: 2,
+                best: 3,
+            }
+        );
+        assert_eq!(
+            progress(&goal(1, Period::Week), today, &days),
+            Progress {
+                current: 1,
+                streak: 3,
+                best: 3,
+            }
+        );
+        assert_eq!(
+            progress(&goal(1, Period::Day), today, &[]),
+            Progress {
+                current: 0,
+                streak: 0,
+                best: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn renders_progress() {
+        let progress = Progress {
+            current: 3,
+            streak: 1,
+            best: 4,
+        };
+        assert_eq!(
+            render(&goal(5, Period::Week), &progress),
+            "Deep work: 3/5 this week [██████░░░░] streak of 1 week, best 4"
+        );
+        assert_eq!(
+            render(&goal(0, Period::Day), &progress),
+            "Deep work: 3/0 today [██████████] streak of 1 day, best 4"
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Knows the holidays of the config, so due dates can be scheduled in business days and away from
+//! days off.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc, Weekday};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{api::rest::TaskDue, config::Config};
+
+/// The file in the store that caches holidays which were downloaded.
+const CACHE_FILE: &str = "holidays.json";
+
+/// How long downloaded holidays are used before they are downloaded again.
+const CACHE_DAYS: i64 = 30;
+
+/// Where the public holidays of a country are downloaded from.
+const COUNTRY_URL: &str = "https://date.nager.at/api/v3/PublicHolidays";
+
+/// Scheduling changes how due dates are resolved, taking weekends and holidays into account.
+#[derive(clap::Args, Debug, Default)]
+pub struct Scheduling {
+    /// Counts due dates like `+3d` in business days, skipping weekends and the holidays of the
+    /// config.
+    #[arg(long = "business-days")]
+    pub business_days: bool,
+    /// Moves due dates that fall on a holiday of the config to the next day that is not one.
+    #[arg(long = "skip-holidays")]
+    pub skip_holidays: bool,
+}
+
+impl Scheduling {
+    /// Resolves the due date locally if any of the modes is set, or leaves it to Todoist
+    /// otherwise.
+    pub async fn due(&self, due: String, cfg: &Config) -> Result<TaskDue> {
+        if !self.business_days && !self.skip_holidays {
+            return Ok(TaskDue::String(due));
+        }
+        let today = cfg
+            .override_time
+            .unwrap_or_else(Utc::now)
+            .with_timezone(&Local)
+            .date_naive();
+        let calendar = Calendar::load(cfg, today.year()).await?;
+        let day = match parse_due(&due, today)? {
+            Due::Date(day) => calendar.next_open(day, self.business_days),
+            Due::Days(days) if self.business_days => calendar.add_business_days(today, days),
+            Due::Days(days) => calendar.next_open(today + Duration::days(days.into()), false),
+            Due::Weeks(weeks) if self.business_days => calendar.add_business_days(today, weeks * 5),
+            Due::Weeks(weeks) => calendar.next_open(today + Duration::weeks(weeks.into()), false),
+        };
+        Ok(TaskDue::Date(day.format("%Y-%m-%d").to_string()))
+    }
+}
+
+/// A due date that can be resolved without Todoist.
+#[derive(Debug, PartialEq, Eq)]
+enum Due {
+    Date(NaiveDate),
+    Days(u32),
+    Weeks(u32),
+}
+
+fn parse_due(due: &str, today: NaiveDate) -> Result<Due> {
+    let due = due.trim().to_lowercase();
+    let offset = |unit| {
+        due.strip_prefix('+')
+            .and_then(|d| d.strip_suffix(unit))
+            .and_then(|n| n.parse::<u32>().ok())
+    };
+    if let Some(days) = offset('d') {
+        return Ok(Due::Days(days));
+    }
+    if let Some(weeks) = offset('w') {
+        return Ok(Due::Weeks(weeks));
+    }
+    match due.as_str() {
+        "today" => Ok(Due::Date(today)),
+        "tomorrow" => Ok(Due::Days(1)),
+        _ => due.parse().map(Due::Date).map_err(|_| {
+            eyre!(
+                "due date {:?} can't be scheduled around holidays, use one like +3d, +1w, tomorrow or 2022-09-01",
+                due
+            )
+        }),
+    }
+}
+
+/// Calendar is the set of holidays from the config.
+#[derive(Debug, Default)]
+pub(crate) struct Calendar {
+    days: BTreeSet<NaiveDate>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    sources: BTreeMap<String, Cached>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Cached {
+    fetched: DateTime<Utc>,
+    days: BTreeSet<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+struct PublicHoliday {
+    date: NaiveDate,
+    #[serde(default = "default_global")]
+    global: bool,
+}
+
+fn default_global() -> bool {
+    true
+}
+
+impl Calendar {
+    /// Loads the holidays of the year and the one after it, downloading them if they are not
+    /// cached yet.
+    pub async fn load(cfg: &Config, year: i32) -> Result<Calendar> {
+        let holidays = &cfg.holidays;
+        if holidays.country.is_none() && holidays.ics.is_none() {
+            return Err(eyre!(
+                "no holidays are set in the config, add a country or an ICS calendar to [holidays]"
+            ));
+        }
+        let store = cfg.store()?;
+        let mut cache: Cache = store.load(CACHE_FILE)?;
+        let now = cfg.override_time.unwrap_or_else(Utc::now);
+        let mut sources = Vec::new();
+        if let Some(country) = &holidays.country {
+            for year in [year, year + 1] {
+                sources.push(format!(
+                    "{}/{}/{}",
+                    COUNTRY_URL,
+                    year,
+                    country.to_uppercase()
+                ));
+            }
+        }
+        sources.extend(holidays.ics.clone());
+        let mut calendar = Calendar::default();
+        for source in sources {
+            let remote = source.starts_with("http://") || source.starts_with("https://");
+            let days = match cache.sources.get(&source) {
+                Some(cached) if remote && now - cached.fetched < Duration::days(CACHE_DAYS) => {
+                    cached.days.clone()
+                }
+                _ => {
+                    let body = if remote {
+                        download(&source).await?
+                    } else {
+                        fs::read_to_string(&source)
+                            .wrap_err_with(|| format!("unable to read holidays from {}", source))?
+                    };
+                    let days = if source.starts_with(COUNTRY_URL) {
+                        parse_public_holidays(&body)?
+                    } else {
+                        parse_ics(&body)?
+                    };
+                    if remote {
+                        cache.sources.insert(
+                            source.clone(),
+                            Cached {
+                                fetched: now,
+                                days: days.clone(),
+                            },
+                        );
+                    }
+                    days
+                }
+            };
+            calendar.days.extend(days);
+        }
+        store.save(CACHE_FILE, &cache)?;
+        Ok(calendar)
+    }
+
+    /// Returns whether the day is a weekday that is not a holiday.
+    pub fn is_business_day(&self, day: NaiveDate) -> bool {
+        !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) && !self.days.contains(&day)
+    }
+
+    /// Returns the day that is the given amount of business days after `day`.
+    pub fn add_business_days(&self, day: NaiveDate, days: u32) -> NaiveDate {
+        let mut day = day;
+        for _ in 0..days {
+            day = self.next_open(day + Duration::days(1), true);
+        }
+        day
+    }
+
+    /// Returns the day itself if it is not a holiday, or the next day that is not. With `business`
+    /// weekends are skipped as well.
+    pub fn next_open(&self, day: NaiveDate, business: bool) -> NaiveDate {
+        day.iter_days()
+            .find(|d| {
+                if business {
+                    self.is_business_day(*d)
+                } else {
+                    !self.days.contains(d)
+                }
+            })
+            .unwrap_or(day)
+    }
+}
+
+async fn download(url: &str) -> Result<String> {
+    reqwest::get(url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .wrap_err_with(|| format!("unable to download holidays from {}", url))?
+        .text()
+        .await
+        .wrap_err_with(|| format!("unable to download holidays from {}", url))
+}
+
+/// Parses the nationwide holidays out of the public holidays of a country.
+fn parse_public_holidays(body: &str) -> Result<BTreeSet<NaiveDate>> {
+    let holidays: Vec<PublicHoliday> =
+        serde_json::from_str(body).wrap_err("invalid public holidays")?;
+    Ok(holidays
+        .into_iter()
+        .filter(|h| h.global)
+        .map(|h| h.date)
+        .collect())
+}
+
+/// Parses the days of all events in an ICS calendar, including every day of events that span
+/// multiple days.
+fn parse_ics(body: &str) -> Result<BTreeSet<NaiveDate>> {
+    let mut days = BTreeSet::new();
+    let (mut start, mut end) = (None, None);
+    for line in body.lines().map(str::trim) {
+        let date = || {
+            let value = line.rsplit(':').next().unwrap_or_default();
+            NaiveDate::parse_from_str(value.get(..8).unwrap_or(value), "%Y%m%d")
+                .wrap_err_with(|| format!("invalid date in ICS calendar: {}", line))
+        };
+        if line == "BEGIN:VEVENT" {
+            (start, end) = (None, None);
+        } else if line.starts_with("DTSTART") {
+            start = Some(date()?);
+        } else if line.starts_with("DTEND") {
+            end = Some(date()?);
+        } else if line == "END:VEVENT" {
+            let Some(start) = start else {
+                continue;
+            };
+            // The end of all-day events is exclusive.
+            let end = end
+                .filter(|e| *e > start)
+                .unwrap_or(start + Duration::days(1));
+            days.extend(start.iter_days().take_while(|d| *d < end));
+        }
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2022, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_calendars() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;VALUE=DATE:20221003\r\n\
+                   SUMMARY:Unity day\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;VALUE=DATE:20221224\r\n\
+                   DTEND;VALUE=DATE:20221227\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+        assert_eq!(
+            parse_ics(ics).unwrap().into_iter().collect::<Vec<_>>(),
+            [date(10, 3), date(12, 24), date(12, 25), date(12, 26)]
+        );
+        let json = r#"[
+            {"date": "2022-10-03", "name": "Unity day", "global": true},
+            {"date": "2022-10-31", "name": "Reformation day", "global": false}
+        ]"#;
+        assert_eq!(
+            parse_public_holidays(json)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [date(10, 3)]
+        );
+    }
+
+    #[test]
+    fn schedules_around_holidays() {
+        let calendar = Calendar {
+            days: [date(8, 29), date(9, 5)].into(),
+        };
+        // A Friday, followed by a holiday on Monday.
+        let friday = date(8, 26);
+        assert_eq!(calendar.add_business_days(friday, 1), date(8, 30));
+        assert_eq!(calendar.add_business_days(friday, 3), date(9, 1));
+        assert_eq!(calendar.add_business_days(friday, 0), friday);
+        assert_eq!(calendar.next_open(date(8, 27), false), date(8, 27));
+        assert_eq!(calendar.next_open(date(8, 27), true), date(8, 30));
+        assert_eq!(calendar.next_open(date(9, 5), false), date(9, 6));
+    }
+
+    #[test]
+    fn parses_due_dates() {
+        let today = date(8, 26);
+        assert_eq!(parse_due("+3d", today).unwrap(), Due::Days(3));
+        assert_eq!(parse_due("+2W", today).unwrap(), Due::Weeks(2));
+        assert_eq!(parse_due("tomorrow", today).unwrap(), Due::Days(1));
+        assert_eq!(parse_due("today", today).unwrap(), Due::Date(today));
+        assert_eq!(
+            parse_due("2022-09-05", today).unwrap(),
+            Due::Date(date(9, 5))
+        );
+        assert!(parse_due("next monday", today).is_err());
+    }
+}
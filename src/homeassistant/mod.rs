@@ -0,0 +1,140 @@
+//! Reports the task load to Home Assistant, as a sensor that dashboards and automations can use.
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+
+use crate::api::rest::{Project, Task};
+
+pub mod sensor;
+
+/// Sensor is the state of the tasks. Home Assistant reads it as JSON, through a REST or command
+/// line sensor or from an MQTT topic.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct Sensor {
+    /// The tasks that are overdue or due today, which is the state of the sensor.
+    due: usize,
+    /// The tasks that are overdue.
+    overdue: usize,
+    /// The tasks that are due later today.
+    today: usize,
+    /// All open tasks.
+    total: usize,
+    /// The task that is due first.
+    next: Option<NextTask>,
+}
+
+/// The task to work on next.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct NextTask {
+    id: String,
+    content: String,
+    /// When the task is due, as a date or a local time.
+    due: String,
+    project: Option<String>,
+    url: String,
+}
+
+impl Sensor {
+    /// Counts the tasks that are due at the given time and finds the one that is due first.
+    fn new(tasks: &[Task], projects: &[Project], now: &DateTime<Utc>) -> Sensor {
+        let today = now.with_timezone(&Local).date_naive();
+        let mut overdue = 0;
+        let mut due_today = 0;
+        for task in tasks {
+            let Some(due) = &task.due else {
+                continue;
+            };
+            let late = match &due.exact {
+                Some(exact) => exact.datetime < *now,
+                None => due.date < today,
+            };
+            if late {
+                overdue += 1;
+            } else if due.date == today {
+                due_today += 1;
+            }
+        }
+        let next = tasks
+            .iter()
+            .filter_map(|task| {
+                let due = task.due.as_ref()?;
+                let at = match &due.exact {
+                    Some(exact) => exact.datetime.with_timezone(&Local).naive_local(),
+                    None => due.date.and_hms_opt(0, 0, 0)?,
+                };
+                Some((at, task))
+            })
+            .min_by_key(|(at, _)| *at)
+            .map(|(_, task)| NextTask {
+                id: task.id.clone(),
+                content: task.content.clone(),
+                due: task
+                    .due
+                    .as_ref()
+                    .map(|due| match &due.exact {
+                        Some(exact) => exact
+                            .datetime
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string(),
+                        None => due.date.to_string(),
+                    })
+                    .unwrap_or_default(),
+                project: projects
+                    .iter()
+                    .find(|p| p.id == task.project_id)
+                    .map(|p| p.name.clone()),
+                url: task.url.to_string(),
+            });
+        Sensor {
+            due: overdue + due_today,
+            overdue,
+            today: due_today,
+            total: tasks.len(),
+            next,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::api::rest::{DueDate, ExactTime};
+
+    use super::*;
+
+    #[test]
+    fn counts_tasks() {
+        let now = Utc::now();
+        let today = now.with_timezone(&Local).date_naive();
+        let due = |date: NaiveDate| {
+            Some(DueDate {
+                string: String::new(),
+                is_recurring: false,
+                date,
+                exact: None,
+            })
+        };
+        let mut late = Task::new("1", "late");
+        late.due = due(today - Duration::days(2));
+        let mut now_due = Task::new("2", "today");
+        now_due.due = due(today);
+        let mut later = Task::new("3", "later");
+        later.due = due(today + Duration::days(3));
+        let mut missed = Task::new("4", "missed");
+        missed.due = Some(DueDate {
+            exact: Some(ExactTime {
+                datetime: (now - Duration::minutes(5)).fixed_offset(),
+                timezone: "UTC".to_string(),
+            }),
+            ..due(today).unwrap()
+        });
+        let tasks = [later, now_due, Task::new("5", "whenever"), missed, late];
+        let sensor = Sensor::new(&tasks, &[], &now);
+        assert_eq!(sensor.due, 3);
+        assert_eq!(sensor.overdue, 2);
+        assert_eq!(sensor.today, 1);
+        assert_eq!(sensor.total, 5);
+        assert_eq!(sensor.next.unwrap().id, "1");
+    }
+}
@@ -0,0 +1,111 @@
+//! Posts completed tasks to webhooks like the ones of Slack or Discord, following the hooks of the
+//! config.
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    api::rest::{CompletedTask, Project},
+    config::{Hook, HookKind},
+};
+
+pub mod run;
+
+/// Returns whether the hook fires for the completed task.
+fn matches(hook: &Hook, task: &CompletedTask, project: Option<&Project>) -> bool {
+    hook.label
+        .as_ref()
+        .is_none_or(|l| task.labels().contains(l))
+        && hook
+            .project
+            .as_ref()
+            .is_none_or(|name| project.is_some_and(|p| p.name.eq_ignore_ascii_case(name)))
+}
+
+/// Builds the message about the completed task in the shape the service of the hook expects.
+fn payload(kind: HookKind, task: &CompletedTask, project: Option<&Project>) -> Value {
+    let mut text = format!("Completed: {}", task.content);
+    if let Some(project) = project {
+        text.push_str(&format!(" [{}]", project.name));
+    }
+    match kind {
+        HookKind::Slack => json!({ "text": text }),
+        HookKind::Discord => json!({ "content": text }),
+        HookKind::Generic => json!({
+            "event": "completed",
+            "text": text,
+            "task": {
+                "id": task.task_id,
+                "content": task.content,
+                "project": project.map(|p| &p.name),
+                "labels": task.labels(),
+                "completed_at": task.completed_at,
+            },
+        }),
+    }
+}
+
+/// Posts the message to the webhook.
+async fn post(client: &reqwest::Client, url: &str, payload: &Value) -> Result<()> {
+    client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload.to_string())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| eyre!("{}", e.without_url()))
+        .wrap_err_with(|| format!("unable to post to the webhook {}", url))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+
+    use crate::api::rest::CompletedTaskDetails;
+
+    use super::*;
+
+    #[test]
+    fn matches_hooks() {
+        let task = CompletedTask {
+            id: "1".to_string(),
+            task_id: "2".to_string(),
+            content: "Ship it".to_string(),
+            project_id: "3".to_string(),
+            section_id: None,
+            completed_at: Utc::now(),
+            task: Some(CompletedTaskDetails {
+                labels: vec!["release".to_string()],
+                ..Default::default()
+            }),
+        };
+        let project = Project::new("3", "Website");
+        let hook = |label: Option<&str>, project: Option<&str>| Hook {
+            label: label.map(str::to_string),
+            project: project.map(str::to_string),
+            ..Default::default()
+        };
+        assert!(matches(&hook(None, None), &task, None));
+        assert!(matches(&hook(Some("release"), None), &task, None));
+        assert!(!matches(&hook(Some("bug"), None), &task, None));
+        assert!(matches(
+            &hook(Some("release"), Some("website")),
+            &task,
+            Some(&project)
+        ));
+        assert!(!matches(&hook(None, Some("Website")), &task, None));
+
+        assert_eq!(
+            payload(HookKind::Slack, &task, Some(&project)),
+            json!({ "text": "Completed: Ship it [Website]" })
+        );
+        assert_eq!(
+            payload(HookKind::Discord, &task, None),
+            json!({ "content": "Completed: Ship it" })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::{eyre::WrapErr, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::rest::Gateway,
+    cancel,
+    command::{Command, Context},
+    config::Config,
+    duration,
+};
+
+/// The file in the store that remembers which completions were already posted.
+const STATE_FILE: &str = "hooks.json";
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Keeps running and checks for completed tasks on an interval. Stop it with Ctrl-C.
+    #[arg(long = "daemon")]
+    daemon: bool,
+    /// How often the daemon checks for completed tasks, like `30s` or `5m`.
+    #[arg(long = "interval", default_value = "1m", value_parser = duration::parse, requires = "daemon")]
+    interval: Duration,
+}
+
+/// State is how far the completed tasks were posted.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    /// When the last check happened. Completions before it were all posted.
+    since: Option<DateTime<Utc>>,
+    /// The completions since then that were posted already.
+    #[serde(default)]
+    posted: BTreeSet<String>,
+}
+
+/// Posts the tasks that were completed since the last run to the hooks of the config, once or as
+/// a daemon.
+pub async fn run(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    if !params.daemon {
+        return check(gw, cfg).await;
+    }
+    let interval = params.interval.to_std().wrap_err("invalid hook interval")?;
+    while !cancel::is_cancelled() {
+        check(gw, cfg).await?;
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = cancel::cancelled() => {}
+        }
+    }
+    Ok(())
+}
+
+/// Posts every completion since the last check that was not posted yet. The first check only
+/// remembers when it happened, so the history of completed tasks is not posted.
+async fn check(gw: &Gateway, cfg: &Config) -> Result<()> {
+    if cfg.hooks.is_empty() {
+        println!("there are no hooks in the config");
+        return Ok(());
+    }
+    let store = cfg.store()?;
+    let mut state: State = store.load(STATE_FILE)?;
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let Some(since) = state.since else {
+        state.since = Some(now);
+        store.save(STATE_FILE, &state)?;
+        println!("posting tasks that are completed from now on");
+        return Ok(());
+    };
+    let (mut completed, projects) = tokio::try_join!(gw.completed(&since), gw.projects())?;
+    // Posts the oldest completions first.
+    completed.reverse();
+    let client = reqwest::Client::new();
+    let mut posted = 0;
+    for task in &completed {
+        if state.posted.contains(&task.id) {
+            continue;
+        }
+        let project = projects.iter().find(|p| p.id == task.project_id);
+        for hook in cfg
+            .hooks
+            .iter()
+            .filter(|h| super::matches(h, task, project))
+        {
+            let payload = super::payload(hook.kind, task, project);
+            if let Err(e) = super::post(&client, &hook.url, &payload).await {
+                // Keeps what was posted so far, so the next check continues from here.
+                store.save(STATE_FILE, &state)?;
+                return Err(e);
+            }
+            posted += 1;
+        }
+        state.posted.insert(task.id.clone());
+    }
+    state.since = Some(now);
+    state.posted = completed.into_iter().map(|t| t.id).collect();
+    store.save(STATE_FILE, &state)?;
+    if posted > 0 {
+        println!("posted {} messages", posted);
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        run(self, &ctx.gw, &ctx.cfg).await
+    }
+}
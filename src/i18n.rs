@@ -0,0 +1,220 @@
+//! Translates the messages of the command line into the language of the user, with the messages
+//! of every language in a [Fluent](https://projectfluent.org) file next to this module.
+use std::sync::OnceLock;
+
+use chrono::NaiveDate;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+/// Language has translated messages, and writes dates in its locale.
+struct Language {
+    code: &'static str,
+    messages: &'static str,
+    locale: chrono::Locale,
+}
+
+/// The languages with messages, of which the first is used for everything that is not translated.
+const LANGUAGES: &[Language] = &[
+    Language {
+        code: "en",
+        messages: include_str!("i18n/en.ftl"),
+        locale: chrono::Locale::en_US,
+    },
+    Language {
+        code: "de",
+        messages: include_str!("i18n/de.ftl"),
+        locale: chrono::Locale::de_DE,
+    },
+];
+
+/// The languages that Todoist understands due dates in.
+const DUE_LANGUAGES: &[&str] = &[
+    "en", "da", "pl", "zh", "ko", "de", "pt", "ja", "it", "fr", "sv", "ru", "es", "nl", "fi", "nb",
+    "tw",
+];
+
+static TRANSLATION: OnceLock<Translation> = OnceLock::new();
+
+/// Translation looks up messages in the chosen language, and in English if they are missing.
+struct Translation {
+    language: &'static Language,
+    bundle: FluentBundle<FluentResource>,
+    fallback: Option<FluentBundle<FluentResource>>,
+    due_lang: Option<String>,
+}
+
+impl Translation {
+    fn new(language: &'static Language, due_lang: Option<String>) -> Self {
+        Self {
+            language,
+            bundle: bundle(language),
+            fallback: match language.code == LANGUAGES[0].code {
+                true => None,
+                false => Some(bundle(&LANGUAGES[0])),
+            },
+            due_lang,
+        }
+    }
+}
+
+fn bundle(language: &Language) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language.code.parse().expect("language codes are valid");
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Terminals don't need the marks that keep the direction of text in arguments.
+    bundle.set_use_isolating(false);
+    let resource =
+        FluentResource::try_new(language.messages.to_string()).expect("message files are valid");
+    bundle
+        .add_resource(resource)
+        .expect("message files have no duplicates");
+    bundle
+}
+
+/// Returns the language that is configured, or else the one of the locale of the system.
+fn preferred(configured: Option<&str>) -> Option<LanguageIdentifier> {
+    let locale = match configured {
+        Some(language) => language.to_string(),
+        None => ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())?,
+    };
+    // Locales of the system look like `de_DE.UTF-8` or `sr_RS@latin`.
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    locale.replace('_', "-").parse().ok()
+}
+
+/// Uses the configured language, or the one of the system, for all messages from now on. Can only
+/// be done once, later calls are ignored.
+pub fn install(configured: Option<&str>) {
+    let preferred = preferred(configured);
+    if preferred.is_none() && configured.is_some() {
+        eprintln!(
+            "unknown language {:?} in the config, using English",
+            configured
+        );
+    }
+    let code = preferred
+        .as_ref()
+        .map(|id| id.language.as_str())
+        .unwrap_or_default();
+    let language = LANGUAGES
+        .iter()
+        .find(|l| l.code == code)
+        .unwrap_or(&LANGUAGES[0]);
+    // Due dates are typed in English unless a language is configured on purpose, as the locale of
+    // the system often isn't what people type in.
+    let due_lang = Some(code)
+        .filter(|_| configured.is_some())
+        .filter(|code| *code != "en" && DUE_LANGUAGES.contains(code))
+        .map(str::to_string);
+    let _ = TRANSLATION.set(Translation::new(language, due_lang));
+}
+
+fn translation() -> &'static Translation {
+    TRANSLATION.get_or_init(|| Translation::new(&LANGUAGES[0], None))
+}
+
+/// Returns the message with the given ID, filled in with the arguments.
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    let translation = translation();
+    let found = std::iter::once(&translation.bundle)
+        .chain(&translation.fallback)
+        .find_map(|bundle| Some((bundle, bundle.get_message(id)?.value()?)));
+    let Some((bundle, pattern)) = found else {
+        return id.to_string();
+    };
+    let mut errors = Vec::new();
+    bundle
+        .format_pattern(pattern, args, &mut errors)
+        .into_owned()
+}
+
+/// Formats the date like `strftime`, with the names of days and months in the chosen language.
+/// The format is usually a message as well, as the order differs between languages.
+pub fn date(date: NaiveDate, format: &str) -> String {
+    date.format_localized(format, translation().language.locale)
+        .to_string()
+}
+
+/// Returns the language that due dates are typed in, if it's not English.
+pub fn due_lang() -> Option<String> {
+    translation().due_lang.clone()
+}
+
+/// Translates a message, like `t!("closed-task", id = id)`.
+macro_rules! t {
+    ($id:literal) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+pub(crate) use t;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn translates_with_fallback() {
+        let german = Translation::new(&LANGUAGES[1], None);
+        let mut args = FluentArgs::new();
+        args.set("count", 1);
+        let pattern = german
+            .bundle
+            .get_message("tasks")
+            .and_then(|m| m.value())
+            .unwrap();
+        let mut errors = Vec::new();
+        assert_eq!(
+            german
+                .bundle
+                .format_pattern(pattern, Some(&args), &mut errors),
+            "1 Aufgabe"
+        );
+        assert!(errors.is_empty());
+        assert_eq!(message("tasks", Some(&args)), "1 task");
+        assert_eq!(message("no-such-message", None), "no-such-message");
+    }
+
+    #[test]
+    fn every_message_is_translated() {
+        // Messages start at the beginning of a line, attributes and variants are indented.
+        let ids = LANGUAGES[0]
+            .messages
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .filter_map(|line| line.split_once(" ="))
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert!(!ids.is_empty());
+        for language in &LANGUAGES[1..] {
+            let bundle = bundle(language);
+            for id in &ids {
+                assert!(
+                    bundle.has_message(id),
+                    "{} is missing {}",
+                    language.code,
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reads_locales() {
+        assert_eq!(
+            preferred(Some("de_DE.UTF-8")).map(|id| id.to_string()),
+            Some("de-DE".to_string())
+        );
+        assert_eq!(
+            preferred(Some("sr_RS@latin")).map(|id| id.to_string()),
+            Some("sr-RS".to_string())
+        );
+        assert_eq!(preferred(Some("")), None);
+    }
+}
@@ -0,0 +1,33 @@
+# Messages of doist in German.
+
+## How much there is to do, in `today` and `upcoming`.
+tasks = { $count ->
+    [one] 1 Aufgabe
+   *[other] { $count } Aufgaben
+}
+overdue = Überfällig
+overdue-count = { $count } überfällig
+estimated = { $duration } geschätzt
+free = { $bar } { $duration } frei
+over = { $bar } { $duration } zu viel
+today-date = Heute, %A, %-d. %B
+day-date = %a, %-d. %b
+day-date-today = %a, %-d. %b (heute)
+day-date-tomorrow = %a, %-d. %b (morgen)
+nothing-due = nichts fällig
+unknown-project = Unbekanntes Projekt
+
+## Changes to tasks.
+created-task = Aufgabe erstellt: { $task }
+closed-task = Aufgabe { $id } geschlossen
+completed-task = Aufgabe { $id } erledigt
+next-due-date = nächstes Fälligkeitsdatum: { $date }
+
+## Output for screen readers.
+task-position = Aufgabe { $index } von { $count }: { $task }
+subtask-level = { $level ->
+    [1] Unteraufgabe
+   *[other] Unteraufgabe Ebene { $level }
+}
+quote = Zitat
+capacity-used = { $percent } % belegt
@@ -0,0 +1,34 @@
+# Messages of doist in English, which every other language falls back to. Dates are formatted like
+# strftime, with the names of days and months in the language.
+
+## How much there is to do, in `today` and `upcoming`.
+tasks = { $count ->
+    [one] 1 task
+   *[other] { $count } tasks
+}
+overdue = Overdue
+overdue-count = { $count } overdue
+estimated = { $duration } estimated
+free = { $bar } { $duration } free
+over = { $bar } { $duration } over
+today-date = Today, %A %-d %B
+day-date = %a %-d %b
+day-date-today = %a %-d %b (today)
+day-date-tomorrow = %a %-d %b (tomorrow)
+nothing-due = nothing due
+unknown-project = Unknown project
+
+## Changes to tasks.
+created-task = created task: { $task }
+closed-task = closed task { $id }
+completed-task = completed task { $id }
+next-due-date = next due date: { $date }
+
+## Output for screen readers.
+task-position = Task { $index } of { $count }: { $task }
+subtask-level = { $level ->
+    [1] subtask
+   *[other] subtask level { $level }
+}
+quote = quote
+capacity-used = { $percent }% used
@@ -0,0 +1,141 @@
+//! Scrubs the content of an import file, so it can be shared in bug reports.
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use color_eyre::{eyre::WrapErr, Result};
+
+use crate::command::{Command, Context};
+
+use super::spec::Spec;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// JSON file in the format of `doist import`, whose content should be scrubbed.
+    file: PathBuf,
+    /// Writes the scrubbed file here instead of printing it.
+    #[arg(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+}
+
+/// Names gives every distinct name a stable replacement, so references between items still work.
+struct Names<'a> {
+    kind: &'a str,
+    names: HashMap<String, String>,
+}
+
+impl<'a> Names<'a> {
+    fn new(kind: &'a str) -> Self {
+        Self {
+            kind,
+            names: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, name: &str) -> String {
+        // The Inbox exists in every account, so it gives nothing away.
+        if name == "Inbox" {
+            return name.to_string();
+        }
+        let next = self.names.len() + 1;
+        self.names
+            .entry(name.to_string())
+            .or_insert_with(|| format!("{}{}", self.kind, next))
+            .clone()
+    }
+}
+
+/// Replaces all names and texts of the spec, while keeping its structure, due dates and
+/// priorities.
+fn anonymize(spec: Spec) -> Spec {
+    let mut projects = Names::new("Project ");
+    let mut sections = Names::new("Section ");
+    let mut labels = Names::new("label");
+    let mut keys = Names::new("task");
+    let mut spec = spec;
+    for project in &mut spec.projects {
+        project.name = projects.get(&project.name);
+        project.parent = project.parent.as_deref().map(|p| projects.get(p));
+        for section in &mut project.sections {
+            *section = sections.get(section);
+        }
+    }
+    for (i, task) in spec.tasks.iter_mut().enumerate() {
+        task.content = format!("Task {}", i + 1);
+        task.description = task
+            .description
+            .as_ref()
+            .map(|_| format!("Description of task {}", i + 1));
+        task.key = task.key.as_deref().map(|k| keys.get(k));
+        task.parent = task.parent.as_deref().map(|k| keys.get(k));
+        task.project = task.project.as_deref().map(|p| projects.get(p));
+        task.section = task.section.as_deref().map(|s| sections.get(s));
+        for label in &mut task.labels {
+            *label = labels.get(label);
+        }
+        for (j, comment) in task.comments.iter_mut().enumerate() {
+            *comment = format!("Comment {} on task {}", j + 1, i + 1);
+        }
+    }
+    spec
+}
+
+impl Command for Params {
+    async fn execute(self, _ctx: &Context) -> Result<()> {
+        let data = fs::read(&self.file)
+            .wrap_err_with(|| format!("unable to read {}", self.file.display()))?;
+        let spec: Spec = serde_json::from_slice(&data).wrap_err("invalid import file")?;
+        let json = serde_json::to_string_pretty(&anonymize(spec))?;
+        match self.output {
+            Some(output) => fs::write(&output, json + "\n")
+                .wrap_err_with(|| format!("unable to write {}", output.display())),
+            None => {
+                println!("{}", json);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keeps_references() {
+        let spec: Spec = serde_json::from_value(serde_json::json!({
+            "projects": [
+                {"name": "Garden", "sections": ["Flowers"]},
+                {"name": "Roses", "parent": "Garden"},
+            ],
+            "tasks": [
+                {"key": "buy", "content": "Buy some flowers", "description": "Red ones",
+                 "project": "Garden", "section": "Flowers", "labels": ["errand"]},
+                {"content": "Plant them", "parent": "buy", "priority": 2, "due": "tomorrow",
+                 "labels": ["garden", "errand"], "comments": ["In the shade"]},
+                {"content": "Water", "project": "Inbox"},
+            ],
+        }))
+        .unwrap();
+        let spec = anonymize(spec);
+        assert_eq!(
+            serde_json::to_value(&spec).unwrap(),
+            serde_json::json!({
+                "projects": [
+                    {"name": "Project 1", "parent": null, "sections": ["Section 1"]},
+                    {"name": "Project 2", "parent": "Project 1", "sections": []},
+                ],
+                "tasks": [
+                    {"key": "task1", "content": "Task 1", "description": "Description of task 1",
+                     "due": null, "priority": null, "labels": ["label1"], "project": "Project 1",
+                     "section": "Section 1", "parent": null, "completed": false},
+                    {"key": null, "content": "Task 2", "description": null, "due": "tomorrow",
+                     "priority": 2, "labels": ["label2", "label1"], "project": null,
+                     "section": null, "parent": "task1", "completed": false,
+                     "comments": ["Comment 1 on task 2"]},
+                    {"key": null, "content": "Task 3", "description": null, "due": null,
+                     "priority": null, "labels": [], "project": "Inbox", "section": null,
+                     "parent": null, "completed": false},
+                ],
+            })
+        );
+    }
+}
@@ -0,0 +1,294 @@
+//! Imports the lists of Google Tasks through the Google Tasks API.
+use std::{fs, path::PathBuf};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use uuid::Uuid;
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{
+    scheduler,
+    spec::{ProjectSpec, Spec, TaskSpec},
+    ExistingOptions,
+};
+
+/// Where access tokens come from if the credentials don't say otherwise.
+const TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// OAuth credentials of an authorized user with access to Google Tasks, as written by `gcloud
+    /// auth application-default login`.
+    #[arg(long = "credentials")]
+    credentials: PathBuf,
+    /// Only imports the list with this name. Can be used multiple times to import more lists.
+    #[arg(short = 'l', long = "list")]
+    lists: Vec<String>,
+    /// Also imports completed tasks, as completed tasks.
+    #[arg(long = "completed")]
+    completed: bool,
+    /// The address of the Google Tasks API.
+    #[arg(
+        long = "api-url",
+        default_value = "https://tasks.googleapis.com/tasks/v1/",
+        hide = true
+    )]
+    api_url: url::Url,
+    #[clap(flatten)]
+    existing: ExistingOptions,
+    #[clap(flatten)]
+    options: scheduler::Options,
+}
+
+/// Credentials of an authorized user, which are exchanged for an access token.
+#[derive(Debug, Deserialize)]
+struct Credentials {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    #[serde(default)]
+    token_uri: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Token {
+    access_token: String,
+}
+
+/// A page of items of the Google Tasks API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Page<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+    next_page_token: Option<String>,
+}
+
+/// A list of tasks, which becomes a project.
+#[derive(Debug, Deserialize)]
+struct TaskList {
+    id: String,
+    title: String,
+}
+
+/// A task of a list, which becomes a task with its notes as description.
+#[derive(Debug, Deserialize)]
+struct GoogleTask {
+    id: String,
+    #[serde(default)]
+    title: String,
+    notes: Option<String>,
+    /// Either `needsAction` or `completed`.
+    status: String,
+    /// The due date, of which only the date is used by Google Tasks.
+    due: Option<String>,
+    /// The ID of the parent task, as tasks can have a single level of subtasks.
+    parent: Option<String>,
+    /// Orders the tasks among their siblings.
+    #[serde(default)]
+    position: String,
+    #[serde(default)]
+    deleted: bool,
+}
+
+impl GoogleTask {
+    fn completed(&self) -> bool {
+        self.status == "completed"
+    }
+}
+
+/// Client for the Google Tasks API.
+struct Client {
+    http: reqwest::Client,
+    url: url::Url,
+    token: String,
+}
+
+impl Client {
+    /// Gets an access token for the credentials.
+    async fn new(credentials: &Credentials, url: url::Url) -> Result<Client> {
+        let http = reqwest::Client::new();
+        let body = http
+            .post(credentials.token_uri.as_deref().unwrap_or(TOKEN_URI))
+            .form(&[
+                ("client_id", credentials.client_id.as_str()),
+                ("client_secret", credentials.client_secret.as_str()),
+                ("refresh_token", credentials.refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| eyre!("{}", e.without_url()))
+            .wrap_err("unable to get an access token for Google Tasks")?
+            .bytes()
+            .await?;
+        let token: Token =
+            serde_json::from_slice(&body).wrap_err("unable to parse the access token of Google")?;
+        Ok(Client {
+            http,
+            url,
+            token: token.access_token,
+        })
+    }
+
+    /// Gets all items of a paged endpoint.
+    async fn all<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut page_token = None;
+        loop {
+            let mut request = self
+                .http
+                .get(self.url.join(path)?)
+                .bearer_auth(&self.token)
+                .query(query)
+                .query(&[("maxResults", "100")]);
+            if let Some(page_token) = &page_token {
+                request = request.query(&[("pageToken", page_token)]);
+            }
+            let body = request
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| eyre!("{}", e.without_url()))
+                .wrap_err("unable to read from Google Tasks")?
+                .bytes()
+                .await?;
+            let page: Page<T> = serde_json::from_slice(&body)
+                .wrap_err("unable to parse the response of Google Tasks")?;
+            items.extend(page.items);
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => return Ok(items),
+            }
+        }
+    }
+}
+
+/// Imports the lists of Google Tasks as projects with their tasks.
+pub async fn gtasks(params: Params, cfg: &Config) -> Result<()> {
+    let data = fs::read(&params.credentials)
+        .wrap_err_with(|| format!("unable to read {}", params.credentials.display()))?;
+    let credentials: Credentials =
+        serde_json::from_slice(&data).wrap_err("invalid Google credentials")?;
+    let client = Client::new(&credentials, params.api_url).await?;
+    let mut lists = Vec::new();
+    for list in client
+        .all::<TaskList>("users/@me/lists", &[])
+        .await?
+        .into_iter()
+        .filter(|l| {
+            params.lists.is_empty()
+                || params
+                    .lists
+                    .iter()
+                    .any(|n| n.eq_ignore_ascii_case(&l.title))
+        })
+    {
+        let tasks = client
+            .all(
+                &format!("lists/{}/tasks", list.id),
+                &[("showCompleted", "true"), ("showHidden", "true")],
+            )
+            .await?;
+        lists.push((list, tasks));
+    }
+    if let Some(missing) = params
+        .lists
+        .iter()
+        .find(|n| !lists.iter().any(|(l, _)| l.title.eq_ignore_ascii_case(n)))
+    {
+        return Err(eyre!("there is no list {:?} in Google Tasks", missing));
+    }
+    // The same lists resume the same import, even if their tasks changed in the meantime.
+    let ids = lists.iter().map(|(l, _)| l.id.as_str()).collect::<Vec<_>>();
+    let id = Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        format!("gtasks:{}", ids.join(",")).as_bytes(),
+    );
+    let spec = spec(lists, params.completed);
+    super::run(&spec, id, &params.existing, &params.options, cfg).await
+}
+
+/// Converts the lists into a spec, with parents before their subtasks.
+fn spec(lists: Vec<(TaskList, Vec<GoogleTask>)>, completed: bool) -> Spec {
+    let mut spec = Spec::default();
+    for (list, mut tasks) in lists {
+        tasks.retain(|t| !t.deleted && !t.title.trim().is_empty() && (completed || !t.completed()));
+        tasks.sort_by(|a, b| {
+            (a.parent.is_some(), &a.position).cmp(&(b.parent.is_some(), &b.position))
+        });
+        let parents = tasks
+            .iter()
+            .filter(|t| t.parent.is_none())
+            .map(|t| t.id.clone())
+            .collect::<Vec<_>>();
+        spec.tasks.extend(tasks.into_iter().map(|t| {
+            TaskSpec {
+                content: t.title.clone(),
+                description: t.notes.clone().filter(|n| !n.trim().is_empty()),
+                due: t
+                    .due
+                    .as_ref()
+                    .and_then(|due| due.get(..10))
+                    .map(str::to_string),
+                project: Some(list.title.clone()),
+                // Subtasks of tasks that are left out end up at the top of the project.
+                parent: t.parent.clone().filter(|p| parents.contains(p)),
+                completed: t.completed(),
+                key: Some(t.id),
+                ..Default::default()
+            }
+        }));
+        spec.projects.push(ProjectSpec {
+            name: list.title,
+            ..Default::default()
+        });
+    }
+    spec
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        gtasks(self, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_lists() {
+        let tasks: Vec<GoogleTask> = serde_json::from_value(serde_json::json!([
+            {"id": "c", "title": "Pack", "status": "needsAction", "parent": "a", "position": "1"},
+            {"id": "a", "title": "Trip", "notes": "To Rome", "status": "needsAction",
+             "due": "2022-08-26T00:00:00.000Z", "position": "0"},
+            {"id": "b", "title": "Book", "status": "completed", "position": "2"},
+            {"id": "d", "title": "Passport", "status": "needsAction", "parent": "b", "position": "0"},
+            {"id": "e", "title": "Gone", "status": "needsAction", "position": "3", "deleted": true},
+        ]))
+        .unwrap();
+        let list = TaskList {
+            id: "1".to_string(),
+            title: "Travel".to_string(),
+        };
+        let spec = spec(vec![(list, tasks)], false);
+        assert_eq!(spec.projects[0].name, "Travel");
+        let tasks = spec
+            .tasks
+            .iter()
+            .map(|t| (t.key.as_deref().unwrap(), t.parent.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(tasks, [("a", None), ("d", None), ("c", Some("a"))]);
+        assert_eq!(spec.tasks[0].description.as_deref(), Some("To Rome"));
+        assert_eq!(spec.tasks[0].due.as_deref(), Some("2022-08-26"));
+    }
+}
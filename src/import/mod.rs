@@ -0,0 +1,159 @@
+//! Imports projects, sections and tasks in bulk.
+//!
+//! Every source is converted into a [`spec::Spec`] first, which is then created step by step by
+//! the [`scheduler`], so big imports stay under the API rate limits and can be resumed.
+use std::{fs, path::PathBuf};
+
+use dialoguer::console::Term;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use uuid::Uuid;
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+    interactive,
+};
+
+use spec::{Existing, Strategies, Strategy};
+
+pub mod anonymize;
+pub mod gtasks;
+#[cfg(feature = "reminders")]
+pub mod reminders;
+pub mod rtm;
+mod scheduler;
+pub mod seed;
+mod spec;
+pub mod template;
+pub mod wunderlist;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// JSON file that lists the `projects` (with their `sections`) and `tasks` to create.
+    // Optional for clap only, so the subcommands can go without it.
+    #[arg(required = true)]
+    file: Option<PathBuf>,
+    #[clap(flatten)]
+    existing: ExistingOptions,
+    #[clap(flatten)]
+    options: scheduler::Options,
+}
+
+/// Options that decide what happens with items that exist already. Asks for each type of item
+/// when not given and running in a terminal, otherwise merges.
+#[derive(clap::Args, Debug)]
+struct ExistingOptions {
+    /// What to do with projects that have the same name as an existing project.
+    #[arg(long = "existing-projects", value_enum)]
+    projects: Option<Strategy>,
+    /// What to do with sections that have the same name as an existing section in the same project.
+    #[arg(long = "existing-sections", value_enum)]
+    sections: Option<Strategy>,
+    /// What to do with tasks that have the same content as an existing task in the same place.
+    #[arg(long = "existing-tasks", value_enum)]
+    tasks: Option<Strategy>,
+}
+
+/// Returns the strategy for existing items of the given kind, asking for it if needed.
+fn strategy(kind: &str, count: usize, flag: Option<Strategy>) -> Result<Strategy> {
+    if let Some(strategy) = flag {
+        return Ok(strategy);
+    }
+    if count == 0 || !Term::stderr().is_term() {
+        return Ok(Strategy::default());
+    }
+    let items = [
+        "Merge into the existing ones",
+        "Skip them and everything in them",
+        "Create duplicates",
+    ];
+    let prompt = format!("{} {} of the import exist already", count, kind);
+    match interactive::select(&prompt, &items)? {
+        Some(0) => Ok(Strategy::Merge),
+        Some(1) => Ok(Strategy::Skip),
+        Some(2) => Ok(Strategy::Duplicate),
+        _ => Err(eyre!("no choice for existing {} made", kind)),
+    }
+}
+
+/// Fails if one of the wanted lists is not among the lists of the source.
+fn check_lists<'a>(
+    wanted: &[String],
+    lists: impl Iterator<Item = &'a String> + Clone,
+    source: &str,
+) -> Result<()> {
+    match wanted
+        .iter()
+        .find(|n| !lists.clone().any(|l| l.eq_ignore_ascii_case(n)))
+    {
+        Some(missing) => Err(eyre!("there is no list {:?} in {}", missing, source)),
+        None => Ok(()),
+    }
+}
+
+/// Imports everything that is described in the import file.
+pub async fn import(params: Params, cfg: &Config) -> Result<()> {
+    let file = params
+        .file
+        .ok_or_else(|| eyre!("no file to import given"))?;
+    let data = fs::read(&file).wrap_err_with(|| format!("unable to read {}", file.display()))?;
+    let spec: spec::Spec = serde_json::from_slice(&data).wrap_err("invalid import file")?;
+    // The same file resumes the same import.
+    let id = Uuid::new_v5(&Uuid::NAMESPACE_OID, &data);
+    run(&spec, id, &params.existing, &params.options, cfg).await
+}
+
+/// Creates everything of the spec. Running it again with the same `id` resumes the import.
+async fn run(
+    spec: &spec::Spec,
+    id: Uuid,
+    existing_opts: &ExistingOptions,
+    options: &scheduler::Options,
+    cfg: &Config,
+) -> Result<()> {
+    if spec.projects.is_empty() && spec.tasks.is_empty() {
+        return Err(eyre!("nothing to import"));
+    }
+    let gw = &cfg.gateway()?;
+    let store = cfg.store()?;
+    let (mut projects, mut sections, mut tasks) =
+        tokio::try_join!(gw.projects(), gw.sections(), async {
+            match spec.tasks.is_empty() {
+                true => Ok(Vec::new()),
+                false => gw.tasks(None).await,
+            }
+        })?;
+    // Items that an earlier attempt of this import created are not duplicates.
+    let created = scheduler::created(&id, &store)?;
+    projects.retain(|p| !created.contains(&p.id));
+    sections.retain(|s| !created.contains(&s.id));
+    tasks.retain(|t| !created.contains(&t.id));
+    let existing = Existing {
+        projects: &projects,
+        sections: &sections,
+        tasks: &tasks,
+    };
+    let duplicates = spec.plan(&existing, Strategies::default())?.duplicates;
+    let strategies = Strategies {
+        projects: strategy("projects", duplicates.projects, existing_opts.projects)?,
+        sections: strategy("sections", duplicates.sections, existing_opts.sections)?,
+        tasks: strategy("tasks", duplicates.tasks, existing_opts.tasks)?,
+    };
+    let plan = spec.plan(&existing, strategies)?;
+    let summary = scheduler::run(&plan.steps, &id, options, gw, &store).await?;
+    println!("{}", summary);
+    if plan.skipped > 0 {
+        println!("skipped {} items that exist already", plan.skipped);
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        import(self, &ctx.cfg).await
+    }
+}
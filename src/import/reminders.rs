@@ -0,0 +1,194 @@
+//! Imports the lists of Apple Reminders, for moving over from it on macOS.
+//!
+//! Reminders are read through JavaScript for Automation, so nothing beyond `osascript` is needed.
+use std::process;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{
+    scheduler,
+    spec::{ProjectSpec, Spec, TaskSpec},
+    ExistingOptions,
+};
+
+/// Reads all lists with their reminders as JSON. Properties are read for all reminders of a list
+/// at once, as reading them one by one takes seconds per reminder. Due dates are written in local
+/// time, so Todoist understands them.
+const SCRIPT: &str = r#"
+const pad = (n) => String(n).padStart(2, "0");
+const day = (d) => `${d.getFullYear()}-${pad(d.getMonth() + 1)}-${pad(d.getDate())}`;
+const time = (d) => `${day(d)} ${pad(d.getHours())}:${pad(d.getMinutes())}`;
+JSON.stringify(
+  Application("Reminders").lists().map((list) => {
+    const r = list.reminders;
+    const [names, bodies, completed, due, allday, priorities] = [
+      r.name(), r.body(), r.completed(), r.dueDate(), r.alldayDueDate(), r.priority(),
+    ];
+    return {
+      name: list.name(),
+      reminders: names.map((name, i) => ({
+        name,
+        body: bodies[i] || null,
+        completed: completed[i],
+        due: allday[i] ? day(allday[i]) : due[i] ? time(due[i]) : null,
+        priority: priorities[i],
+      })),
+    };
+  })
+);
+"#;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Only imports the list with this name. Can be used multiple times to import more lists.
+    #[arg(short = 'l', long = "list")]
+    lists: Vec<String>,
+    /// Also imports completed reminders, as completed tasks.
+    #[arg(long = "completed")]
+    completed: bool,
+    #[clap(flatten)]
+    existing: ExistingOptions,
+    #[clap(flatten)]
+    options: scheduler::Options,
+}
+
+/// A list of reminders, which becomes a project.
+#[derive(Debug, Deserialize)]
+struct List {
+    name: String,
+    reminders: Vec<Reminder>,
+}
+
+/// A reminder, which becomes a task.
+#[derive(Debug, Deserialize)]
+struct Reminder {
+    name: String,
+    body: Option<String>,
+    completed: bool,
+    /// The due date as `YYYY-MM-DD` or `YYYY-MM-DD HH:MM` in local time.
+    due: Option<String>,
+    /// 0 for none, 1 to 4 for high, 5 for medium and 6 to 9 for low.
+    priority: u8,
+}
+
+/// Imports the lists of Apple Reminders as projects with their reminders as tasks.
+pub async fn reminders(params: Params, cfg: &Config) -> Result<()> {
+    if !cfg!(target_os = "macos") {
+        return Err(eyre!("Apple Reminders can only be imported on macOS"));
+    }
+    let output = process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", SCRIPT])
+        .output()
+        .wrap_err("unable to run osascript")?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "unable to read Apple Reminders, allow access to them in the privacy settings: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let lists: Vec<List> =
+        serde_json::from_slice(&output.stdout).wrap_err("unable to parse Apple Reminders")?;
+    let spec = spec(lists, &params.lists, params.completed)?;
+    // The same reminders resume the same import.
+    let id = Uuid::new_v5(&Uuid::NAMESPACE_OID, &serde_json::to_vec(&spec)?);
+    super::run(&spec, id, &params.existing, &params.options, cfg).await
+}
+
+/// Converts the lists into a spec, keeping only the selected lists or all if none are selected.
+fn spec(lists: Vec<List>, selected: &[String], completed: bool) -> Result<Spec> {
+    if let Some(missing) = selected
+        .iter()
+        .find(|name| !lists.iter().any(|l| l.name.eq_ignore_ascii_case(name)))
+    {
+        return Err(eyre!("there is no list {:?} in Apple Reminders", missing));
+    }
+    let mut spec = Spec::default();
+    for list in lists {
+        if !selected.is_empty() && !selected.iter().any(|s| s.eq_ignore_ascii_case(&list.name)) {
+            continue;
+        }
+        spec.tasks.extend(
+            list.reminders
+                .into_iter()
+                .filter(|r| completed || !r.completed)
+                .map(|r| TaskSpec {
+                    content: r.name,
+                    description: r.body.filter(|b| !b.trim().is_empty()),
+                    due: r.due,
+                    priority: match r.priority {
+                        1..=4 => Some(1),
+                        5 => Some(2),
+                        6..=9 => Some(3),
+                        _ => None,
+                    },
+                    project: Some(list.name.clone()),
+                    completed: r.completed,
+                    ..Default::default()
+                }),
+        );
+        spec.projects.push(ProjectSpec {
+            name: list.name,
+            ..Default::default()
+        });
+    }
+    Ok(spec)
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        reminders(self, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lists() -> Vec<List> {
+        serde_json::from_value(serde_json::json!([
+            {"name": "Groceries", "reminders": [
+                {"name": "Milk", "body": "", "completed": false, "due": "2022-08-26", "priority": 0},
+                {"name": "Bread", "body": null, "completed": true, "due": null, "priority": 0},
+            ]},
+            {"name": "Work", "reminders": [
+                {"name": "Call Bob", "body": "About the offer", "completed": false,
+                 "due": "2022-08-26 14:30", "priority": 1},
+            ]},
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn converts_lists() {
+        let spec = spec(lists(), &[], false).unwrap();
+        assert_eq!(
+            spec.projects.iter().map(|p| &p.name).collect::<Vec<_>>(),
+            ["Groceries", "Work"]
+        );
+        assert_eq!(spec.tasks.len(), 2);
+        assert_eq!(spec.tasks[0].content, "Milk");
+        assert_eq!(spec.tasks[0].description, None);
+        assert_eq!(spec.tasks[0].due.as_deref(), Some("2022-08-26"));
+        assert_eq!(spec.tasks[0].priority, None);
+        assert_eq!(spec.tasks[1].project.as_deref(), Some("Work"));
+        assert_eq!(spec.tasks[1].priority, Some(1));
+        assert_eq!(spec.tasks[1].due.as_deref(), Some("2022-08-26 14:30"));
+
+        let spec = super::spec(lists(), &["groceries".to_string()], true).unwrap();
+        assert_eq!(spec.projects.len(), 1);
+        assert_eq!(spec.tasks.len(), 2);
+        assert!(spec.tasks[1].completed);
+
+        assert!(super::spec(lists(), &["Nope".to_string()], false).is_err());
+    }
+}
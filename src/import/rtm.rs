@@ -0,0 +1,260 @@
+//! Imports the JSON export of Remember The Milk.
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use chrono::{DateTime, TimeZone};
+use color_eyre::{eyre::WrapErr, Result};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{
+    scheduler,
+    spec::{ProjectSpec, Spec, TaskSpec},
+    ExistingOptions,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The JSON file that Remember The Milk exports under Settings → Account → Export.
+    file: PathBuf,
+    /// Only imports the list with this name. Can be used multiple times to import more lists.
+    #[arg(short = 'l', long = "list")]
+    lists: Vec<String>,
+    /// Also imports completed tasks, as completed tasks.
+    #[arg(long = "completed")]
+    completed: bool,
+    #[clap(flatten)]
+    existing: ExistingOptions,
+    #[clap(flatten)]
+    options: scheduler::Options,
+}
+
+/// The parts of the export that are imported.
+#[derive(Debug, Deserialize)]
+struct Export {
+    #[serde(default)]
+    lists: Vec<List>,
+    #[serde(default)]
+    tasks: Vec<RtmTask>,
+    #[serde(default)]
+    notes: Vec<Note>,
+}
+
+/// A list of tasks, which becomes a project.
+#[derive(Debug, Deserialize)]
+struct List {
+    id: String,
+    name: String,
+    /// Only smart lists have a filter, and they don't contain tasks of their own.
+    filter: Option<String>,
+}
+
+/// A task of a list, which becomes a task with its notes as description.
+#[derive(Debug, Deserialize)]
+struct RtmTask {
+    id: String,
+    /// Notes belong to the series of a task, which is shared by all its repetitions.
+    series_id: String,
+    list_id: String,
+    name: String,
+    /// One of `P1`, `P2`, `P3` or `PN` for no priority.
+    #[serde(default)]
+    priority: String,
+    /// Milliseconds since the epoch.
+    date_due: Option<i64>,
+    #[serde(default)]
+    date_due_has_time: bool,
+    date_completed: Option<i64>,
+    date_trashed: Option<i64>,
+    #[serde(default)]
+    tags: Vec<String>,
+    parent_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    series_id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    content: String,
+}
+
+/// Imports the lists of a Remember The Milk export as projects with their tasks.
+pub async fn rtm(params: Params, cfg: &Config) -> Result<()> {
+    let data = fs::read(&params.file)
+        .wrap_err_with(|| format!("unable to read {}", params.file.display()))?;
+    let export: Export =
+        serde_json::from_slice(&data).wrap_err("invalid Remember The Milk export")?;
+    super::check_lists(
+        &params.lists,
+        export.lists.iter().map(|l| &l.name),
+        "Remember The Milk",
+    )?;
+    // The same file resumes the same import.
+    let id = Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        &[b"rtm:".as_slice(), data.as_slice()].concat(),
+    );
+    let spec = spec(export, &params.lists, params.completed, &chrono::Local);
+    super::run(&spec, id, &params.existing, &params.options, cfg).await
+}
+
+/// Converts the export into a spec, with parents before their subtasks. Due dates are read in the
+/// given time zone, as Remember The Milk stores dates without a time as midnight of the user.
+fn spec<Tz: TimeZone>(export: Export, lists: &[String], completed: bool, tz: &Tz) -> Spec
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let projects = export
+        .lists
+        .into_iter()
+        .filter(|l| {
+            l.filter.is_none()
+                && (lists.is_empty() || lists.iter().any(|n| n.eq_ignore_ascii_case(&l.name)))
+        })
+        .map(|l| (l.id, l.name))
+        .collect::<HashMap<_, _>>();
+    let mut notes = HashMap::<_, Vec<_>>::new();
+    for note in export.notes {
+        let text = [note.title.trim(), note.content.trim()]
+            .into_iter()
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.is_empty() {
+            notes.entry(note.series_id).or_default().push(text);
+        }
+    }
+    let mut tasks = export
+        .tasks
+        .into_iter()
+        .filter(|t| {
+            t.date_trashed.is_none()
+                && !t.name.trim().is_empty()
+                && projects.contains_key(&t.list_id)
+                && (completed || t.date_completed.is_none())
+        })
+        .collect::<Vec<_>>();
+    let parents = tasks
+        .iter()
+        .map(|t| (t.id.clone(), t.parent_id.clone()))
+        .collect::<HashMap<_, _>>();
+    // Subtasks can be nested, so every task goes after all of its ancestors.
+    let depth = |task: &RtmTask| {
+        let mut depth = 0;
+        let mut parent = task.parent_id.as_ref();
+        while let Some(Some(next)) = parent.map(|p| parents.get(p)) {
+            depth += 1;
+            parent = next.as_ref();
+            if depth > parents.len() {
+                break;
+            }
+        }
+        depth
+    };
+    tasks.sort_by_cached_key(depth);
+    let mut spec = Spec::default();
+    let mut names = projects.values().cloned().collect::<Vec<_>>();
+    names.sort();
+    spec.projects = names
+        .into_iter()
+        .map(|name| ProjectSpec {
+            name,
+            ..Default::default()
+        })
+        .collect();
+    spec.tasks = tasks
+        .into_iter()
+        .map(|t| TaskSpec {
+            content: t.name,
+            description: notes.get(&t.series_id).map(|n| n.join("\n\n")),
+            due: t
+                .date_due
+                .and_then(DateTime::from_timestamp_millis)
+                .map(|due| {
+                    let due = due.with_timezone(tz);
+                    match t.date_due_has_time {
+                        true => due.format("%Y-%m-%d %H:%M").to_string(),
+                        false => due.format("%Y-%m-%d").to_string(),
+                    }
+                }),
+            priority: match t.priority.as_str() {
+                "P1" => Some(1),
+                "P2" => Some(2),
+                "P3" => Some(3),
+                _ => None,
+            },
+            labels: t.tags,
+            project: projects.get(&t.list_id).cloned(),
+            // Subtasks of tasks that are left out end up at the top of the project.
+            parent: t.parent_id.filter(|p| parents.contains_key(p)),
+            completed: t.date_completed.is_some(),
+            key: Some(t.id),
+            ..Default::default()
+        })
+        .collect();
+    spec
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        rtm(self, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_export() {
+        let export: Export = serde_json::from_value(serde_json::json!({
+            "lists": [
+                {"id": "1", "name": "Travel"},
+                {"id": "2", "name": "This week", "filter": "dueBefore:\"1 week\""},
+            ],
+            "tasks": [
+                {"id": "c", "series_id": "sc", "list_id": "1", "name": "Socks", "parent_id": "b"},
+                {"id": "b", "series_id": "sb", "list_id": "1", "name": "Pack", "parent_id": "a"},
+                {"id": "a", "series_id": "sa", "list_id": "1", "name": "Trip", "priority": "P1",
+                 "date_due": 1661472000000_i64, "tags": ["rome"]},
+                {"id": "d", "series_id": "sd", "list_id": "1", "name": "Book",
+                 "date_completed": 1661472000000_i64},
+                {"id": "e", "series_id": "se", "list_id": "1", "name": "Passport",
+                 "parent_id": "d", "date_due": 1661520600000_i64, "date_due_has_time": true},
+                {"id": "f", "series_id": "sf", "list_id": "1", "name": "Gone",
+                 "date_trashed": 1661472000000_i64},
+            ],
+            "notes": [
+                {"series_id": "sa", "title": "Hotel", "content": "Near the Pantheon"},
+                {"series_id": "sa", "content": "Bring a hat"},
+            ],
+        }))
+        .unwrap();
+        let spec = spec(export, &[], false, &chrono::Utc);
+        let projects = spec.projects.iter().map(|p| &p.name).collect::<Vec<_>>();
+        assert_eq!(projects, ["Travel"]);
+        let tasks = spec
+            .tasks
+            .iter()
+            .map(|t| (t.key.as_deref().unwrap(), t.parent.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tasks,
+            [("a", None), ("e", None), ("b", Some("a")), ("c", Some("b"))]
+        );
+        assert_eq!(
+            spec.tasks[0].description.as_deref(),
+            Some("Hotel\nNear the Pantheon\n\nBring a hat")
+        );
+        assert_eq!(spec.tasks[0].due.as_deref(), Some("2022-08-26"));
+        assert_eq!(spec.tasks[0].priority, Some(1));
+        assert_eq!(spec.tasks[0].labels, ["rome"]);
+        assert_eq!(spec.tasks[1].due.as_deref(), Some("2022-08-26 13:30"));
+    }
+}
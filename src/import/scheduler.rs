@@ -0,0 +1,499 @@
+//! Runs the steps of an import at a pace that stays under the API rate limits.
+//!
+//! The IDs of everything that was created are saved after each step, so an import that was
+//! interrupted continues where it stopped when running it again.
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Report, Result,
+};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        rest::{
+            CreateComment, CreateProject, CreateSection, CreateTask, Gateway, TaskDue, ThreadID,
+        },
+        sync::{SyncCommand, SYNC_BATCH_SIZE},
+    },
+    cancel, chart, output,
+    store::Store,
+};
+
+use super::spec::{resolve, KeyedStep, Ref, Step};
+
+/// Options that control how fast an import runs.
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    /// Maximum number of requests per minute. The Todoist API allows about 450 requests in 15
+    /// minutes.
+    #[arg(long = "rate", default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..))]
+    rate: u32,
+    /// Creates every item with its own request, instead of batching them through the Sync API.
+    #[arg(long = "nobatch")]
+    nobatch: bool,
+}
+
+/// How many requests can be sent at once before pacing kicks in.
+const BURST: u32 = 10;
+
+/// Pacer spaces out requests so they don't exceed a rate, while allowing short bursts.
+struct Pacer {
+    interval: Duration,
+    burst: u32,
+    /// The time at which the next request would be sent if requests were perfectly spaced.
+    next: Option<Instant>,
+}
+
+impl Pacer {
+    fn new(per_minute: u32, burst: u32) -> Pacer {
+        Pacer {
+            interval: Duration::from_secs(60) / per_minute.max(1),
+            burst: burst.max(1),
+            next: None,
+        }
+    }
+
+    /// Reserves a slot for a request and returns how long to wait until it can be sent.
+    fn delay(&mut self, now: Instant) -> Duration {
+        let next = self.next.map_or(now, |next| next.max(now));
+        let allowed = next
+            .checked_sub(self.interval * (self.burst - 1))
+            .map_or(now, |allowed| allowed.max(now));
+        self.next = Some(next + self.interval);
+        allowed - now
+    }
+
+    /// Waits for the next request to be allowed, or until the import is cancelled.
+    async fn wait(&mut self) {
+        let delay = self.delay(Instant::now());
+        if !delay.is_zero() {
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancel::cancelled() => {}
+            }
+        }
+    }
+}
+
+/// Progress remembers the IDs of everything an import created, keyed by [`KeyedStep::key`].
+#[derive(Debug, Serialize, Deserialize)]
+struct Progress {
+    /// Identifies this run of the import, which the requests are keyed by. Resuming keeps it, so
+    /// the API ignores requests that already ran, but running the same import again after it
+    /// finished gets a new one, as the API would ignore all of its requests otherwise.
+    #[serde(default = "Uuid::new_v4")]
+    run: Uuid,
+    created: HashMap<String, String>,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self {
+            run: Uuid::new_v4(),
+            created: HashMap::new(),
+        }
+    }
+}
+
+/// Summary counts what was created by an import.
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub projects: usize,
+    pub sections: usize,
+    pub tasks: usize,
+    pub comments: usize,
+    pub completed: usize,
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "imported {} projects, {} sections and {} tasks",
+            self.projects, self.sections, self.tasks
+        )?;
+        if self.comments > 0 {
+            write!(f, " with {} comments", self.comments)?;
+        }
+        if self.completed > 0 {
+            write!(f, ", {} of them completed", self.completed)?;
+        }
+        Ok(())
+    }
+}
+
+fn progress_file(id: &Uuid) -> String {
+    format!("imports/{}.json", id)
+}
+
+/// Returns the IDs of everything that a previous attempt of the import with the same `id` created.
+pub fn created(id: &Uuid, store: &Store) -> Result<HashSet<String>> {
+    let progress: Progress = store.load(&progress_file(id))?;
+    Ok(progress.created.into_values().collect())
+}
+
+/// Runs all steps that did not run yet in a previous attempt of the import with the same `id`.
+pub async fn run(
+    steps: &[KeyedStep],
+    id: &Uuid,
+    opts: &Options,
+    gw: &Gateway,
+    store: &Store,
+) -> Result<Summary> {
+    let file = progress_file(id);
+    let mut progress: Progress = store.load(&file)?;
+    // Saved before anything is sent, so that an interrupted run is resumed with the same keys.
+    store.save(&file, &progress)?;
+    let remaining = steps
+        .iter()
+        .filter(|s| !progress.created.contains_key(&s.key))
+        .collect::<Vec<_>>();
+    if remaining.len() < steps.len() {
+        eprintln!(
+            "resuming import, {} of {} items were already created",
+            steps.len() - remaining.len(),
+            steps.len()
+        );
+    }
+    let bar = ProgressBar::new(remaining.len() as u64).with_style(
+        ProgressStyle::with_template("{wide_bar} {pos}/{len} ETA {eta}")
+            .expect("progress template is valid")
+            .progress_chars(chart::progress_chars()),
+    );
+    // Screen readers would read out every redraw of the bar.
+    if output::a11y() {
+        bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    let mut pacer = Pacer::new(opts.rate, BURST);
+    let mut summary = Summary::default();
+    let batch_size = if opts.nobatch { 1 } else { SYNC_BATCH_SIZE };
+    for batch in remaining.chunks(batch_size) {
+        pacer.wait().await;
+        let mut outcome = match cancel::check() {
+            Ok(()) => {
+                if opts.nobatch {
+                    execute(batch[0], &progress.run, &progress.created, gw).await
+                } else {
+                    execute_batch(batch, &progress.run, &progress.created, gw).await
+                }
+            }
+            Err(e) => Outcome::failed(e),
+        };
+        for step in batch {
+            if let Some(created) = outcome.created.remove(&step.key) {
+                progress.created.insert(step.key.clone(), created);
+                match step.step {
+                    Step::Project { .. } => summary.projects += 1,
+                    Step::Section { .. } => summary.sections += 1,
+                    Step::Task { .. } => summary.tasks += 1,
+                    Step::Comment { .. } => summary.comments += 1,
+                    Step::Close { .. } => summary.completed += 1,
+                }
+                bar.inc(1);
+            }
+        }
+        store.save(&file, &progress)?;
+        if let Some(e) = outcome.error {
+            bar.abandon();
+            return Err(e).wrap_err(format!(
+                "import stopped after {} of {} items, run the same import again to resume",
+                progress.created.len(),
+                steps.len()
+            ));
+        }
+    }
+    bar.finish_and_clear();
+    store.remove(&file)?;
+    Ok(summary)
+}
+
+/// Outcome contains the IDs of the items a request created, keyed by [`KeyedStep::key`], and the
+/// error that stopped the rest from being created.
+#[derive(Default)]
+struct Outcome {
+    created: HashMap<String, String>,
+    error: Option<Report>,
+}
+
+impl Outcome {
+    fn failed(error: Report) -> Outcome {
+        Outcome {
+            error: Some(error),
+            ..Default::default()
+        }
+    }
+}
+
+/// Creates what the step describes with a request to the REST API. The request is keyed by the
+/// run of the import and the step, so that the API ignores it if an earlier attempt already sent
+/// it.
+async fn execute(
+    step: &KeyedStep,
+    run: &Uuid,
+    created: &HashMap<String, String>,
+    gw: &Gateway,
+) -> Outcome {
+    let key = Uuid::new_v5(run, step.key.as_bytes());
+    match Gateway::operation(key, create(&step.step, created, gw)).await {
+        Ok(id) => Outcome {
+            created: HashMap::from([(step.key.clone(), id)]),
+            error: None,
+        },
+        Err(e) => Outcome::failed(e),
+    }
+}
+
+/// Creates everything that the steps describe with a single request to the Sync API. Steps can
+/// reference items that are created earlier in the same batch.
+async fn execute_batch(
+    batch: &[&KeyedStep],
+    run: &Uuid,
+    created: &HashMap<String, String>,
+    gw: &Gateway,
+) -> Outcome {
+    let commands = match commands(batch, run, created) {
+        Ok(commands) => commands,
+        Err(e) => return Outcome::failed(e),
+    };
+    let resp = match gw.sync(&commands).await {
+        Ok(resp) => resp,
+        Err(e) => return Outcome::failed(e),
+    };
+    let mut outcome = Outcome::default();
+    for (step, command) in batch.iter().zip(&commands) {
+        let id = match &command.temp_id {
+            Some(temp_id) => resp.temp_id_mapping.get(temp_id).cloned(),
+            // Commands that change an item keep its ID, which can be a temporary one of the batch.
+            None => command.args["id"].as_str().map(|id| {
+                resp.temp_id_mapping
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string())
+            }),
+        };
+        match (resp.error(command), id) {
+            (None, Some(id)) => {
+                outcome.created.insert(step.key.clone(), id);
+            }
+            (error, _) => {
+                let error = error.unwrap_or_else(|| "no ID was returned".to_string());
+                outcome.error = Some(eyre!("unable to create {}: {}", step.key, error));
+                break;
+            }
+        }
+    }
+    outcome
+}
+
+/// Translates the steps into Sync API commands.
+///
+/// The UUIDs and temporary IDs are derived from the run of the import and the step, so that the
+/// API ignores commands that already ran when a batch is sent again.
+fn commands(
+    batch: &[&KeyedStep],
+    run: &Uuid,
+    created: &HashMap<String, String>,
+) -> Result<Vec<SyncCommand>> {
+    let mut temp_ids: HashMap<&str, String> = HashMap::new();
+    let mut commands = Vec::with_capacity(batch.len());
+    for step in batch {
+        let resolve = |r: &Option<Ref>| -> Result<serde_json::Value> {
+            Ok(match r {
+                Some(Ref::Step(key)) if temp_ids.contains_key(key.as_str()) => {
+                    temp_ids[key.as_str()].clone().into()
+                }
+                Some(r) => resolve(r, created)?.into(),
+                None => serde_json::Value::Null,
+            })
+        };
+        let (kind, args) = match &step.step {
+            Step::Project { name, parent } => (
+                "project_add",
+                json!({"name": name, "parent_id": resolve(parent)?}),
+            ),
+            Step::Section { name, project } => (
+                "section_add",
+                json!({"name": name, "project_id": resolve(&Some(project.clone()))?}),
+            ),
+            Step::Task {
+                content,
+                description,
+                due,
+                priority,
+                labels,
+                project,
+                section,
+                parent,
+            } => (
+                "item_add",
+                json!({
+                    "content": content,
+                    "description": description,
+                    "due": due.as_ref().map(|due| json!({"string": due})),
+                    "priority": priority,
+                    "labels": labels,
+                    "project_id": resolve(project)?,
+                    "section_id": resolve(section)?,
+                    "parent_id": resolve(parent)?,
+                }),
+            ),
+            Step::Comment { task, content } => (
+                "note_add",
+                json!({"item_id": resolve(&Some(task.clone()))?, "content": content}),
+            ),
+            Step::Close { task } => ("item_close", json!({"id": resolve(&Some(task.clone()))?})),
+        };
+        let mut args = args;
+        if let Some(args) = args.as_object_mut() {
+            args.retain(|_, v| !v.is_null());
+        }
+        let uuid = Uuid::new_v5(run, step.key.as_bytes());
+        // Only commands that add an item get a temporary ID for it.
+        let temp_id = match step.step {
+            Step::Close { .. } => None,
+            _ => {
+                temp_ids.insert(&step.key, uuid.to_string());
+                Some(uuid.to_string())
+            }
+        };
+        commands.push(SyncCommand {
+            kind: kind.to_string(),
+            uuid,
+            temp_id,
+            args,
+        });
+    }
+    Ok(commands)
+}
+
+/// Creates what the step describes and returns the ID of the created item.
+async fn create(step: &Step, created: &HashMap<String, String>, gw: &Gateway) -> Result<String> {
+    let resolve = |r: &Option<_>| r.as_ref().map(|r| resolve(r, created)).transpose();
+    let id = match step {
+        Step::Project { name, parent } => {
+            gw.create_project(&CreateProject {
+                name: name.clone(),
+                parent_id: resolve(parent)?,
+                ..Default::default()
+            })
+            .await?
+            .id
+        }
+        Step::Section { name, project } => {
+            gw.create_section(&CreateSection {
+                name: name.clone(),
+                project_id: super::spec::resolve(project, created)?,
+                ..Default::default()
+            })
+            .await?
+            .id
+        }
+        Step::Task {
+            content,
+            description,
+            due,
+            priority,
+            labels,
+            project,
+            section,
+            parent,
+        } => {
+            gw.create(&CreateTask {
+                content: content.clone(),
+                description: description.clone(),
+                project_id: resolve(project)?,
+                section_id: resolve(section)?,
+                parent_id: resolve(parent)?,
+                labels: labels.clone(),
+                priority: *priority,
+                due: due.clone().map(TaskDue::String),
+                ..Default::default()
+            })
+            .await?
+            .id
+        }
+        Step::Comment { task, content } => {
+            gw.create_comment(&CreateComment {
+                thread: ThreadID::Task {
+                    task_id: super::spec::resolve(task, created)?,
+                },
+                content: content.clone(),
+                attachment: None,
+            })
+            .await?
+            .id
+        }
+        Step::Close { task } => {
+            let id = super::spec::resolve(task, created)?;
+            gw.close(&id).await?;
+            id
+        }
+    };
+    Ok(id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paces_requests() {
+        let start = Instant::now();
+        let mut pacer = Pacer::new(60, 3);
+        // The burst goes out immediately.
+        for _ in 0..3 {
+            assert_eq!(pacer.delay(start), Duration::ZERO);
+        }
+        // Then requests are spaced out by the rate.
+        assert_eq!(pacer.delay(start), Duration::from_secs(1));
+        let later = start + Duration::from_secs(1);
+        assert_eq!(pacer.delay(later), Duration::from_secs(1));
+        // After a pause, a burst can go out again.
+        let much_later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(pacer.delay(much_later), Duration::ZERO);
+        }
+        assert_eq!(pacer.delay(much_later), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn closes_created_tasks() {
+        let run = Uuid::new_v4();
+        let steps = [
+            KeyedStep {
+                key: "task:#0".to_string(),
+                step: Step::Task {
+                    content: "Done".to_string(),
+                    description: None,
+                    due: None,
+                    priority: None,
+                    labels: Vec::new(),
+                    project: None,
+                    section: None,
+                    parent: None,
+                },
+            },
+            KeyedStep {
+                key: "task:#0/close".to_string(),
+                step: Step::Close {
+                    task: Ref::Step("task:#0".to_string()),
+                },
+            },
+        ];
+        let commands = commands(&steps.iter().collect::<Vec<_>>(), &run, &HashMap::new()).unwrap();
+        assert_eq!(commands[1].kind, "item_close");
+        assert_eq!(commands[1].temp_id, None);
+        assert_eq!(
+            commands[1].args["id"].as_str(),
+            commands[0].temp_id.as_deref()
+        );
+    }
+}
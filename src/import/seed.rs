@@ -0,0 +1,255 @@
+//! Creates realistic, but made up projects, sections, labels and tasks, like for trying out doist
+//! with a test account.
+use color_eyre::{eyre::eyre, Result};
+use uuid::Uuid;
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{
+    scheduler,
+    spec::{ProjectSpec, Spec, TaskSpec},
+    ExistingOptions,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    // Not named like the fields of `ExistingOptions`, as clap needs unique names.
+    /// How many tasks to create.
+    #[arg(long = "tasks", value_name = "TASKS", default_value_t = 100)]
+    task_count: usize,
+    /// How many projects to spread the tasks over.
+    #[arg(long = "projects", value_name = "PROJECTS", default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    project_count: u64,
+    /// Generates different data for different seeds. The same seed always generates the same data,
+    /// so running it again resumes an interrupted run.
+    #[arg(long = "seed", default_value_t = 1)]
+    seed: u64,
+    #[clap(flatten)]
+    existing: ExistingOptions,
+    #[clap(flatten)]
+    options: scheduler::Options,
+}
+
+const PROJECTS: &[&str] = &[
+    "Home",
+    "Work",
+    "Groceries",
+    "Website relaunch",
+    "Garden",
+    "Travel",
+    "Reading list",
+    "Fitness",
+    "Finances",
+    "Side project",
+    "Birthday party",
+    "Car",
+];
+const SECTIONS: &[&str] = &["Backlog", "Next up", "Waiting", "Ideas", "This week"];
+const VERBS: &[&str] = &[
+    "Buy", "Call", "Email", "Fix", "Plan", "Review", "Write", "Clean", "Book", "Order", "Pay",
+    "Schedule", "Read", "Update", "Prepare",
+];
+const OBJECTS: &[&str] = &[
+    "the quarterly report",
+    "new tires",
+    "the plumber",
+    "a birthday gift",
+    "the garden shed",
+    "tickets for the concert",
+    "the insurance bill",
+    "the landing page",
+    "a table for Friday",
+    "the onboarding docs",
+    "the bike",
+    "flowers for the balcony",
+    "the team offsite",
+    "the tax return",
+    "the pull request",
+];
+const LABELS: &[&str] = &[
+    "errand", "phone", "computer", "quick", "waiting", "home", "office",
+];
+const DUES: &[&str] = &[
+    "today",
+    "tomorrow",
+    "yesterday",
+    "in 2 days",
+    "next monday",
+    "in 1 week",
+    "every friday",
+    "in 1 month",
+];
+const DESCRIPTIONS: &[&str] = &[
+    "Check the notes from last time first.",
+    "Ask for a quote before deciding.",
+    "See the link in the shared folder.",
+];
+
+/// Rng is a small pseudo random number generator (SplitMix64), which is plenty for made up data.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// Returns true with the given chance in percent.
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+/// Creates the made up data in the Todoist account.
+pub async fn seed(params: Params, cfg: &Config) -> Result<()> {
+    if params.task_count == 0 {
+        return Err(eyre!("no tasks to create"));
+    }
+    let spec = spec(
+        params.seed,
+        params.project_count as usize,
+        params.task_count,
+    );
+    let id = Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        format!(
+            "seed:{}:{}:{}",
+            params.seed, params.project_count, params.task_count
+        )
+        .as_bytes(),
+    );
+    super::run(&spec, id, &params.existing, &params.options, cfg).await
+}
+
+/// Generates the spec, which is always the same for the same arguments.
+fn spec(seed: u64, projects: usize, tasks: usize) -> Spec {
+    let mut rng = Rng(seed);
+    let mut spec = Spec::default();
+    for i in 0..projects {
+        let name = match PROJECTS.get(i) {
+            Some(name) => name.to_string(),
+            None => format!(
+                "{} {}",
+                PROJECTS[i % PROJECTS.len()],
+                i / PROJECTS.len() + 1
+            ),
+        };
+        let sections = (0..rng.below(4))
+            .map(|_| rng.pick(SECTIONS).to_string())
+            .fold(Vec::new(), |mut sections, s| {
+                if !sections.contains(&s) {
+                    sections.push(s);
+                }
+                sections
+            });
+        spec.projects.push(ProjectSpec {
+            name,
+            sections,
+            ..Default::default()
+        });
+    }
+    // Subtasks go below an earlier task of the same project.
+    let mut parents: Vec<Option<usize>> = vec![None; projects];
+    for i in 0..tasks {
+        let p = rng.below(projects);
+        let project = &spec.projects[p];
+        let parent = match parents[p] {
+            Some(parent) if rng.chance(20) => Some(parent),
+            _ => None,
+        };
+        let section = match parent {
+            Some(parent) => spec.tasks[parent].section.clone(),
+            None if !project.sections.is_empty() && rng.chance(60) => {
+                Some(project.sections[rng.below(project.sections.len())].clone())
+            }
+            None => None,
+        };
+        let mut labels = Vec::new();
+        for _ in 0..rng.below(3) {
+            let label = rng.pick(LABELS).to_string();
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        let task = TaskSpec {
+            key: Some(format!("task{}", i + 1)),
+            content: format!("{} {}", rng.pick(VERBS), rng.pick(OBJECTS)),
+            description: rng.chance(15).then(|| rng.pick(DESCRIPTIONS).to_string()),
+            due: rng.chance(40).then(|| rng.pick(DUES).to_string()),
+            priority: match rng.below(10) {
+                0 => Some(1),
+                1 => Some(2),
+                2 | 3 => Some(3),
+                _ => None,
+            },
+            labels,
+            project: Some(project.name.clone()),
+            section,
+            parent: parent.map(|parent| format!("task{}", parent + 1)),
+            completed: false,
+            comments: Vec::new(),
+        };
+        if parent.is_none() {
+            parents[p] = Some(i);
+        }
+        spec.tasks.push(task);
+    }
+    spec
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        seed(self, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generates_spec() {
+        let spec = spec(7, 15, 200);
+        assert_eq!(spec.projects.len(), 15);
+        assert_eq!(spec.projects[12].name, "Home 2");
+        assert_eq!(spec.tasks.len(), 200);
+        for (i, task) in spec.tasks.iter().enumerate() {
+            let project = spec
+                .projects
+                .iter()
+                .find(|p| Some(&p.name) == task.project.as_ref())
+                .unwrap();
+            if let Some(section) = &task.section {
+                assert!(project.sections.contains(section));
+            }
+            if let Some(parent) = &task.parent {
+                let parent = spec.tasks[..i]
+                    .iter()
+                    .find(|t| t.key.as_ref() == Some(parent))
+                    .unwrap();
+                assert_eq!(parent.project, task.project);
+                assert_eq!(parent.section, task.section);
+            }
+        }
+        assert!(spec.tasks.iter().any(|t| t.parent.is_some()));
+
+        assert_eq!(
+            serde_json::to_string(&spec).unwrap(),
+            serde_json::to_string(&super::spec(7, 15, 200)).unwrap()
+        );
+    }
+}
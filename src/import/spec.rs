@@ -0,0 +1,616 @@
+use std::collections::HashMap;
+
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::rest::{Priority, Project, Section, Task},
+    tasks,
+};
+
+/// Spec describes everything that should be created by an import. All importers convert their
+/// source into a Spec first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Spec {
+    /// Projects to create, parents before their children.
+    #[serde(default)]
+    pub projects: Vec<ProjectSpec>,
+    /// Tasks to create, parents before their children.
+    #[serde(default)]
+    pub tasks: Vec<TaskSpec>,
+}
+
+/// A project that will be created. Projects with the same name as an existing project are handled
+/// by [`Strategies::projects`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectSpec {
+    /// Name of the project.
+    pub name: String,
+    /// Name of the parent project, which either exists already or is part of the import.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Names of sections in the project that will be created.
+    #[serde(default)]
+    pub sections: Vec<String>,
+}
+
+/// A task that will be created.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TaskSpec {
+    /// Key that is used to reference this task as the parent of other tasks in the import.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Name of the task.
+    pub content: String,
+    /// Description of the task.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Due date in natural language, like `tomorrow`.
+    #[serde(default)]
+    pub due: Option<String>,
+    /// Priority as displayed in the UI, with 1 as the most urgent.
+    #[serde(default)]
+    pub priority: Option<usize>,
+    /// Names of labels of the task.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Name of the project of the task, which either exists already or is part of the import.
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Name of the section in the project of the task.
+    #[serde(default)]
+    pub section: Option<String>,
+    /// The key of the parent task, which needs to be listed before this task.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Completes the task after creating it, for tasks that are done already.
+    #[serde(default)]
+    pub completed: bool,
+    /// Comments to add to the task, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<String>,
+}
+
+/// Ref references something that exists already or is created by an earlier [`Step`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ref {
+    /// The ID of something that exists already.
+    Existing(String),
+    /// The key of the step that creates it.
+    Step(String),
+}
+
+/// Step is a single item that the import creates.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Creates a project.
+    Project { name: String, parent: Option<Ref> },
+    /// Creates a section in a project.
+    Section { name: String, project: Ref },
+    /// Creates a task.
+    Task {
+        content: String,
+        description: Option<String>,
+        due: Option<String>,
+        priority: Option<Priority>,
+        labels: Vec<String>,
+        project: Option<Ref>,
+        section: Option<Ref>,
+        parent: Option<Ref>,
+    },
+    /// Adds a comment to a task that the import created.
+    Comment { task: Ref, content: String },
+    /// Completes a task that the import created.
+    Close { task: Ref },
+}
+
+/// A [`Step`] together with the key that identifies it in the import.
+#[derive(Debug, PartialEq, Eq)]
+pub struct KeyedStep {
+    /// Identifies the step, so it can be referenced and skipped when resuming.
+    pub key: String,
+    /// What to create.
+    pub step: Step,
+}
+
+/// Strategy decides what happens with items of the import that exist already in the account.
+#[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Reuses the existing item and adds everything the import puts into it.
+    #[default]
+    Merge,
+    /// Leaves the existing item alone and skips everything the import puts into it.
+    Skip,
+    /// Creates the item anyway.
+    Duplicate,
+}
+
+/// The [`Strategy`] to use for each type of item.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Strategies {
+    pub projects: Strategy,
+    pub sections: Strategy,
+    pub tasks: Strategy,
+}
+
+/// Existing describes what is in the account already.
+pub struct Existing<'a> {
+    pub projects: &'a [Project],
+    pub sections: &'a [Section],
+    pub tasks: &'a [Task],
+}
+
+/// Counts items of each type.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub projects: usize,
+    pub sections: usize,
+    pub tasks: usize,
+}
+
+/// Plan describes what an import does.
+#[derive(Debug, Default)]
+pub struct Plan {
+    /// The steps that need to run in order.
+    pub steps: Vec<KeyedStep>,
+    /// Items of the import that exist already.
+    pub duplicates: Counts,
+    /// How many items are not created because of [`Strategy::Skip`].
+    pub skipped: usize,
+}
+
+impl Plan {
+    fn create(&mut self, key: String, step: Step) -> Option<Ref> {
+        self.steps.push(KeyedStep {
+            key: key.clone(),
+            step,
+        });
+        Some(Ref::Step(key))
+    }
+
+    /// Applies the strategy to an item that exists already. Returns what to reference the item
+    /// with, or none if it is skipped.
+    fn existing(
+        &mut self,
+        strategy: Strategy,
+        id: &str,
+        key: String,
+        step: impl FnOnce() -> Step,
+    ) -> Option<Ref> {
+        match strategy {
+            Strategy::Merge => Some(Ref::Existing(id.to_string())),
+            Strategy::Skip => {
+                self.skipped += 1;
+                None
+            }
+            Strategy::Duplicate => self.create(key, step()),
+        }
+    }
+}
+
+impl Spec {
+    /// Turns the spec into the list of steps that need to run in order. Items with the same name
+    /// as an existing item are handled with the given strategies.
+    ///
+    /// A skipped item is kept as `None` while planning, so that everything inside of it is
+    /// skipped as well.
+    pub fn plan(&self, existing: &Existing, strategies: Strategies) -> Result<Plan> {
+        let mut plan = Plan::default();
+        let existing_project = |name: &str| existing.projects.iter().find(|p| p.name == name);
+        let existing_section = |project: &Ref, name: &str| match project {
+            Ref::Existing(id) => existing
+                .sections
+                .iter()
+                .find(|s| &s.project_id == id && s.name == name),
+            Ref::Step(_) => None,
+        };
+        let mut project_refs: HashMap<&str, Option<Ref>> = HashMap::new();
+        let project_ref = |refs: &HashMap<&str, Option<Ref>>, name: &str| match refs.get(name) {
+            Some(r) => Some(r.clone()),
+            None => existing_project(name).map(|p| Some(Ref::Existing(p.id.clone()))),
+        };
+        let mut section_refs: HashMap<(&str, &str), Option<Ref>> = HashMap::new();
+        for project in &self.projects {
+            let parent = match &project.parent {
+                Some(parent) => match project_ref(&project_refs, parent) {
+                    Some(Some(parent)) => Some(parent),
+                    Some(None) => {
+                        plan.skipped += 1 + project.sections.len();
+                        project_refs.insert(&project.name, None);
+                        continue;
+                    }
+                    None => return Err(eyre!("unknown parent project {:?}", parent)),
+                },
+                None => None,
+            };
+            let r = match project_refs.get(project.name.as_str()) {
+                Some(r) => r.clone(),
+                None => {
+                    let key = format!("project:{}", project.name);
+                    let step = || Step::Project {
+                        name: project.name.clone(),
+                        parent,
+                    };
+                    match existing_project(&project.name) {
+                        Some(p) => {
+                            plan.duplicates.projects += 1;
+                            plan.existing(strategies.projects, &p.id, key, step)
+                        }
+                        None => plan.create(key, step()),
+                    }
+                }
+            };
+            project_refs.insert(&project.name, r.clone());
+            let Some(r) = r else {
+                plan.skipped += project.sections.len();
+                continue;
+            };
+            for section in &project.sections {
+                let key = format!("section:{}/{}", project.name, section);
+                let step = || Step::Section {
+                    name: section.clone(),
+                    project: r.clone(),
+                };
+                let section_ref = match existing_section(&r, section) {
+                    Some(s) => {
+                        plan.duplicates.sections += 1;
+                        plan.existing(strategies.sections, &s.id, key, step)
+                    }
+                    None => plan.create(key, step()),
+                };
+                section_refs.insert((&project.name, section), section_ref);
+            }
+        }
+        let inbox = existing.projects.iter().find(|p| p.is_inbox_project);
+        let mut task_refs: HashMap<&str, Option<Ref>> = HashMap::new();
+        for (index, task) in self.tasks.iter().enumerate() {
+            let priority = task
+                .priority
+                .map(tasks::Priority::try_from)
+                .transpose()?
+                .map(Priority::from);
+            let project = match &task.project {
+                Some(name) => Some(project_ref(&project_refs, name).ok_or_else(|| {
+                    eyre!("unknown project {:?} for task {:?}", name, task.content)
+                })?),
+                None => None,
+            };
+            let section = match (&task.project, &project, &task.section) {
+                (Some(_), Some(None), Some(_)) => Some(None),
+                (Some(project_name), Some(Some(project)), Some(name)) => Some(
+                    section_refs
+                        .get(&(project_name.as_str(), name.as_str()))
+                        .cloned()
+                        .or_else(|| {
+                            existing_section(project, name)
+                                .map(|s| Some(Ref::Existing(s.id.clone())))
+                        })
+                        .ok_or_else(|| {
+                            eyre!("unknown section {:?} for task {:?}", name, task.content)
+                        })?,
+                ),
+                (_, _, Some(name)) => {
+                    return Err(eyre!(
+                        "section {:?} of task {:?} needs a project",
+                        name,
+                        task.content
+                    ))
+                }
+                _ => None,
+            };
+            let parent = match &task.parent {
+                Some(parent) => Some(task_refs.get(parent.as_str()).cloned().ok_or_else(|| {
+                    eyre!(
+                        "parent {:?} of task {:?} needs to be listed before it",
+                        parent,
+                        task.content
+                    )
+                })?),
+                None => None,
+            };
+            let key = match &task.key {
+                Some(key) => format!("task:{}", key),
+                None => format!("task:#{}", index),
+            };
+            let r = match (project, section, parent) {
+                (Some(None), _, _) | (_, Some(None), _) | (_, _, Some(None)) => {
+                    plan.skipped += 1;
+                    None
+                }
+                (project, section, parent) => {
+                    let (project, section, parent) =
+                        (project.flatten(), section.flatten(), parent.flatten());
+                    let step = || Step::Task {
+                        content: task.content.clone(),
+                        description: task.description.clone(),
+                        due: task.due.clone(),
+                        priority,
+                        labels: task.labels.clone(),
+                        project: project.clone(),
+                        section: section.clone(),
+                        parent: parent.clone(),
+                    };
+                    match duplicate_task(existing, inbox, task, &project, &section, &parent) {
+                        Some(t) => {
+                            plan.duplicates.tasks += 1;
+                            plan.existing(strategies.tasks, &t.id, key, step)
+                        }
+                        None => plan.create(key, step()),
+                    }
+                }
+            };
+            // Only tasks that the import creates get comments and are completed, existing ones are
+            // left alone.
+            if let Some(Ref::Step(key)) = &r {
+                for (index, content) in task.comments.iter().enumerate() {
+                    plan.create(
+                        format!("{}/comment:{}", key, index),
+                        Step::Comment {
+                            task: Ref::Step(key.clone()),
+                            content: content.clone(),
+                        },
+                    );
+                }
+            }
+            if let (true, Some(Ref::Step(key))) = (task.completed, &r) {
+                plan.create(
+                    format!("{}/close", key),
+                    Step::Close {
+                        task: Ref::Step(key.clone()),
+                    },
+                );
+            }
+            if let Some(task_key) = &task.key {
+                if task_refs.insert(task_key, r).is_some() {
+                    return Err(eyre!("duplicate task key {:?}", task_key));
+                }
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// Finds an existing task with the same content in the same place. Only tasks whose project,
+/// section and parent exist already can have duplicates.
+fn duplicate_task<'a>(
+    existing: &Existing<'a>,
+    inbox: Option<&Project>,
+    task: &TaskSpec,
+    project: &Option<Ref>,
+    section: &Option<Ref>,
+    parent: &Option<Ref>,
+) -> Option<&'a Task> {
+    let id = |r: &Option<Ref>| match r {
+        Some(Ref::Existing(id)) => Ok(Some(id.clone())),
+        Some(Ref::Step(_)) => Err(()),
+        None => Ok(None),
+    };
+    let project = id(project).ok()?.or_else(|| inbox.map(|p| p.id.clone()))?;
+    let section = id(section).ok()?;
+    let parent = id(parent).ok()?;
+    existing.tasks.iter().find(|t| {
+        t.content == task.content
+            && t.project_id == project
+            && t.section_id == section
+            && t.parent_id == parent
+    })
+}
+
+/// Resolves a [`Ref`] to an ID, using the IDs of the items that the import created so far.
+pub fn resolve(r: &Ref, created: &HashMap<String, String>) -> Result<String> {
+    match r {
+        Ref::Existing(id) => Ok(id.clone()),
+        Ref::Step(key) => created
+            .get(key)
+            .cloned()
+            .ok_or_else(|| eyre!("{} was not created yet", key)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec(json: serde_json::Value) -> Spec {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn existing() -> (Vec<Project>, Vec<Section>, Vec<Task>) {
+        let projects = serde_json::from_value(serde_json::json!([{
+            "id": "1", "name": "Home", "order": 1, "comment_count": 0, "color": "blue", "is_shared": false,
+            "is_favorite": false, "is_inbox_project": false, "is_team_inbox": false,
+            "url": "https://todoist.com/showProject?id=1", "view_style": "list"
+        }]))
+        .unwrap();
+        let sections = serde_json::from_value(serde_json::json!([
+            {"id": "11", "project_id": "1", "order": 1, "name": "Kitchen"}
+        ]))
+        .unwrap();
+        let tasks = serde_json::from_value(serde_json::json!([{
+            "id": "111", "project_id": "1", "section_id": "11", "content": "Clean",
+            "description": "", "is_completed": false, "labels": [], "order": 1, "priority": 1,
+            "comment_count": 0, "creator_id": "1", "created_at": "2021-01-01T00:00:00Z",
+            "url": "https://todoist.com/showTask?id=111"
+        }]))
+        .unwrap();
+        (projects, sections, tasks)
+    }
+
+    #[test]
+    fn plans_steps() {
+        let (projects, sections, tasks) = existing();
+        let existing = Existing {
+            projects: &projects,
+            sections: &sections,
+            tasks: &tasks,
+        };
+        let plan = spec(serde_json::json!({
+            "projects": [
+                {"name": "Home", "sections": ["Kitchen", "Garden"]},
+                {"name": "Chores", "parent": "Home"},
+            ],
+            "tasks": [
+                {"key": "clean", "content": "Clean", "project": "Home", "section": "Kitchen"},
+                {"content": "Dishes", "parent": "clean", "priority": 1, "project": "Chores"},
+            ],
+        }))
+        .plan(&existing, Strategies::default())
+        .unwrap();
+        let steps = plan.steps;
+        let keys = steps.iter().map(|s| s.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec!["section:Home/Garden", "project:Chores", "task:#1"]
+        );
+        assert_eq!(
+            plan.duplicates,
+            Counts {
+                projects: 1,
+                sections: 1,
+                tasks: 1
+            }
+        );
+        assert_eq!(
+            steps[1].step,
+            Step::Project {
+                name: "Chores".to_string(),
+                parent: Some(Ref::Existing("1".to_string()))
+            }
+        );
+        match &steps[2].step {
+            Step::Task {
+                priority,
+                project,
+                parent,
+                ..
+            } => {
+                assert_eq!(*priority, Some(Priority::Urgent));
+                assert_eq!(*project, Some(Ref::Step("project:Chores".to_string())));
+                assert_eq!(*parent, Some(Ref::Existing("111".to_string())));
+            }
+            step => panic!("unexpected step {:?}", step),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_references() {
+        let (projects, sections, tasks) = existing();
+        let existing = Existing {
+            projects: &projects,
+            sections: &sections,
+            tasks: &tasks,
+        };
+        for json in [
+            serde_json::json!({"tasks": [{"content": "a", "project": "Nope"}]}),
+            serde_json::json!({"tasks": [{"content": "a", "section": "Kitchen"}]}),
+            serde_json::json!({"tasks": [{"content": "a", "parent": "b"}, {"key": "b", "content": "b"}]}),
+            serde_json::json!({"tasks": [{"key": "a", "content": "a"}, {"key": "a", "content": "b"}]}),
+            serde_json::json!({"projects": [{"name": "a", "parent": "Nope"}]}),
+            serde_json::json!({"tasks": [{"content": "a", "priority": 5}]}),
+        ] {
+            assert!(
+                spec(json.clone())
+                    .plan(&existing, Strategies::default())
+                    .is_err(),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn applies_strategies() {
+        let (projects, sections, tasks) = existing();
+        let existing = Existing {
+            projects: &projects,
+            sections: &sections,
+            tasks: &tasks,
+        };
+        let spec = spec(serde_json::json!({
+            "projects": [
+                {"name": "Home", "sections": ["Kitchen"]},
+                {"name": "Chores", "parent": "Home"},
+            ],
+            "tasks": [
+                {"key": "clean", "content": "Clean", "project": "Home", "section": "Kitchen"},
+                {"content": "Dishes", "parent": "clean", "project": "Home", "section": "Kitchen"},
+                {"content": "Laundry", "project": "Chores"},
+                {"content": "Call mom"},
+            ],
+        }));
+        let keys = |strategies| {
+            let plan = spec.plan(&existing, strategies).unwrap();
+            let keys = plan.steps.into_iter().map(|s| s.key).collect::<Vec<_>>();
+            (keys, plan.skipped)
+        };
+        assert_eq!(
+            keys(Strategies {
+                projects: Strategy::Skip,
+                ..Default::default()
+            }),
+            (vec!["task:#3".to_string()], 6)
+        );
+        assert_eq!(
+            keys(Strategies {
+                tasks: Strategy::Skip,
+                ..Default::default()
+            }),
+            (
+                vec![
+                    "project:Chores".to_string(),
+                    "task:#2".to_string(),
+                    "task:#3".to_string()
+                ],
+                2
+            )
+        );
+        assert_eq!(
+            keys(Strategies {
+                projects: Strategy::Duplicate,
+                sections: Strategy::Duplicate,
+                tasks: Strategy::Duplicate,
+            })
+            .0,
+            vec![
+                "project:Home",
+                "section:Home/Kitchen",
+                "project:Chores",
+                "task:clean",
+                "task:#1",
+                "task:#2",
+                "task:#3"
+            ]
+        );
+    }
+
+    #[test]
+    fn completes_created_tasks() {
+        let (projects, sections, tasks) = existing();
+        let existing = Existing {
+            projects: &projects,
+            sections: &sections,
+            tasks: &tasks,
+        };
+        let plan = spec(serde_json::json!({
+            "tasks": [
+                {"content": "Clean", "project": "Home", "section": "Kitchen", "completed": true},
+                {"content": "Cook", "project": "Home", "completed": true},
+            ],
+        }))
+        .plan(&existing, Strategies::default())
+        .unwrap();
+        let keys = plan
+            .steps
+            .iter()
+            .map(|s| s.key.as_str())
+            .collect::<Vec<_>>();
+        // The existing task is merged into and stays open.
+        assert_eq!(keys, vec!["task:#1", "task:#1/close"]);
+        assert_eq!(
+            plan.steps[1].step,
+            Step::Close {
+                task: Ref::Step("task:#1".to_string())
+            }
+        );
+    }
+}
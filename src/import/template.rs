@@ -0,0 +1,422 @@
+//! Exports projects as CSV templates in the format that Todoist uses for its templates, and
+//! imports them back, so that projects which are done over and over can be stamped out.
+use std::{fs, path::PathBuf};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{DurationUnit, Gateway, Task},
+        tree::Tree,
+    },
+    command::{Command, Context},
+    config::Config,
+    csv, interactive,
+};
+
+use super::{
+    scheduler,
+    spec::{ProjectSpec, Spec, Strategy, TaskSpec},
+    ExistingOptions,
+};
+
+/// The columns of a Todoist template, in the order Todoist writes them.
+const COLUMNS: [&str; 12] = [
+    "TYPE",
+    "CONTENT",
+    "DESCRIPTION",
+    "PRIORITY",
+    "INDENT",
+    "AUTHOR",
+    "RESPONSIBLE",
+    "DATE",
+    "DATE_LANG",
+    "TIMEZONE",
+    "DURATION",
+    "DURATION_UNIT",
+];
+
+#[derive(clap::Parser, Debug)]
+pub struct ExportParams {
+    /// The project to export, matched fuzzily by its name.
+    project: String,
+    /// The CSV file to write the template to. Prints the template if omitted.
+    file: Option<PathBuf>,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct ImportParams {
+    /// The CSV template to import, like one exported from Todoist or with `template export`.
+    file: PathBuf,
+    /// The name of the project to add the sections and tasks of the template to. It is created if
+    /// there is no project with that name. Defaults to the name of the file.
+    #[arg(long = "into")]
+    into: Option<String>,
+    #[clap(flatten)]
+    existing: ExistingOptions,
+    #[clap(flatten)]
+    options: scheduler::Options,
+}
+
+/// Splits CSV text into rows of fields. Quoted fields can contain commas, quotes and newlines.
+fn parse(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(eyre!("a quoted field is never closed"));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Splits the labels at the end of the content off, as templates keep them in the content like
+/// `Pack bags @travel`.
+fn labels(content: &str) -> (String, Vec<String>) {
+    let mut words = content.split_whitespace().collect::<Vec<_>>();
+    let mut labels = Vec::new();
+    while let Some(label) = words
+        .last()
+        .and_then(|w| w.strip_prefix('@'))
+        .filter(|l| !l.is_empty())
+    {
+        labels.insert(0, label.to_string());
+        words.pop();
+    }
+    match labels.is_empty() {
+        true => (content.trim().to_string(), labels),
+        false => (words.join(" "), labels),
+    }
+}
+
+/// Returns the row of a task, which is indented by how deep the task is nested.
+fn task_row(task: &Tree<Task>) -> Vec<String> {
+    let content = std::iter::once(task.content.clone())
+        .chain(task.labels.iter().map(|l| format!("@{}", l)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (amount, unit) = match task.duration {
+        Some(d) => (
+            d.amount.to_string(),
+            match d.unit {
+                DurationUnit::Minute => "minute",
+                DurationUnit::Day => "day",
+            },
+        ),
+        None => (String::new(), ""),
+    };
+    let due = task.due.as_ref().map(|d| d.string.clone());
+    vec![
+        "task".to_string(),
+        content,
+        task.description.clone(),
+        // Templates count priorities like the UI, with 1 as the most urgent.
+        (5 - task.priority as u8).to_string(),
+        (task.depth + 1).to_string(),
+        String::new(),
+        String::new(),
+        due.clone().unwrap_or_default(),
+        due.map(|_| "en".to_string()).unwrap_or_default(),
+        String::new(),
+        amount,
+        unit.to_string(),
+    ]
+}
+
+/// Returns the row of a section or a comment, which only has content.
+fn row(kind: &str, content: &str, indent: usize) -> Vec<String> {
+    let mut row = vec![String::new(); COLUMNS.len()];
+    row[0] = kind.to_string();
+    row[1] = content.to_string();
+    if indent > 0 {
+        row[4] = indent.to_string();
+    }
+    row
+}
+
+/// Adds the rows of the tasks and their comments, subtasks after their parent.
+async fn task_rows(rows: &mut Vec<Vec<String>>, tasks: &[&Tree<Task>], gw: &Gateway) -> Result<()> {
+    for task in tasks.iter().flat_map(|t| t.flatten()) {
+        rows.push(task_row(task));
+        if task.comment_count > 0 {
+            for comment in gw.task_comments(&task.id).await? {
+                rows.push(row("note", &comment.content, task.depth + 1));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the sections and tasks of the project as a CSV template.
+pub async fn export(params: ExportParams, gw: &Gateway) -> Result<()> {
+    let (projects, sections, tasks) =
+        tokio::try_join!(gw.projects(), gw.sections(), gw.tasks(None))?;
+    let project = interactive::fuzz_select(&projects, &params.project)?;
+    let tasks = Tree::from_items(
+        tasks
+            .into_iter()
+            .filter(|t| t.project_id == project.id)
+            .collect(),
+    )?;
+    let mut sections = sections
+        .iter()
+        .filter(|s| s.project_id == project.id)
+        .collect::<Vec<_>>();
+    sections.sort_by_key(|s| s.order);
+
+    let mut rows = vec![COLUMNS.map(String::from).to_vec()];
+    let unsectioned = tasks
+        .iter()
+        .filter(|t| t.section_id.is_none())
+        .collect::<Vec<_>>();
+    task_rows(&mut rows, &unsectioned, gw).await?;
+    for section in sections {
+        rows.push(row("section", &section.name, 0));
+        let tasks = tasks
+            .iter()
+            .filter(|t| t.section_id.as_ref() == Some(&section.id))
+            .collect::<Vec<_>>();
+        task_rows(&mut rows, &tasks, gw).await?;
+    }
+    let csv = rows
+        .iter()
+        .map(|row| csv::row(row))
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n";
+    match params.file {
+        Some(file) => {
+            fs::write(&file, csv)
+                .wrap_err_with(|| format!("unable to write {}", file.display()))?;
+            println!(
+                "exported {} tasks of {} to {}",
+                tasks.iter().map(|t| t.flatten().len()).sum::<usize>(),
+                Content(&project.name),
+                file.display()
+            );
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+/// Turns the rows of a template into a spec that adds its sections and tasks to the project.
+fn spec(rows: &[Vec<String>], project: &str) -> Result<Spec> {
+    let Some((header, rows)) = rows.split_first() else {
+        return Err(eyre!("the template is empty"));
+    };
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let (Some(kind), Some(content)) = (column("TYPE"), column("CONTENT")) else {
+        return Err(eyre!(
+            "not a Todoist template, it needs the TYPE and CONTENT columns"
+        ));
+    };
+    let (description, priority, indent, date) = (
+        column("DESCRIPTION"),
+        column("PRIORITY"),
+        column("INDENT"),
+        column("DATE"),
+    );
+    let mut spec = Spec {
+        projects: vec![ProjectSpec {
+            name: project.to_string(),
+            ..Default::default()
+        }],
+        tasks: Vec::new(),
+    };
+    let mut section = None;
+    // The keys of the tasks that later tasks can be nested in, by their indent.
+    let mut parents: Vec<String> = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        // The header is the first line.
+        let line = index + 2;
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|c| row.get(c))
+                .map(|f| f.trim())
+                .filter(|f| !f.is_empty())
+        };
+        let Some(kind) = field(Some(kind)) else {
+            continue;
+        };
+        let content = field(Some(content)).unwrap_or_default();
+        match kind.to_lowercase().as_str() {
+            "section" => {
+                spec.projects[0].sections.push(content.to_string());
+                section = Some(content.to_string());
+                parents.clear();
+            }
+            "task" => {
+                let depth = match field(indent) {
+                    Some(indent) => indent
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|i| *i > 0)
+                        .ok_or_else(|| eyre!("invalid indent {:?} on line {}", indent, line))?,
+                    None => 1,
+                };
+                if depth > parents.len() + 1 {
+                    return Err(eyre!(
+                        "the task on line {} is nested deeper than the task before it",
+                        line
+                    ));
+                }
+                parents.truncate(depth - 1);
+                let key = format!("line{}", line);
+                let (content, labels) = labels(content);
+                spec.tasks.push(TaskSpec {
+                    key: Some(key.clone()),
+                    content,
+                    description: field(description).map(str::to_string),
+                    due: field(date).map(str::to_string),
+                    priority: field(priority)
+                        .and_then(|p| p.parse().ok())
+                        .filter(|p| (1..=4).contains(p)),
+                    labels,
+                    project: Some(project.to_string()),
+                    section: section.clone(),
+                    parent: parents.last().cloned(),
+                    ..Default::default()
+                });
+                parents.push(key);
+            }
+            "note" => match spec.tasks.last_mut() {
+                Some(task) => task.comments.push(content.to_string()),
+                None => eprintln!("skipping the note on line {}, as it is not on a task", line),
+            },
+            // Holds settings of the project, like how it is viewed.
+            "meta" => {}
+            other => return Err(eyre!("unknown type {:?} on line {}", other, line)),
+        }
+    }
+    Ok(spec)
+}
+
+/// Adds the sections and tasks of the template to the project.
+pub async fn import(params: ImportParams, cfg: &Config) -> Result<()> {
+    let project = match params.into {
+        Some(into) => into,
+        None => params
+            .file
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .ok_or_else(|| eyre!("no project to import into, use --into"))?,
+    };
+    let data = fs::read_to_string(&params.file)
+        .wrap_err_with(|| format!("unable to read {}", params.file.display()))?;
+    let spec =
+        spec(&parse(&data).wrap_err("invalid template")?, &project).wrap_err("invalid template")?;
+    // The same template in the same project resumes the same import.
+    let id = Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        format!("{}\n{}", project, data).as_bytes(),
+    );
+    // The project is where the template goes, so it is used if it exists.
+    let existing = ExistingOptions {
+        projects: Some(params.existing.projects.unwrap_or(Strategy::Merge)),
+        ..params.existing
+    };
+    super::run(&spec, id, &existing, &params.options, cfg).await
+}
+
+impl Command for ExportParams {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        export(self, &ctx.gw).await
+    }
+}
+
+impl Command for ImportParams {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        import(self, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_csv() {
+        let rows =
+            parse("TYPE,CONTENT\r\ntask,\"Say \"\"hi\"\", then\nleave\"\n\ntask,Go").unwrap();
+        assert_eq!(
+            rows,
+            [
+                vec!["TYPE", "CONTENT"],
+                vec!["task", "Say \"hi\", then\nleave"],
+                vec![""],
+                vec!["task", "Go"],
+            ]
+        );
+        assert!(parse("task,\"open").is_err());
+    }
+
+    #[test]
+    fn splits_labels() {
+        assert_eq!(
+            labels("Pack bags @travel @home"),
+            (
+                "Pack bags".to_string(),
+                vec!["travel".to_string(), "home".to_string()]
+            )
+        );
+        assert_eq!(
+            labels("Mail me@example.com"),
+            ("Mail me@example.com".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn nests_tasks() {
+        let rows = parse(
+            "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT\n\
+             task,Prepare,,4,1\n\
+             task,Check @ops,Really,1,2\n\
+             note,Looks good,,,\n\
+             section,Launch,,,\n\
+             task,Ship,,4,1\n",
+        )
+        .unwrap();
+        let spec = spec(&rows, "Release").unwrap();
+        assert_eq!(spec.projects[0].name, "Release");
+        assert_eq!(spec.projects[0].sections, ["Launch"]);
+        let check = &spec.tasks[1];
+        assert_eq!(check.parent.as_deref(), Some("line2"));
+        assert_eq!(check.labels, ["ops"]);
+        assert_eq!(check.priority, Some(1));
+        assert_eq!(check.comments, ["Looks good"]);
+        assert_eq!(spec.tasks[2].section.as_deref(), Some("Launch"));
+        assert_eq!(spec.tasks[2].parent, None);
+
+        let rows = parse("TYPE,CONTENT,INDENT\ntask,Deep,2\n").unwrap();
+        assert!(super::spec(&rows, "Release").is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Imports the `Tasks.json` of a Wunderlist export archive.
+use std::{fs, path::PathBuf};
+
+use color_eyre::{eyre::WrapErr, Result};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{
+    scheduler,
+    spec::{ProjectSpec, Spec, TaskSpec},
+    ExistingOptions,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The `Tasks.json` file of the archive that Wunderlist exports.
+    file: PathBuf,
+    /// Only imports the list with this name. Can be used multiple times to import more lists.
+    #[arg(short = 'l', long = "list")]
+    lists: Vec<String>,
+    /// Also imports completed tasks, as completed tasks.
+    #[arg(long = "completed")]
+    completed: bool,
+    #[clap(flatten)]
+    existing: ExistingOptions,
+    #[clap(flatten)]
+    options: scheduler::Options,
+}
+
+/// A list of tasks, which becomes a project.
+#[derive(Debug, Deserialize)]
+struct List {
+    title: String,
+    #[serde(default)]
+    tasks: Vec<WunderlistTask>,
+}
+
+/// A task of a list, which becomes a task with its notes as description and its subtasks below it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WunderlistTask {
+    id: u64,
+    title: String,
+    #[serde(default)]
+    completed: bool,
+    /// Starred tasks become the most urgent ones.
+    #[serde(default)]
+    starred: bool,
+    /// The due date, which never has a time in Wunderlist.
+    due_date: Option<String>,
+    #[serde(default)]
+    notes: Vec<Note>,
+    #[serde(default)]
+    subtasks: Vec<Subtask>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    #[serde(default)]
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Subtask {
+    title: String,
+    #[serde(default)]
+    completed: bool,
+}
+
+/// Imports the lists of a Wunderlist export as projects with their tasks.
+pub async fn wunderlist(params: Params, cfg: &Config) -> Result<()> {
+    let data = fs::read(&params.file)
+        .wrap_err_with(|| format!("unable to read {}", params.file.display()))?;
+    let lists: Vec<List> = serde_json::from_slice(&data).wrap_err("invalid Wunderlist export")?;
+    super::check_lists(&params.lists, lists.iter().map(|l| &l.title), "Wunderlist")?;
+    // The same file resumes the same import.
+    let id = Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        &[b"wunderlist:".as_slice(), data.as_slice()].concat(),
+    );
+    let spec = spec(lists, &params.lists, params.completed);
+    super::run(&spec, id, &params.existing, &params.options, cfg).await
+}
+
+/// Converts the lists into a spec, with every subtask right after its parent.
+fn spec(lists: Vec<List>, wanted: &[String], completed: bool) -> Spec {
+    let mut spec = Spec::default();
+    for list in lists {
+        if !wanted.is_empty() && !wanted.iter().any(|n| n.eq_ignore_ascii_case(&list.title)) {
+            continue;
+        }
+        for task in list.tasks {
+            if task.title.trim().is_empty() || (task.completed && !completed) {
+                continue;
+            }
+            let key = task.id.to_string();
+            let notes = task
+                .notes
+                .iter()
+                .map(|n| n.content.trim())
+                .filter(|n| !n.is_empty())
+                .collect::<Vec<_>>();
+            spec.tasks.push(TaskSpec {
+                content: task.title,
+                description: Some(notes.join("\n\n")).filter(|d| !d.is_empty()),
+                due: task
+                    .due_date
+                    .and_then(|due| due.get(..10).map(str::to_string)),
+                priority: task.starred.then_some(1),
+                project: Some(list.title.clone()),
+                completed: task.completed,
+                key: Some(key.clone()),
+                ..Default::default()
+            });
+            spec.tasks.extend(
+                task.subtasks
+                    .into_iter()
+                    .filter(|s| !s.title.trim().is_empty() && (completed || !s.completed))
+                    .map(|s| TaskSpec {
+                        content: s.title,
+                        project: Some(list.title.clone()),
+                        parent: Some(key.clone()),
+                        completed: s.completed,
+                        ..Default::default()
+                    }),
+            );
+        }
+        spec.projects.push(ProjectSpec {
+            name: list.title,
+            ..Default::default()
+        });
+    }
+    spec
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        wunderlist(self, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_lists() {
+        let lists: Vec<List> = serde_json::from_value(serde_json::json!([
+            {"title": "Travel", "tasks": [
+                {"id": 1, "title": "Trip", "starred": true, "dueDate": "2022-08-26",
+                 "notes": [{"content": "To Rome"}],
+                 "subtasks": [{"title": "Pack"}, {"title": "Passport", "completed": true}]},
+                {"id": 2, "title": "Book", "completed": true,
+                 "subtasks": [{"title": "Compare prices"}]},
+            ]},
+            {"title": "Work", "tasks": [{"id": 3, "title": "Report"}]},
+        ]))
+        .unwrap();
+        let spec = spec(lists, &["travel".to_string()], false);
+        let projects = spec.projects.iter().map(|p| &p.name).collect::<Vec<_>>();
+        assert_eq!(projects, ["Travel"]);
+        let tasks = spec
+            .tasks
+            .iter()
+            .map(|t| (t.content.as_str(), t.parent.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(tasks, [("Trip", None), ("Pack", Some("1"))]);
+        assert_eq!(spec.tasks[0].description.as_deref(), Some("To Rome"));
+        assert_eq!(spec.tasks[0].due.as_deref(), Some("2022-08-26"));
+        assert_eq!(spec.tasks[0].priority, Some(1));
+    }
+}
@@ -4,8 +4,12 @@ use fuzzy_matcher::FuzzyMatcher;
 use owo_colors::OwoColorize;
 use std::iter;
 
-use crate::api::rest::{
-    Label, LabelID, Priority, Project, ProjectID, Section, SectionID, Task, TaskID,
+use crate::{
+    api::rest::{
+        ArchivedProject, Label, LabelID, Priority, Project, ProjectID, Section, SectionID, Task,
+        TaskID,
+    },
+    cancel, history, output,
 };
 use color_eyre::{eyre::eyre, eyre::WrapErr, Result};
 
@@ -13,6 +17,8 @@ use color_eyre::{eyre::eyre, eyre::WrapErr, Result};
 pub struct Selection<T: FuzzSelect> {
     name: Option<String>,
     id: Option<T::ID>,
+    /// The name of the flag that takes the ID, to script interactive choices with.
+    id_flag: &'static str,
 }
 
 macro_rules! selection {
@@ -33,6 +39,7 @@ macro_rules! selection {
                         .get_one::<String>($select_name)
                         .map(|i| i.to_owned()),
                     id,
+                    id_flag: $select_id,
                 })
             }
 
@@ -92,6 +99,16 @@ selection!(
     "ID of the project to use. Does nothing if -P is specified."
 );
 
+selection!(
+    ArchivedProject,
+    "project",
+    "project",
+    'P',
+    "project_id",
+    "Uses the archived project with the closest name, if possible. Does fuzzy matching for the name.",
+    "ID of the archived project to use. Does nothing if -P is specified."
+);
+
 // TODO: filter down selection based on selected project if any
 selection!(
     Section,
@@ -120,14 +137,21 @@ impl<T: FuzzSelect + std::fmt::Display> Selection<T> {
         let selection = Self::optional(self, items)?;
         match selection {
             Some(s) => Ok(s),
-            None => Ok(select("select item", items)?
-                .map(|i| &items[i])
-                .ok_or_else(|| eyre!("no selection made"))?),
+            None => {
+                let item = select("select item", items)?
+                    .map(|i| &items[i])
+                    .ok_or_else(|| eyre!("no selection made"))?;
+                history::chose([format!("--{}", self.id_flag), item.id().to_string()]);
+                Ok(item)
+            }
         }
     }
 }
 
 pub fn select<T: ToString>(prompt: &str, items: &[T]) -> Result<Option<usize>> {
+    if output::a11y() {
+        return select_numbered(prompt, items);
+    }
     let result = dialoguer::FuzzySelect::with_theme(&dialoguer::theme::ColorfulTheme {
         fuzzy_match_highlight_style: dialoguer::console::Style::new()
             .for_stderr()
@@ -140,10 +164,42 @@ pub fn select<T: ToString>(prompt: &str, items: &[T]) -> Result<Option<usize>> {
     .with_prompt(prompt)
     .default(0)
     .interact_opt()
+    .map_err(cancel::interrupted)
     .wrap_err("Unable to make a selection")?;
     Ok(result)
 }
 
+/// Lists the items with their numbers once and asks for the number of one, as screen readers can't
+/// follow a list that is redrawn while typing.
+fn select_numbered<T: ToString>(prompt: &str, items: &[T]) -> Result<Option<usize>> {
+    if items.is_empty() {
+        return Ok(None);
+    }
+    eprintln!("{}", prompt);
+    for (index, item) in items.iter().enumerate() {
+        eprintln!("{} of {}: {}", index + 1, items.len(), item.to_string());
+    }
+    let number = dialoguer::Input::<String>::new()
+        .with_prompt(format!(
+            "Number from 1 to {}, or nothing to cancel",
+            items.len()
+        ))
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), &str> {
+            match input.trim() {
+                "" => Ok(()),
+                input => match input.parse::<usize>() {
+                    Ok(n) if (1..=items.len()).contains(&n) => Ok(()),
+                    _ => Err("not one of the numbers"),
+                },
+            }
+        })
+        .interact_text()
+        .map_err(cancel::interrupted)
+        .wrap_err("Unable to make a selection")?;
+    Ok(number.trim().parse::<usize>().ok().map(|n| n - 1))
+}
+
 pub fn fuzz_select<'a, T: FuzzSelect>(items: &'a [T], input: &'_ str) -> Result<&'a T> {
     if items.is_empty() {
         return Err(eyre!("no items available for selection, aborting"));
@@ -158,7 +214,7 @@ pub fn fuzz_select<'a, T: FuzzSelect>(items: &'a [T], input: &'_ str) -> Result<
 }
 
 pub trait FuzzSelect {
-    type ID: std::cmp::PartialEq + std::clone::Clone;
+    type ID: std::cmp::PartialEq + std::clone::Clone + std::fmt::Display;
 
     fn id(&self) -> Self::ID;
     fn name(&self) -> &str;
@@ -175,6 +231,17 @@ impl FuzzSelect for Project {
     }
 }
 
+impl FuzzSelect for ArchivedProject {
+    type ID = ProjectID;
+
+    fn id(&self) -> ProjectID {
+        self.id.clone()
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 impl FuzzSelect for Section {
     type ID = SectionID;
 
@@ -221,6 +288,7 @@ pub fn input_content(content: &str) -> Result<String> {
         })
         .with_initial_text(content.to_owned())
         .interact_text()
+        .map_err(cancel::interrupted)
         .wrap_err("No input made")
 }
 
@@ -230,6 +298,7 @@ pub fn input_optional(prompt: &str, default: Option<String>) -> Result<Option<St
         .allow_empty(true)
         .with_initial_text(default.unwrap_or("".to_owned()))
         .interact_text()
+        .map_err(cancel::interrupted)
         .wrap_err("No input made")?
         .as_str()
     {
@@ -238,6 +307,15 @@ pub fn input_optional(prompt: &str, default: Option<String>) -> Result<Option<St
     }
 }
 
+pub fn confirm(prompt: &str) -> Result<bool> {
+    dialoguer::Confirm::new()
+        .with_prompt(prompt)
+        .default(true)
+        .interact()
+        .map_err(cancel::interrupted)
+        .wrap_err("No input made")
+}
+
 pub fn input_project(
     projects: &[Project],
     sections: &[Section],
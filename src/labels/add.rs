@@ -1,5 +1,8 @@
-use crate::api::rest::{CreateLabel, Gateway};
-use color_eyre::Result;
+use crate::{
+    api::rest::{CreateLabel, Gateway},
+    command::{Command, Context},
+};
+use color_eyre::{eyre::eyre, Result};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
@@ -17,3 +20,16 @@ pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
     println!("created label: {}", &label);
     Ok(())
 }
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(eyre!("label name can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        add(self, &ctx.gw).await
+    }
+}
@@ -1,4 +1,8 @@
-use crate::api::rest::Gateway;
+use crate::{
+    api::rest::Gateway,
+    cancel,
+    command::{Command, Context},
+};
 use color_eyre::{eyre::eyre, Result};
 
 use super::{label::Selection, LabelSelect};
@@ -17,8 +21,15 @@ pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
         return Err(eyre!("no labels selected"));
     }
     for label in labels {
+        cancel::check()?;
         gw.delete_label(&label.id).await?;
         println!("deleted label: {}", &label);
     }
     Ok(())
 }
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        delete(self, &ctx.gw).await
+    }
+}
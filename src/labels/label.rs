@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{
     api::rest::{FullLabel, Label},
-    interactive,
+    history, interactive,
 };
 use color_eyre::{eyre::eyre, Result};
 
@@ -54,16 +54,13 @@ impl LabelSelect {
 
         if self.label_names.is_none() {
             if found_labels.is_empty() && selection == Selection::MustChoose {
-                return Ok(vec![all_labels
-                    .remove(
-                        &label_list[interactive::select(
-                            "Select label",
-                            &label_list.iter().map(FullLabel).collect::<Vec<_>>(),
-                        )?
-                        .ok_or_else(|| eyre!("no labels selected"))?]
-                        .id,
-                    )
-                    .unwrap()]);
+                let chosen = &label_list[interactive::select(
+                    "Select label",
+                    &label_list.iter().map(FullLabel).collect::<Vec<_>>(),
+                )?
+                .ok_or_else(|| eyre!("no labels selected"))?];
+                history::chose(["--label_id".to_string(), chosen.id.clone()]);
+                return Ok(vec![all_labels.remove(&chosen.id).unwrap()]);
             }
             return Ok(found_labels);
         }
@@ -1,13 +1,38 @@
-use crate::api::rest::{FullLabel, Gateway};
+use crate::{
+    api::rest::{FullLabel, Gateway},
+    command::{Command, Context},
+    output,
+};
 use color_eyre::Result;
 
+use super::shared;
+
 #[derive(clap::Parser, Debug)]
-pub struct Params {}
+pub struct Params {
+    /// Lists the shared labels instead, which are the labels on the tasks of shared projects that
+    /// are not personal labels.
+    #[arg(short = 's', long = "shared")]
+    shared: bool,
+    #[clap(flatten)]
+    records: output::Records,
+}
 
-pub async fn list(_params: Params, gw: &Gateway) -> Result<()> {
+pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
+    if params.shared {
+        for name in gw.shared_labels(true).await? {
+            params.records.print(shared::display(&name));
+        }
+        return Ok(());
+    }
     let labels = gw.labels().await?;
     for label in labels {
-        println!("{}", &FullLabel(&label));
+        params.records.print(FullLabel(&label));
     }
     Ok(())
 }
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.gw).await
+    }
+}
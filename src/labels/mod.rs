@@ -3,4 +3,7 @@ pub mod delete;
 mod label;
 /// Controls things that work with [`crate::api::rest::Label`]s.
 pub mod list;
+pub mod shared;
+pub mod suggest;
+pub mod update;
 pub use label::{LabelSelect, Selection};
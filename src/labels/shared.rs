@@ -0,0 +1,83 @@
+//! Works with shared labels, which are the labels on the tasks of shared projects. They are not
+//! personal labels with an ID, so they are only known by their name.
+use color_eyre::{eyre::eyre, Result};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{content::Content, rest::Gateway},
+    command::{Command, Context},
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct RenameParams {
+    /// The name of the shared label to rename, with or without the leading `@`.
+    name: String,
+    /// The new name of the label.
+    new_name: String,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct RemoveParams {
+    /// The name of the shared label to remove from all tasks, with or without the leading `@`.
+    name: String,
+}
+
+/// Renders the name of a shared label the way labels are shown.
+pub fn display(name: &str) -> String {
+    format!("@{}", Content(name))
+        .if_supports_color(Stream::Stdout, |text| text.bright_blue())
+        .to_string()
+}
+
+/// Returns the name of the label without the `@` it is written with in tasks.
+fn name(name: &str) -> &str {
+    name.trim().trim_start_matches('@')
+}
+
+/// Makes sure the shared label exists, as the API accepts names that no task carries.
+async fn ensure_exists(name: &str, gw: &Gateway) -> Result<()> {
+    if !gw.shared_labels(false).await?.iter().any(|n| n == name) {
+        return Err(eyre!("no task carries the shared label @{}", name));
+    }
+    Ok(())
+}
+
+/// Renames the shared label on every task that carries it.
+pub async fn rename(params: RenameParams, gw: &Gateway) -> Result<()> {
+    let (old, new) = (name(&params.name), name(&params.new_name));
+    ensure_exists(old, gw).await?;
+    gw.rename_shared_label(old, new).await?;
+    println!("renamed shared label {} to {}", display(old), display(new));
+    Ok(())
+}
+
+/// Removes the shared label from every task that carries it.
+pub async fn remove(params: RemoveParams, gw: &Gateway) -> Result<()> {
+    let name = name(&params.name);
+    ensure_exists(name, gw).await?;
+    gw.remove_shared_label(name).await?;
+    println!("removed shared label: {}", display(name));
+    Ok(())
+}
+
+impl Command for RenameParams {
+    fn validate(&self) -> Result<()> {
+        if name(&self.new_name).is_empty() {
+            return Err(eyre!("label name can not be empty"));
+        }
+        if name(&self.name) == name(&self.new_name) {
+            return Err(eyre!("the new name is the same as the old one"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        rename(self, &ctx.gw).await
+    }
+}
+
+impl Command for RemoveParams {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        remove(self, &ctx.gw).await
+    }
+}
@@ -0,0 +1,170 @@
+//! Suggests labels for new tasks, from keyword rules of the config and from the labels that were
+//! given to earlier tasks with similar words.
+use std::collections::{BTreeSet, HashMap};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{api::rest::Label, config::LabelRule, store::Store};
+
+/// Keeps how often words were used in tasks, and which labels these tasks had.
+pub(crate) const HISTORY_FILE: &str = "label_history.json";
+
+/// How many tasks with a word need to have had a label before it is suggested.
+const MIN_USES: usize = 2;
+
+/// History is what was learned from the tasks that were added before.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    words: HashMap<String, WordUses>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WordUses {
+    /// In how many tasks the word was used.
+    tasks: usize,
+    /// How many of these tasks had each label.
+    labels: HashMap<String, usize>,
+}
+
+impl History {
+    /// Loads the history from the store.
+    pub fn load(store: &Store) -> Result<History> {
+        store.load(HISTORY_FILE)
+    }
+
+    /// Learns the labels of a new task and saves the history to the store.
+    pub fn learn(mut self, store: &Store, content: &str, labels: &[Label]) -> Result<()> {
+        self.record(
+            content,
+            &labels.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(),
+        );
+        store.save(HISTORY_FILE, &self)
+    }
+
+    /// Counts the words of the content and the labels that were used with them.
+    fn record(&mut self, content: &str, labels: &[&str]) {
+        for word in words(content).into_iter().collect::<BTreeSet<_>>() {
+            let uses = self.words.entry(word).or_default();
+            uses.tasks += 1;
+            for label in labels {
+                *uses.labels.entry(label.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Returns the names of labels that at least half of the earlier tasks with one of the words
+    /// had.
+    fn suggest(&self, words: &[String]) -> BTreeSet<&str> {
+        words
+            .iter()
+            .filter_map(|w| self.words.get(w))
+            .flat_map(|uses| {
+                uses.labels
+                    .iter()
+                    .filter(|(_, &n)| n >= MIN_USES && n * 2 >= uses.tasks)
+                    .map(|(label, _)| label.as_str())
+            })
+            .collect()
+    }
+}
+
+/// Returns the labels that are suggested for the content, leaving out the ones that are already
+/// `chosen`. Only labels that exist are suggested, in the order of `labels`.
+pub fn suggest<'a>(
+    content: &str,
+    rules: &[LabelRule],
+    history: &History,
+    labels: &'a [Label],
+    chosen: &[Label],
+) -> Vec<&'a Label> {
+    let words = words(content);
+    let mut names = history.suggest(&words);
+    for rule in rules {
+        let keyword = self::words(&rule.keyword);
+        if !keyword.is_empty() && words.windows(keyword.len()).any(|w| w == keyword) {
+            names.insert(&rule.label);
+        }
+    }
+    labels
+        .iter()
+        .filter(|l| names.contains(l.name.as_str()))
+        .filter(|l| !chosen.iter().any(|c| c.id == l.id))
+        .collect()
+}
+
+/// Splits the content into lowercase words, ignoring punctuation.
+fn words(content: &str) -> Vec<String> {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn label(id: &str, name: &str) -> Label {
+        Label {
+            id: id.to_string(),
+            name: name.to_string(),
+            color: "red".to_string(),
+            order: 0,
+            is_favorite: false,
+        }
+    }
+
+    #[test]
+    fn suggests_from_rules() {
+        let labels = [label("1", "finance"), label("2", "home")];
+        let rules = [
+            LabelRule {
+                keyword: "Invoice".to_string(),
+                label: "finance".to_string(),
+            },
+            LabelRule {
+                keyword: "water plants".to_string(),
+                label: "home".to_string(),
+            },
+            LabelRule {
+                keyword: "invoice".to_string(),
+                label: "missing".to_string(),
+            },
+        ];
+        let history = History::default();
+        let names = |content: &str, chosen: &[Label]| {
+            suggest(content, &rules, &history, &labels, chosen)
+                .iter()
+                .map(|l| l.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names("Send invoice, then water plants!", &[]),
+            ["finance", "home"]
+        );
+        assert_eq!(names("Send invoices", &[]), Vec::<&str>::new());
+        assert_eq!(names("plants need water", &[]), Vec::<&str>::new());
+        assert_eq!(
+            names("Send invoice", &[label("1", "finance")]),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn suggests_from_history() {
+        let mut history = History::default();
+        history.record("Call the dentist", &["health"]);
+        history.record("Book dentist appointment", &["health"]);
+        history.record("Call mom", &["family"]);
+        history.record("Call the bank", &[]);
+        history.record("Call the plumber", &[]);
+        assert_eq!(
+            history.suggest(&words("dentist on monday")),
+            BTreeSet::from(["health"])
+        );
+        // Only one of four calls was with family.
+        assert!(history.suggest(&words("call grandpa")).is_empty());
+    }
+}
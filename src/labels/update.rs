@@ -0,0 +1,62 @@
+use crate::{
+    api::rest::{Gateway, UpdateLabel},
+    command::{Command, Context},
+};
+use color_eyre::{eyre::eyre, Result};
+
+use super::{label::Selection, LabelSelect};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    label: LabelSelect,
+    /// Renames the label.
+    #[arg(short = 'n', long = "name")]
+    name: Option<String>,
+    /// Sets the color of the label, like `red` or `sky_blue`.
+    #[arg(short = 'c', long = "color")]
+    color: Option<String>,
+    /// Marks the label as a favorite, or not with `--favorite=false`.
+    #[arg(long = "favorite", num_args = 0..=1, default_missing_value = "true")]
+    favorite: Option<bool>,
+}
+
+pub async fn update(params: Params, gw: &Gateway) -> Result<()> {
+    let labels = params
+        .label
+        .labels(&gw.labels().await?, Selection::MustChoose)?;
+    let [label] = labels.as_slice() else {
+        return Err(eyre!("select exactly one label to update"));
+    };
+    let updated = gw
+        .update_label(
+            &label.id,
+            &UpdateLabel {
+                name: params.name,
+                color: params.color,
+                is_favorite: params.favorite,
+                ..Default::default()
+            },
+        )
+        .await?;
+    println!("updated label: {}", &updated);
+    Ok(())
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.is_none() && self.color.is_none() && self.favorite.is_none() {
+            return Err(eyre!(
+                "nothing to update, use --name, --color or --favorite"
+            ));
+        }
+        if self.name.as_ref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(eyre!("label name can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        update(self, &ctx.gw).await
+    }
+}
@@ -10,14 +10,59 @@
 //! ```
 #![warn(missing_docs)]
 pub mod api;
+mod audit;
+mod cache;
+mod cancel;
+mod chart;
 mod command;
 mod comments;
 pub mod config;
+mod csv;
+mod debug;
+mod digest;
+mod duration;
+mod editor;
+mod events;
+#[cfg(feature = "integrations")]
+mod export;
+mod failure;
+mod filters;
+mod goals;
+mod history;
+mod holidays;
+#[cfg(feature = "integrations")]
+mod homeassistant;
+#[cfg(feature = "integrations")]
+mod hooks;
+mod i18n;
+#[cfg(feature = "importers")]
+mod import;
 mod interactive;
 mod labels;
+mod notify;
+#[cfg(feature = "integrations")]
+mod obsidian;
+mod operations;
+mod output;
+mod pager;
+mod preview;
 mod projects;
+mod recurrence;
+#[cfg(feature = "integrations")]
+mod remote;
+mod report;
 mod sections;
+#[cfg(feature = "integrations")]
+mod serve;
+mod store;
 mod tasks;
+mod timer;
+#[cfg(feature = "tray")]
+mod tray;
+mod usage;
+mod waiting;
+#[cfg(feature = "integrations")]
+mod worklog;
 
 #[doc(hidden)]
 pub use command::Arguments;
@@ -0,0 +1,447 @@
+//! Reminds of tasks shortly before they are due, following the notification rules of the config.
+//! It checks once, for use from cron, or keeps checking as a daemon.
+//!
+//! During quiet hours or while notifications are snoozed, they are queued and delivered once
+//! notifications are allowed again.
+use std::{collections::BTreeMap, process};
+
+use chrono::{DateTime, Duration, Local, NaiveTime, Utc};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::rest::{Gateway, Project, ProjectID, Task, TaskID},
+    cancel,
+    command::{Command, Context},
+    config::{Config, Notifications},
+    duration,
+};
+
+/// The file in the store that remembers which tasks were already notified about.
+const SENT_FILE: &str = "notified.json";
+/// The file in the store that contains the snooze and the notifications that wait for it to end.
+const MUTE_FILE: &str = "notify_mute.json";
+
+/// How long after a task was due a missed notification is still delivered.
+const LATE_MINUTES: i64 = 60;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Keeps running and checks for notifications on an interval. Stop it with Ctrl-C.
+    #[arg(long = "daemon")]
+    daemon: bool,
+    /// How often the daemon checks for notifications, like `30s` or `5m`.
+    #[arg(long = "interval", default_value = "1m", value_parser = duration::parse, requires = "daemon")]
+    interval: Duration,
+    /// Mutes all notifications for this long, like `2h`, and queues them until then. `0` ends the
+    /// snooze.
+    #[arg(long = "snooze", value_parser = duration::parse, conflicts_with = "daemon")]
+    snooze: Option<Duration>,
+}
+
+/// Notification is a reminder of a task, scheduled for some time before the task is due.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Notification {
+    task: TaskID,
+    content: String,
+    due: DateTime<Utc>,
+    at: DateTime<Utc>,
+}
+
+impl Notification {
+    fn text(&self) -> String {
+        format!(
+            "{} is due at {}",
+            self.content,
+            self.due.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        )
+    }
+}
+
+/// Rules are the notification rules of the config, with their durations parsed and their projects
+/// looked up.
+#[derive(Debug, Default)]
+struct Rules {
+    before: Duration,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Default)]
+struct Rule {
+    label: Option<String>,
+    project: Option<ProjectID>,
+    before: Option<Duration>,
+    never: bool,
+}
+
+impl Rules {
+    fn new(notifications: &Notifications, projects: &[Project]) -> Result<Rules> {
+        let before = |before: &Option<String>| {
+            before
+                .as_deref()
+                .map(duration::parse)
+                .transpose()
+                .map_err(|e| eyre!("invalid notification time: {}", e))
+        };
+        let rules = notifications
+            .rules
+            .iter()
+            .map(|rule| {
+                let project = rule
+                    .project
+                    .as_ref()
+                    .map(|name| {
+                        projects
+                            .iter()
+                            .find(|p| p.name.eq_ignore_ascii_case(name))
+                            .map(|p| p.id.clone())
+                            .ok_or_else(|| {
+                                eyre!("notification rule has an unknown project {:?}", name)
+                            })
+                    })
+                    .transpose()?;
+                Ok(Rule {
+                    label: rule.label.clone(),
+                    project,
+                    before: before(&rule.before)?,
+                    never: rule.never,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Rules {
+            before: before(&notifications.before)?.unwrap_or_else(Duration::zero),
+            rules,
+        })
+    }
+
+    /// Returns how long before it is due the task is notified about, or none if it never is.
+    fn before(&self, task: &Task) -> Option<Duration> {
+        let rule = self.rules.iter().find(|rule| {
+            rule.label.as_ref().is_none_or(|l| task.labels.contains(l))
+                && rule.project.as_ref().is_none_or(|p| task.project_id == *p)
+        });
+        match rule {
+            Some(rule) if rule.never => None,
+            Some(rule) => Some(rule.before.unwrap_or(self.before)),
+            None => Some(self.before),
+        }
+    }
+}
+
+/// Schedules the notifications of all tasks that are due at an exact time.
+fn schedule(tasks: &[Task], rules: &Rules) -> Vec<Notification> {
+    let mut notifications = tasks
+        .iter()
+        .filter_map(|task| {
+            let due = task
+                .due
+                .as_ref()?
+                .exact
+                .as_ref()?
+                .datetime
+                .with_timezone(&Utc);
+            Some(Notification {
+                task: task.id.clone(),
+                content: task.content.clone(),
+                due,
+                at: due - rules.before(task)?,
+            })
+        })
+        .collect::<Vec<_>>();
+    notifications.sort_by_key(|n| n.at);
+    notifications
+}
+
+/// Sent remembers the due time that every task was notified about, so a task is only notified
+/// about again when it is rescheduled.
+type Sent = BTreeMap<TaskID, DateTime<Utc>>;
+
+/// Returns the notifications that should be delivered now.
+fn pending<'a>(
+    notifications: &'a [Notification],
+    sent: &Sent,
+    now: DateTime<Utc>,
+) -> Vec<&'a Notification> {
+    notifications
+        .iter()
+        .filter(|n| {
+            n.at <= now
+                && now < n.due + Duration::minutes(LATE_MINUTES)
+                && sent.get(&n.task) != Some(&n.due)
+        })
+        .collect()
+}
+
+/// Mute holds back notifications while they are snoozed or during quiet hours.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Mute {
+    /// Until when notifications are snoozed.
+    until: Option<DateTime<Utc>>,
+    /// The notifications that are waiting to be delivered.
+    queue: Vec<Notification>,
+}
+
+/// QuietHours is a time range of every day without notifications, which can span midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct QuietHours {
+    from: NaiveTime,
+    to: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parses ranges like `22:00-07:00`.
+    fn parse(s: &str) -> Result<QuietHours> {
+        let invalid = || {
+            eyre!(
+                "invalid quiet hours {:?}, expected something like 22:00-07:00",
+                s
+            )
+        };
+        let (from, to) = s.split_once('-').ok_or_else(invalid)?;
+        let time = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        Ok(QuietHours {
+            from: time(from)?,
+            to: time(to)?,
+        })
+    }
+
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.from <= self.to {
+            self.from <= time && time < self.to
+        } else {
+            self.from <= time || time < self.to
+        }
+    }
+}
+
+/// Returns whether notifications are muted at the time, by a snooze or quiet hours.
+fn muted(mute: &Mute, quiet: &[QuietHours], now: DateTime<Utc>) -> bool {
+    let time = now.with_timezone(&Local).time();
+    mute.until.is_some_and(|until| now < until) || quiet.iter().any(|q| q.contains(time))
+}
+
+/// Delivers the notifications that are due, once or on an interval.
+pub async fn notify(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    if let Some(snooze) = params.snooze {
+        return self::snooze(snooze, cfg);
+    }
+    if !params.daemon {
+        return check(gw, cfg).await;
+    }
+    let interval = params
+        .interval
+        .to_std()
+        .wrap_err("invalid notification interval")?;
+    while !cancel::is_cancelled() {
+        // Tasks and projects may have changed since the last check.
+        gw.refresh().await;
+        // A failed check, like when the network is down for a moment, is tried again on the next
+        // tick instead of ending the daemon.
+        if let Err(e) = check(gw, cfg).await {
+            eprintln!("unable to check for notifications: {:#}", e);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = cancel::cancelled() => {}
+        }
+    }
+    Ok(())
+}
+
+fn snooze(snooze: Duration, cfg: &Config) -> Result<()> {
+    let store = cfg.store()?;
+    let mut mute: Mute = store.load(MUTE_FILE)?;
+    if snooze <= Duration::zero() {
+        mute.until = None;
+        println!("notifications are no longer snoozed");
+    } else {
+        let until = cfg.override_time.unwrap_or_else(Utc::now) + snooze;
+        mute.until = Some(until);
+        println!(
+            "notifications are snoozed until {}",
+            until.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        );
+    }
+    store.save(MUTE_FILE, &mute)
+}
+
+async fn check(gw: &Gateway, cfg: &Config) -> Result<()> {
+    let quiet = cfg
+        .notifications
+        .quiet_hours
+        .iter()
+        .map(|q| QuietHours::parse(q))
+        .collect::<Result<Vec<_>>>()?;
+    let (tasks, projects) = tokio::try_join!(gw.tasks(None), gw.projects())?;
+    let rules = Rules::new(&cfg.notifications, &projects)?;
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let store = cfg.store()?;
+    let mut sent: Sent = store.load(SENT_FILE)?;
+    let mut mute: Mute = store.load(MUTE_FILE)?;
+    let notifications = schedule(&tasks, &rules);
+    let pending = pending(&notifications, &sent, now)
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    for notification in &pending {
+        sent.insert(notification.task.clone(), notification.due);
+    }
+    mute.queue.extend(pending);
+    if !muted(&mute, &quiet, now) {
+        mute.until = None;
+        for notification in mute.queue.drain(..) {
+            deliver(&cfg.notifications.command, &notification.text())?;
+        }
+    }
+    sent.retain(|_, due| now < *due + Duration::minutes(LATE_MINUTES));
+    store.save(MUTE_FILE, &mute)?;
+    store.save(SENT_FILE, &sent)
+}
+
+/// Runs the notification command with the text, or prints the text if there is no command.
+fn deliver(command: &[String], text: &str) -> Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        println!("{}", text);
+        return Ok(());
+    };
+    let status = process::Command::new(program)
+        .args(args)
+        .arg(text)
+        .status()
+        .wrap_err_with(|| format!("unable to run notification command {}", program))?;
+    if !status.success() {
+        return Err(eyre!("notification command {} failed: {}", program, status));
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        notify(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        api::rest::{DueDate, ExactTime},
+        config::NotificationRule,
+    };
+
+    use super::*;
+
+    fn task(id: &str, project: &str, labels: &[&str], due: DateTime<Utc>) -> Task {
+        let mut task = Task::new(id, id);
+        task.project_id = project.to_string();
+        task.labels = labels.iter().map(|l| l.to_string()).collect();
+        task.due = Some(DueDate {
+            string: String::new(),
+            date: due.date_naive(),
+            is_recurring: false,
+            exact: Some(ExactTime {
+                datetime: due.fixed_offset(),
+                timezone: "UTC".to_string(),
+            }),
+        });
+        task
+    }
+
+    #[test]
+    fn follows_rules() {
+        let groceries = Project::new("1", "Groceries");
+        let notifications = Notifications {
+            before: Some("5m".to_string()),
+            rules: vec![
+                NotificationRule {
+                    label: Some("meeting".to_string()),
+                    before: Some("30m".to_string()),
+                    ..Default::default()
+                },
+                NotificationRule {
+                    project: Some("groceries".to_string()),
+                    never: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let rules = Rules::new(&notifications, &[groceries]).unwrap();
+        let due = "2022-08-26T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let tasks = [
+            task("meeting", "2", &["meeting"], due),
+            task("milk", "1", &[], due),
+            task("meeting in groceries", "1", &["meeting"], due),
+            task("other", "2", &[], due),
+            Task::new("no time", "no time"),
+        ];
+        let at = schedule(&tasks, &rules)
+            .into_iter()
+            .map(|n| (n.task, (due - n.at).num_minutes()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            at,
+            [
+                ("meeting".to_string(), 30),
+                ("meeting in groceries".to_string(), 30),
+                ("other".to_string(), 5),
+            ]
+        );
+        let notifications = Notifications {
+            rules: vec![NotificationRule {
+                project: Some("unknown".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(Rules::new(&notifications, &[]).is_err());
+    }
+
+    #[test]
+    fn delivers_once() {
+        let due = "2022-08-26T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let notifications = schedule(
+            &[task("1", "1", &[], due)],
+            &Rules {
+                before: Duration::minutes(10),
+                ..Default::default()
+            },
+        );
+        let at = |minutes| due + Duration::minutes(minutes);
+        let mut sent = Sent::new();
+        assert!(pending(&notifications, &sent, at(-11)).is_empty());
+        assert_eq!(pending(&notifications, &sent, at(-10)).len(), 1);
+        assert_eq!(pending(&notifications, &sent, at(30)).len(), 1);
+        assert!(pending(&notifications, &sent, at(LATE_MINUTES)).is_empty());
+        sent.insert("1".to_string(), due);
+        assert!(pending(&notifications, &sent, at(0)).is_empty());
+        // Rescheduling the task notifies again.
+        sent.insert("1".to_string(), at(-60));
+        assert_eq!(pending(&notifications, &sent, at(0)).len(), 1);
+    }
+
+    #[test]
+    fn mutes_notifications() {
+        let night = QuietHours::parse("22:00-07:00").unwrap();
+        let lunch = QuietHours::parse(" 12:00 - 13:00 ").unwrap();
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(night.contains(time(23, 0)));
+        assert!(night.contains(time(6, 59)));
+        assert!(!night.contains(time(7, 0)));
+        assert!(lunch.contains(time(12, 30)));
+        assert!(!lunch.contains(time(13, 0)));
+        assert!(QuietHours::parse("22:00").is_err());
+        assert!(QuietHours::parse("10pm-7am").is_err());
+
+        let now = "2022-08-26T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let snoozed = Mute {
+            until: Some(now + Duration::hours(1)),
+            queue: Vec::new(),
+        };
+        assert!(muted(&snoozed, &[], now));
+        assert!(!muted(&snoozed, &[], now + Duration::hours(1)));
+        assert!(!muted(&Mute::default(), &[], now));
+    }
+}
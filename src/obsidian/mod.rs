@@ -0,0 +1,148 @@
+//! Keeps the tasks of the day in the daily note of an Obsidian vault, where they can be checked
+//! off like any other checklist.
+//!
+//! doist owns the part of the note between the [`START`] and [`END`] markers. Every task in it
+//! carries its ID in an HTML comment, which Obsidian hides in its preview.
+use crate::api::rest::{Task, TaskID};
+
+pub mod sync;
+
+/// Starts the section of the note that doist maintains.
+const START: &str = "<!-- doist:start -->";
+/// Ends the section of the note that doist maintains.
+const END: &str = "<!-- doist:end -->";
+
+/// Section is what was found in the section of a note.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Section {
+    /// The tasks that were checked off in the note, with their content.
+    checked: Vec<(TaskID, String)>,
+    /// Lines without a task, like tasks that were closed before or notes, which are kept as they
+    /// are.
+    kept: Vec<String>,
+}
+
+impl Section {
+    /// Reads the section of the note. Notes without a section have nothing in it.
+    fn parse(note: &str) -> Section {
+        let mut section = Section::default();
+        let Some((_, rest)) = note.split_once(START) else {
+            return section;
+        };
+        let inner = rest.split_once(END).map_or(rest, |(inner, _)| inner);
+        for line in inner.lines().filter(|l| !l.trim().is_empty()) {
+            match task_line(line) {
+                Some((id, content, true)) => section.checked.push((id, content)),
+                // Open tasks are written again from the tasks that are still open.
+                Some((_, _, false)) => {}
+                None => section.kept.push(line.to_string()),
+            }
+        }
+        section
+    }
+
+    /// Renders the section with the open tasks, followed by the checked tasks without their ID so
+    /// they are not closed again, and the kept lines.
+    fn render(&self, tasks: &[Task]) -> String {
+        let mut lines = vec![START.to_string()];
+        lines.extend(tasks.iter().map(|task| {
+            format!(
+                "- [ ] {} <!-- doist:{} -->",
+                task.content.replace('\n', " "),
+                task.id
+            )
+        }));
+        lines.extend(
+            self.checked
+                .iter()
+                .map(|(_, content)| format!("- [x] {}", content)),
+        );
+        lines.extend(self.kept.iter().cloned());
+        lines.push(END.to_string());
+        lines.join("\n")
+    }
+}
+
+/// Returns the ID, content and whether it was checked for a line of a task.
+fn task_line(line: &str) -> Option<(TaskID, String, bool)> {
+    let line = line.trim();
+    let (checked, rest) = if let Some(rest) = line.strip_prefix("- [ ]") {
+        (false, rest)
+    } else {
+        let rest = line
+            .strip_prefix("- [x]")
+            .or_else(|| line.strip_prefix("- [X]"))?;
+        (true, rest)
+    };
+    let (content, marker) = rest.rsplit_once("<!-- doist:")?;
+    let id = marker.strip_suffix("-->")?.trim();
+    if id.is_empty() {
+        return None;
+    }
+    Some((id.to_string(), content.trim().to_string(), checked))
+}
+
+/// Replaces the section of the note, or adds it under the heading at the end of the note.
+fn update(note: &str, section: &str, heading: &str) -> String {
+    if let Some((before, rest)) = note.split_once(START) {
+        let after = rest.split_once(END).map_or("", |(_, after)| after);
+        return format!("{}{}{}", before, section, after);
+    }
+    let mut note = note.trim_end().to_string();
+    if !note.is_empty() {
+        note.push_str("\n\n");
+    }
+    if !heading.is_empty() {
+        note.push_str(heading);
+        note.push('\n');
+    }
+    note.push_str(section);
+    note.push('\n');
+    note
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_section() {
+        let note = "# Monday\n\n## Tasks\n<!-- doist:start -->\n- [ ] Open <!-- doist:1 -->\n- [x] Done <!-- doist:2 -->\n- [x] Closed earlier\nRemember the milk\n<!-- doist:end -->\n\n- [x] Not ours <!-- doist:3 -->\n";
+        assert_eq!(
+            Section::parse(note),
+            Section {
+                checked: vec![("2".to_string(), "Done".to_string())],
+                kept: vec![
+                    "- [x] Closed earlier".to_string(),
+                    "Remember the milk".to_string()
+                ],
+            }
+        );
+        assert_eq!(Section::parse("# Monday\n"), Section::default());
+    }
+
+    #[test]
+    fn updates_note() {
+        let section = Section {
+            checked: vec![("2".to_string(), "Done".to_string())],
+            kept: vec!["Remember the milk".to_string()],
+        }
+        .render(&[]);
+        assert_eq!(
+            section,
+            "<!-- doist:start -->\n- [x] Done\nRemember the milk\n<!-- doist:end -->"
+        );
+        assert_eq!(
+            update("# Monday\n", &section, "## Tasks"),
+            format!("# Monday\n\n## Tasks\n{}\n", section)
+        );
+        assert_eq!(
+            update(
+                "before\n<!-- doist:start -->\nold\n<!-- doist:end -->\nafter\n",
+                "<!-- doist:start -->\n<!-- doist:end -->",
+                "## Tasks"
+            ),
+            "before\n<!-- doist:start -->\n<!-- doist:end -->\nafter\n"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use chrono::{Local, Utc};
+use color_eyre::{eyre::WrapErr, Result};
+
+use crate::{
+    api::rest::Gateway,
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::Section;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The folder of the Obsidian vault.
+    #[arg(long = "vault")]
+    vault: PathBuf,
+    /// The folder of the daily notes, relative to the vault.
+    #[arg(long = "folder")]
+    folder: Option<PathBuf>,
+    /// The name of daily notes, as a date format like `%Y-%m-%d` for `2022-08-26.md`.
+    #[arg(long = "date-format", default_value = "%Y-%m-%d")]
+    date_format: String,
+    /// The tasks that are written into the note.
+    #[arg(short = 'f', long = "filter", default_value = "today | overdue")]
+    filter: String,
+    /// The heading that the tasks are added under, if the note does not have them yet.
+    #[arg(long = "heading", default_value = "## Tasks")]
+    heading: String,
+}
+
+/// Closes the tasks that were checked off in the daily note, then writes the open tasks into it.
+pub async fn sync(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let today = cfg
+        .override_time
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local)
+        .format(&params.date_format)
+        .to_string();
+    let mut path = params.vault;
+    if let Some(folder) = params.folder {
+        path.push(folder);
+    }
+    path.push(format!("{}.md", today));
+    let note = match fs::read_to_string(&path) {
+        Ok(note) => note,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).wrap_err_with(|| format!("unable to read {}", path.display())),
+    };
+    let section = Section::parse(&note);
+    for (id, _) in &section.checked {
+        gw.close(id).await?;
+    }
+    let mut tasks = gw
+        .tasks(Some(&params.filter))
+        .await?
+        .into_iter()
+        // Closing can take a moment to show up in filters.
+        .filter(|t| !section.checked.iter().any(|(id, _)| *id == t.id))
+        .collect::<Vec<_>>();
+    tasks.sort();
+    let note = super::update(&note, &section.render(&tasks), &params.heading);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).wrap_err_with(|| format!("unable to create {}", dir.display()))?;
+    }
+    fs::write(&path, note).wrap_err_with(|| format!("unable to write {}", path.display()))?;
+    println!(
+        "closed {} tasks and wrote {} tasks to {}",
+        section.checked.len(),
+        tasks.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        sync(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,103 @@
+//! Remembers the operations that modify data until they finish, so that running the same command
+//! again after it failed part way, like on a timeout, continues the same operation. Its requests
+//! are keyed by the operation, see [`crate::api::rest::Gateway::operation`], so Todoist ignores
+//! the ones that already went through instead of creating everything twice.
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{history, store::Store};
+
+/// Where the operations that did not finish are kept, by the command line that started them.
+pub(crate) const OPERATIONS_FILE: &str = "operations.json";
+
+/// How long an operation that did not finish is continued by running the same command again.
+/// After that, the command starts over, as Todoist only ignores repeated requests for a while.
+fn expiry() -> Duration {
+    Duration::days(1)
+}
+
+/// Pending is an operation that modified data, but did not finish.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Pending {
+    key: Uuid,
+    started: DateTime<Utc>,
+}
+
+/// Journal tracks the operation of the current command line.
+pub(crate) struct Journal {
+    store: Store,
+    command: String,
+    key: Uuid,
+    /// If the operation is in the store, either from an earlier run or since it modified data.
+    pending: AtomicBool,
+}
+
+impl Journal {
+    /// Opens the operation of the command line, which continues the last one of the same command
+    /// line if that did not finish.
+    pub fn open(store: Store, args: &[String]) -> Journal {
+        let command = history::command_line(&history::redact_args(args));
+        let pending = load(&store)
+            .remove(&command)
+            .filter(|p| p.started + expiry() > Utc::now());
+        Journal {
+            store,
+            command,
+            key: pending.as_ref().map_or_else(Uuid::new_v4, |p| p.key),
+            pending: AtomicBool::new(pending.is_some()),
+        }
+    }
+
+    /// Returns the key that the requests of the operation are derived from.
+    pub fn key(&self) -> Uuid {
+        self.key
+    }
+
+    /// Notes that the operation is about to modify data, so that it is continued if it doesn't
+    /// finish. Failing to do so only warns, as the operation can still run.
+    pub fn modifies(&self) {
+        if self.pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let mut operations = load(&self.store);
+        operations.insert(
+            self.command.clone(),
+            Pending {
+                key: self.key,
+                started: Utc::now(),
+            },
+        );
+        if let Err(e) = self.save(operations) {
+            eprintln!("unable to remember the operation: {:#}", e);
+        }
+    }
+
+    /// Forgets the operation, as it finished, so that running the command again starts a new one.
+    pub fn finish(&self) {
+        if !self.pending.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let mut operations = load(&self.store);
+        operations.remove(&self.command);
+        if let Err(e) = self.save(operations) {
+            eprintln!("unable to forget the finished operation: {:#}", e);
+        }
+    }
+
+    /// Saves the operations, leaving out the ones that expired.
+    fn save(&self, mut operations: HashMap<String, Pending>) -> color_eyre::Result<()> {
+        operations.retain(|_, p| p.started + expiry() > Utc::now());
+        self.store.save(OPERATIONS_FILE, &operations)
+    }
+}
+
+/// Loads the operations that did not finish. Unreadable ones are started over.
+fn load(store: &Store) -> HashMap<String, Pending> {
+    store.load(OPERATIONS_FILE).unwrap_or_default()
+}
@@ -0,0 +1,176 @@
+//! Machine readable output of commands, so their results can be used in scripts, and output
+//! that screen readers can follow.
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::i18n::t;
+
+/// Whether the output is meant for screen readers, see [`install_a11y`].
+static A11Y: AtomicBool = AtomicBool::new(false);
+
+/// Makes all output from now on friendly to screen readers: no progress bars, symbols or drawings,
+/// but words, numbered items and prompts that are asked line by line.
+pub fn install_a11y() {
+    A11Y.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if the output is meant for screen readers.
+pub fn a11y() -> bool {
+    A11Y.load(Ordering::Relaxed)
+}
+
+/// Returns the emoji if the terminal shows colors, or the text for plain terminals and screen
+/// readers.
+pub fn symbol(text: &'static str, emoji: &'static str) -> String {
+    match a11y() {
+        true => text.to_string(),
+        false => text
+            .if_supports_color(Stream::Stdout, |_| emoji)
+            .to_string(),
+    }
+}
+
+/// Tells screen readers where a task is in a list, like `Task 2 of 5: …`. Returns the task as is
+/// for everybody else.
+pub fn position(index: usize, count: usize, task: impl Display) -> String {
+    match a11y() {
+        true => t!(
+            "task-position",
+            index = index + 1,
+            count = count,
+            task = task.to_string()
+        ),
+        false => task.to_string(),
+    }
+}
+
+/// Options of commands that can print their results as JSON.
+#[derive(clap::Args, Debug, Default)]
+pub struct Json {
+    /// Prints the results as JSON instead of a human readable list.
+    #[arg(long = "json")]
+    pub json: bool,
+    /// Only includes the given comma separated fields in the JSON output. Nested fields are
+    /// selected with dots, like `id,content,due.date`.
+    #[arg(long = "fields", value_delimiter = ',', requires = "json")]
+    pub fields: Vec<String>,
+}
+
+impl Json {
+    /// Prints the items as a JSON array that only contains the selected fields.
+    pub fn print<T: Serialize>(&self, items: &[T]) -> Result<()> {
+        let items = items
+            .iter()
+            .map(|item| Ok(select(&serde_json::to_value(item)?, &self.fields)))
+            .collect::<Result<Vec<_>>>()?;
+        println!("{}", serde_json::to_string_pretty(&items)?);
+        Ok(())
+    }
+}
+
+/// Options of commands that print one record for each item.
+#[derive(clap::Args, Debug, Default)]
+pub struct Records {
+    /// Ends every record with a NUL character instead of a newline, so that newlines in the
+    /// content stay intact when piped into `xargs -0`.
+    #[arg(short = '0', long = "null")]
+    pub null: bool,
+}
+
+impl Records {
+    /// Prints a single record followed by its delimiter.
+    pub fn print(&self, record: impl Display) {
+        if self.null {
+            print!("{}\0", record);
+        } else {
+            println!("{}", record);
+        }
+    }
+}
+
+/// Projects the value onto the dot separated paths of the fields. Fields that don't exist are
+/// null, so the shape of the result only depends on the fields that were asked for. Without any
+/// fields the value is returned as is.
+pub fn select(value: &Value, fields: &[String]) -> Value {
+    if fields.is_empty() {
+        return value.clone();
+    }
+    let mut selected = Value::Object(Map::new());
+    for field in fields.iter().filter(|f| !f.is_empty()) {
+        let path = field.split('.').collect::<Vec<_>>();
+        let found = path
+            .iter()
+            .try_fold(value, |value, key| lookup(value, key))
+            .cloned()
+            .unwrap_or(Value::Null);
+        insert(&mut selected, &path, found);
+    }
+    selected
+}
+
+/// Returns the field of an object, or the element of an array if the key is an index.
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(o) => o.get(key),
+        Value::Array(a) => key.parse::<usize>().ok().and_then(|i| a.get(i)),
+        _ => None,
+    }
+}
+
+/// Inserts the value at the path, creating the objects that lead to it.
+fn insert(target: &mut Value, path: &[&str], value: Value) {
+    let (Value::Object(object), Some((key, rest))) = (target, path.split_first()) else {
+        return;
+    };
+    if rest.is_empty() {
+        object.insert(key.to_string(), value);
+        return;
+    }
+    let child = object
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !child.is_object() {
+        *child = Value::Object(Map::new());
+    }
+    insert(child, rest, value);
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn selects_fields() {
+        let value = json!({
+            "id": "1",
+            "content": "hello",
+            "labels": ["one", "two"],
+            "due": {"date": "2022-01-01", "string": "tomorrow"},
+            "parent_id": null,
+        });
+        let fields = |fields: &str| {
+            fields
+                .split(',')
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(select(&value, &[]), value);
+        assert_eq!(
+            select(&value, &fields("id,due.date,labels.1")),
+            json!({"id": "1", "due": {"date": "2022-01-01"}, "labels": {"1": "two"}})
+        );
+        assert_eq!(
+            select(&value, &fields("nope,parent_id.date,content.length")),
+            json!({"nope": null, "parent_id": {"date": null}, "content": {"length": null}})
+        );
+    }
+}
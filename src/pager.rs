@@ -0,0 +1,51 @@
+//! Shows long output through a pager, so it doesn't flood the terminal.
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use dialoguer::console::Term;
+
+/// The pager that is used if `$PAGER` is not set. Searching is done with `/` in less.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Prints the output, using `$PAGER` if the output does not fit on the terminal screen.
+///
+/// Output is printed directly if stdout is not a terminal, or if the pager could not be started.
+pub fn page(output: &str) -> Result<()> {
+    let term = Term::stdout();
+    let fits = match term.size_checked() {
+        Some((rows, _)) => output.lines().count() < rows as usize,
+        None => true,
+    };
+    if !term.is_term() || fits {
+        println!("{}", output);
+        return Ok(());
+    }
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut args = pager.split_whitespace();
+    let child = args.next().and_then(|program| {
+        Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+    let mut child = match child {
+        Some(child) => child,
+        None => {
+            println!("{}", output);
+            return Ok(());
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager might be closed before it read everything, which is fine.
+        let _ = writeln!(stdin, "{}", output);
+    }
+    child.wait().wrap_err("pager exited unexpectedly")?;
+    Ok(())
+}
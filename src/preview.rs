@@ -0,0 +1,205 @@
+//! Renders previews of images inline in terminals that support an image protocol.
+//!
+//! Kitty and iTerm2 (and terminals implementing its protocol, like WezTerm) are supported. Sixel
+//! would require decoding the images ourselves, so those terminals only see the links to the
+//! images, same as all other terminals, as they are part of the regular output anyway.
+use std::{fmt::Write, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use color_eyre::{eyre::eyre, Result};
+use dialoguer::console::Term;
+
+use crate::cancel;
+
+/// Images larger than this are not downloaded for previews.
+const MAX_SIZE: u64 = 5 * 1024 * 1024;
+/// The height of a preview in terminal rows. The width is scaled to keep the aspect ratio.
+const ROWS: u32 = 20;
+/// Kitty requires the image data to be split up into escape sequences of at most this size.
+const KITTY_CHUNK: usize = 4096;
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// The protocol that is used to display images in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    /// The [kitty graphics protocol](https://sw.kovidgoyal.net/kitty/graphics-protocol/).
+    Kitty,
+    /// The [iTerm2 inline images protocol](https://iterm2.com/documentation-images.html).
+    ITerm,
+}
+
+impl Protocol {
+    /// Detects the image protocol of the terminal that stdout is attached to.
+    fn detect() -> Option<Protocol> {
+        if !Term::stdout().is_term() {
+            return None;
+        }
+        Self::from_env(|key| std::env::var(key).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Option<Protocol> {
+        if var("KITTY_WINDOW_ID").is_some() || var("TERM").as_deref() == Some("xterm-kitty") {
+            return Some(Protocol::Kitty);
+        }
+        match var("TERM_PROGRAM").as_deref() {
+            Some("iTerm.app") | Some("WezTerm") => Some(Protocol::ITerm),
+            _ => None,
+        }
+    }
+
+    /// Creates the escape sequences to display the image, if the format is supported.
+    fn encode(&self, image: &[u8]) -> Option<String> {
+        let data = STANDARD.encode(image);
+        match self {
+            Protocol::Kitty => {
+                // Kitty can only directly display PNG data, everything else needs decoding.
+                if !image.starts_with(PNG_MAGIC) {
+                    return None;
+                }
+                let mut out = String::new();
+                let mut start = 0;
+                while start < data.len() {
+                    let end = (start + KITTY_CHUNK).min(data.len());
+                    let more = u8::from(end < data.len());
+                    // base64 is always ASCII, so slicing by bytes is safe.
+                    let chunk = &data[start..end];
+                    if start == 0 {
+                        write!(out, "\x1b_Gf=100,a=T,r={ROWS},m={more};{chunk}\x1b\\").ok()?;
+                    } else {
+                        write!(out, "\x1b_Gm={more};{chunk}\x1b\\").ok()?;
+                    }
+                    start = end;
+                }
+                Some(out)
+            }
+            Protocol::ITerm => Some(format!(
+                "\x1b]1337;File=inline=1;size={};height={ROWS};preserveAspectRatio=1:{data}\x07",
+                image.len()
+            )),
+        }
+    }
+}
+
+/// Returns the URLs of all markdown images (`![alt](url)`) that are linked in the text.
+pub fn image_links(text: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+        let Some(link) = rest.find("](").map(|i| &rest[i + 2..]) else {
+            break;
+        };
+        let Some(end) = link.find(')') else {
+            break;
+        };
+        // Links can have a title after the URL, which we don't need.
+        if let Some(url) = link[..end].split_whitespace().next() {
+            if url.starts_with("https://") || url.starts_with("http://") {
+                links.push(url.to_string());
+            }
+        }
+        rest = &link[end..];
+    }
+    links
+}
+
+/// Shows previews of the images behind the URLs if the terminal supports it.
+///
+/// Images that can't be loaded are skipped, as their links are still part of the regular output.
+pub async fn show(urls: &[String]) -> Result<()> {
+    let protocol = match Protocol::detect() {
+        Some(protocol) if !urls.is_empty() => protocol,
+        _ => return Ok(()),
+    };
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    for url in urls {
+        cancel::check()?;
+        let image = match fetch(&client, url).await {
+            Ok(image) => image,
+            Err(e) => {
+                eprintln!("unable to preview {}: {}", url, e);
+                continue;
+            }
+        };
+        if let Some(preview) = protocol.encode(&image) {
+            println!("Preview: {}", url);
+            println!("{}", preview);
+        }
+    }
+    Ok(())
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let mut resp = client.get(url).send().await?.error_for_status()?;
+    let too_large = || eyre!("image is larger than {} bytes", MAX_SIZE);
+    if resp.content_length().is_some_and(|len| len > MAX_SIZE) {
+        return Err(too_large());
+    }
+    let mut image = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        image.extend_from_slice(&chunk);
+        if image.len() as u64 > MAX_SIZE {
+            return Err(too_large());
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_protocol() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(
+            Protocol::from_env(env(&[("TERM", "xterm-kitty")])),
+            Some(Protocol::Kitty)
+        );
+        assert_eq!(
+            Protocol::from_env(env(&[("KITTY_WINDOW_ID", "1")])),
+            Some(Protocol::Kitty)
+        );
+        assert_eq!(
+            Protocol::from_env(env(&[("TERM_PROGRAM", "iTerm.app")])),
+            Some(Protocol::ITerm)
+        );
+        assert_eq!(Protocol::from_env(env(&[("TERM", "xterm-256color")])), None);
+    }
+
+    #[test]
+    fn encodes_images() {
+        let mut png = PNG_MAGIC.to_vec();
+        png.resize(PNG_MAGIC.len() + KITTY_CHUNK, 0);
+        let kitty = Protocol::Kitty.encode(&png).unwrap();
+        assert!(kitty.starts_with("\x1b_Gf=100,a=T,r=20,m=1;"));
+        assert!(kitty.contains("\x1b_Gm=0;"));
+        assert!(Protocol::Kitty.encode(b"GIF89a").is_none());
+
+        let iterm = Protocol::ITerm.encode(b"GIF89a").unwrap();
+        assert_eq!(
+            iterm,
+            "\x1b]1337;File=inline=1;size=6;height=20;preserveAspectRatio=1:R0lGODlh\x07"
+        );
+    }
+
+    #[test]
+    fn finds_image_links() {
+        assert_eq!(
+            image_links(
+                "see ![cat](https://example.com/cat.png \"a cat\") and [not](https://a.b) \
+                 ![local](cat.png) ![dog](http://example.com/dog.jpg)"
+            ),
+            vec!["https://example.com/cat.png", "http://example.com/dog.jpg"]
+        );
+        assert!(image_links("no images ![broken](").is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    api::rest::{ArchivedProject, Gateway, Project},
+    command::{Command, Context},
+    interactive,
+};
+use color_eyre::{eyre::eyre, Result};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+}
+
+pub async fn archive(params: Params, gw: &Gateway) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params.project.mandatory(&projects)?;
+    gw.archive_project(&project.id).await?;
+    println!("archived project: {}", &project);
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        archive(self, &ctx.gw).await
+    }
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct UnarchiveParams {
+    #[clap(flatten)]
+    project: interactive::Selection<ArchivedProject>,
+}
+
+/// Unarchives one of the archived projects, so it shows up with its tasks again.
+pub async fn unarchive(params: UnarchiveParams, gw: &Gateway) -> Result<()> {
+    let projects = gw.archived_projects().await?;
+    if projects.is_empty() {
+        return Err(eyre!("there are no archived projects"));
+    }
+    let project = params.project.mandatory(&projects)?;
+    gw.unarchive_project(&project.id).await?;
+    println!("unarchived project: {}", &project);
+    Ok(())
+}
+
+impl Command for UnarchiveParams {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        unarchive(self, &ctx.gw).await
+    }
+}
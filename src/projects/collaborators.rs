@@ -0,0 +1,37 @@
+use crate::{
+    api::rest::{FullCollaborator, Gateway, Project},
+    command::{Command, Context},
+    interactive, output,
+};
+use color_eyre::{eyre::eyre, Result};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    #[clap(flatten)]
+    output: output::Json,
+}
+
+pub async fn collaborators(params: Params, gw: &Gateway) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params.project.mandatory(&projects)?;
+    if !project.is_shared {
+        return Err(eyre!("project {} is not shared", project.name));
+    }
+    let mut collaborators = gw.collaborators(&project.id).await?;
+    collaborators.sort_by(|a, b| a.name.cmp(&b.name));
+    if params.output.json {
+        return params.output.print(&collaborators);
+    }
+    for collaborator in &collaborators {
+        println!("{}", FullCollaborator(collaborator));
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        collaborators(self, &ctx.gw).await
+    }
+}
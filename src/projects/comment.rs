@@ -1,14 +1,20 @@
-use color_eyre::Result;
+use std::path::PathBuf;
+
+use color_eyre::{eyre::eyre, Result};
 
 use crate::{
     api::rest::{CreateComment, FullComment, Gateway, Project, ThreadID},
-    interactive,
+    command::{Command, Context},
+    comments, interactive,
 };
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     /// The text of the comment. Supports Markdown.
     content: String,
+    /// A file to attach to the comment, like a PDF or an image.
+    #[arg(long = "file")]
+    file: Option<PathBuf>,
     #[clap(flatten)]
     project: interactive::Selection<Project>,
 }
@@ -17,14 +23,29 @@ pub struct Params {
 pub async fn comment(params: Params, gw: &Gateway) -> Result<()> {
     let projects = gw.projects().await?;
     let project = params.project.mandatory(&projects)?;
+    let attachment = comments::attach(params.file.as_deref(), gw).await?;
     let comment = gw
         .create_comment(&CreateComment {
             thread: ThreadID::Project {
                 project_id: project.id.clone(),
             },
             content: params.content,
+            attachment,
         })
         .await?;
     println!("created comment: {}", FullComment(&comment));
     Ok(())
 }
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.content.trim().is_empty() {
+            return Err(eyre!("comment can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        comment(self, &ctx.gw).await
+    }
+}
@@ -0,0 +1,32 @@
+use color_eyre::Result;
+
+use crate::{
+    api::rest::{Gateway, Project},
+    command::{Command, Context},
+    comments, interactive,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+}
+
+/// Lists the comments of a project.
+pub async fn comments(params: Params, gw: &Gateway) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params.project.mandatory(&projects)?;
+    let comments = gw.project_comments(&project.id).await?;
+    if comments.is_empty() {
+        println!("there are no comments on the project");
+        return Ok(());
+    }
+    println!("{}", comments::list(&comments));
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        comments(self, &ctx.gw).await
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     api::rest::{Gateway, Project},
+    command::{Command, Context},
     interactive,
 };
 use color_eyre::Result;
@@ -17,3 +18,9 @@ pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
     println!("deleted project: {}", &project);
     Ok(())
 }
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        delete(self, &ctx.gw).await
+    }
+}
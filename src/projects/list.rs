@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
-use crate::api::rest::{Gateway, Project, Task};
+use crate::{
+    api::rest::{Gateway, Project, Task},
+    command::{Command, Context},
+    output,
+};
 use color_eyre::{eyre::eyre, Result};
 
 #[derive(clap::Parser, Debug)]
@@ -8,10 +12,27 @@ pub struct Params {
     /// If specified, will only show projects whose tasks are passing this filter.
     #[arg(short = 'f', long = "filter")]
     pub filter: Option<String>,
+    /// Lists the archived projects instead, which can be unarchived with `projects unarchive`.
+    #[arg(long = "archived", conflicts_with = "filter")]
+    pub archived: bool,
+    #[clap(flatten)]
+    pub output: output::Json,
+    #[clap(flatten)]
+    pub records: output::Records,
 }
 
 /// Lists available projects.
 pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
+    if params.archived {
+        let projects = gw.archived_projects().await?;
+        if params.output.json {
+            return params.output.print(&projects);
+        }
+        for project in projects.iter() {
+            params.records.print(project);
+        }
+        return Ok(());
+    }
     let projects = gw.projects().await?;
     if let Some(filter) = params.filter {
         let tasks = gw.tasks(Some(&filter)).await?;
@@ -19,13 +40,22 @@ pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
             return Err(eyre!("no tasks match the given filter"))?;
         }
         let projects = filtered_projects(&projects, &tasks)?;
+        if params.output.json {
+            let projects = projects.into_iter().map(|(p, _)| p).collect::<Vec<_>>();
+            return params.output.print(&projects);
+        }
         for (project, tasks) in projects.iter() {
-            println!("{} (Tasks: {})", &project, tasks);
+            params
+                .records
+                .print(format!("{} (Tasks: {})", &project, tasks));
         }
         return Ok(());
     }
+    if params.output.json {
+        return params.output.print(&projects);
+    }
     for project in projects.iter() {
-        println!("{}", &project);
+        params.records.print(project);
     }
     Ok(())
 }
@@ -45,6 +75,12 @@ fn filtered_projects<'a>(
     Ok(projects)
 }
 
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.gw).await
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use color_eyre::Result;
+use serde::Serialize;
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{CompletedTask, Gateway, Project, Section, SectionID, Task},
+    },
+    chart,
+    command::{Command, Context},
+    interactive, output,
+};
+
+/// How many characters the progress bars and the roadmap are wide.
+const BAR_WIDTH: usize = 20;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    #[clap(flatten)]
+    output: output::Json,
+}
+
+/// Milestone is a section of a project, with how far along its tasks are.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct Milestone {
+    section_id: SectionID,
+    name: String,
+    completed: usize,
+    total: usize,
+    percent: usize,
+    earliest_due: Option<NaiveDate>,
+    latest_due: Option<NaiveDate>,
+}
+
+impl Milestone {
+    fn new(section: &Section, tasks: &[Task], completed: &[CompletedTask]) -> Milestone {
+        let active = tasks
+            .iter()
+            .filter(|t| t.section_id.as_ref() == Some(&section.id))
+            .collect::<Vec<_>>();
+        // Recurring tasks stay active when they are completed, so they never count as done.
+        let active_ids = active.iter().map(|t| &t.id).collect::<HashSet<_>>();
+        let done = completed
+            .iter()
+            .filter(|c| c.section_id.as_ref() == Some(&section.id))
+            .filter(|c| !active_ids.contains(&c.task_id))
+            .map(|c| &c.task_id)
+            .collect::<HashSet<_>>()
+            .len();
+        let total = active.len() + done;
+        let dates = active
+            .iter()
+            .filter_map(|t| t.due.as_ref().map(|d| d.date))
+            .collect::<Vec<_>>();
+        Milestone {
+            section_id: section.id.clone(),
+            name: section.name.clone(),
+            completed: done,
+            total,
+            percent: (done * 100).checked_div(total).unwrap_or(0),
+            earliest_due: dates.iter().min().copied(),
+            latest_due: dates.iter().max().copied(),
+        }
+    }
+
+    fn progress(&self) -> String {
+        let filled = (self.completed * BAR_WIDTH)
+            .checked_div(self.total)
+            .unwrap_or(0);
+        format!(
+            "{} {:>3}% ({}/{})",
+            chart::bar(filled, BAR_WIDTH),
+            self.percent,
+            self.completed,
+            self.total
+        )
+    }
+}
+
+/// Shows the sections of a project as milestones, with their progress and when they are due.
+pub async fn milestones(params: Params, gw: &Gateway) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params.project.mandatory(&projects)?;
+    let (sections, tasks, completed) = tokio::try_join!(
+        gw.sections(),
+        gw.tasks(None),
+        gw.completed_in_project(&project.id)
+    )?;
+    let mut sections = sections
+        .into_iter()
+        .filter(|s| s.project_id == project.id)
+        .collect::<Vec<_>>();
+    sections.sort();
+    let milestones = sections
+        .iter()
+        .map(|s| Milestone::new(s, &tasks, &completed))
+        .collect::<Vec<_>>();
+    if params.output.json {
+        return params.output.print(&milestones);
+    }
+    println!("Milestones of {}:", project);
+    print!("{}", render(&milestones));
+    Ok(())
+}
+
+/// Renders the progress of all milestones, followed by a roadmap that shows when each of them is
+/// due on a shared timeline.
+fn render(milestones: &[Milestone]) -> String {
+    let names: Vec<String> = milestones
+        .iter()
+        .map(|m| Content(&m.name).to_string())
+        .collect();
+    let width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+    let mut out = String::new();
+    for (m, name) in milestones.iter().zip(&names) {
+        let due = match (m.earliest_due, m.latest_due) {
+            (Some(earliest), Some(latest)) if earliest == latest => format!("  due {}", earliest),
+            (Some(earliest), Some(latest)) => format!("  due {} to {}", earliest, latest),
+            _ => String::new(),
+        };
+        out.push_str(&format!(
+            "{:width$}  {}{}\n",
+            name,
+            m.progress(),
+            due,
+            width = width
+        ));
+    }
+    let start = milestones.iter().filter_map(|m| m.earliest_due).min();
+    let end = milestones.iter().filter_map(|m| m.latest_due).max();
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return out,
+    };
+    let days = (end - start).num_days().max(1) as usize;
+    let column = |date: NaiveDate| (date - start).num_days() as usize * (BAR_WIDTH - 1) / days;
+    out.push_str(&format!("\nRoadmap from {} to {}:\n", start, end));
+    for (m, name) in milestones.iter().zip(&names) {
+        let columns = match (m.earliest_due, m.latest_due) {
+            (Some(earliest), Some(latest)) => Some((column(earliest), column(latest))),
+            _ => None,
+        };
+        out.push_str(&format!(
+            "{:width$}  {}\n",
+            name,
+            chart::span(columns, BAR_WIDTH),
+            width = width
+        ));
+    }
+    out
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        milestones(self, &ctx.gw).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn milestone(
+        name: &str,
+        completed: usize,
+        total: usize,
+        due: Option<(&str, &str)>,
+    ) -> Milestone {
+        Milestone {
+            section_id: name.to_string(),
+            name: name.to_string(),
+            completed,
+            total,
+            percent: (completed * 100).checked_div(total).unwrap_or(0),
+            earliest_due: due.map(|(e, _)| e.parse().unwrap()),
+            latest_due: due.map(|(_, l)| l.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn renders_roadmap() {
+        let milestones = [
+            milestone("Alpha", 1, 2, Some(("2022-09-01", "2022-09-10"))),
+            milestone("Beta", 0, 4, Some(("2022-09-10", "2022-09-20"))),
+            milestone("Later", 0, 0, None),
+        ];
+        assert_eq!(
+            render(&milestones),
+            "Alpha  [██████████░░░░░░░░░░]  50% (1/2)  due 2022-09-01 to 2022-09-10\n\
+             Beta   [░░░░░░░░░░░░░░░░░░░░]   0% (0/4)  due 2022-09-10 to 2022-09-20\n\
+             Later  [░░░░░░░░░░░░░░░░░░░░]   0% (0/0)\n\
+             \n\
+             Roadmap from 2022-09-01 to 2022-09-20:\n\
+             Alpha  │━━━━━━━━━━          │\n\
+             Beta   │         ━━━━━━━━━━━│\n\
+             Later  │                    │\n"
+        );
+    }
+}
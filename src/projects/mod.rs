@@ -1,7 +1,12 @@
 //! Controls things that work with [`crate::api::rest::Project`]s.
 pub mod add;
+pub mod archive;
+pub mod collaborators;
 pub mod comment;
+pub mod comments;
 pub mod delete;
 pub mod list;
+pub mod milestones;
+pub mod rename;
 pub(crate) mod state;
 pub mod view;
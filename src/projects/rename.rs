@@ -0,0 +1,42 @@
+use crate::{
+    api::rest::{Gateway, Project, UpdateProject},
+    command::{Command, Context},
+    interactive,
+};
+use color_eyre::{eyre::eyre, Result};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    /// The new name of the project.
+    name: String,
+}
+
+pub async fn rename(params: Params, gw: &Gateway) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params.project.mandatory(&projects)?;
+    gw.update_project(
+        &project.id,
+        &UpdateProject {
+            name: Some(params.name.clone()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    println!("renamed project {} to {}", &project.name, params.name);
+    Ok(())
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(eyre!("project name can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        rename(self, &ctx.gw).await
+    }
+}
@@ -1,14 +1,19 @@
 use crate::{
     api::rest::{Gateway, Project},
-    comments, interactive,
+    command::{Command, Context},
+    comments, interactive, pager,
     projects::state::State,
 };
 use color_eyre::{eyre::eyre, Result};
+use std::fmt::Write;
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     #[clap(flatten)]
     project: interactive::Selection<Project>,
+    /// Prints everything directly instead of using a pager for long output.
+    #[arg(long = "nopager")]
+    nopager: bool,
 }
 
 pub async fn view(params: Params, gw: &Gateway) -> Result<()> {
@@ -19,23 +24,35 @@ pub async fn view(params: Params, gw: &Gateway) -> Result<()> {
     let tree = state
         .project(&project.id)
         .ok_or_else(|| eyre!("full project list contained invalid data"))?;
-    println!("Project: {}", &tree.item);
+    let mut output = String::new();
+    writeln!(output, "Project: {}", &tree.item)?;
     if !tree.subitems.is_empty() {
-        println!("Subprojects:");
+        writeln!(output, "Subprojects:")?;
         for project in &tree.subitems {
-            println!("{}", project.item)
+            writeln!(output, "{}", project.item)?;
         }
     }
     let sections = state.sections(&project.id);
     if !sections.is_empty() {
-        println!("Sections:");
+        writeln!(output, "Sections:")?;
         for section in sections {
-            println!("{}", section)
+            writeln!(output, "{}", section)?;
         }
     }
     if project.comment_count > 0 {
         let comments = gw.project_comments(&project.id).await?;
-        comments::list(&comments)
+        writeln!(output, "{}", comments::list(&comments))?;
+    }
+    let output = output.trim_end_matches('\n');
+    if params.nopager {
+        println!("{}", output);
+        return Ok(());
+    }
+    pager::page(output)
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        view(self, &ctx.gw).await
     }
-    Ok(())
 }
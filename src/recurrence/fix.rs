@@ -0,0 +1,128 @@
+use chrono::{Local, NaiveDate, Utc};
+use color_eyre::{eyre::eyre, Result};
+use dialoguer::console::Term;
+
+use crate::{
+    api::rest::{Gateway, TaskDue, TaskID, UpdateTask},
+    command::{Command, Context},
+    config::Config,
+    interactive,
+};
+
+use super::Recurrence;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The ID of the task whose recurrence is fixed.
+    id: TaskID,
+    /// The new recurrence, instead of editing the current one interactively. Without a terminal
+    /// it is submitted if it can be previewed.
+    #[arg(long = "to")]
+    to: Option<String>,
+    /// How many of the next occurrences are previewed.
+    #[arg(short = 'n', long = "count", default_value_t = 5)]
+    count: usize,
+}
+
+/// Edits the recurrence of a task, previewing its next occurrences before submitting it.
+pub async fn fix(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let task = gw.task(&params.id).await?;
+    let today = cfg
+        .override_time
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local)
+        .date_naive();
+    let current = task
+        .due
+        .as_ref()
+        .filter(|d| d.is_recurring)
+        .map(|d| d.string.clone());
+    match &current {
+        Some(current) => {
+            println!("Current recurrence of {:?}: {}", task.content, current);
+            preview(current, today, params.count);
+        }
+        None => println!("{:?} does not repeat yet", task.content),
+    }
+    let interactive = Term::stderr().is_term();
+    if params.to.is_none() && !interactive {
+        return Err(eyre!(
+            "editing the recurrence needs an interactive terminal, or give it with --to"
+        ));
+    }
+    let mut next = params.to;
+    let recurrence = loop {
+        let recurrence = match next.take() {
+            Some(recurrence) => recurrence,
+            None => match interactive::input_optional("Recurrence", current.clone())? {
+                Some(recurrence) => recurrence,
+                None => {
+                    println!("nothing changed");
+                    return Ok(());
+                }
+            },
+        };
+        println!("New recurrence: {}", recurrence);
+        let previewed = preview(&recurrence, today, params.count);
+        if !interactive {
+            if !previewed {
+                return Err(eyre!(
+                    "not submitting {:?}, as it could not be previewed",
+                    recurrence
+                ));
+            }
+            break recurrence;
+        }
+        let prompt = if previewed {
+            "Submit this recurrence?"
+        } else {
+            "Submit it without a preview?"
+        };
+        if interactive::confirm(prompt)? {
+            break recurrence;
+        }
+        next = interactive::input_optional("Recurrence", Some(recurrence))?;
+        if next.is_none() {
+            println!("nothing changed");
+            return Ok(());
+        }
+    };
+    gw.update(
+        &task.id,
+        &UpdateTask {
+            due: Some(TaskDue::String(recurrence.clone())),
+            ..Default::default()
+        },
+    )
+    .await?;
+    println!("{:?} now repeats {}", task.content, recurrence);
+    Ok(())
+}
+
+/// Prints the next occurrences of the recurrence, returning whether it could be previewed.
+fn preview(recurrence: &str, today: NaiveDate, count: usize) -> bool {
+    let recurrence = match recurrence.parse::<Recurrence>() {
+        Ok(recurrence) => recurrence,
+        Err(e) => {
+            eprintln!("cannot preview: {:#}", e);
+            return false;
+        }
+    };
+    let time = recurrence
+        .time
+        .map(|t| t.format(" %H:%M").to_string())
+        .unwrap_or_default();
+    for day in recurrence.occurrences(today, count) {
+        println!("  {}{}", day.format("%a %Y-%m-%d"), time);
+    }
+    if recurrence.after_completion {
+        println!("  (counted from the completion, assuming it is completed when due)");
+    }
+    true
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        fix(self, &ctx.gw, &ctx.cfg).await
+    }
+}
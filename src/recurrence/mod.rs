@@ -0,0 +1,294 @@
+//! A local engine for the recurring due dates of Todoist, like `every 2 weeks` or `every mon, fri`,
+//! so their next occurrences can be previewed before they are sent to the API.
+//!
+//! Only a subset of what Todoist understands is supported.
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveTime, Weekday};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Report, Result,
+};
+
+pub mod fix;
+
+/// How far ahead occurrences are searched for, so rules that rarely match can't loop forever.
+const SEARCH_DAYS: i64 = 366 * 8;
+
+/// Recurrence is a parsed recurring due date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Recurrence {
+    /// Whether the next occurrence is counted from the completion instead of the due date, as
+    /// written with `every!`.
+    pub after_completion: bool,
+    /// The time of day of every occurrence, if any.
+    pub time: Option<NaiveTime>,
+    rule: Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    /// Every n-th unit, counted from the start.
+    Interval(u32, Unit),
+    /// Every given day of the week.
+    Weekdays(Vec<Weekday>),
+    /// Every given day of the month.
+    MonthDays(Vec<MonthDay>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MonthDay {
+    Day(u32),
+    Last,
+}
+
+impl FromStr for Recurrence {
+    type Err = Report;
+
+    /// Parses strings like `daily`, `every 3 days`, `every other week`, `every weekday`,
+    /// `every mon, fri`, `every 1st and 15th` or `every! last day at 17:00`.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        let (s, time) = match s.rsplit_once(" at ") {
+            Some((rule, time)) => (rule.trim(), Some(parse_time(time)?)),
+            None => (s.as_str(), None),
+        };
+        let (after_completion, rest) = match s {
+            "daily" => (false, "day"),
+            "weekly" => (false, "week"),
+            "monthly" => (false, "month"),
+            "yearly" | "annually" => (false, "year"),
+            _ => {
+                if let Some(rest) = s.strip_prefix("every!") {
+                    (true, rest)
+                } else if let Some(rest) = s.strip_prefix("every ") {
+                    (false, rest)
+                } else {
+                    return Err(eyre!("recurrences start with \"every\", not {:?}", s));
+                }
+            }
+        };
+        Ok(Recurrence {
+            after_completion,
+            time,
+            rule: parse_rule(rest.trim())
+                .wrap_err_with(|| format!("unsupported recurrence {:?}", s))?,
+        })
+    }
+}
+
+fn parse_rule(s: &str) -> Result<Rule> {
+    match s {
+        "weekday" | "workday" => {
+            return Ok(Rule::Weekdays(vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]))
+        }
+        "weekend" => return Ok(Rule::Weekdays(vec![Weekday::Sat, Weekday::Sun])),
+        _ => {}
+    }
+    let (n, unit) = match s.split_once(' ') {
+        Some(("other", unit)) => (Some(2), unit),
+        Some((n, unit)) => match n.parse::<u32>() {
+            Ok(n) => (Some(n), unit),
+            Err(_) => (None, s),
+        },
+        None => (None, s),
+    };
+    if let Some(unit) = parse_unit(unit) {
+        let n = n.unwrap_or(1);
+        if n == 0 {
+            return Err(eyre!("the interval can't be 0"));
+        }
+        return Ok(Rule::Interval(n, unit));
+    }
+    if n.is_some() {
+        return Err(eyre!("unknown unit {:?}", unit));
+    }
+    let items = s
+        .split([',', '&'])
+        .flat_map(|i| i.split(" and "))
+        .map(str::trim)
+        .filter(|i| !i.is_empty())
+        .collect::<Vec<_>>();
+    if let Ok(mut days) = items
+        .iter()
+        .map(|i| i.parse::<Weekday>())
+        .collect::<Result<Vec<_>, _>>()
+    {
+        days.sort_by_key(|d| d.num_days_from_monday());
+        days.dedup();
+        return Ok(Rule::Weekdays(days));
+    }
+    items
+        .iter()
+        .map(|i| parse_month_day(i))
+        .collect::<Result<Vec<_>>>()
+        .map(Rule::MonthDays)
+}
+
+fn parse_unit(s: &str) -> Option<Unit> {
+    match s.strip_suffix('s').unwrap_or(s) {
+        "day" => Some(Unit::Day),
+        "week" => Some(Unit::Week),
+        "month" => Some(Unit::Month),
+        "year" => Some(Unit::Year),
+        _ => None,
+    }
+}
+
+fn parse_month_day(s: &str) -> Result<MonthDay> {
+    if s == "last" || s == "last day" {
+        return Ok(MonthDay::Last);
+    }
+    let day = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| s.strip_suffix(suffix))
+        .and_then(|d| d.parse::<u32>().ok())
+        .ok_or_else(|| eyre!("expected a weekday or a day of the month, not {:?}", s))?;
+    match day {
+        1..=31 => Ok(MonthDay::Day(day)),
+        _ => Err(eyre!("there is no {} day in a month", s)),
+    }
+}
+
+/// Parses times like `17:00`, `9am` or `5:30pm`.
+fn parse_time(s: &str) -> Result<NaiveTime> {
+    let s = s.trim();
+    let (s, offset) = if let Some(s) = s.strip_suffix("am") {
+        (s, Some(0))
+    } else if let Some(s) = s.strip_suffix("pm") {
+        (s, Some(12))
+    } else {
+        (s, None)
+    };
+    let (hour, minute) = s.trim().split_once(':').unwrap_or((s.trim(), "0"));
+    let (Ok(hour), Ok(minute)) = (hour.parse::<u32>(), minute.parse::<u32>()) else {
+        return Err(eyre!("invalid time {:?}", s));
+    };
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return Err(eyre!("invalid time {:?}", s)),
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(|| eyre!("invalid time {:?}", s))
+}
+
+impl Recurrence {
+    /// Returns the first `count` occurrences on or after `start`.
+    pub fn occurrences(&self, start: NaiveDate, count: usize) -> Vec<NaiveDate> {
+        if let Rule::Interval(n, unit) = self.rule {
+            return (0..count as u32)
+                .filter_map(|i| match unit {
+                    Unit::Day => start.checked_add_signed(Duration::days((n * i).into())),
+                    Unit::Week => start.checked_add_signed(Duration::weeks((n * i).into())),
+                    Unit::Month => start.checked_add_months(Months::new(n * i)),
+                    Unit::Year => start.checked_add_months(Months::new(12 * n * i)),
+                })
+                .collect();
+        }
+        start
+            .iter_days()
+            .take(SEARCH_DAYS as usize)
+            .filter(|day| self.matches(*day))
+            .take(count)
+            .collect()
+    }
+
+    fn matches(&self, day: NaiveDate) -> bool {
+        match &self.rule {
+            Rule::Interval(..) => false,
+            Rule::Weekdays(days) => days.contains(&day.weekday()),
+            Rule::MonthDays(days) => days.iter().any(|d| match d {
+                MonthDay::Day(d) => day.day() == *d,
+                MonthDay::Last => (day + Duration::days(1)).day() == 1,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_recurrences() {
+        let rule = |s: &str| s.parse::<Recurrence>().unwrap().rule;
+        assert_eq!(rule("every day"), Rule::Interval(1, Unit::Day));
+        assert_eq!(rule("Daily"), Rule::Interval(1, Unit::Day));
+        assert_eq!(rule("every 3 weeks"), Rule::Interval(3, Unit::Week));
+        assert_eq!(rule("every other month"), Rule::Interval(2, Unit::Month));
+        assert_eq!(rule("every! 2 years"), Rule::Interval(2, Unit::Year));
+        assert_eq!(
+            rule("every fri, monday"),
+            Rule::Weekdays(vec![Weekday::Mon, Weekday::Fri])
+        );
+        assert_eq!(
+            rule("every weekend"),
+            Rule::Weekdays(vec![Weekday::Sat, Weekday::Sun])
+        );
+        assert_eq!(
+            rule("every 1st and 15th"),
+            Rule::MonthDays(vec![MonthDay::Day(1), MonthDay::Day(15)])
+        );
+        assert_eq!(
+            rule("every last day"),
+            Rule::MonthDays(vec![MonthDay::Last])
+        );
+
+        let r = "every! 2 days at 5:30pm".parse::<Recurrence>().unwrap();
+        assert!(r.after_completion);
+        assert_eq!(r.time, NaiveTime::from_hms_opt(17, 30, 0));
+        assert_eq!(
+            "every mon at 9".parse::<Recurrence>().unwrap().time,
+            NaiveTime::from_hms_opt(9, 0, 0)
+        );
+
+        for bad in [
+            "tomorrow",
+            "every 0 days",
+            "every 2 fortnights",
+            "every 32nd",
+            "every mon, 1st",
+            "every day at 25:00",
+        ] {
+            assert!(bad.parse::<Recurrence>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn finds_occurrences() {
+        // A Friday.
+        let start = NaiveDate::from_ymd_opt(2022, 8, 26).unwrap();
+        let dates = |s: &str, count| {
+            s.parse::<Recurrence>()
+                .unwrap()
+                .occurrences(start, count)
+                .into_iter()
+                .map(|d| d.format("%m-%d").to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(dates("every 3 days", 3), ["08-26", "08-29", "09-01"]);
+        assert_eq!(dates("every other week", 2), ["08-26", "09-09"]);
+        assert_eq!(dates("monthly", 3), ["08-26", "09-26", "10-26"]);
+        assert_eq!(dates("every weekday", 3), ["08-26", "08-29", "08-30"]);
+        assert_eq!(dates("every tue, thu", 3), ["08-30", "09-01", "09-06"]);
+        assert_eq!(
+            dates("every 1st, last", 4),
+            ["08-31", "09-01", "09-30", "10-01"]
+        );
+        assert_eq!(dates("every 31st", 2), ["08-31", "10-31"]);
+    }
+}
@@ -0,0 +1,30 @@
+use std::{fs, io, path::PathBuf};
+
+use color_eyre::{eyre::WrapErr, Result};
+
+/// Dir keeps the entries as files in a folder, which another tool may keep in sync.
+pub(crate) struct Dir {
+    path: PathBuf,
+}
+
+impl Dir {
+    pub fn new(path: &str) -> Dir {
+        Dir { path: path.into() }
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<String>> {
+        let file = self.path.join(name);
+        match fs::read_to_string(&file) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).wrap_err_with(|| format!("unable to read {}", file.display())),
+        }
+    }
+
+    pub fn put(&self, name: &str, data: &str) -> Result<()> {
+        fs::create_dir_all(&self.path)
+            .wrap_err_with(|| format!("unable to create {}", self.path.display()))?;
+        let file = self.path.join(name);
+        fs::write(&file, data).wrap_err_with(|| format!("unable to write to {}", file.display()))
+    }
+}
@@ -0,0 +1,78 @@
+use std::process;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+
+use super::dir::Dir;
+
+/// Git keeps the entries as files in a clone of a git repository, and commits and pushes them
+/// after every sync. Clones without a remote only get the commits.
+pub(crate) struct Git {
+    pub dir: Dir,
+    path: String,
+}
+
+impl Git {
+    pub fn new(path: &str) -> Git {
+        Git {
+            dir: Dir::new(path),
+            path: path.to_string(),
+        }
+    }
+
+    /// Pulls the branch of the same name from the remote, like the one that is pushed to.
+    pub fn pull(&self) -> Result<()> {
+        let Some(remote) = self.remote()? else {
+            return Ok(());
+        };
+        self.git(&["fetch", "--quiet", &remote])?;
+        let branch = self.git(&["symbolic-ref", "--short", "HEAD"])?;
+        let tracked = format!("refs/remotes/{}/{}", remote, branch);
+        // Nothing was pushed to the branch yet.
+        if self
+            .git(&["rev-parse", "--verify", "--quiet", &tracked])
+            .is_ok()
+        {
+            self.git(&["pull", "--rebase", "--quiet", &remote, &branch])?;
+        }
+        Ok(())
+    }
+
+    pub fn push(&self) -> Result<()> {
+        self.git(&["add", "--all"])?;
+        if self.git(&["status", "--porcelain"])?.is_empty() {
+            return Ok(());
+        }
+        self.git(&["commit", "--quiet", "--message", "Sync doist state"])?;
+        if let Some(remote) = self.remote()? {
+            self.git(&["push", "--quiet", "--set-upstream", &remote, "HEAD"])?;
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the remote that is pushed to, if the clone has one.
+    fn remote(&self) -> Result<Option<String>> {
+        Ok(self.git(&["remote"])?.lines().next().map(|r| r.to_string()))
+    }
+
+    /// Runs git in the clone and returns its trimmed output.
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let output = process::Command::new("git")
+            .arg("-C")
+            .arg(&self.path)
+            .args(args)
+            .output()
+            .wrap_err("unable to run git")?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "git {} failed in {}: {}",
+                args[0],
+                self.path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+/// Merges two versions of a journal. Keeps the lines of the remote in their order, followed by the
+/// local lines that the remote does not have yet. Lines are never dropped, so every machine ends up
+/// with the same lines once it synced after the others.
+pub(super) fn journal(local: &str, remote: &str) -> String {
+    let mut seen = HashSet::new();
+    let mut merged = String::new();
+    for line in remote.lines().chain(local.lines()) {
+        if !line.trim().is_empty() && seen.insert(line) {
+            merged.push_str(line);
+            merged.push('\n');
+        }
+    }
+    merged
+}
+
+/// Resolution says which side of a snapshot is kept.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Resolution {
+    /// Both sides are the same.
+    Unchanged,
+    /// The remote replaces the local snapshot.
+    Pull,
+    /// The local snapshot replaces the remote.
+    Push,
+    /// Both sides changed since the last sync, and the local snapshot replaces the remote.
+    Conflict,
+}
+
+/// Decides which side of a snapshot is kept, given the fingerprint that it had after the last sync.
+pub(super) fn snapshot(
+    base: Option<&str>,
+    local: Option<&str>,
+    remote: Option<&str>,
+) -> Resolution {
+    match (local, remote) {
+        (None, None) => Resolution::Unchanged,
+        (None, Some(_)) => Resolution::Pull,
+        (Some(_), None) => Resolution::Push,
+        (Some(local), Some(remote)) if local == remote => Resolution::Unchanged,
+        (Some(local), Some(remote)) => {
+            if base == Some(fingerprint(local).as_str()) {
+                Resolution::Pull
+            } else if base == Some(fingerprint(remote).as_str()) {
+                Resolution::Push
+            } else {
+                Resolution::Conflict
+            }
+        }
+    }
+}
+
+/// Decides which side of an entry is kept like [`snapshot`] does, but tells an entry that was
+/// deleted on one side apart from one that is new on the other: if the side that still has it did
+/// not change it since the last sync, it was deleted.
+pub(super) fn entry(base: Option<&str>, local: Option<&str>, remote: Option<&str>) -> Resolution {
+    match (local, remote) {
+        (None, Some(remote)) if base == Some(fingerprint(remote).as_str()) => Resolution::Push,
+        (Some(local), None) if base == Some(fingerprint(local).as_str()) => Resolution::Pull,
+        _ => snapshot(base, local, remote),
+    }
+}
+
+/// Returns a short identifier of the contents, which is remembered to tell whether it changed.
+pub(super) fn fingerprint(data: &str) -> String {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, data.as_bytes()).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merges_journals() {
+        assert_eq!(journal("a\nb\n", "a\nc\n"), "a\nc\nb\n");
+        assert_eq!(journal("", "a\n"), "a\n");
+        assert_eq!(journal("a\n", ""), "a\n");
+        assert_eq!(journal("a\nb", "a\nb\n"), "a\nb\n");
+        // Syncing the result again on the other machine changes nothing.
+        let merged = journal("a\nb\n", "a\nc\n");
+        assert_eq!(journal(&merged, &merged), merged);
+    }
+
+    #[test]
+    fn resolves_snapshots() {
+        let base = fingerprint("old");
+        let base = Some(base.as_str());
+        assert_eq!(
+            snapshot(base, Some("old"), Some("old")),
+            Resolution::Unchanged
+        );
+        assert_eq!(snapshot(base, Some("old"), Some("new")), Resolution::Pull);
+        assert_eq!(snapshot(base, Some("new"), Some("old")), Resolution::Push);
+        assert_eq!(
+            snapshot(base, Some("mine"), Some("theirs")),
+            Resolution::Conflict
+        );
+        assert_eq!(
+            snapshot(None, Some("mine"), Some("theirs")),
+            Resolution::Conflict
+        );
+        assert_eq!(snapshot(None, None, Some("theirs")), Resolution::Pull);
+        assert_eq!(snapshot(None, Some("mine"), None), Resolution::Push);
+        assert_eq!(snapshot(None, None, None), Resolution::Unchanged);
+    }
+
+    #[test]
+    fn resolves_deleted_entries() {
+        let base = fingerprint("old");
+        let base = Some(base.as_str());
+        // Deleted on one side, and unchanged on the other.
+        assert_eq!(entry(base, None, Some("old")), Resolution::Push);
+        assert_eq!(entry(base, Some("old"), None), Resolution::Pull);
+        // Deleted on one side, but changed on the other.
+        assert_eq!(entry(base, None, Some("new")), Resolution::Pull);
+        assert_eq!(entry(base, Some("new"), None), Resolution::Push);
+        // New on one side.
+        assert_eq!(entry(None, None, Some("new")), Resolution::Pull);
+        assert_eq!(entry(None, Some("new"), None), Resolution::Push);
+    }
+}
@@ -0,0 +1,96 @@
+//! Shares local state like notes and pins with other machines, through a location that the user
+//! provides in the config, like a git repository or a WebDAV folder. Journals that are only ever
+//! appended to are merged line by line, so entries written on different machines never get lost.
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    config::{RemoteKind, SyncRemote},
+    labels::suggest::HISTORY_FILE,
+    tasks::{defer::DEFER_FILE, note::NOTES_FILE, pin::PINS_FILE, someday::REVIEW_FILE},
+    timer::LEDGER_FILE,
+    waiting::WAITING_FILE,
+};
+
+mod dir;
+mod git;
+mod merge;
+mod s3;
+pub mod sync;
+mod webdav;
+
+/// Entries of the store that are only appended to, which are merged by keeping the lines of both
+/// sides. The audit log is left out, as older entries may have secrets in their command lines.
+const JOURNALS: &[&str] = &[LEDGER_FILE];
+/// Entries of the store that are replaced as a whole. Changes of one side replace the other side,
+/// and if both sides changed the local one is kept.
+const SNAPSHOTS: &[&str] = &[
+    DEFER_FILE,
+    HISTORY_FILE,
+    NOTES_FILE,
+    PINS_FILE,
+    REVIEW_FILE,
+    WAITING_FILE,
+];
+/// The entry of the remote that holds the templates of the config.
+const TEMPLATES: &str = "templates.json";
+/// The entry of the store that remembers what the snapshots looked like after the last sync.
+const MANIFEST_FILE: &str = "sync.json";
+
+/// Remote is a location that keeps a copy of the entries of the store.
+pub(crate) enum Remote {
+    Dir(dir::Dir),
+    Git(git::Git),
+    WebDav(webdav::WebDav),
+    S3(s3::S3),
+}
+
+impl Remote {
+    /// Sets up the remote that is described in the config.
+    pub fn new(cfg: &SyncRemote) -> Result<Remote> {
+        if cfg.location.is_empty() {
+            return Err(eyre!("the sync location in the config is empty"));
+        }
+        Ok(match cfg.kind {
+            RemoteKind::Dir => Remote::Dir(dir::Dir::new(&cfg.location)),
+            RemoteKind::Git => Remote::Git(git::Git::new(&cfg.location)),
+            RemoteKind::WebDav => Remote::WebDav(webdav::WebDav::new(cfg)?),
+            RemoteKind::S3 => Remote::S3(s3::S3::new(cfg)?),
+        })
+    }
+
+    /// Brings the remote up to date before anything is read from it.
+    pub fn pull(&self) -> Result<()> {
+        match self {
+            Remote::Git(git) => git.pull(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the contents of the entry in the remote, or none if it does not exist yet.
+    pub async fn get(&self, name: &str) -> Result<Option<String>> {
+        match self {
+            Remote::Dir(dir) => dir.get(name),
+            Remote::Git(git) => git.dir.get(name),
+            Remote::WebDav(webdav) => webdav.get(name).await,
+            Remote::S3(s3) => s3.get(name).await,
+        }
+    }
+
+    /// Replaces the contents of the entry in the remote.
+    pub async fn put(&self, name: &str, data: &str) -> Result<()> {
+        match self {
+            Remote::Dir(dir) => dir.put(name, data),
+            Remote::Git(git) => git.dir.put(name, data),
+            Remote::WebDav(webdav) => webdav.put(name, data).await,
+            Remote::S3(s3) => s3.put(name, data).await,
+        }
+    }
+
+    /// Publishes the entries that were put, for remotes that do not do so right away.
+    pub fn push(&self) -> Result<()> {
+        match self {
+            Remote::Git(git) => git.push(),
+            _ => Ok(()),
+        }
+    }
+}
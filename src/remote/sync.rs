@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    command::{Command, Context},
+    config::{Config, Template},
+};
+
+use super::{
+    merge::{self, Resolution},
+    Remote, JOURNALS, MANIFEST_FILE, SNAPSHOTS, TEMPLATES,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Syncs the journals, snapshots and templates with the remote of the config, and tells what was
+/// changed on either side.
+pub async fn sync(cfg: &Config) -> Result<()> {
+    let remote = cfg.sync.as_ref().ok_or_else(|| {
+        eyre!("there is no sync location in the config, add a [sync] section with its kind and location")
+    })?;
+    let remote = Remote::new(remote)?;
+    let store = cfg.store()?;
+    remote.pull()?;
+    let mut changes = Vec::new();
+    for name in JOURNALS {
+        let local = store.read(name)?.unwrap_or_default();
+        let theirs = remote.get(name).await?.unwrap_or_default();
+        let merged = merge::journal(&local, &theirs);
+        if merged != local {
+            store.write(name, &merged)?;
+            changes.push(format!("pulled new entries of {}", name));
+        }
+        if merged != theirs {
+            remote.put(name, &merged).await?;
+            changes.push(format!("pushed new entries of {}", name));
+        }
+    }
+    let mut manifest: BTreeMap<String, String> = store.load(MANIFEST_FILE)?;
+    for name in SNAPSHOTS {
+        let local = store.read(name)?;
+        let theirs = remote.get(name).await?;
+        let base = manifest.get(*name).map(|f| f.as_str());
+        let kept = match merge::snapshot(base, local.as_deref(), theirs.as_deref()) {
+            Resolution::Unchanged => local,
+            Resolution::Pull => {
+                store.write(name, theirs.as_deref().unwrap_or_default())?;
+                changes.push(format!("pulled {}", name));
+                theirs
+            }
+            resolution => {
+                if resolution == Resolution::Conflict {
+                    eprintln!(
+                        "{} changed here and on the remote since the last sync, kept the one from here",
+                        name
+                    );
+                }
+                remote
+                    .put(name, local.as_deref().unwrap_or_default())
+                    .await?;
+                changes.push(format!("pushed {}", name));
+                local
+            }
+        };
+        if let Some(kept) = kept {
+            manifest.insert(name.to_string(), merge::fingerprint(&kept));
+        }
+    }
+    sync_templates(&remote, cfg, &mut manifest, &mut changes).await?;
+    store.save(MANIFEST_FILE, &manifest)?;
+    remote.push()?;
+    if changes.is_empty() {
+        println!("everything is in sync");
+    }
+    for change in changes {
+        println!("{}", change);
+    }
+    Ok(())
+}
+
+/// Merges the templates of the config with the remote by their name, like snapshots each. The
+/// manifest remembers what every template looked like after the last sync, so that a template that
+/// was deleted on one side is deleted on the other, instead of coming back.
+async fn sync_templates(
+    remote: &Remote,
+    cfg: &Config,
+    manifest: &mut BTreeMap<String, String>,
+    changes: &mut Vec<String>,
+) -> Result<()> {
+    let theirs: BTreeMap<String, Template> = match remote.get(TEMPLATES).await? {
+        Some(data) => serde_json::from_str(&data)?,
+        None => BTreeMap::new(),
+    };
+    let base_key = |name: &str| format!("{}/{}", TEMPLATES, name);
+    let names = cfg
+        .templates
+        .keys()
+        .chain(theirs.keys())
+        .cloned()
+        .chain(
+            manifest
+                .keys()
+                .filter_map(|key| key.strip_prefix(&base_key("")))
+                .map(str::to_string),
+        )
+        .collect::<BTreeSet<_>>();
+    let mut merged = BTreeMap::new();
+    for name in names {
+        let local = cfg.templates.get(&name);
+        let remote = theirs.get(&name);
+        let key = base_key(&name);
+        let kept = match merge::entry(
+            manifest.get(&key).map(|f| f.as_str()),
+            local.map(serde_json::to_string).transpose()?.as_deref(),
+            remote.map(serde_json::to_string).transpose()?.as_deref(),
+        ) {
+            Resolution::Unchanged | Resolution::Push => local,
+            Resolution::Pull => remote,
+            Resolution::Conflict => {
+                eprintln!(
+                    "template {} changed here and on the remote since the last sync, kept the one from here",
+                    name
+                );
+                local
+            }
+        };
+        match kept {
+            Some(template) => {
+                manifest.insert(key, merge::fingerprint(&serde_json::to_string(template)?));
+                merged.insert(name, template.clone());
+            }
+            None => {
+                manifest.remove(&key);
+            }
+        }
+    }
+    if merged != cfg.templates {
+        // Loaded again, so that flags of this run never end up in the config file.
+        let mut saved = match &cfg.prefix {
+            Some(prefix) => Config::load_prefix(prefix)?,
+            None => Config::load()?,
+        };
+        saved.templates = merged.clone();
+        saved.save()?;
+        changes.push("pulled templates".to_string());
+    }
+    if merged != theirs {
+        remote
+            .put(TEMPLATES, &serde_json::to_string_pretty(&merged)?)
+            .await?;
+        changes.push("pushed templates".to_string());
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        sync(&ctx.cfg).await
+    }
+}
@@ -0,0 +1,77 @@
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use reqwest::StatusCode;
+
+use crate::config::SyncRemote;
+
+/// WebDav keeps the entries as files in a folder of a WebDAV server, which has to exist already.
+pub(crate) struct WebDav {
+    client: reqwest::Client,
+    url: url::Url,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl WebDav {
+    pub fn new(cfg: &SyncRemote) -> Result<WebDav> {
+        Ok(WebDav {
+            client: reqwest::Client::new(),
+            url: folder_url(&cfg.location)?,
+            username: cfg.username.clone(),
+            password: cfg.password.clone(),
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, name: &str) -> Result<reqwest::RequestBuilder> {
+        let url = self.url.join(name)?;
+        let request = self.client.request(method, url);
+        Ok(match &self.username {
+            Some(username) => request.basic_auth(username, self.password.as_ref()),
+            None => request,
+        })
+    }
+
+    pub async fn get(&self, name: &str) -> Result<Option<String>> {
+        let resp = self
+            .request(reqwest::Method::GET, name)?
+            .send()
+            .await
+            .wrap_err_with(|| format!("unable to get {} from WebDAV", name))?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(eyre!(
+                "unable to get {} from WebDAV: {}",
+                name,
+                resp.status()
+            ));
+        }
+        Ok(Some(resp.text().await?))
+    }
+
+    pub async fn put(&self, name: &str, data: &str) -> Result<()> {
+        let resp = self
+            .request(reqwest::Method::PUT, name)?
+            .body(data.to_string())
+            .send()
+            .await
+            .wrap_err_with(|| format!("unable to put {} to WebDAV", name))?;
+        if !resp.status().is_success() {
+            return Err(eyre!("unable to put {} to WebDAV: {}", name, resp.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Parses the URL of a folder, which needs to end with a slash so entries are joined into it.
+pub(super) fn folder_url(location: &str) -> Result<url::Url> {
+    let mut url = url::Url::parse(location)
+        .wrap_err_with(|| format!("invalid sync location {}", location))?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
@@ -0,0 +1,268 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, Local, Utc};
+use color_eyre::Result;
+use serde::Serialize;
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, ProjectID, TaskID},
+    },
+    command::{Command, Context},
+    config::Config,
+    duration::{self, Since},
+    output, timer,
+};
+
+/// What the tasks are grouped by.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    /// The project of the task.
+    Project,
+    /// Each label of the task.
+    Label,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Compares the tasks that time was tracked on since then, like `30d`, `monday` or
+    /// `2022-08-01`.
+    #[arg(short = 's', long = "since", default_value = "30d")]
+    since: Since,
+    /// Groups the tasks by their project, or by each of their labels.
+    #[arg(value_enum, long = "by", default_value_t = Group::Project)]
+    by: Group,
+    #[clap(flatten)]
+    output: output::Json,
+}
+
+/// Estimate is what is known about a task that was tracked.
+struct Estimate {
+    project_id: ProjectID,
+    labels: Vec<String>,
+    minutes: u32,
+}
+
+/// Row compares the estimates of a group of tasks with the time tracked on them.
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+struct Row {
+    name: String,
+    tasks: usize,
+    estimated_minutes: i64,
+    tracked_minutes: i64,
+}
+
+impl Row {
+    fn add(&mut self, estimated: u32, tracked: Duration) {
+        self.tasks += 1;
+        self.estimated_minutes += i64::from(estimated);
+        self.tracked_minutes += tracked.num_minutes();
+    }
+
+    /// How much more or less time was tracked than estimated, in percent of the estimate.
+    fn deviation(&self) -> i64 {
+        if self.estimated_minutes == 0 {
+            return 0;
+        }
+        ((self.tracked_minutes - self.estimated_minutes) as f64 * 100.0
+            / self.estimated_minutes as f64)
+            .round() as i64
+    }
+}
+
+/// Groups the tracked tasks that have an estimate, sorted by name.
+fn rows(
+    tracked: &BTreeMap<TaskID, Duration>,
+    estimates: &HashMap<TaskID, Estimate>,
+    project_names: &HashMap<ProjectID, String>,
+    by: Group,
+) -> Vec<Row> {
+    let mut rows: BTreeMap<String, Row> = BTreeMap::new();
+    for (id, spent) in tracked {
+        let Some(estimate) = estimates.get(id) else {
+            continue;
+        };
+        let names = match by {
+            Group::Project => vec![project_names
+                .get(&estimate.project_id)
+                .cloned()
+                .unwrap_or_else(|| estimate.project_id.clone())],
+            Group::Label if estimate.labels.is_empty() => vec!["no label".to_string()],
+            Group::Label => estimate.labels.iter().map(|l| format!("@{}", l)).collect(),
+        };
+        for name in names {
+            rows.entry(name.clone())
+                .or_insert_with(|| Row {
+                    name,
+                    ..Default::default()
+                })
+                .add(estimate.minutes, *spent);
+        }
+    }
+    rows.into_values().collect()
+}
+
+fn render(rows: &[Row], total: &Row, left_out: usize) -> String {
+    let width = rows
+        .iter()
+        .chain([total])
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or_default();
+    let mut out = String::new();
+    for row in rows.iter().chain([total]) {
+        out.push_str(&format!(
+            "{:width$}  {:>3} {:5}  estimated {:>7}  tracked {:>7}  {:+}%\n",
+            Content(&row.name).to_string(),
+            row.tasks,
+            if row.tasks == 1 { "task" } else { "tasks" },
+            duration::format(Duration::minutes(row.estimated_minutes)),
+            duration::format(Duration::minutes(row.tracked_minutes)),
+            row.deviation(),
+            width = width
+        ));
+    }
+    if left_out > 0 {
+        out.push_str(&format!(
+            "{} tracked {} without an estimate {} left out\n",
+            left_out,
+            if left_out == 1 { "task" } else { "tasks" },
+            if left_out == 1 { "is" } else { "are" }
+        ));
+    }
+    out
+}
+
+/// Compares the estimates of tasks with the time that the timers tracked on them.
+pub async fn accuracy(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let since = params
+        .since
+        .resolve(now.with_timezone(&Local))
+        .with_timezone(&Utc);
+    let mut tracked: BTreeMap<TaskID, Duration> = BTreeMap::new();
+    for t in timer::ledger(&cfg.store()?)? {
+        if t.start >= since {
+            *tracked
+                .entry(t.task_id.clone())
+                .or_insert_with(Duration::zero) += t.elapsed(now);
+        }
+    }
+    if tracked.is_empty() {
+        if params.output.json {
+            return params.output.print(&Vec::<Row>::new());
+        }
+        println!("no time was tracked since {}", since.with_timezone(&Local));
+        return Ok(());
+    }
+    let ids = tracked.keys().cloned().collect::<Vec<_>>();
+    let (active, completed, projects) =
+        tokio::try_join!(gw.tasks_by_ids(&ids), gw.completed(&since), gw.projects())?;
+    let mut estimates = HashMap::new();
+    for task in completed {
+        if let Some(minutes) = task.task.as_ref().and_then(|t| t.duration) {
+            estimates.insert(
+                task.task_id.clone(),
+                Estimate {
+                    labels: task.labels().to_vec(),
+                    project_id: task.project_id,
+                    minutes: minutes.minutes(),
+                },
+            );
+        }
+    }
+    for task in active {
+        if let Some(minutes) = task.duration {
+            estimates.insert(
+                task.id.clone(),
+                Estimate {
+                    project_id: task.project_id.clone(),
+                    labels: task.labels.clone(),
+                    minutes: minutes.minutes(),
+                },
+            );
+        }
+    }
+    let project_names = projects.into_iter().map(|p| (p.id, p.name)).collect();
+    let rows = rows(&tracked, &estimates, &project_names, params.by);
+    if params.output.json {
+        return params.output.print(&rows);
+    }
+    let mut total = Row {
+        name: "Total".to_string(),
+        ..Default::default()
+    };
+    for (id, spent) in &tracked {
+        if let Some(estimate) = estimates.get(id) {
+            total.add(estimate.minutes, *spent);
+        }
+    }
+    let left_out = tracked.len() - total.tasks;
+    if total.tasks == 0 {
+        println!(
+            "none of the {} tracked tasks has an estimate to compare with",
+            tracked.len()
+        );
+        return Ok(());
+    }
+    print!("{}", render(&rows, &total, left_out));
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        accuracy(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn groups_rows() {
+        let tracked = BTreeMap::from([
+            ("1".to_string(), Duration::minutes(45)),
+            ("2".to_string(), Duration::minutes(20)),
+            ("3".to_string(), Duration::minutes(10)),
+        ]);
+        let estimates = HashMap::from([
+            (
+                "1".to_string(),
+                Estimate {
+                    project_id: "10".to_string(),
+                    labels: vec!["deep".to_string(), "code".to_string()],
+                    minutes: 30,
+                },
+            ),
+            (
+                "2".to_string(),
+                Estimate {
+                    project_id: "10".to_string(),
+                    labels: vec![],
+                    minutes: 30,
+                },
+            ),
+        ]);
+        let names = HashMap::from([("10".to_string(), "Work".to_string())]);
+        let row = |name: &str, tasks, estimated_minutes, tracked_minutes| Row {
+            name: name.to_string(),
+            tasks,
+            estimated_minutes,
+            tracked_minutes,
+        };
+
+        let by_project = rows(&tracked, &estimates, &names, Group::Project);
+        assert_eq!(by_project, vec![row("Work", 2, 60, 65)]);
+        assert_eq!(by_project[0].deviation(), 8);
+        assert_eq!(
+            rows(&tracked, &estimates, &names, Group::Label),
+            vec![
+                row("@code", 1, 30, 45),
+                row("@deep", 1, 30, 45),
+                row("no label", 1, 30, 20),
+            ]
+        );
+    }
+}
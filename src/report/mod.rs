@@ -0,0 +1,2 @@
+//! Reports that combine the data of Todoist with what is tracked locally.
+pub mod accuracy;
@@ -1,8 +1,9 @@
 use crate::{
     api::rest::{CreateSection, Gateway, Project},
+    command::{Command, Context},
     interactive,
 };
-use color_eyre::Result;
+use color_eyre::{eyre::eyre, Result};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
@@ -25,3 +26,16 @@ pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
     println!("created section: {}", &section);
     Ok(())
 }
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(eyre!("section name can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        add(self, &ctx.gw).await
+    }
+}
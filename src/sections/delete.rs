@@ -1,5 +1,6 @@
 use crate::{
     api::rest::{Gateway, Section},
+    command::{Command, Context},
     interactive,
 };
 use color_eyre::Result;
@@ -18,3 +19,9 @@ pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
     println!("deleted section: {}", &section);
     Ok(())
 }
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        delete(self, &ctx.gw).await
+    }
+}
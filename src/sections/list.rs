@@ -1,6 +1,7 @@
 use crate::{
     api::rest::{Gateway, Project},
-    interactive,
+    command::{Command, Context},
+    interactive, output,
 };
 use color_eyre::Result;
 
@@ -8,6 +9,8 @@ use color_eyre::Result;
 pub struct Params {
     #[clap(flatten)]
     project: interactive::Selection<Project>,
+    #[clap(flatten)]
+    records: output::Records,
 }
 
 /// Lists available sections in a project.
@@ -20,9 +23,17 @@ pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
         .into_iter()
         .filter(|s| s.project_id == project.id)
         .collect::<Vec<_>>();
-    println!("{} sections:", project);
+    if !params.records.null {
+        println!("{} sections:", project);
+    }
     for s in sections {
-        println!("{}", s);
+        params.records.print(s);
     }
     Ok(())
 }
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.gw).await
+    }
+}
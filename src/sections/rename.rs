@@ -0,0 +1,35 @@
+use crate::{
+    api::rest::{Gateway, Section},
+    command::{Command, Context},
+    interactive,
+};
+use color_eyre::{eyre::eyre, Result};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    section: interactive::Selection<Section>,
+    /// The new name of the section.
+    name: String,
+}
+
+pub async fn rename(params: Params, gw: &Gateway) -> Result<()> {
+    let sections = gw.sections().await?;
+    let section = params.section.mandatory(&sections)?;
+    gw.rename_section(&section.id, &params.name).await?;
+    println!("renamed section {} to {}", &section.name, params.name);
+    Ok(())
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(eyre!("section name can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        rename(self, &ctx.gw).await
+    }
+}
@@ -0,0 +1,242 @@
+//! Serves a small API for editor plugins over stdin and stdout, so a plugin can list, add and
+//! toggle tasks without parsing the output of other commands.
+//!
+//! The protocol is [msgpack-rpc](https://github.com/msgpack-rpc/msgpack-rpc/blob/master/spec.md),
+//! which Neovim speaks natively with `jobstart(..., {'rpc': v:true})` and `rpcrequest()`.
+use std::{
+    io::{self, Write},
+    thread,
+};
+
+use chrono::Local;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use crate::{
+    api::rest::{CreateTask, Gateway, Task, TaskDue, TaskID},
+    cancel,
+    command::{Command, Context},
+    config::Config,
+    tasks::Priority,
+};
+
+/// The type of a msgpack-rpc request, which expects a response.
+const REQUEST: u64 = 0;
+/// The type of a msgpack-rpc response.
+const RESPONSE: u64 = 1;
+/// The type of a msgpack-rpc notification, which gets no response.
+const NOTIFICATION: u64 = 2;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Speaks msgpack-rpc on stdin and stdout, as used by Neovim. This is the only protocol for
+    /// now.
+    #[arg(long = "msgpack-rpc", required = true)]
+    msgpack_rpc: bool,
+}
+
+/// The options of the `add` method.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AddOptions {
+    /// Replaces the description that is taken from the lines after the first one.
+    description: Option<String>,
+    /// The due date in natural language.
+    due: Option<String>,
+    /// The priority as in the UI, 1 being the most urgent.
+    priority: Option<usize>,
+    /// The name of the project.
+    project: Option<String>,
+}
+
+/// Handles requests until stdin is closed or the server is stopped with Ctrl-C.
+pub async fn serve(cfg: &Config) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    // Reading blocks, so it happens on its own thread and the requests are handled as they come.
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            let msg = match rmp_serde::from_read::<_, Value>(&mut stdin) {
+                Err(rmp_serde::decode::Error::InvalidMarkerRead(e))
+                    if e.kind() == io::ErrorKind::UnexpectedEof =>
+                {
+                    return;
+                }
+                msg => msg.wrap_err("invalid msgpack-rpc message"),
+            };
+            let failed = msg.is_err();
+            if tx.send(msg).is_err() || failed {
+                return;
+            }
+        }
+    });
+    loop {
+        let msg = tokio::select! {
+            msg = rx.recv() => msg,
+            _ = cancel::cancelled() => None,
+        };
+        let Some(msg) = msg else {
+            return Ok(());
+        };
+        handle(msg?, cfg).await?;
+    }
+}
+
+/// Handles a single message, and responds to it if it is a request.
+async fn handle(msg: Value, cfg: &Config) -> Result<()> {
+    let kind = msg.get(0).and_then(Value::as_u64);
+    match (kind, msg.as_array().map(Vec::as_slice)) {
+        (Some(REQUEST), Some([_, id, Value::String(method), Value::Array(args)])) => {
+            let (error, result) = match call(method, args, cfg).await {
+                Ok(result) => (Value::Null, result),
+                Err(e) => (Value::String(format!("{:#}", e)), Value::Null),
+            };
+            let mut stdout = io::stdout().lock();
+            rmp_serde::encode::write(&mut stdout, &json!([RESPONSE, id, error, result]))?;
+            stdout.flush()?;
+        }
+        (Some(NOTIFICATION), Some([_, Value::String(method), Value::Array(args)])) => {
+            if let Err(e) = call(method, args, cfg).await {
+                eprintln!("{}: {:#}", method, e);
+            }
+        }
+        _ => return Err(eyre!("invalid msgpack-rpc message {}", msg)),
+    }
+    Ok(())
+}
+
+/// Calls the method with the arguments of the request.
+async fn call(method: &str, args: &[Value], cfg: &Config) -> Result<Value> {
+    let gw = cfg.gateway()?;
+    match method {
+        "list" => list(arg(args, 0)?, &gw, cfg).await,
+        "add" => add(arg(args, 0)?, arg(args, 1)?, &gw).await,
+        "toggle" => toggle(arg(args, 0)?, &gw).await,
+        _ => Err(eyre!("unknown method {:?}", method)),
+    }
+}
+
+/// Returns the argument at the index, where missing arguments are nil.
+fn arg<T: DeserializeOwned>(args: &[Value], index: usize) -> Result<T> {
+    serde_json::from_value(args.get(index).cloned().unwrap_or_default())
+        .wrap_err_with(|| format!("invalid argument {}", index + 1))
+}
+
+/// Lists the tasks of the filter, or of the default filter, in a shape that fits a quickfix list.
+async fn list(filter: Option<String>, gw: &Gateway, cfg: &Config) -> Result<Value> {
+    let filter = filter.unwrap_or_else(|| cfg.default_filter.clone());
+    let (mut tasks, projects) = tokio::try_join!(gw.tasks(Some(&filter)), gw.projects())?;
+    tasks.sort();
+    Ok(tasks
+        .iter()
+        .map(|task| {
+            let project = projects
+                .iter()
+                .find(|p| p.id == task.project_id)
+                .map(|p| &p.name);
+            let due = due(task);
+            let mut text = task.content.clone();
+            if let Some(project) = project {
+                text.push_str(&format!(" #{}", project));
+            }
+            if let Some(due) = &due {
+                text.push_str(&format!(" ({})", due));
+            }
+            json!({
+                "id": task.id,
+                "text": text,
+                "content": task.content,
+                "description": task.description,
+                "project": project,
+                "due": due,
+                "priority": 5 - task.priority as u8,
+                "url": task.url,
+            })
+        })
+        .collect())
+}
+
+fn due(task: &Task) -> Option<String> {
+    let due = task.due.as_ref()?;
+    Some(match &due.exact {
+        Some(exact) => exact
+            .datetime
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => due.date.to_string(),
+    })
+}
+
+/// Adds a task from the lines of a selection. The first line that is not empty becomes the name
+/// of the task, and the lines after it the description.
+async fn add(lines: String, options: Option<AddOptions>, gw: &Gateway) -> Result<Value> {
+    let options = options.unwrap_or_default();
+    let mut lines = lines.lines().skip_while(|l| l.trim().is_empty());
+    let content = lines
+        .next()
+        .map(|l| l.trim().to_string())
+        .ok_or_else(|| eyre!("the task needs a name"))?;
+    let rest = lines.collect::<Vec<_>>().join("\n");
+    let description = options
+        .description
+        .or_else(|| Some(rest.trim().to_string()).filter(|d| !d.is_empty()));
+    let project_id = match &options.project {
+        Some(name) => Some(
+            gw.projects()
+                .await?
+                .into_iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| eyre!("there is no project named {}", name))?
+                .id,
+        ),
+        None => None,
+    };
+    let priority = options
+        .priority
+        .map(Priority::try_from)
+        .transpose()?
+        .map(Into::into);
+    let task = gw
+        .create(&CreateTask {
+            content,
+            description,
+            project_id,
+            priority,
+            due: options.due.map(TaskDue::String),
+            ..Default::default()
+        })
+        .await?;
+    Ok(json!({
+        "id": task.id,
+        "content": task.content,
+        "url": task.url,
+    }))
+}
+
+/// Closes the task if it is open, or reopens it if it was closed already.
+async fn toggle(id: TaskID, gw: &Gateway) -> Result<Value> {
+    // Closed tasks are not returned as active tasks, so a task that can't be fetched is reopened.
+    let done = match gw.task(&id).await {
+        Ok(task) if !task.is_completed => {
+            gw.close(&id).await?;
+            true
+        }
+        _ => {
+            gw.reopen(&id).await?;
+            false
+        }
+    };
+    Ok(json!({"id": id, "done": done}))
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        serve(&ctx.cfg).await
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+
+use super::Storage;
+
+/// JsonFiles keeps every entry in a file of its own inside the state directory of the app.
+#[derive(Debug)]
+pub(crate) struct JsonFiles {
+    dir: xdg::BaseDirectories,
+}
+
+impl JsonFiles {
+    /// Creates a storage that works with files in the given directories.
+    pub fn new(dir: xdg::BaseDirectories) -> JsonFiles {
+        JsonFiles { dir }
+    }
+
+    /// Returns the path of a file in the store, creating its parent directories if needed.
+    fn place(&self, name: &str) -> Result<PathBuf> {
+        self.dir
+            .place_state_file(name)
+            .wrap_err_with(|| format!("unable to place {} into the state directory", name))
+    }
+}
+
+impl Storage for JsonFiles {
+    fn read(&self, name: &str) -> Result<Option<String>> {
+        let file = self.dir.get_state_file(name);
+        match fs::read_to_string(&file) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).wrap_err_with(|| format!("unable to read {}", file.display())),
+        }
+    }
+
+    fn write(&self, name: &str, data: &str) -> Result<()> {
+        let file = self.place(name)?;
+        fs::write(&file, data).wrap_err_with(|| format!("unable to write to {}", file.display()))
+    }
+
+    fn append(&self, name: &str, line: &str) -> Result<()> {
+        let file = self.place(name)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file)
+            .and_then(|mut f| f.write_all(line.as_bytes()))
+            .wrap_err_with(|| format!("unable to write to {}", file.display()))
+    }
+
+    fn remove(&self, name: &str) -> Result<()> {
+        let file = self.dir.get_state_file(name);
+        match fs::remove_file(&file) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(e).wrap_err_with(|| format!("unable to remove {}", file.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, u64)> {
+        let mut files = self
+            .dir
+            .list_state_files("")
+            .into_iter()
+            .filter_map(|path| {
+                let size = fs::metadata(&path).ok()?.len();
+                Some((path.file_name()?.to_string_lossy().into_owned(), size))
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+}
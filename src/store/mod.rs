@@ -0,0 +1,97 @@
+//! Keeps local state of the app, like logs, in a [`Storage`] backend. By default every entry is a
+//! JSON file inside the XDG state directory.
+use color_eyre::{eyre::WrapErr, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+mod json;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub(crate) use json::JsonFiles;
+#[cfg(feature = "sqlite")]
+pub(crate) use sqlite::Sqlite;
+
+/// Storage persists named entries of text, which the [`Store`] fills with JSON.
+pub(crate) trait Storage: std::fmt::Debug + Send + Sync {
+    /// Returns the contents of the entry, or none if it does not exist yet.
+    fn read(&self, name: &str) -> Result<Option<String>>;
+    /// Replaces the contents of the entry.
+    fn write(&self, name: &str, data: &str) -> Result<()>;
+    /// Adds the line to the end of the entry, creating it if needed.
+    fn append(&self, name: &str, line: &str) -> Result<()>;
+    /// Removes the entry, if it exists.
+    fn remove(&self, name: &str) -> Result<()>;
+    /// Returns the names and sizes of all entries, sorted by name.
+    fn entries(&self) -> Vec<(String, u64)>;
+}
+
+/// Store reads and writes JSON values in the storage backend of the app.
+#[derive(Debug)]
+pub(crate) struct Store {
+    storage: Box<dyn Storage>,
+}
+
+impl Store {
+    /// Creates a store that keeps its entries in the given storage.
+    pub fn new(storage: impl Storage + 'static) -> Store {
+        Store {
+            storage: Box::new(storage),
+        }
+    }
+
+    /// Loads the JSON value of the entry, or the default value if it does not exist yet.
+    pub fn load<T: DeserializeOwned + Default>(&self, name: &str) -> Result<T> {
+        match self.storage.read(name)? {
+            Some(data) => serde_json::from_str(&data)
+                .wrap_err_with(|| format!("invalid contents in {}", name)),
+            None => Ok(T::default()),
+        }
+    }
+
+    /// Saves the value as JSON into the entry, replacing what was there before.
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<()> {
+        self.storage.write(name, &serde_json::to_string(value)?)
+    }
+
+    /// Returns the contents of the entry as they are stored, or none if it does not exist yet.
+    #[cfg(feature = "integrations")]
+    pub fn read(&self, name: &str) -> Result<Option<String>> {
+        self.storage.read(name)
+    }
+
+    /// Replaces the contents of the entry with text that is already serialized.
+    #[cfg(feature = "integrations")]
+    pub fn write(&self, name: &str, data: &str) -> Result<()> {
+        self.storage.write(name, data)
+    }
+
+    /// Returns the names and sizes of all entries in the store, sorted by name.
+    pub fn files(&self) -> Vec<(String, u64)> {
+        self.storage.entries()
+    }
+
+    /// Removes the entry, if it exists.
+    pub fn remove(&self, name: &str) -> Result<()> {
+        self.storage.remove(name)
+    }
+
+    /// Appends the value as a single JSON line to the entry.
+    pub fn append<T: Serialize>(&self, name: &str, value: &T) -> Result<()> {
+        let mut line = serde_json::to_string(value)?;
+        line.push('\n');
+        self.storage.append(name, &line)
+    }
+
+    /// Reads all JSON lines that were [`Store::append`]ed to the entry.
+    pub fn lines<T: DeserializeOwned>(&self, name: &str) -> Result<Vec<T>> {
+        let Some(data) = self.storage.read(name)? else {
+            return Ok(Vec::new());
+        };
+        data.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).wrap_err_with(|| format!("invalid entry in {}", name))
+            })
+            .collect()
+    }
+}
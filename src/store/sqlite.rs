@@ -0,0 +1,131 @@
+use std::{path::Path, sync::Mutex, time::Duration};
+
+use color_eyre::{eyre::WrapErr, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::Storage;
+
+/// Sqlite keeps all entries in a single SQLite database. Every write is a transaction of its own,
+/// so concurrent runs of the app, like a background `prewarm`, never leave an entry half written.
+#[derive(Debug)]
+pub(crate) struct Sqlite {
+    conn: Mutex<Connection>,
+}
+
+impl Sqlite {
+    /// Opens the database at the given path, creating it if needed.
+    pub fn open(path: &Path) -> Result<Sqlite> {
+        let conn = Connection::open(path)
+            .wrap_err_with(|| format!("unable to open {}", path.display()))?;
+        // Waits for other runs of the app that are writing at the same time.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS entries (name TEXT PRIMARY KEY, data TEXT NOT NULL)",
+            [],
+        )
+        .wrap_err_with(|| format!("unable to set up {}", path.display()))?;
+        Ok(Sqlite {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+impl Storage for Sqlite {
+    fn read(&self, name: &str) -> Result<Option<String>> {
+        self.conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT data FROM entries WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .wrap_err_with(|| format!("unable to read {}", name))
+    }
+
+    fn write(&self, name: &str, data: &str) -> Result<()> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO entries (name, data) VALUES (?1, ?2)
+                 ON CONFLICT (name) DO UPDATE SET data = excluded.data",
+                params![name, data],
+            )
+            .wrap_err_with(|| format!("unable to write to {}", name))?;
+        Ok(())
+    }
+
+    fn append(&self, name: &str, line: &str) -> Result<()> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO entries (name, data) VALUES (?1, ?2)
+                 ON CONFLICT (name) DO UPDATE SET data = data || excluded.data",
+                params![name, line],
+            )
+            .wrap_err_with(|| format!("unable to write to {}", name))?;
+        Ok(())
+    }
+
+    fn remove(&self, name: &str) -> Result<()> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM entries WHERE name = ?1", params![name])
+            .wrap_err_with(|| format!("unable to remove {}", name))?;
+        Ok(())
+    }
+
+    fn entries(&self) -> Vec<(String, u64)> {
+        let conn = self.conn.lock().unwrap();
+        let entries = conn
+            .prepare("SELECT name, length(CAST(data AS BLOB)) FROM entries ORDER BY name")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            });
+        entries.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::store::Store;
+
+    #[test]
+    fn stores_entries() {
+        let dir = std::env::temp_dir().join(format!("doist-sqlite-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = Store::new(Sqlite::open(&dir.join("state.sqlite3")).unwrap());
+
+        assert_eq!(
+            store.load::<Vec<u32>>("pins.json").unwrap(),
+            Vec::<u32>::new()
+        );
+        store.save("pins.json", &vec![1, 2]).unwrap();
+        store.save("pins.json", &vec![3]).unwrap();
+        assert_eq!(store.load::<Vec<u32>>("pins.json").unwrap(), vec![3]);
+
+        store.append("audit.jsonl", &"first").unwrap();
+        store.append("audit.jsonl", &"second").unwrap();
+        assert_eq!(
+            store.lines::<String>("audit.jsonl").unwrap(),
+            vec!["first".to_string(), "second".to_string()]
+        );
+        assert_eq!(
+            store.files(),
+            vec![
+                ("audit.jsonl".to_string(), 17),
+                ("pins.json".to_string(), 3)
+            ]
+        );
+
+        store.remove("audit.jsonl").unwrap();
+        assert!(store.lines::<String>("audit.jsonl").unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,18 +1,31 @@
 use std::collections::HashMap;
 
-use color_eyre::Result;
+use chrono::{Local, Utc};
+use color_eyre::{eyre::eyre, Result};
+use dialoguer::console::Term;
 
 use crate::{
     api::{
-        rest::{CreateTask, Gateway, Label, Project, Section, TableTask, TaskDue},
+        rest::{Collaborator, CreateTask, Gateway, Label, Project, Section, TableTask, TaskID},
         tree::Tree,
     },
+    command::{Command, Context},
     config::Config,
+    holidays::Scheduling,
+    i18n::{self, t},
     interactive,
-    labels::{self, LabelSelect},
+    labels::{self, suggest, LabelSelect},
     tasks::Priority,
 };
 
+use super::{
+    assignee,
+    here::Here,
+    smart_due::{self, SmartDue},
+    state::State,
+    template,
+};
+
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     /// Name (title) of the task to add to the todo list.
@@ -22,6 +35,8 @@ pub struct Params {
     /// Examples: "in two days" "tomorrow", "every 2 days from Monday"
     #[arg(short = 'd', long = "due")]
     due: Option<String>,
+    #[clap(flatten)]
+    scheduling: Scheduling,
     /// Description that has more details about the task.
     #[arg(short = 'D', long = "desc")]
     desc: Option<String>,
@@ -32,28 +47,133 @@ pub struct Params {
     project: interactive::Selection<Project>,
     #[clap(flatten)]
     section: interactive::Selection<Section>,
+    /// Adds the task as a subtask of the task with this ID, in the project of that task. Without
+    /// an ID, the parent task is picked from a list of all tasks.
+    #[arg(
+        long = "parent",
+        num_args = 0..=1,
+        conflicts_with_all = ["project", "project_id", "section", "section_id"]
+    )]
+    parent: Option<Option<TaskID>>,
     #[clap(flatten)]
     labels: LabelSelect,
+    /// Assigns the task to a collaborator of the shared project, given by name or email address.
+    #[arg(short = 'a', long = "assign")]
+    assign: Option<String>,
+    /// Fills in the template with this name from the config, which can contain the task name as
+    /// `{{name}}`. A description given with `--desc` replaces the one of the template.
+    #[arg(short = 't', long = "template")]
+    template: Option<String>,
+    /// Keeps due dates that are written in the task name, like "pay rent on the 1st", as part of
+    /// the name instead of using them as the due date.
+    #[arg(long = "nosmartdue")]
+    nosmartdue: bool,
+    /// Adds the labels that are suggested for the task name without asking for confirmation.
+    /// Suggestions are made from the `label_rules` of the config and the labels of earlier tasks.
+    #[arg(long = "auto-label")]
+    auto_label: bool,
+    /// Notes the git repository and branch of the current directory in the description, and uses
+    /// the project that the `repos` of the config map the repository to, unless one is given.
+    #[arg(long = "here")]
+    here: bool,
+    /// The file and line the task is about, like `src/main.rs:42`, to note with `--here`.
+    #[arg(long = "at", requires = "here")]
+    at: Option<String>,
 }
 
 pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let mut name = params.name;
+    let mut due = params.due;
+    // Due dates are only found in English in the task name.
+    let mut due_lang = i18n::due_lang();
+    if due.is_none() && !params.nosmartdue {
+        if let Some(smart) = smart_due::detect(&name) {
+            if accept_smart_due(&smart)? {
+                name = smart.content;
+                due = Some(smart.due);
+                due_lang = None;
+            }
+        }
+    }
+    let mut description = params.desc;
+    if let Some(template) = &params.template {
+        let template = cfg
+            .templates
+            .get(template)
+            .ok_or_else(|| eyre!("there is no template named {} in the config", template))?;
+        let now = cfg.override_time.unwrap_or_else(Utc::now);
+        let expanded = template::expand(template, &name, now.with_timezone(&Local))?;
+        name = expanded.content;
+        description = description.or(expanded.description);
+    }
+    let here = if params.here {
+        Some(Here::detect()?)
+    } else {
+        None
+    };
+    if let Some(here) = &here {
+        let from = here.describe(params.at.as_deref());
+        description = Some(match description {
+            Some(description) => format!("{}\n\n{}", description, from),
+            None => from,
+        });
+    }
+    let parent = match params.parent {
+        Some(Some(id)) => Some(id),
+        Some(None) => Some(pick_parent(gw, cfg).await?),
+        None => None,
+    };
     let (projects, sections) = tokio::try_join!(gw.projects(), gw.sections())?;
-    let project = params.project.optional(&projects)?;
+    let mut project = params.project.optional(&projects)?;
+    if let Some(name) = here.and_then(|h| cfg.repos.get(&h.repo)) {
+        // Subtasks always end up in the project of their parent.
+        if project.is_none() && parent.is_none() {
+            project =
+                Some(projects.iter().find(|p| p.name == *name).ok_or_else(|| {
+                    eyre!("there is no project named {} for this repository", name)
+                })?);
+        }
+    }
     let section = params.section.optional(&sections)?;
-    let labels = params
+    let assignee = match &params.assign {
+        Some(who) => {
+            let project = project
+                .filter(|p| p.is_shared)
+                .ok_or_else(|| eyre!("--assign needs a shared project to be chosen"))?;
+            let collaborators = gw.collaborators(&project.id).await?;
+            Some(assignee::resolve(&collaborators, who)?.clone())
+        }
+        None => None,
+    };
+    let all_labels = gw.labels().await?;
+    let mut labels = params
         .labels
-        .labels(&gw.labels().await?, labels::Selection::AllowEmpty)?;
+        .labels(&all_labels, labels::Selection::AllowEmpty)?;
+    let history = if params.auto_label || cfg.suggest_labels {
+        Some(suggest::History::load(&cfg.store()?)?)
+    } else {
+        None
+    };
+    if let Some(history) = &history {
+        let suggested = suggest::suggest(&name, &cfg.label_rules, history, &all_labels, &labels);
+        if accept_suggestions(&suggested, params.auto_label)? {
+            labels.extend(suggested.into_iter().cloned());
+        }
+    }
     let mut create = CreateTask {
-        content: params.name,
-        description: params.desc,
+        content: name.clone(),
+        description,
         priority: params.priority.map(|p| p.into()),
         project_id: project.map(|p| p.id.clone()),
         section_id: section.map(|s| s.id.clone()),
+        parent_id: parent,
         labels: labels.iter().map(|l| l.name.clone()).collect(),
+        assignee: assignee.as_ref().map(|a| a.id.clone()),
         ..Default::default()
     };
-    if let Some(due) = params.due {
-        create.due = Some(TaskDue::String(due));
+    if let Some(due) = due {
+        create.due = Some(params.scheduling.due(due, cfg).await?);
+        create.due_lang = due_lang;
     }
     let labels = if !create.labels.is_empty() {
         let mut labels: HashMap<_, _> = gw
@@ -70,7 +190,63 @@ pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     } else {
         Vec::new()
     };
-    create_task(create, project, section, &labels, gw, cfg).await
+    create_task(
+        create,
+        project,
+        section,
+        &labels,
+        assignee.as_ref(),
+        gw,
+        cfg,
+    )
+    .await?;
+    if let Some(history) = history {
+        history.learn(&cfg.store()?, &name, &labels)?;
+    }
+    Ok(())
+}
+
+/// Lets the user pick the parent of a subtask from all tasks.
+async fn pick_parent(gw: &Gateway, cfg: &Config) -> Result<TaskID> {
+    let state = State::fetch_tree(Some("all"), gw, cfg).await?;
+    state
+        .select_task()?
+        .map(|task| task.id.clone())
+        .ok_or_else(|| eyre!("no parent task selected"))
+}
+
+/// Asks whether the due date that was found in the task name should be used. Without anybody
+/// to ask it is used, like the Todoist app does.
+fn accept_smart_due(smart: &SmartDue) -> Result<bool> {
+    if !Term::stderr().is_term() {
+        eprintln!("using {:?} from the task name as due date", smart.due);
+        return Ok(true);
+    }
+    interactive::confirm(&format!(
+        "Use {:?} as due date and name the task {:?}?",
+        smart.due, smart.content
+    ))
+}
+
+/// Asks whether the suggested labels should be added, unless they are added automatically.
+/// Suggestions are dropped if there is nobody to ask.
+fn accept_suggestions(suggested: &[&Label], auto: bool) -> Result<bool> {
+    if suggested.is_empty() {
+        return Ok(false);
+    }
+    let names = suggested
+        .iter()
+        .map(|l| format!("@{}", l.name))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if auto {
+        eprintln!("adding suggested labels {}", names);
+        return Ok(true);
+    }
+    if !Term::stderr().is_term() {
+        return Ok(false);
+    }
+    interactive::confirm(&format!("Add suggested labels {}?", names))
 }
 
 pub(super) async fn create_task(
@@ -78,6 +254,7 @@ pub(super) async fn create_task(
     project: Option<&Project>,
     section: Option<&Section>,
     labels: &[Label],
+    assignee: Option<&Collaborator>,
     gw: &Gateway,
     cfg: &Config,
 ) -> Result<()> {
@@ -86,6 +263,20 @@ pub(super) async fn create_task(
     table.1 = project;
     table.2 = section;
     table.3 = labels.iter().collect();
-    println!("created task: {}", table);
+    table.4 = assignee;
+    println!("{}", t!("created-task", task = table.to_string()));
     Ok(())
 }
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(eyre!("task name can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        add(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,92 @@
+//! Turns the tasks of a label into the agenda of a meeting, like the topics of a recurring 1:1.
+use chrono::{Local, Utc};
+use color_eyre::Result;
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{CreateComment, Gateway, Task, ThreadID, UpdateTask},
+    },
+    command::{Command, Context},
+    config::Config,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The label whose open tasks are the topics of the agenda, like `@1on1-alice`.
+    label: String,
+    /// After printing the agenda, comments on every task that it was discussed today and removes
+    /// the label from it.
+    #[arg(long = "discussed")]
+    discussed: bool,
+}
+
+/// Prints the open tasks with the label as a Markdown agenda.
+pub async fn agenda(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let label = params.label.trim_start_matches('@');
+    let filter = format!("@{}", label.replace(' ', "\\ "));
+    let mut tasks = gw
+        .tasks(Some(&filter))
+        .await?
+        .into_iter()
+        .filter(|t| t.labels.iter().any(|l| l == label))
+        .collect::<Vec<_>>();
+    tasks.sort();
+    let today = cfg
+        .override_time
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local)
+        .format("%Y-%m-%d")
+        .to_string();
+    print!("{}", render(label, &today, &tasks));
+    if !params.discussed {
+        return Ok(());
+    }
+    for task in &tasks {
+        gw.create_comment(&CreateComment {
+            thread: ThreadID::Task {
+                task_id: task.id.clone(),
+            },
+            content: format!("discussed on {}", today),
+            attachment: None,
+        })
+        .await?;
+        gw.update(
+            &task.id,
+            &UpdateTask {
+                labels: Some(
+                    task.labels
+                        .iter()
+                        .filter(|l| *l != label)
+                        .cloned()
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+    eprintln!("marked {} tasks as discussed", tasks.len());
+    Ok(())
+}
+
+fn render(label: &str, date: &str, tasks: &[Task]) -> String {
+    let mut out = format!("# Agenda for @{} on {}\n\n", Content(label), date);
+    if tasks.is_empty() {
+        out.push_str("Nothing to discuss.\n");
+    }
+    for task in tasks {
+        out.push_str(&format!("- [ ] {}\n", Content(&task.content)));
+        let description = Content(&task.description).to_string();
+        for line in description.lines().filter(|l| !l.trim().is_empty()) {
+            out.push_str(&format!("  > {}\n", line));
+        }
+    }
+    out
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        agenda(self, &ctx.gw, &ctx.cfg).await
+    }
+}
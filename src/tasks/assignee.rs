@@ -0,0 +1,67 @@
+use color_eyre::{eyre::eyre, Result};
+
+use crate::api::rest::Collaborator;
+
+/// Finds the collaborator that is meant by the name or email address. Exact matches of the email
+/// or name win, otherwise the name has to contain the text for exactly one collaborator.
+pub(super) fn resolve<'a>(
+    collaborators: &'a [Collaborator],
+    who: &str,
+) -> Result<&'a Collaborator> {
+    let needle = who.to_lowercase();
+    if let Some(c) = collaborators
+        .iter()
+        .find(|c| c.email.to_lowercase() == needle)
+        .or_else(|| {
+            collaborators
+                .iter()
+                .find(|c| c.name.to_lowercase() == needle)
+        })
+    {
+        return Ok(c);
+    }
+    let matching = collaborators
+        .iter()
+        .filter(|c| c.name.to_lowercase().contains(&needle))
+        .collect::<Vec<_>>();
+    match matching[..] {
+        [c] => Ok(c),
+        [] => Err(eyre!("nobody named {} collaborates on the project", who)),
+        _ => Err(eyre!(
+            "{} matches several collaborators: {}",
+            who,
+            matching
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn collaborator(id: &str, name: &str, email: &str) -> Collaborator {
+        Collaborator {
+            id: id.to_string(),
+            name: name.to_string(),
+            email: email.to_string(),
+        }
+    }
+
+    #[test]
+    fn resolves_collaborators() {
+        let collaborators = vec![
+            collaborator("1", "Ann Lee", "ann@example.com"),
+            collaborator("2", "Anna Berg", "anna@example.com"),
+            collaborator("3", "Bob", "bob@example.com"),
+        ];
+        assert_eq!(resolve(&collaborators, "ANNA@example.com").unwrap().id, "2");
+        assert_eq!(resolve(&collaborators, "ann lee").unwrap().id, "1");
+        assert_eq!(resolve(&collaborators, "berg").unwrap().id, "2");
+        assert!(resolve(&collaborators, "ann").is_err());
+        assert!(resolve(&collaborators, "carol").is_err());
+    }
+}
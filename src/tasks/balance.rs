@@ -0,0 +1,276 @@
+//! Spreads the tasks of the coming days more evenly, by moving tasks away from days that have too
+//! much to do.
+use std::collections::BTreeMap;
+
+use chrono::{Local, NaiveDate, Utc};
+use color_eyre::{eyre::eyre, Result};
+use dialoguer::console::Term;
+use serde_json::json;
+
+use crate::{
+    api::{
+        rest::{Gateway, Task},
+        sync::SYNC_BATCH_SIZE,
+    },
+    command::{Command, Context},
+    config::Config,
+    interactive,
+};
+
+/// What makes a day busy.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Load {
+    /// The number of tasks.
+    Count,
+    /// The sum of the estimated durations of the tasks, in minutes.
+    Estimate,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// How many days, starting today, are balanced.
+    #[arg(long = "days", default_value_t = 7)]
+    days: u32,
+    /// What makes a day busy. Tasks without an estimate are ignored when balancing by estimate.
+    #[arg(long = "by", value_enum, default_value_t = Load::Count)]
+    by: Load,
+    /// The most a day should have. Defaults to the average of all days, rounded up.
+    #[arg(long = "max")]
+    max: Option<u32>,
+    /// Applies the plan without asking for confirmation.
+    #[arg(short = 'y', long = "yes")]
+    yes: bool,
+}
+
+/// A task that is moved to another day.
+#[derive(Debug, PartialEq, Eq)]
+struct Move<'a> {
+    task: &'a Task,
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+/// Proposes a plan to move tasks from overloaded days to lighter ones, and applies it after
+/// confirmation.
+pub async fn balance(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let today = cfg
+        .override_time
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local)
+        .date_naive();
+    let days = today
+        .iter_days()
+        .take(params.days.max(1) as usize)
+        .collect::<Vec<_>>();
+    let mut tasks = gw.tasks(None).await?;
+    tasks.sort();
+    let scheduled = tasks
+        .iter()
+        .filter_map(|t| Some((t, t.due.as_ref()?.date, weight(t, params.by))))
+        .filter(|(_, day, _)| days.contains(day))
+        .collect::<Vec<_>>();
+    let before = loads(&days, &scheduled);
+    let max = params.max.unwrap_or_else(|| {
+        let total = before.values().sum::<u32>();
+        total.div_ceil(days.len() as u32).max(1)
+    });
+    let moves = plan(&days, &scheduled, max);
+    let mut after = before.clone();
+    for m in &moves {
+        let weight = weight(m.task, params.by);
+        *after.entry(m.from).or_default() -= weight;
+        *after.entry(m.to).or_default() += weight;
+    }
+    println!("Day         Before  After");
+    for day in &days {
+        println!(
+            "{}  {:>6} {:>6}{}",
+            day.format("%a %m-%d"),
+            before[day],
+            after[day],
+            if before[day] > max {
+                "  overloaded"
+            } else {
+                ""
+            }
+        );
+    }
+    if moves.is_empty() {
+        println!("nothing can be moved to stay within {} per day", max);
+        return Ok(());
+    }
+    println!();
+    for m in &moves {
+        println!(
+            "Move {:?} from {} to {}",
+            m.task.content,
+            m.from.format("%a %m-%d"),
+            m.to.format("%a %m-%d")
+        );
+    }
+    let apply = if params.yes {
+        true
+    } else if Term::stderr().is_term() {
+        interactive::confirm(&format!("Apply these {} moves?", moves.len()))?
+    } else {
+        eprintln!("run again with --yes to apply the plan");
+        false
+    };
+    if !apply {
+        return Ok(());
+    }
+    apply_moves(&moves, gw).await?;
+    println!("moved {} tasks", moves.len());
+    Ok(())
+}
+
+/// Returns how much the task adds to the load of its day.
+fn weight(task: &Task, by: Load) -> u32 {
+    match by {
+        Load::Count => 1,
+        Load::Estimate => task.duration.map(|d| d.minutes()).unwrap_or_default(),
+    }
+}
+
+fn loads(days: &[NaiveDate], scheduled: &[(&Task, NaiveDate, u32)]) -> BTreeMap<NaiveDate, u32> {
+    let mut loads = days.iter().map(|d| (*d, 0)).collect::<BTreeMap<_, _>>();
+    for (_, day, weight) in scheduled {
+        *loads.entry(*day).or_default() += weight;
+    }
+    loads
+}
+
+/// Moves the least urgent tasks of every day above `max` to the lightest day that still has room
+/// for them, preferring days close to the original one. Recurring tasks and tasks with an exact
+/// time stay where they are, as moving them would lose their schedule.
+fn plan<'a>(
+    days: &[NaiveDate],
+    scheduled: &[(&'a Task, NaiveDate, u32)],
+    max: u32,
+) -> Vec<Move<'a>> {
+    let mut loads = loads(days, scheduled);
+    let mut moves = Vec::new();
+    for day in days {
+        let mut movable = scheduled
+            .iter()
+            .filter(|(task, d, weight)| {
+                d == day
+                    && *weight > 0
+                    && task
+                        .due
+                        .as_ref()
+                        .is_some_and(|due| !due.is_recurring && due.exact.is_none())
+            })
+            .collect::<Vec<_>>();
+        movable.sort_by_key(|(task, _, _)| task.priority);
+        for (task, _, weight) in movable {
+            if loads[day] <= max {
+                break;
+            }
+            let target = days
+                .iter()
+                .filter(|d| *d != day && loads[*d] + weight <= max)
+                .min_by_key(|d| (loads[*d], (**d - *day).abs()));
+            let Some(target) = target else {
+                continue;
+            };
+            *loads.entry(*day).or_default() -= weight;
+            *loads.entry(*target).or_default() += weight;
+            moves.push(Move {
+                task,
+                from: *day,
+                to: *target,
+            });
+        }
+    }
+    moves
+}
+
+/// Moves the tasks in batches through the Sync API.
+async fn apply_moves(moves: &[Move<'_>], gw: &Gateway) -> Result<()> {
+    let mut failed = Vec::new();
+    for batch in moves.chunks(SYNC_BATCH_SIZE) {
+        let commands = batch
+            .iter()
+            .map(|m| {
+                gw.sync_command(
+                    "item_update",
+                    json!({
+                        "id": m.task.id,
+                        "due": {"date": m.to.format("%Y-%m-%d").to_string()},
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+        let resp = gw.sync(&commands).await?;
+        for (m, command) in batch.iter().zip(&commands) {
+            if let Some(error) = resp.error(command) {
+                failed.push(format!("{:?}: {}", m.task.content, error));
+            }
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "unable to move {} tasks:\n{}",
+            failed.len(),
+            failed.join("\n")
+        ))
+    }
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        balance(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use crate::api::rest::{DueDate, Priority};
+
+    use super::*;
+
+    fn task(id: &str, day: NaiveDate, priority: Priority, recurring: bool) -> Task {
+        let mut task = Task::new(id, id);
+        task.priority = priority;
+        task.due = Some(DueDate {
+            string: String::new(),
+            date: day,
+            is_recurring: recurring,
+            exact: None,
+        });
+        task
+    }
+
+    #[test]
+    fn plans_moves() {
+        let monday = NaiveDate::from_ymd_opt(2022, 8, 29).unwrap();
+        let days = monday.iter_days().take(4).collect::<Vec<_>>();
+        let day = |n| monday + Duration::days(n);
+        let tasks = [
+            task("urgent", day(1), Priority::Urgent, false),
+            task("repeats", day(1), Priority::Normal, true),
+            task("low", day(1), Priority::Normal, false),
+            task("high", day(1), Priority::High, false),
+            task("busy", day(2), Priority::Normal, false),
+            task("also busy", day(3), Priority::Normal, false),
+        ];
+        let scheduled = tasks
+            .iter()
+            .map(|t| (t, t.due.as_ref().unwrap().date, 1))
+            .collect::<Vec<_>>();
+        let moves = plan(&days, &scheduled, 2)
+            .into_iter()
+            .map(|m| (m.task.id.as_str(), m.to))
+            .collect::<Vec<_>>();
+        // The least urgent movable tasks go to the lightest days, closest to their day first.
+        assert_eq!(moves, [("low", day(0)), ("high", day(0))]);
+        assert!(plan(&days, &scheduled, 4).is_empty());
+        // Without enough room anywhere, nothing is moved.
+        assert!(plan(&days[1..2], &scheduled, 1).is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+//! Runs the same change on several tasks at once.
+use std::future::Future;
+
+use color_eyre::{eyre::eyre, Result};
+use futures::{stream, StreamExt};
+
+use crate::api::rest::TaskID;
+
+/// How many tasks are changed at the same time, which keeps big changes under the rate limits.
+const CONCURRENT_CHANGES: usize = 4;
+
+/// Runs the change on all tasks concurrently and returns the results in the order of the tasks.
+/// The tasks whose change failed are reported, failing after all others were changed.
+pub async fn run<'a, T, F, Fut>(verb: &str, ids: &'a [TaskID], change: F) -> Result<Vec<T>>
+where
+    F: Fn(&'a TaskID) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    finish(verb, attempt(ids, change).await)
+}
+
+/// Runs the change on all tasks concurrently and returns the result of each task, in the order of
+/// the tasks, so that the tasks that changed are known even if others failed.
+pub async fn attempt<'a, T, F, Fut>(ids: &'a [TaskID], change: F) -> Vec<(&'a TaskID, Result<T>)>
+where
+    F: Fn(&'a TaskID) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let results = stream::iter(ids)
+        .map(change)
+        .buffered(CONCURRENT_CHANGES)
+        .collect::<Vec<_>>()
+        .await;
+    ids.iter().zip(results).collect()
+}
+
+/// Returns the tasks whose change succeeded.
+pub fn succeeded<T>(results: &[(&TaskID, Result<T>)]) -> Vec<TaskID> {
+    results
+        .iter()
+        .filter(|(_, result)| result.is_ok())
+        .map(|(id, _)| (*id).clone())
+        .collect()
+}
+
+/// Returns the results of the changes, reporting the tasks whose change failed and failing if any
+/// did.
+pub fn finish<T>(verb: &str, results: Vec<(&TaskID, Result<T>)>) -> Result<Vec<T>> {
+    // A single task fails with its own error, like it always did.
+    if results.len() == 1 {
+        return results.into_iter().map(|(_, result)| result).collect();
+    }
+    let total = results.len();
+    let mut changed = Vec::new();
+    let mut failed = 0;
+    for (id, result) in results {
+        match result {
+            Ok(result) => changed.push(result),
+            Err(e) => {
+                eprintln!("unable to {} task {}: {:#}", verb, id, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(eyre!("unable to {} {} of {} tasks", verb, failed, total));
+    }
+    Ok(changed)
+}
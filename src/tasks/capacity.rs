@@ -0,0 +1,104 @@
+//! Summarizes the load of a day, so the views of days double as a planner.
+use chrono::Duration;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{rest::Task, tree::Tree},
+    chart,
+    config::Config,
+    duration,
+    i18n::t,
+    output,
+};
+
+/// How many characters the bar of the used capacity takes.
+const BAR_WIDTH: i64 = 10;
+
+/// Returns the sum of the estimated minutes of the tasks and their subtasks.
+fn estimated(tasks: &[&Tree<Task>]) -> i64 {
+    tasks
+        .iter()
+        .flat_map(|t| t.flatten())
+        .filter_map(|t| t.duration)
+        .map(|d| i64::from(d.minutes()))
+        .sum()
+}
+
+/// Renders the bar of how much of the capacity the estimate fills, with the time that is left.
+fn bar(estimated: i64, capacity: i64) -> String {
+    let filled = match capacity {
+        0 => BAR_WIDTH,
+        _ => (estimated * BAR_WIDTH / capacity).clamp(0, BAR_WIDTH),
+    };
+    let bar = match output::a11y() {
+        true => t!(
+            "capacity-used",
+            percent = match capacity {
+                0 => 100,
+                _ => estimated * 100 / capacity,
+            }
+        ),
+        false => chart::bar(filled as usize, BAR_WIDTH as usize),
+    };
+    let free = capacity - estimated;
+    match free >= 0 {
+        true => t!(
+            "free",
+            bar = bar
+                .if_supports_color(Stream::Stdout, |t| t.green())
+                .to_string(),
+            duration = duration::format(Duration::minutes(free))
+        ),
+        false => t!(
+            "over",
+            bar = bar
+                .if_supports_color(Stream::Stdout, |t| t.bright_red())
+                .to_string(),
+            duration = duration::format(Duration::minutes(-free))
+        ),
+    }
+}
+
+/// Returns how many tasks there are, counting subtasks as well.
+pub fn total(tasks: &[&Tree<Task>]) -> usize {
+    tasks.iter().map(|t| t.flatten().len()).sum()
+}
+
+/// Describes how many tasks there are, counting subtasks as well.
+pub fn count(tasks: &[&Tree<Task>]) -> String {
+    t!("tasks", count = total(tasks))
+}
+
+/// Describes the load of the tasks of a day, like `2h estimated [██████░░░░] 2h free`. The bar
+/// is only shown with `capacity` and if `work_hours` are configured. Returns nothing if there is
+/// nothing to tell.
+pub fn load(tasks: &[&Tree<Task>], cfg: &Config, capacity: bool) -> Option<String> {
+    let estimated = estimated(tasks);
+    let mut parts = Vec::new();
+    if estimated > 0 {
+        parts.push(t!(
+            "estimated",
+            duration = duration::format(Duration::minutes(estimated))
+        ));
+    }
+    if let Some(hours) = cfg.work_hours.filter(|_| capacity) {
+        parts.push(bar(estimated, (hours * 60.0).round() as i64));
+    }
+    match parts.is_empty() {
+        true => None,
+        false => Some(parts.join(" ")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_bar() {
+        assert_eq!(bar(0, 480), "[░░░░░░░░░░] 8h free");
+        assert_eq!(bar(120, 480), "[██░░░░░░░░] 6h free");
+        assert_eq!(bar(540, 480), "[██████████] 1h over");
+        assert_eq!(bar(30, 0), "[██████████] 30m over");
+    }
+}
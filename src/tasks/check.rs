@@ -0,0 +1,78 @@
+//! Checks for tasks that are due soon, so scripts can react to impending deadlines through the
+//! exit code.
+use chrono::{DateTime, Duration, Local, Utc};
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    api::{
+        rest::{Gateway, Task},
+        tree::TreeFlattenExt,
+    },
+    command::{Command, Context},
+    config::Config,
+    duration,
+};
+
+use super::state::State;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Counts tasks that are due within this window from now, like `2h` or `1d`. Overdue tasks are
+    /// always counted.
+    #[arg(long = "due-within", value_parser = duration::parse)]
+    due_within: Duration,
+    /// Fails if more than this many tasks are due within the window.
+    #[arg(long = "max", default_value_t = 0)]
+    max: usize,
+    /// Only checks tasks that match this filter, instead of all tasks.
+    #[arg(short = 'f', long = "filter")]
+    filter: Option<String>,
+    /// Doesn't list the tasks that are due, only sets the exit code.
+    #[arg(short = 'q', long = "quiet")]
+    quiet: bool,
+}
+
+/// Lists the tasks that are due within the window, and fails if there are too many of them.
+pub async fn check(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = State::fetch_tree(params.filter.as_deref(), gw, cfg).await?;
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let until = now + params.due_within;
+    let due = state
+        .tasks
+        .flat_tree()
+        .into_iter()
+        .filter(|task| due_before(task, &until))
+        .collect::<Vec<_>>();
+    if !params.quiet {
+        for task in &due {
+            println!("{}", state.table_task(task));
+        }
+    }
+    if due.len() > params.max {
+        return Err(eyre!(
+            "{} tasks are due within {}, more than the allowed {}",
+            due.len(),
+            duration::format(params.due_within),
+            params.max
+        ));
+    }
+    Ok(())
+}
+
+/// Returns true if the task is due before the given time. Tasks without a time are due on their
+/// day, so they count if the day has started by then.
+fn due_before(task: &Task, until: &DateTime<Utc>) -> bool {
+    match &task.due {
+        Some(due) => match &due.exact {
+            Some(exact) => exact.datetime <= *until,
+            None => due.date <= until.with_timezone(&Local).date_naive(),
+        },
+        None => false,
+    }
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        check(self, &ctx.gw, &ctx.cfg).await
+    }
+}
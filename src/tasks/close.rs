@@ -3,15 +3,20 @@ use owo_colors::{OwoColorize, Stream};
 
 use crate::{
     api::{self, rest::Gateway},
+    command::{Command, Context},
     config::Config,
+    i18n::t,
 };
 
-use super::filter;
+use super::{
+    bulk, filter,
+    undo::{self, Operation},
+};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     #[clap(flatten)]
-    pub task: filter::TaskOrInteractive,
+    pub tasks: filter::TasksOrInteractive,
     /// Complete will completely close a task, even if it's recurring.
     /// Since the REST API does not support completely closing tasks, this will change the due date
     /// of the task to "today" and then close it.
@@ -19,35 +24,73 @@ pub struct Params {
     pub complete: bool,
 }
 
+/// Closes all given tasks at once.
 pub async fn close(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let id = params
-        .task
-        .task_id(gw, cfg)
+    let ids = params
+        .tasks
+        .task_ids(gw, cfg)
         .await
         .wrap_err("no task selected for closing")?;
-    if params.complete {
-        return complete(&id, gw).await;
+    let verb = if params.complete { "complete" } else { "close" };
+    let results = bulk::attempt(&ids, |id| async move {
+        match params.complete {
+            true => complete(id, gw).await,
+            false => close_task(id, gw).await,
+        }
+    })
+    .await;
+    // Only the tasks that were closed are reopened by undo.
+    let ids = bulk::succeeded(&results);
+    if !ids.is_empty() {
+        undo::record(Operation::Close { ids }, cfg);
     }
-    gw.close(&id).await?;
-    println!("closed task {}", id.clone().bright_red());
-    let task = gw.task(&id).await?;
+    let closed = bulk::finish(verb, results)?;
+    for message in closed {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+/// Closes the task and describes it, together with the next due date of a recurring task.
+pub(super) async fn close_task(id: &api::rest::TaskID, gw: &Gateway) -> Result<String> {
+    gw.close(id).await?;
+    let mut message = t!(
+        "closed-task",
+        id = id
+            .if_supports_color(Stream::Stdout, |text| text.bright_red())
+            .to_string()
+    );
+    let task = gw.task(id).await?;
     if !task.is_completed {
         if let Some(due) = task.due {
             if let Some(exact) = due.exact {
-                println!("next due date: {}", exact.datetime);
+                message.push_str(&format!(
+                    "\n{}",
+                    t!("next-due-date", date = exact.datetime.to_string())
+                ));
             } else {
-                println!("next due date: {}", due.date);
+                message.push_str(&format!(
+                    "\n{}",
+                    t!("next-due-date", date = due.date.to_string())
+                ));
             }
         }
     }
-    Ok(())
+    Ok(message)
 }
 
-pub async fn complete(id: &api::rest::TaskID, gw: &Gateway) -> Result<()> {
+async fn complete(id: &api::rest::TaskID, gw: &Gateway) -> Result<String> {
     gw.complete(id).await?;
-    println!(
-        "completed task {}",
-        id.if_supports_color(Stream::Stdout, |text| text.bright_red())
-    );
-    Ok(())
+    Ok(t!(
+        "completed-task",
+        id = id
+            .if_supports_color(Stream::Stdout, |text| text.bright_red())
+            .to_string()
+    ))
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        close(self, &ctx.gw, &ctx.cfg).await
+    }
 }
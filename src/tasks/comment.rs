@@ -1,7 +1,11 @@
-use color_eyre::Result;
+use std::path::PathBuf;
+
+use color_eyre::{eyre::eyre, Result};
 
 use crate::{
     api::rest::{CreateComment, FullComment, Gateway, ThreadID},
+    command::{Command, Context},
+    comments,
     config::Config,
 };
 
@@ -11,6 +15,9 @@ use super::filter::TaskOrInteractive;
 pub struct Params {
     /// The text of the comment. Supports Markdown.
     content: String,
+    /// A file to attach to the comment, like a PDF or an image.
+    #[arg(long = "file")]
+    file: Option<PathBuf>,
     #[clap(flatten)]
     task: TaskOrInteractive,
 }
@@ -18,12 +25,27 @@ pub struct Params {
 /// Creates a new comment for a task.
 pub async fn comment(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let (id, _) = params.task.task(gw, cfg).await?;
+    let attachment = comments::attach(params.file.as_deref(), gw).await?;
     let comment = gw
         .create_comment(&CreateComment {
             thread: ThreadID::Task { task_id: id },
             content: params.content,
+            attachment,
         })
         .await?;
     println!("created comment: {}", FullComment(&comment));
     Ok(())
 }
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.content.trim().is_empty() {
+            return Err(eyre!("comment can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        comment(self, &ctx.gw, &ctx.cfg).await
+    }
+}
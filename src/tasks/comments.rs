@@ -0,0 +1,34 @@
+use color_eyre::Result;
+
+use crate::{
+    api::rest::Gateway,
+    command::{Command, Context},
+    comments,
+    config::Config,
+};
+
+use super::filter::TaskOrInteractive;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    task: TaskOrInteractive,
+}
+
+/// Lists the comments of a task.
+pub async fn comments(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let id = params.task.task_id(gw, cfg).await?;
+    let comments = gw.task_comments(&id).await?;
+    if comments.is_empty() {
+        println!("there are no comments on the task");
+        return Ok(());
+    }
+    println!("{}", comments::list(&comments));
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        comments(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,113 @@
+//! Reviews the tasks that were completed recently, which the REST API does not return.
+use std::collections::HashMap;
+
+use chrono::{Local, NaiveDate, Utc};
+use color_eyre::Result;
+use futures::{StreamExt, TryStreamExt};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{CompletedTask, Project, ProjectID},
+    },
+    command::{Command, Context},
+    duration::Since,
+    interactive, output,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Shows the tasks completed since then, like `today`, `monday`, `7d` or `2022-08-01`.
+    #[arg(short = 's', long = "since", default_value = "7d")]
+    since: Since,
+    /// Only shows the tasks completed in this project.
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    #[clap(flatten)]
+    output: output::Json,
+}
+
+/// Renders the completed tasks by the day they were completed on, most recent first. `day` is the
+/// day of the tasks that were rendered before, so pages can be rendered one after the other.
+fn render(
+    completed: &[CompletedTask],
+    projects: &HashMap<&ProjectID, &Project>,
+    day: &mut Option<NaiveDate>,
+) -> String {
+    let mut out = String::new();
+    for task in completed {
+        let completed_at = task.completed_at.with_timezone(&Local);
+        if *day != Some(completed_at.date_naive()) {
+            if day.is_some() {
+                out.push('\n');
+            }
+            *day = Some(completed_at.date_naive());
+            out.push_str(&format!(
+                "{}\n",
+                completed_at
+                    .format("%A %-d %B")
+                    .to_string()
+                    .if_supports_color(Stream::Stdout, |t| t.bold())
+            ));
+        }
+        out.push_str(&format!(
+            "  {} {}",
+            completed_at
+                .format("%H:%M")
+                .if_supports_color(Stream::Stdout, |t| t.bright_black()),
+            Content(&task.content)
+        ));
+        if let Some(project) = projects.get(&task.project_id) {
+            out.push_str(&format!(
+                " [{}]",
+                Content(&project.name).if_supports_color(Stream::Stdout, |t| t.blue())
+            ));
+        }
+        for label in task.labels() {
+            out.push_str(&format!(
+                " @{}",
+                Content(label).if_supports_color(Stream::Stdout, |t| t.bright_green())
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        let now = ctx.cfg.override_time.unwrap_or_else(Utc::now);
+        let since = self
+            .since
+            .resolve(now.with_timezone(&Local))
+            .with_timezone(&Utc);
+        let projects = ctx.gw.projects().await?;
+        let mut pages = match self.project.optional(&projects)? {
+            Some(project) => ctx
+                .gw
+                .completed_in_project_pages(&project.id, &since)
+                .boxed(),
+            None => ctx.gw.completed_pages(&since).boxed(),
+        };
+        if self.output.json {
+            let mut completed = pages.try_concat().await?;
+            completed.sort_by_key(|c| std::cmp::Reverse(c.completed_at));
+            return self.output.print(&completed);
+        }
+        // Long histories are shown page by page, as the pages come most recent first already.
+        let projects = projects.iter().map(|p| (&p.id, p)).collect();
+        let mut day = None;
+        while let Some(mut page) = pages.try_next().await? {
+            page.sort_by_key(|c| std::cmp::Reverse(c.completed_at));
+            print!("{}", render(&page, &projects, &mut day));
+        }
+        if day.is_none() {
+            println!(
+                "no tasks were completed since {}",
+                since.with_timezone(&Local)
+            );
+        }
+        Ok(())
+    }
+}
@@ -3,9 +3,10 @@ use owo_colors::OwoColorize;
 use strum::EnumIter;
 
 use crate::{
-    api::rest::{CreateTask, Gateway, TaskDue},
+    api::rest::{CreateTask, Gateway, Priority, TaskDue},
+    command::{Command, Context},
     config::Config,
-    interactive,
+    history, i18n, interactive,
 };
 
 use super::add::create_task;
@@ -52,6 +53,38 @@ impl From<usize> for Selection {
     }
 }
 
+/// Returns the arguments of the `add` that creates the same task without asking anything.
+fn script(create: &CreateTask, due: Option<&str>) -> Vec<String> {
+    let priority = create.priority.map(|p| match p {
+        Priority::Urgent => "1",
+        Priority::VeryHigh => "2",
+        Priority::High => "3",
+        Priority::Normal => "4",
+    });
+    let flags = [
+        ("--due", due),
+        ("--desc", create.description.as_deref()),
+        ("--project_id", create.project_id.as_deref()),
+        ("--section_id", create.section_id.as_deref()),
+        ("--priority", priority),
+    ];
+    let mut args = vec!["add".to_string()];
+    for (flag, value) in flags {
+        if let Some(value) = value {
+            args.extend([flag.to_string(), value.to_string()]);
+        }
+    }
+    // Without a due date, `add` would look for one in the name.
+    if due.is_none() {
+        args.push("--nosmartdue".to_string());
+    }
+    if create.content.starts_with('-') {
+        args.push("--".to_string());
+    }
+    args.push(create.content.clone());
+    args
+}
+
 pub async fn create(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let mut create = CreateTask {
         content: interactive::input_content("")?,
@@ -119,8 +152,46 @@ pub async fn create(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
             Selection::Priority => create.priority = interactive::input_priority()?,
         }
     }
+    history::amounts_to(script(&create, due.as_deref()));
     if let Some(due) = due {
         create.due = Some(TaskDue::String(due));
+        create.due_lang = i18n::due_lang();
+    }
+    create_task(create, None, None, &[], None, gw, cfg).await
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        create(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scripts_task() {
+        let create = CreateTask {
+            content: "-1 to the plan".to_string(),
+            project_id: Some("123".to_string()),
+            priority: Some(Priority::VeryHigh),
+            ..Default::default()
+        };
+        assert_eq!(
+            script(&create, Some("tomorrow")),
+            [
+                "add",
+                "--due",
+                "tomorrow",
+                "--project_id",
+                "123",
+                "--priority",
+                "2",
+                "--",
+                "-1 to the plan"
+            ]
+        );
+        assert!(script(&create, None).contains(&"--nosmartdue".to_string()));
     }
-    create_task(create, None, None, &[], gw, cfg).await
 }
@@ -0,0 +1,161 @@
+//! Defers tasks until another task is done, hiding them from task lists in the meantime. Which
+//! tasks are deferred is only kept locally.
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{eyre::eyre, Result};
+use owo_colors::{OwoColorize, Stream};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, TaskID},
+        tree::TreeFlattenExt,
+    },
+    command::{Command, Context},
+    config::Config,
+    store::Store,
+};
+
+use super::state::State;
+
+/// The file in the [`Store`] that contains the deferred tasks.
+pub(crate) const DEFER_FILE: &str = "deferred.json";
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID of the task to defer. Lists the deferred tasks if omitted.
+    id: Option<TaskID>,
+    /// The Task ID of the task that has to be done first.
+    #[arg(
+        short = 'u',
+        long = "until-done",
+        requires = "id",
+        conflicts_with = "remove"
+    )]
+    until_done: Option<TaskID>,
+    /// Shows the task again right away.
+    #[arg(short = 'r', long = "remove", requires = "id")]
+    remove: bool,
+}
+
+/// Deferral describes the task that a deferred task waits for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Deferral {
+    /// The content of the task at the time it was deferred.
+    pub content: String,
+    /// The task that has to be done first.
+    pub until_done: TaskID,
+    /// The content of the task that has to be done first.
+    pub until_content: String,
+    /// When the task was deferred.
+    pub since: DateTime<Utc>,
+}
+
+fn load(store: &Store) -> Result<BTreeMap<TaskID, Deferral>> {
+    store.load(DEFER_FILE)
+}
+
+/// Removes the deferred tasks from the state, unless the task they wait for is done by now. Those
+/// are shown again and forgotten about.
+pub(crate) async fn hide<'a>(state: State<'a>, gw: &Gateway, cfg: &Config) -> Result<State<'a>> {
+    let store = cfg.store()?;
+    let mut deferred = load(&store)?;
+    if deferred.is_empty() {
+        return Ok(state);
+    }
+    let mut waiting_for = deferred
+        .values()
+        .map(|d| d.until_done.clone())
+        .collect::<Vec<_>>();
+    waiting_for.sort();
+    waiting_for.dedup();
+    // Only active tasks are returned, so missing ones were completed or deleted.
+    let active = gw
+        .tasks_by_ids(&waiting_for)
+        .await?
+        .into_iter()
+        .map(|t| t.id)
+        .collect::<HashSet<_>>();
+    let before = deferred.len();
+    deferred.retain(|id, d| {
+        let waits = active.contains(&d.until_done);
+        if !waits {
+            eprintln!(
+                "task {} is back, as {} {} is done",
+                id,
+                d.until_done,
+                Content(&d.until_content)
+            );
+        }
+        waits
+    });
+    if deferred.len() != before {
+        store.save(DEFER_FILE, &deferred)?;
+    }
+    let hidden = deferred.into_keys().collect::<HashSet<_>>();
+    let mut state = state;
+    state.tasks = state.tasks.drop_trees(&hidden);
+    Ok(state)
+}
+
+/// Defers a task until another one is done, shows it again, or lists the deferred tasks.
+pub async fn defer(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let store = cfg.store()?;
+    let mut deferred = load(&store)?;
+    let Some(id) = params.id else {
+        if deferred.is_empty() {
+            println!("no tasks are deferred");
+        }
+        for (id, d) in &deferred {
+            println!(
+                "{} {} waits for {} {}",
+                id.if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+                Content(&d.content),
+                d.until_done
+                    .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+                Content(&d.until_content)
+            );
+        }
+        return Ok(());
+    };
+    if params.remove {
+        if deferred.remove(&id).is_none() {
+            return Err(eyre!("task {} is not deferred", id));
+        }
+        store.save(DEFER_FILE, &deferred)?;
+        println!("task {} is shown again", id);
+        return Ok(());
+    }
+    let until = params
+        .until_done
+        .ok_or_else(|| eyre!("--until-done is needed to defer a task"))?;
+    if until == id {
+        return Err(eyre!("a task can not wait for itself"));
+    }
+    let (task, other) = tokio::try_join!(gw.task(&id), gw.task(&until))?;
+    deferred.insert(
+        id.clone(),
+        Deferral {
+            content: task.content,
+            until_done: until.clone(),
+            until_content: other.content.clone(),
+            since: cfg.override_time.unwrap_or_else(Utc::now),
+        },
+    );
+    store.save(DEFER_FILE, &deferred)?;
+    println!(
+        "deferred task {} until {} {} is done",
+        id,
+        until,
+        Content(&other.content)
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        defer(self, &ctx.gw, &ctx.cfg).await
+    }
+}
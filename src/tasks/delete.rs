@@ -0,0 +1,77 @@
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use dialoguer::console::Term;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::rest::Gateway,
+    command::{Command, Context},
+    config::Config,
+    interactive,
+};
+
+use super::{
+    filter,
+    undo::{self, Operation},
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    pub task: filter::TaskOrInteractive,
+    /// Deletes the task without asking for confirmation.
+    #[arg(short = 'y', long = "yes")]
+    pub yes: bool,
+}
+
+/// Deletes a task together with its subtasks, which `undo` can only create again as new tasks.
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let (id, state) = params
+        .task
+        .task(gw, cfg)
+        .await
+        .wrap_err("no task selected for deleting")?;
+    if !params.yes {
+        // The task can be outside of the filter if it was given by ID.
+        let (content, subtasks) = match state.task(&id) {
+            Some(task) => (task.content.clone(), task.subitems.len()),
+            None => (gw.task(&id).await?.content, 0),
+        };
+        let mut prompt = format!("Delete the task \"{}\"", content);
+        if subtasks > 0 {
+            prompt.push_str(&format!(" and its {} subtasks", subtasks));
+        }
+        prompt.push_str(" for good?");
+        if !Term::stderr().is_term() {
+            return Err(eyre!(
+                "run again with --yes to delete the task without asking"
+            ));
+        }
+        if !interactive::confirm(&prompt)? {
+            return Ok(());
+        }
+    }
+    // The subtasks are deleted with the task, so they are remembered as well.
+    let snapshot = gw
+        .tasks(None)
+        .await
+        .and_then(|tasks| undo::subtree(&id, tasks));
+    gw.delete_task(&id).await?;
+    match snapshot {
+        Ok(tasks) => undo::record(Operation::Delete { tasks }, cfg),
+        Err(e) => eprintln!("unable to remember the change for undo: {:#}", e),
+    }
+    println!(
+        "deleted task {}",
+        id.if_supports_color(Stream::Stdout, |text| text.bright_red())
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        delete(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,80 @@
+//! Finishes tasks, telling apart recurring tasks, which only advance to their next occurrence.
+use chrono::Utc;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::rest::{Gateway, TaskDue, TaskID, UpdateTask},
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{bulk, close, filter};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    pub tasks: filter::TasksOrInteractive,
+    /// Removes the recurrence of recurring tasks instead of closing them. They stay open and due
+    /// on their current occurrence.
+    #[arg(short = 's', long = "stop-recurring")]
+    pub stop_recurring: bool,
+}
+
+/// Closes all given tasks, or stops their recurrence.
+pub async fn done(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let ids = params
+        .tasks
+        .task_ids(gw, cfg)
+        .await
+        .wrap_err("no task selected")?;
+    let done = bulk::run("finish", &ids, |id| {
+        done_task(id, params.stop_recurring, gw)
+    })
+    .await?;
+    for message in done {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+async fn done_task(id: &TaskID, stop_recurring: bool, gw: &Gateway) -> Result<String> {
+    let task = gw.task(id).await?;
+    let recurrence = task.due.filter(|d| d.is_recurring);
+    match (recurrence, stop_recurring) {
+        (Some(due), false) => {
+            let closed = close::close_task(id, gw).await?;
+            Ok(format!(
+                "task {} recurs {}, so it advances to its next occurrence instead of being \
+                 completed. Use `--stop-recurring` to end the recurrence.\n{}",
+                id, due.string, closed
+            ))
+        }
+        (Some(due), true) => {
+            let update = UpdateTask {
+                due: Some(match &due.exact {
+                    Some(exact) => TaskDue::DateTime(exact.datetime.with_timezone(&Utc)),
+                    None => TaskDue::Date(due.date.format("%Y-%m-%d").to_string()),
+                }),
+                ..Default::default()
+            };
+            gw.update(id, &update).await?;
+            Ok(format!(
+                "stopped the recurrence of task {}, it stays due on {}",
+                id.if_supports_color(Stream::Stdout, |text| text.bright_red()),
+                due.date
+            ))
+        }
+        (None, true) => Err(eyre!("task {} is not recurring", id)),
+        (None, false) => close::close_task(id, gw).await,
+    }
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        done(self, &ctx.gw, &ctx.cfg).await
+    }
+}
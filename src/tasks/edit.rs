@@ -1,24 +1,58 @@
-use color_eyre::Result;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use dialoguer::console::Term;
 
 use crate::{
     api::{
         self,
-        rest::{Gateway, TaskDue, UpdateTask},
+        rest::{Gateway, TaskID, UpdateTask, UserID},
+        tree::TreeFlattenExt,
     },
+    command::{Command, Context},
     config::Config,
+    holidays::Scheduling,
+    i18n, interactive,
     labels::{self, LabelSelect},
-    tasks::{filter::TaskOrInteractive, Priority},
+    tasks::{
+        assignee, bulk,
+        filter::TasksOrInteractive,
+        state::State,
+        undo::{self, Operation},
+        Priority,
+    },
 };
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
-    #[clap(flatten)]
-    pub task: TaskOrInteractive,
+    /// The Task ID as provided from the Todoist API. Several tasks can be edited at once by
+    /// separating their IDs with commas. If omitted, will use the tasks of `--filter`, or
+    /// interactively select a task.
+    // Takes a single value, so the quick edit text can follow it.
+    #[arg(value_delimiter = ',', num_args = 1, action = clap::ArgAction::Set)]
+    pub ids: Vec<api::rest::TaskID>,
+    /// Edits all tasks that match this filter query, like `overdue & @quick`. The tasks are
+    /// shown before they are changed, to confirm the edit.
+    #[arg(short = 'f', long = "filter", conflicts_with = "ids")]
+    pub filter: Option<String>,
+    /// Sets a field as `field:value`, like `due:today` or `priority:2`. The fields are `name`,
+    /// `desc`, `due`, `priority` and `assign`. Can be used multiple times to set more fields.
+    #[arg(short = 's', long = "set", value_name = "FIELD:VALUE")]
+    pub set: Vec<SetField>,
+    /// Edits the tasks of `--filter` without asking for confirmation.
+    #[arg(short = 'y', long = "yes")]
+    pub yes: bool,
     /// Name of a task
     #[arg(short = 'n', long = "name")]
     pub name: Option<String>,
     #[arg(short = 'd', long = "due")]
     pub due: Option<String>,
+    #[clap(flatten)]
+    pub scheduling: Scheduling,
     /// Description of a task.
     #[arg(short = 'D', long = "desc")]
     pub desc: Option<String>,
@@ -27,22 +61,174 @@ pub struct Params {
     pub priority: Option<Priority>,
     #[clap(flatten)]
     pub labels: LabelSelect,
+    /// Assigns the tasks to a collaborator of their shared projects, given by name or email
+    /// address.
+    #[arg(short = 'a', long = "assign")]
+    pub assign: Option<String>,
+    /// Quick edit text that follows the task ID. Words become the new name of the task, while
+    /// `!!p1` to `!!p4` set the priority and `due:tomorrow` or `due:"next monday"` set the due
+    /// date.
+    ///
+    /// Example: `doist edit 123 "call mom !!p1 due:tomorrow"`
+    #[arg(value_name = "QUICK_EDIT")]
+    pub quick: Option<String>,
 }
 
 impl Params {
     pub fn new(id: api::rest::TaskID) -> Self {
         Self {
-            task: TaskOrInteractive::with_id(id),
+            ids: vec![id],
+            filter: None,
+            set: Vec::new(),
+            yes: false,
             name: None,
             due: None,
+            scheduling: Scheduling::default(),
             desc: None,
             priority: None,
             labels: LabelSelect::default(),
+            assign: None,
+            quick: None,
         }
     }
+
+    /// Moves the values of `--set` into the flags of their fields. Setting a field both ways is
+    /// an error.
+    fn apply_sets(&mut self) -> Result<()> {
+        for set in std::mem::take(&mut self.set) {
+            let (field, twice) = match set {
+                SetField::Name(name) => ("name", self.name.replace(name).is_some()),
+                SetField::Desc(desc) => ("desc", self.desc.replace(desc).is_some()),
+                SetField::Due(due) => ("due", self.due.replace(due).is_some()),
+                SetField::Priority(p) => ("priority", self.priority.replace(p).is_some()),
+                SetField::Assign(who) => ("assign", self.assign.replace(who).is_some()),
+            };
+            if twice {
+                return Err(eyre!("{} was given more than once", field));
+            }
+        }
+        Ok(())
+    }
+
+    /// Merges the quick edit text with the explicitly given flags. Specifying the same field
+    /// through both is an error.
+    fn quick_edit(&self) -> Result<QuickEdit> {
+        let quick = match &self.quick {
+            Some(quick) => QuickEdit::parse(quick)?,
+            None => QuickEdit::default(),
+        };
+        if quick.content.is_some() && self.name.is_some() {
+            return Err(eyre!("name was given both as quick edit text and --name"));
+        }
+        if quick.due.is_some() && self.due.is_some() {
+            return Err(eyre!("due was given both as quick edit text and --due"));
+        }
+        if quick.priority.is_some() && self.priority.is_some() {
+            return Err(eyre!(
+                "priority was given both as quick edit text and --priority"
+            ));
+        }
+        Ok(QuickEdit {
+            content: quick.content.or_else(|| self.name.clone()),
+            due: quick.due.or_else(|| self.due.clone()),
+            priority: quick.priority.or(self.priority),
+        })
+    }
 }
 
-pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+/// A field that `--set` changes on the tasks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetField {
+    Name(String),
+    Desc(String),
+    Due(String),
+    Priority(Priority),
+    Assign(String),
+}
+
+impl FromStr for SetField {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (field, value) = s
+            .split_once(':')
+            .ok_or_else(|| eyre!("{} is not formatted as field:value", s))?;
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(eyre!("the value of {} can not be empty", field));
+        }
+        match field.trim() {
+            "name" | "content" => Ok(SetField::Name(value.to_string())),
+            "desc" | "description" => Ok(SetField::Desc(value.to_string())),
+            "due" => Ok(SetField::Due(value.to_string())),
+            "priority" => {
+                let priority: usize = value
+                    .trim_start_matches('p')
+                    .parse()
+                    .map_err(|_| eyre!("invalid priority {}, use 1 to 4", value))?;
+                Ok(SetField::Priority(priority.try_into()?))
+            }
+            "assign" | "assignee" => Ok(SetField::Assign(value.to_string())),
+            field => Err(eyre!(
+                "unknown field {}, use name, desc, due, priority or assign",
+                field
+            )),
+        }
+    }
+}
+
+/// Fields that can be changed through the quick edit syntax.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct QuickEdit {
+    content: Option<String>,
+    due: Option<String>,
+    priority: Option<Priority>,
+}
+
+impl QuickEdit {
+    fn parse(input: &str) -> Result<QuickEdit> {
+        let mut quick = QuickEdit::default();
+        let mut content = Vec::new();
+        let mut words = input.split_whitespace();
+        while let Some(word) = words.next() {
+            if let Some(priority) = word.strip_prefix("!!p") {
+                let priority: usize = priority
+                    .parse()
+                    .map_err(|_| eyre!("invalid priority {}, use !!p1 to !!p4", word))?;
+                quick.priority = Some(priority.try_into()?);
+            } else if let Some(due) = word.strip_prefix("due:") {
+                let due = match due.strip_prefix('"') {
+                    Some(quoted) => {
+                        let mut due = quoted.to_string();
+                        while !due.ends_with('"') {
+                            let word = words
+                                .next()
+                                .ok_or_else(|| eyre!("missing closing quote for due date"))?;
+                            due.push(' ');
+                            due.push_str(word);
+                        }
+                        due.pop();
+                        due
+                    }
+                    None => due.to_string(),
+                };
+                if due.is_empty() {
+                    return Err(eyre!("due date can not be empty"));
+                }
+                quick.due = Some(due);
+            } else {
+                content.push(word);
+            }
+        }
+        if !content.is_empty() {
+            quick.content = Some(content.join(" "));
+        }
+        Ok(quick)
+    }
+}
+
+pub async fn edit(mut params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    params.apply_sets()?;
     let labels = {
         let labels = params
             .labels
@@ -53,16 +239,194 @@ pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
             Some(labels.into_iter().map(|l| l.name).collect())
         }
     };
+    let quick = params.quick_edit()?;
+    let changes = describe(&quick, &params, labels.as_deref());
     let mut update = UpdateTask {
-        content: params.name,
+        content: quick.content,
         description: params.desc,
-        priority: params.priority.map(|p| p.into()),
+        priority: quick.priority.map(|p| p.into()),
         labels,
         ..Default::default()
     };
-    if let Some(due) = params.due {
-        update.due = Some(TaskDue::String(due))
+    if let Some(due) = quick.due {
+        update.due = Some(params.scheduling.due(due, cfg).await?);
+        update.due_lang = i18n::due_lang();
+    }
+    let ids = match &params.filter {
+        Some(filter) => match filtered(filter, &changes, params.yes, gw, cfg).await? {
+            Some(ids) => ids,
+            None => return Ok(()),
+        },
+        None => {
+            TasksOrInteractive::new(params.ids, None)
+                .task_ids(gw, cfg)
+                .await?
+        }
+    };
+    if let Some(who) = &params.assign {
+        update.assignee = Some(Some(assignee(who, &ids, gw).await?));
     }
-    gw.update(&params.task.task_id(gw, cfg).await?, &update)
+    let snapshot = undo::snapshot(&ids, gw).await;
+    let results = bulk::attempt(&ids, |id| gw.update(id, &update)).await;
+    let changed = bulk::succeeded(&results);
+    undo::record_snapshot(snapshot, &changed, |tasks| Operation::Edit { tasks }, cfg);
+    let edited = bulk::finish("edit", results)?;
+    if edited.len() > 1 {
+        println!("edited {} tasks", edited.len());
+    }
+    Ok(())
+}
+
+/// Returns the tasks that match the filter, after showing them with the changes and asking for
+/// confirmation. Returns nothing if the edit was not confirmed.
+async fn filtered(
+    filter: &str,
+    changes: &str,
+    yes: bool,
+    gw: &Gateway,
+    cfg: &Config,
+) -> Result<Option<Vec<TaskID>>> {
+    if yes {
+        let tasks = TasksOrInteractive::new(Vec::new(), Some(filter.to_string()));
+        return Ok(Some(tasks.task_ids(gw, cfg).await?));
+    }
+    let state = State::fetch_tree(Some(filter), gw, cfg)
         .await
+        .wrap_err("unable to get the tasks of the filter")?;
+    let tasks = state.tasks.flat_tree();
+    if tasks.is_empty() {
+        return Err(eyre!("no tasks match the filter {}", filter));
+    }
+    let ids = tasks.iter().map(|t| t.id.clone()).collect();
+    println!("{} on {} tasks:", changes, tasks.len());
+    for task in &tasks {
+        println!("  {}", state.table_task(task));
+    }
+    if !Term::stderr().is_term() {
+        return Err(eyre!(
+            "run again with --yes to edit the tasks without asking"
+        ));
+    }
+    match interactive::confirm(&format!("Edit these {} tasks?", tasks.len()))? {
+        true => Ok(Some(ids)),
+        false => Ok(None),
+    }
+}
+
+/// Describes the changes of the edit, like `set due to "today" and priority to 2`.
+fn describe(quick: &QuickEdit, params: &Params, labels: Option<&[String]>) -> String {
+    let mut changes = Vec::new();
+    if let Some(content) = &quick.content {
+        changes.push(format!("name to \"{}\"", content));
+    }
+    if let Some(desc) = &params.desc {
+        changes.push(format!("description to \"{}\"", desc));
+    }
+    if let Some(due) = &quick.due {
+        changes.push(format!("due to \"{}\"", due));
+    }
+    if let Some(priority) = quick.priority {
+        if let Some(value) = priority.to_possible_value() {
+            changes.push(format!("priority to {}", value.get_name()));
+        }
+    }
+    if let Some(labels) = labels {
+        changes.push(format!("labels to {}", labels.join(", ")));
+    }
+    if let Some(who) = &params.assign {
+        changes.push(format!("assignee to {}", who));
+    }
+    match changes.split_last() {
+        None => "change nothing".to_string(),
+        Some((last, [])) => format!("set {}", last),
+        Some((last, rest)) => format!("set {} and {}", rest.join(", "), last),
+    }
+}
+
+/// Resolves the collaborator among everyone who collaborates on the projects of the tasks.
+async fn assignee(who: &str, ids: &[TaskID], gw: &Gateway) -> Result<UserID> {
+    let (tasks, projects) = tokio::try_join!(gw.tasks_by_ids(ids), gw.projects())?;
+    let mut shared = projects
+        .iter()
+        .filter(|p| p.is_shared && tasks.iter().any(|t| t.project_id == p.id))
+        .peekable();
+    if shared.peek().is_none() {
+        return Err(eyre!("--assign needs the tasks to be in shared projects"));
+    }
+    let mut collaborators = futures::future::try_join_all(shared.map(|p| gw.collaborators(&p.id)))
+        .await?
+        .concat();
+    collaborators.sort_by(|a, b| a.id.cmp(&b.id));
+    collaborators.dedup_by(|a, b| a.id == b.id);
+    Ok(assignee::resolve(&collaborators, who)?.id.clone())
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        self.quick_edit().map(|_| ())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        edit(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_quick_edit() {
+        assert_eq!(
+            QuickEdit::parse("call  mom !!p1 due:tomorrow").unwrap(),
+            QuickEdit {
+                content: Some("call mom".to_string()),
+                due: Some("tomorrow".to_string()),
+                priority: Some(Priority::Urgent),
+            }
+        );
+        assert_eq!(
+            QuickEdit::parse(r#"due:"next monday 8am" !!p3"#).unwrap(),
+            QuickEdit {
+                content: None,
+                due: Some("next monday 8am".to_string()),
+                priority: Some(Priority::High),
+            }
+        );
+        assert_eq!(
+            QuickEdit::parse("just a name").unwrap(),
+            QuickEdit {
+                content: Some("just a name".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parses_set() {
+        assert_eq!(
+            "due:next monday".parse::<SetField>().unwrap(),
+            SetField::Due("next monday".to_string())
+        );
+        assert_eq!(
+            "priority:2".parse::<SetField>().unwrap(),
+            SetField::Priority(Priority::VeryHigh)
+        );
+        assert_eq!(
+            "priority:p1".parse::<SetField>().unwrap(),
+            SetField::Priority(Priority::Urgent)
+        );
+        assert!("due".parse::<SetField>().is_err());
+        assert!("due:".parse::<SetField>().is_err());
+        assert!("color:red".parse::<SetField>().is_err());
+        assert!("priority:5".parse::<SetField>().is_err());
+    }
+
+    #[test]
+    fn rejects_bad_quick_edit() {
+        assert!(QuickEdit::parse("!!p5").is_err());
+        assert!(QuickEdit::parse("!!pa").is_err());
+        assert!(QuickEdit::parse(r#"due:"never closed"#).is_err());
+        assert!(QuickEdit::parse("due:").is_err());
+    }
 }
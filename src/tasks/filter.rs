@@ -3,6 +3,7 @@ use color_eyre::{eyre::eyre, Result};
 use crate::{
     api::rest::{Gateway, TaskID},
     config::Config,
+    history,
 };
 
 use super::state::State;
@@ -46,6 +47,10 @@ impl TaskOrInteractive {
             filter: Filter::new(None),
         }
     }
+    /// Returns the ID of the task if it was given, instead of being selected interactively.
+    pub fn id(&self) -> Option<&TaskID> {
+        self.id.as_ref()
+    }
     pub async fn task_id(&self, gw: &Gateway, cfg: &Config) -> Result<TaskID> {
         let (id, _) = self.task(gw, cfg).await?;
         Ok(id)
@@ -59,15 +64,73 @@ impl TaskOrInteractive {
         let state = State::fetch_tree(Some(&self.filter.select(cfg)), gw, cfg).await?;
         let id = match &self.id {
             Some(id) => id.clone(),
-            None => state
-                .select_task()?
-                .map(|t| t.id.clone())
-                .ok_or_else(|| eyre!("no task selected"))?,
+            None => {
+                let id = state
+                    .select_task()?
+                    .map(|t| t.id.clone())
+                    .ok_or_else(|| eyre!("no task selected"))?;
+                history::chose([id.clone()]);
+                id
+            }
         };
         Ok((id, state))
     }
 }
 
+/// TasksOrInteractive is like [`TaskOrInteractive`], but for commands that can work on several
+/// tasks at once.
+#[derive(clap::Parser, Debug)]
+pub struct TasksOrInteractive {
+    /// The Task IDs as provided from the Todoist API, separated by spaces or commas. If omitted,
+    /// will use the tasks of `--filter`, or interactively select a task.
+    #[arg(value_delimiter = ',')]
+    ids: Vec<TaskID>,
+    /// Uses all tasks that match this filter query, like `overdue & @quick`.
+    #[arg(short = 'f', long = "filter", conflicts_with = "ids")]
+    filter: Option<String>,
+}
+
+impl TasksOrInteractive {
+    pub fn new(ids: Vec<TaskID>, filter: Option<String>) -> Self {
+        Self { ids, filter }
+    }
+
+    /// Returns the IDs of the given tasks, or of all tasks that match the filter. Selects a task
+    /// interactively from the default filter if neither was given.
+    pub async fn task_ids(&self, gw: &Gateway, cfg: &Config) -> Result<Vec<TaskID>> {
+        if !self.ids.is_empty() {
+            return Ok(self.ids.clone());
+        }
+        match &self.filter {
+            Some(filter) => {
+                let ids = gw
+                    .tasks(Some(filter))
+                    .await?
+                    .into_iter()
+                    .map(|t| t.id)
+                    .collect::<Vec<_>>();
+                if ids.is_empty() {
+                    return Err(eyre!("no tasks match the filter {}", filter));
+                }
+                Ok(ids)
+            }
+            None => {
+                let select = TaskOrInteractive {
+                    id: None,
+                    filter: Filter::new(None),
+                };
+                Ok(vec![select.task_id(gw, cfg).await?])
+            }
+        }
+    }
+}
+
+impl From<TaskID> for TasksOrInteractive {
+    fn from(id: TaskID) -> Self {
+        Self::new(vec![id], None)
+    }
+}
+
 impl From<TaskID> for TaskOrInteractive {
     fn from(id: TaskID) -> Self {
         Self::with_id(id)
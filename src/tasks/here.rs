@@ -0,0 +1,78 @@
+//! Finds out which git repository a task is added from, so notes taken while coding end up in the
+//! project of the repository and remember where they came from.
+use std::{path::Path, process};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+
+/// Here is the git repository of the current directory.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Here {
+    /// The name of the directory of the repository.
+    pub repo: String,
+    /// The checked out branch, if it is not detached.
+    pub branch: Option<String>,
+}
+
+impl Here {
+    /// Looks up the repository of the current directory.
+    pub fn detect() -> Result<Here> {
+        let root = git(&["rev-parse", "--show-toplevel"])?
+            .ok_or_else(|| eyre!("--here only works inside of a git repository"))?;
+        let repo = Path::new(&root)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| eyre!("unable to name the git repository at {}", root))?;
+        // Unlike rev-parse this also knows the branch of a repository without commits.
+        let branch = git(&["symbolic-ref", "--short", "HEAD"])?;
+        Ok(Here { repo, branch })
+    }
+
+    /// Describes where the task was added, and optionally the file and line it is about.
+    pub fn describe(&self, at: Option<&str>) -> String {
+        let mut description = format!("From {}", self.repo);
+        if let Some(branch) = &self.branch {
+            description.push_str(&format!(" on {}", branch));
+        }
+        if let Some(at) = at {
+            description.push_str(&format!(" at {}", at));
+        }
+        description
+    }
+}
+
+/// Runs git and returns its trimmed output, or nothing if it failed.
+fn git(args: &[&str]) -> Result<Option<String>> {
+    let output = process::Command::new("git")
+        .args(args)
+        .stderr(process::Stdio::null())
+        .output()
+        .wrap_err("unable to run git")?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(stdout).filter(|s| output.status.success() && !s.is_empty()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn describes() {
+        let here = Here {
+            repo: "doist".to_string(),
+            branch: Some("main".to_string()),
+        };
+        assert_eq!(here.describe(None), "From doist on main");
+        assert_eq!(
+            here.describe(Some("src/main.rs:42")),
+            "From doist on main at src/main.rs:42"
+        );
+        let detached = Here {
+            branch: None,
+            ..here
+        };
+        assert_eq!(detached.describe(None), "From doist");
+    }
+}
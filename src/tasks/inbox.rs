@@ -0,0 +1,139 @@
+//! Triages the tasks of the Inbox, deciding for each where it goes, GTD style.
+use color_eyre::{eyre::eyre, Result};
+use dialoguer::console::Term;
+
+use crate::{
+    api::{
+        rest::{Gateway, MoveTarget, ProjectID, Task, TaskDue, UpdateTask},
+        tree::TreeFlattenExt,
+    },
+    command::{Command, Context},
+    config::Config,
+    interactive,
+};
+
+use super::state::State;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Walks through the tasks one by one, offering to move, schedule, prioritize or close each.
+    #[arg(short = 'i', long = "interactive")]
+    interactive: bool,
+}
+
+/// The actions that are offered for every task, in this order.
+const ACTIONS: [&str; 5] = ["Next", "Move", "Schedule", "Prioritize", "Close"];
+
+/// Counts what happened during a triage.
+#[derive(Debug, Default)]
+struct Triage {
+    moved: usize,
+    scheduled: usize,
+    prioritized: usize,
+    closed: usize,
+}
+
+/// Lists the tasks of the Inbox, or triages them one by one.
+pub async fn inbox(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = State::fetch_tree(Some("#Inbox"), gw, cfg).await?;
+    let inbox = state
+        .projects
+        .values()
+        .find(|p| p.is_inbox_project)
+        .map(|p| p.id.clone())
+        .ok_or_else(|| eyre!("there is no Inbox project"))?;
+    let state = state.filter(|t| t.project_id == inbox);
+    if state.tasks.is_empty() {
+        println!("the Inbox is empty");
+        return Ok(());
+    }
+    if !params.interactive {
+        for task in state.tasks.flat_tree() {
+            println!("{}", state.table_task_in_project(task));
+        }
+        return Ok(());
+    }
+    if !Term::stderr().is_term() {
+        return Err(eyre!("triaging the Inbox needs an interactive terminal"));
+    }
+    let mut triage = Triage::default();
+    'tasks: for task in &state.tasks {
+        loop {
+            let prompt = state.table_task_in_project(task).to_string();
+            let Some(action) = interactive::select(&prompt, &ACTIONS)? else {
+                break 'tasks;
+            };
+            match action {
+                1 if triage_move(task, &inbox, &state, gw).await? => {
+                    triage.moved += 1;
+                    continue 'tasks;
+                }
+                2 => {
+                    if let Some(due) = interactive::input_optional("Due", None)? {
+                        let update = UpdateTask {
+                            due: Some(TaskDue::String(due)),
+                            ..Default::default()
+                        };
+                        gw.update(&task.id, &update).await?;
+                        triage.scheduled += 1;
+                    }
+                }
+                3 => {
+                    if let Some(priority) = interactive::input_priority()? {
+                        let update = UpdateTask {
+                            priority: Some(priority),
+                            ..Default::default()
+                        };
+                        gw.update(&task.id, &update).await?;
+                        triage.prioritized += 1;
+                    }
+                }
+                4 => {
+                    gw.close(&task.id).await?;
+                    triage.closed += 1;
+                    continue 'tasks;
+                }
+                0 => continue 'tasks,
+                _ => {}
+            }
+        }
+    }
+    println!(
+        "moved {}, scheduled {}, prioritized {} and closed {} tasks",
+        triage.moved, triage.scheduled, triage.prioritized, triage.closed
+    );
+    Ok(())
+}
+
+/// Asks for a project other than the Inbox and a section in it, and moves the task there. Returns
+/// false if no project was selected.
+async fn triage_move(
+    task: &Task,
+    inbox: &ProjectID,
+    state: &State<'_>,
+    gw: &Gateway,
+) -> Result<bool> {
+    let mut projects = state
+        .projects
+        .values()
+        .filter(|p| p.id != *inbox)
+        .cloned()
+        .collect::<Vec<_>>();
+    projects.sort_by_key(|p| p.order);
+    let sections = state.sections.values().cloned().collect::<Vec<_>>();
+    let Some((project, section)) = interactive::input_project(&projects, &sections)? else {
+        return Ok(false);
+    };
+    let target = match section {
+        Some(section) => MoveTarget::Section(section),
+        None => MoveTarget::Project(project),
+    };
+    gw.move_task(&task.id, &target).await?;
+    Ok(true)
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        inbox(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -2,17 +2,23 @@ use std::ops::Not;
 
 use crate::{
     api::{
-        rest::{Gateway, Project, Section, Task},
+        rest::{Gateway, Project, Section, Task, TaskID},
         tree::Tree,
     },
+    cancel,
+    command::{Command, Context},
     config::Config,
-    interactive, labels,
+    filters, interactive, labels, output,
     tasks::{
-        close, edit, filter,
+        close, defer, edit, filter, pin, reopen,
         state::{State, TaskMenu},
+        watch,
     },
 };
-use color_eyre::{eyre::WrapErr, Result};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
 use owo_colors::OwoColorize;
 use strum::{Display, FromRepr, VariantNames};
 
@@ -22,6 +28,9 @@ use super::create;
 pub struct Params {
     #[clap(flatten)]
     filter: filter::Filter,
+    /// Lists the tasks of the saved filter with this name, see `doist filters`.
+    #[arg(long = "saved", conflicts_with = "filter")]
+    saved: Option<String>,
     /// Disables interactive mode and simply displays the list.
     #[arg(short = 'n', long = "nointeractive")]
     nointeractive: bool,
@@ -39,11 +48,32 @@ pub struct Params {
     /// can be done until the program is exited from.
     #[arg(short = 'i', long = "interactive")]
     continuous: bool,
+    #[clap(flatten)]
+    output: output::Json,
+    #[clap(flatten)]
+    records: output::Records,
+    #[clap(flatten)]
+    watch: watch::Options,
 }
 
 /// List lists the tasks of the current user accessing the gateway with the given filter.
-pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    if params.continuous && !params.nointeractive {
+pub async fn list(mut params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    if let Some(saved) = params.saved.take() {
+        let filters = gw.filters().await?;
+        let filter = filters::find(&filters, &saved)?;
+        params.filter.set_filter(Some(&filter.query));
+    }
+    if params.watch.watch {
+        // Every refresh fetches everything again, or changes to projects, sections and labels
+        // would never show.
+        return watch::watch(&params.watch, gw, || async {
+            gw.refresh().await;
+            fetch_list(&params, gw, cfg).await
+        })
+        .await;
+    }
+    let nointeractive = params.nointeractive || params.output.json || params.records.null;
+    if params.continuous && !nointeractive {
         return list_interactive(params, gw, cfg).await;
     }
     match list_action(&params, gw, cfg).await {
@@ -52,18 +82,34 @@ pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     }
 }
 
-async fn list_action(params: &Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+/// Fetches the tasks that the params filter for.
+async fn fetch_list<'a>(params: &Params, gw: &Gateway, cfg: &'a Config) -> Result<State<'a>> {
     let state = if params.expand {
         State::fetch_full_tree(Some(&params.filter.select(cfg)), gw, cfg).await
     } else {
         State::fetch_tree(Some(&params.filter.select(cfg)), gw, cfg).await
     }?;
-    let state = filter_list(state, params).await?;
-    if params.nointeractive {
-        list_tasks(&state.tasks, &state);
+    let state = defer::hide(state, gw, cfg).await?;
+    filter_list(state, params).await
+}
+
+async fn list_action(params: &Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = fetch_list(params, gw, cfg).await?;
+    if params.output.json {
+        let mut tasks = Vec::new();
+        flatten_tasks(&state.tasks, &mut tasks);
+        params.output.print(&tasks)?;
+    } else if params.nointeractive || params.records.null {
+        // Pins are left out, so that the records are only the tasks that match the filter.
+        list_tasks(&state.tasks, &state, &params.records);
     } else {
+        for line in pin::lines(gw, cfg).await {
+            println!("{}", line);
+        }
         match state.select_task()? {
-            Some(task) => select_task_option(task, &state, gw).await?,
+            Some(task) => {
+                select_task_option(task, &state, gw).await?;
+            }
             None => {
                 println!("No selection was made");
             }
@@ -74,13 +120,15 @@ async fn list_action(params: &Params, gw: &Gateway, cfg: &Config) -> Result<()>
 
 async fn list_interactive(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let mut params = params;
-    loop {
-        match list_interactive_action(&mut params, gw, cfg).await {
+    let mut closed = None;
+    while !cancel::is_cancelled() {
+        match list_interactive_action(&mut params, &mut closed, gw, cfg).await {
             Ok(ListAction::Cancel) => return Ok(()),
             Ok(_) => {}
             Err(e) => return Err(e),
         }
     }
+    Ok(())
 }
 
 /// Describes the action the user made when calling [`list_interactive_action`].
@@ -89,8 +137,12 @@ pub enum ListAction {
     Cancel,
 }
 
+/// The last task that was closed in the interactive mode, with its content.
+type Closed = Option<(TaskID, String)>;
+
 async fn list_interactive_action(
     params: &mut Params,
+    closed: &mut Closed,
     gw: &Gateway,
     cfg: &Config,
 ) -> Result<ListAction> {
@@ -102,26 +154,30 @@ async fn list_interactive_action(
     }?;
 
     let state = filter_list(state, params).await?;
+    for line in pin::lines(gw, cfg).await {
+        println!("{}", line);
+    }
     match state.select_or_menu()? {
         TaskMenu::Menu => {
-            match interactive::select(
-                "Select Action:",
-                &[
-                    "Create Task...",
-                    &format!(
-                        "Set Filter{}...",
-                        filter
-                            .is_empty()
-                            .not()
-                            .then(|| format!(" ({})", filter.yellow()))
-                            .unwrap_or_default()
-                    ),
-                    "| Show All Tasks",
-                    "| Inbox",
-                    "| Upcoming",
-                    "| Default Filter",
-                ],
-            )? {
+            let mut actions = vec![
+                "Create Task...".to_string(),
+                format!(
+                    "Set Filter{}...",
+                    if filter.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", filter.yellow())
+                    }
+                ),
+                "| Show All Tasks".to_string(),
+                "| Inbox".to_string(),
+                "| Upcoming".to_string(),
+                "| Default Filter".to_string(),
+            ];
+            if let Some((_, content)) = closed {
+                actions.push(format!("Reopen \"{}\"", content));
+            }
+            match interactive::select("Select Action:", &actions)? {
                 // TODO change this once we have async closures and can iterate over a Vec<(str, async Fn)>
                 Some(0) => create::create(create::Params {}, gw, cfg).await?,
                 Some(1) => {
@@ -134,13 +190,20 @@ async fn list_interactive_action(
                 Some(3) => params.filter.set_filter(Some("#inbox")),
                 Some(4) => params.filter.set_filter(Some(&cfg.default_filter)),
                 Some(5) => params.filter.set_filter(Some("(today | overdue)")),
+                Some(6) => {
+                    if let Some((id, _)) = closed.take() {
+                        reopen::reopen_task(&id, gw).await?;
+                    }
+                }
                 Some(_) => unreachable!(),
                 None => {}
             };
             Ok(ListAction::Action)
         }
         TaskMenu::Select(task) => {
-            select_task_option(task, &state, gw).await?;
+            if let Some(id) = select_task_option(task, &state, gw).await? {
+                *closed = Some((id, task.content.clone()));
+            }
             Ok(ListAction::Action)
         }
         TaskMenu::None => {
@@ -190,12 +253,37 @@ async fn filter_list<'a>(state: State<'a>, params: &'_ Params) -> Result<State<'
     Ok(state)
 }
 
-fn list_tasks<'a>(tasks: &'a [Tree<Task>], state: &'a State) {
+fn list_tasks<'a>(tasks: &'a [Tree<Task>], state: &'a State, records: &output::Records) {
+    let mut flat = Vec::new();
+    flatten_tasks(tasks, &mut flat);
+    let count = flat.len();
+    print_tasks(tasks, state, records, &mut 0, count);
+}
+
+/// Prints the tasks with their subtasks below them, counting their position in the list.
+fn print_tasks<'a>(
+    tasks: &'a [Tree<Task>],
+    state: &'a State,
+    records: &output::Records,
+    index: &mut usize,
+    count: usize,
+) {
     let mut tasks = tasks.to_vec();
     tasks.sort();
     for task in tasks.iter() {
-        println!("{}", state.table_task(task));
-        list_tasks(&task.subitems, state);
+        records.print(output::position(*index, count, state.table_task(task)));
+        *index += 1;
+        print_tasks(&task.subitems, state, records, index, count);
+    }
+}
+
+/// Collects the tasks in the same order as [`list_tasks`] shows them.
+fn flatten_tasks<'a>(tasks: &'a [Tree<Task>], out: &mut Vec<&'a Task>) {
+    let mut tasks = tasks.iter().collect::<Vec<_>>();
+    tasks.sort();
+    for task in tasks {
+        out.push(&task.item);
+        flatten_tasks(&task.subitems, out);
     }
 }
 
@@ -207,46 +295,54 @@ enum TaskOptions {
     Quit,
 }
 
-async fn select_task_option<'a, 'b>(
+/// Shows the options for a task and runs the selected one. Returns the ID of the task if it was
+/// closed for good, so it can be reopened.
+async fn select_task_option<'a>(
     task: &'a Tree<Task>,
     state: &'a State<'_>,
-    gw: &'b Gateway,
-) -> Result<()> {
+    gw: &Gateway,
+) -> Result<Option<TaskID>> {
     println!("{}", state.full_task(task));
     let result = match make_selection(TaskOptions::VARIANTS)? {
         Some(index) => TaskOptions::from_repr(index).unwrap(),
         None => {
             println!("No selection made");
-            return Ok(());
+            return Ok(None);
         }
     };
+    // Closing a recurring task only moves it to its next date, there is nothing to reopen.
+    let recurring = task.due.as_ref().is_some_and(|d| d.is_recurring);
     match result {
         TaskOptions::Close => {
             close::close(
                 close::Params {
-                    task: task.id.clone().into(),
+                    tasks: task.id.clone().into(),
                     complete: false,
                 },
                 gw,
                 state.config,
             )
-            .await?
+            .await?;
+            if !recurring {
+                return Ok(Some(task.id.clone()));
+            }
         }
         TaskOptions::Complete => {
             close::close(
                 close::Params {
-                    task: task.id.clone().into(),
+                    tasks: task.id.clone().into(),
                     complete: true,
                 },
                 gw,
                 state.config,
             )
-            .await?
+            .await?;
+            return Ok(Some(task.id.clone()));
         }
         TaskOptions::Edit => edit_task(task, gw, state.config).await?,
         TaskOptions::Quit => {}
     };
-    Ok(())
+    Ok(None)
 }
 
 #[derive(Display, FromRepr, VariantNames)]
@@ -272,13 +368,18 @@ async fn edit_task(task: &Tree<Task>, gw: &Gateway, cfg: &Config) -> Result<()>
     match result {
         EditOptions::Quit => {}
         EditOptions::Priority => {
-            let selection = dialoguer::Select::new()
-                .with_prompt("Set priority")
-                .items(&["1 - Urgent", "2 - Very High", "3 - High", "4 - Normal"])
-                .default((4 - task.priority as u8) as usize)
-                .interact()
-                .wrap_err("Bad user input")?
-                + 1;
+            let items = ["1 - Urgent", "2 - Very High", "3 - High", "4 - Normal"];
+            let selection = match output::a11y() {
+                true => interactive::select("Set priority", &items)?
+                    .ok_or_else(|| eyre!("no priority selected"))?,
+                false => dialoguer::Select::new()
+                    .with_prompt("Set priority")
+                    .items(&items)
+                    .default((4 - task.priority as u8) as usize)
+                    .interact()
+                    .map_err(cancel::interrupted)
+                    .wrap_err("Bad user input")?,
+            } + 1;
             let mut params = edit::Params::new(task.id.clone());
             params.priority = Some(selection.try_into()?);
             edit::edit(params, gw, cfg).await?;
@@ -287,6 +388,7 @@ async fn edit_task(task: &Tree<Task>, gw: &Gateway, cfg: &Config) -> Result<()>
             let text = dialoguer::Input::new()
                 .with_prompt("New value")
                 .interact_text()
+                .map_err(cancel::interrupted)
                 .wrap_err("Bad user input")?;
             let mut params = edit::Params::new(task.id.clone());
             match result {
@@ -309,9 +411,19 @@ async fn edit_task(task: &Tree<Task>, gw: &Gateway, cfg: &Config) -> Result<()>
 }
 
 fn make_selection<T: ToString + std::fmt::Display>(variants: &[T]) -> Result<Option<usize>> {
+    if output::a11y() {
+        return interactive::select("Select an option", variants);
+    }
     dialoguer::FuzzySelect::with_theme(&dialoguer::theme::ColorfulTheme::default())
         .items(variants)
         .default(0)
         .interact_opt()
+        .map_err(cancel::interrupted)
         .wrap_err("Unable to make a selection")
 }
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,209 @@
+//! Merges duplicate tasks into one, keeping what each of them knew.
+use chrono::{NaiveDateTime, Utc};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{
+        rest::{CreateComment, DueDate, Gateway, Task, TaskDue, TaskID, ThreadID, UpdateTask},
+        tree::Tree,
+    },
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{bulk, state::State};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The IDs of the tasks to merge, separated by spaces or commas. The first task is kept, and
+    /// the others are merged into it and closed.
+    #[arg(value_delimiter = ',', num_args = 2.., required = true)]
+    ids: Vec<TaskID>,
+}
+
+/// Returns when the task is due, so the earliest due date can be found. Tasks without an exact
+/// time count as due at the start of their day.
+fn due_at(due: &DueDate) -> NaiveDateTime {
+    match &due.exact {
+        Some(exact) => exact.datetime.naive_local(),
+        None => due.date.and_time(Default::default()),
+    }
+}
+
+/// Returns the update that gives the kept task everything that the duplicates knew.
+fn merged(keep: &Task, duplicates: &[&Task]) -> UpdateTask {
+    let mut update = UpdateTask::default();
+    let descriptions = std::iter::once(keep)
+        .chain(duplicates.iter().copied())
+        .map(|t| t.description.trim())
+        .filter(|d| !d.is_empty())
+        .collect::<Vec<_>>();
+    let description = descriptions.join("\n\n");
+    if description != keep.description.trim() {
+        update.description = Some(description);
+    }
+    let mut labels = keep.labels.clone();
+    for label in duplicates.iter().flat_map(|t| &t.labels) {
+        if !labels.contains(label) {
+            labels.push(label.clone());
+        }
+    }
+    if labels != keep.labels {
+        update.labels = Some(labels);
+    }
+    let earliest = duplicates
+        .iter()
+        .filter_map(|t| t.due.as_ref())
+        .min_by_key(|due| due_at(due));
+    if let Some(due) = earliest {
+        if keep
+            .due
+            .as_ref()
+            .is_none_or(|kept| due_at(due) < due_at(kept))
+        {
+            update.due = Some(match (&due.exact, due.is_recurring) {
+                (_, true) => TaskDue::String(due.string.clone()),
+                (Some(exact), false) => TaskDue::DateTime(exact.datetime.with_timezone(&Utc)),
+                (None, false) => TaskDue::Date(due.date.to_string()),
+            });
+        }
+    }
+    update
+}
+
+/// Merges the tasks into the first one, copying the comments of the others to it, and closes the
+/// others with a comment that points at the kept task.
+pub async fn merge(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = State::fetch_ids(&params.ids, gw, cfg).await?;
+    let mut tasks = Vec::new();
+    for id in &params.ids {
+        let task = state
+            .task(id)
+            .ok_or_else(|| eyre!("task {} does not exist or is completed", id))?;
+        if tasks.iter().any(|t: &&Tree<Task>| t.id == task.id) {
+            return Err(eyre!("task {} is given more than once", id));
+        }
+        tasks.push(task);
+    }
+    let (keep, duplicates) = tasks
+        .split_first()
+        .expect("at least two tasks are required");
+    if let Some(parent) = duplicates.iter().find(|d| d.find(&keep.id).is_some()) {
+        return Err(eyre!(
+            "task {} can't be merged into its subtask {}, as closing it closes the subtask",
+            parent.id,
+            keep.id
+        ));
+    }
+    let duplicates = duplicates.iter().map(|t| &t.item).collect::<Vec<_>>();
+    let update = merged(keep, &duplicates);
+    gw.update(&keep.id, &update)
+        .await
+        .wrap_err("unable to update the merged task")?;
+
+    for duplicate in duplicates.iter().filter(|t| t.comment_count > 0) {
+        for comment in gw.task_comments(&duplicate.id).await? {
+            gw.create_comment(&CreateComment {
+                thread: ThreadID::Task {
+                    task_id: keep.id.clone(),
+                },
+                content: format!(
+                    "From merged task \"{}\" ({}):\n\n{}",
+                    duplicate.content, duplicate.url, comment.content
+                ),
+                attachment: comment.attachment,
+            })
+            .await?;
+        }
+    }
+    let ids = duplicates.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+    let duplicates = &duplicates;
+    bulk::run("merge", &ids, |id| async move {
+        let duplicate = duplicates
+            .iter()
+            .find(|t| &t.id == id)
+            .expect("duplicate is among the tasks");
+        gw.create_comment(&CreateComment {
+            thread: ThreadID::Task {
+                task_id: id.clone(),
+            },
+            content: format!("Merged into \"{}\" ({})", keep.content, keep.url),
+            attachment: None,
+        })
+        .await?;
+        // Closing a recurring task would only move it to its next date.
+        match duplicate.due.as_ref().is_some_and(|d| d.is_recurring) {
+            true => gw.complete(id).await,
+            false => gw.close(id).await,
+        }
+    })
+    .await?;
+
+    let kept = Tree::new(gw.task(&keep.id).await?);
+    for id in &ids {
+        println!(
+            "closed task {}",
+            id.if_supports_color(Stream::Stdout, |text| text.bright_red())
+        );
+    }
+    println!("merged into: {}", state.table_task(&kept));
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        merge(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task(id: &str, description: &str, labels: &[&str], due: Option<&str>) -> Task {
+        let mut task: Task = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "project_id": "1",
+            "content": id,
+            "description": description,
+            "labels": labels,
+            "priority": 1,
+            "order": 1,
+            "is_completed": false,
+            "comment_count": 0,
+            "created_at": "2022-08-01T00:00:00Z",
+            "creator_id": "1",
+            "url": format!("https://todoist.com/showTask?id={}", id),
+        }))
+        .unwrap();
+        task.due = due.map(|date| DueDate {
+            string: date.to_string(),
+            date: date.parse().unwrap(),
+            is_recurring: false,
+            exact: None,
+        });
+        task
+    }
+
+    #[test]
+    fn merges_fields() {
+        let keep = task("1", "First", &["a"], Some("2022-09-10"));
+        let second = task("2", "", &["b", "a"], Some("2022-09-01"));
+        let third = task("3", "Third", &["c"], None);
+        let update = merged(&keep, &[&second, &third]);
+        assert_eq!(update.description.as_deref(), Some("First\n\nThird"));
+        assert_eq!(
+            update.labels,
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        assert!(matches!(update.due, Some(TaskDue::Date(d)) if d == "2022-09-01"));
+
+        let update = merged(&second, &[&keep]);
+        assert_eq!(update.description.as_deref(), Some("First"));
+        assert!(update.due.is_none());
+    }
+}
@@ -1,13 +1,43 @@
 //! Controls things that work with [`crate::api::rest::Task`]s.
 pub mod add;
+pub mod agenda;
+mod assignee;
+pub mod balance;
+mod bulk;
+mod capacity;
+pub mod check;
 pub mod close;
 pub mod comment;
+pub mod comments;
+pub mod completed;
 pub mod create;
+pub mod defer;
+pub mod delete;
+pub mod done;
 pub mod edit;
 mod filter;
+mod here;
+pub mod inbox;
 pub mod list;
+pub mod merge;
+pub mod move_task;
+pub mod note;
+pub mod pick;
+pub mod pin;
+pub mod postpone;
 mod priority;
+pub mod quick;
+pub mod reopen;
+pub mod search;
+mod smart_due;
+pub mod someday;
+pub mod split;
 mod state;
+mod template;
+pub mod today;
+pub mod undo;
+pub mod upcoming;
 pub mod view;
+mod watch;
 
 pub use priority::*;
@@ -0,0 +1,113 @@
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, MoveTarget, Project, Section, TaskID},
+    },
+    command::{Command, Context},
+    config::Config,
+    interactive,
+};
+
+use super::{
+    bulk,
+    filter::TasksOrInteractive,
+    undo::{self, Operation},
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    tasks: TasksOrInteractive,
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    /// The section to move to. Only sections of the project are considered if one is given.
+    #[clap(flatten)]
+    section: interactive::Selection<Section>,
+    /// Moves the tasks under the task with this ID, as its subtasks.
+    #[arg(
+        long = "parent",
+        conflicts_with_all = ["project", "project_id", "section", "section_id"]
+    )]
+    parent: Option<TaskID>,
+}
+
+/// Moves tasks to another project, section or parent task. Asks for the project if none of them
+/// is given.
+pub async fn move_tasks(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let ids = params
+        .tasks
+        .task_ids(gw, cfg)
+        .await
+        .wrap_err("no task selected for moving")?;
+    let (target, name) = match params.parent {
+        Some(parent) => {
+            if ids.contains(&parent) {
+                return Err(eyre!("a task can not be moved under itself"));
+            }
+            let name = format!("under task {}", parent);
+            (MoveTarget::Parent(parent), name)
+        }
+        None => target(&params.project, &params.section, gw).await?,
+    };
+    let snapshot = undo::snapshot(&ids, gw).await;
+    let target = &target;
+    let results = bulk::attempt(&ids, |id| async move {
+        gw.move_task(id, target).await?;
+        Ok(id)
+    })
+    .await;
+    let changed = bulk::succeeded(&results);
+    undo::record_snapshot(snapshot, &changed, |tasks| Operation::Move { tasks }, cfg);
+    let moved = bulk::finish("move", results)?;
+    for id in moved {
+        println!(
+            "moved task {} {}",
+            id.if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Returns the section or project to move to, with a description of it.
+async fn target(
+    project: &interactive::Selection<Project>,
+    section: &interactive::Selection<Section>,
+    gw: &Gateway,
+) -> Result<(MoveTarget, String)> {
+    let (projects, mut sections) = tokio::try_join!(gw.projects(), gw.sections())?;
+    let project = project.optional(&projects)?;
+    if let Some(project) = project {
+        sections.retain(|s| s.project_id == project.id);
+    }
+    if let Some(section) = section.optional(&sections)? {
+        let project = projects.iter().find(|p| p.id == section.project_id);
+        let name = match project {
+            Some(project) => format!("to {}/{}", Content(&project.name), Content(&section.name)),
+            None => format!("to {}", Content(&section.name)),
+        };
+        return Ok((MoveTarget::Section(section.id.clone()), name));
+    }
+    let project = match project {
+        Some(project) => project,
+        None => interactive::select("Select project to move to", &projects)?
+            .map(|i| &projects[i])
+            .ok_or_else(|| eyre!("no project selected for moving"))?,
+    };
+    Ok((
+        MoveTarget::Project(project.id.clone()),
+        format!("to {}", Content(&project.name)),
+    ))
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        move_tasks(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,88 @@
+//! Private notes on tasks that are only kept locally and never sent to the API.
+use std::{collections::HashMap, fmt::Write};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{content::Content, rest::TaskID},
+    config::Config,
+    store::Store,
+};
+
+/// The file in the [`Store`] that contains the notes of all tasks.
+pub(crate) const NOTES_FILE: &str = "notes.json";
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Use `list` to find out what ID your task has.
+    id: TaskID,
+    /// The text of the note. Shows the notes of the task if omitted.
+    text: Option<String>,
+    /// Removes all notes of the task.
+    #[arg(long = "clear", conflicts_with = "text")]
+    clear: bool,
+}
+
+/// Note is a single private note on a task.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Note {
+    /// When the note was written.
+    pub created_at: DateTime<Utc>,
+    /// The text of the note.
+    pub text: String,
+}
+
+/// Returns the notes of the task, oldest first.
+pub(crate) fn notes(store: &Store, id: &TaskID) -> Result<Vec<Note>> {
+    let mut notes: HashMap<TaskID, Vec<Note>> = store.load(NOTES_FILE)?;
+    Ok(notes.remove(id).unwrap_or_default())
+}
+
+/// Renders the notes as a list, to be displayed after the task they belong to.
+pub(crate) fn list(notes: &[Note]) -> String {
+    let mut out = String::from("Notes:");
+    for note in notes {
+        // Writing into a String can not fail.
+        let _ = write!(
+            out,
+            "\n-----\nWritten: {}\n{}",
+            note.created_at,
+            Content(&note.text)
+        );
+    }
+    out
+}
+
+/// Adds a note to a task, or shows or clears the notes of the task. Works without a token, as
+/// notes never reach the API.
+pub fn note(params: Params, cfg: &Config) -> Result<()> {
+    if params.text.as_ref().is_some_and(|t| t.trim().is_empty()) {
+        return Err(eyre!("note can not be empty"));
+    }
+    let store = cfg.store()?;
+    let mut notes: HashMap<TaskID, Vec<Note>> = store.load(NOTES_FILE)?;
+    if params.clear {
+        if notes.remove(&params.id).is_some() {
+            store.save(NOTES_FILE, &notes)?;
+        }
+        println!("removed notes of task {}", params.id);
+        return Ok(());
+    }
+    match params.text {
+        Some(text) => {
+            notes.entry(params.id.clone()).or_default().push(Note {
+                created_at: cfg.override_time.unwrap_or_else(Utc::now),
+                text,
+            });
+            store.save(NOTES_FILE, &notes)?;
+            println!("added note to task {}", params.id);
+        }
+        None => match notes.get(&params.id) {
+            Some(notes) => println!("{}", list(notes)),
+            None => println!("task {} has no notes", params.id),
+        },
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! Lets an external picker like fzf choose a task, so shell bindings can be built on top of doist.
+use std::{
+    io::Write,
+    process::{self, Stdio},
+};
+
+use chrono::Local;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+
+use crate::{
+    api::{
+        rest::{Gateway, Task},
+        tree::TreeFlattenExt,
+    },
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{filter::Filter, state::State};
+
+/// The picker that is used if the config has none.
+const DEFAULT_PICKER: [&str; 3] = ["fzf", "--delimiter=\t", "--with-nth=2.."];
+
+/// The field of the chosen task that is printed.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// The ID, to pass on to other commands.
+    Id,
+    /// The link to the task in the Todoist UI.
+    Url,
+    /// The name of the task.
+    Content,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    filter: Filter,
+    /// Which field of the chosen task is printed.
+    #[arg(long = "print", value_enum, default_value_t = Field::Id)]
+    print: Field,
+    /// Only prints the candidates, as one tab separated line per task that starts with its ID, to
+    /// pipe them into a picker yourself.
+    #[arg(long = "candidates")]
+    candidates: bool,
+}
+
+/// Prints the candidates, or runs the picker of the config on them and prints the chosen task.
+pub async fn pick(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = State::fetch_tree(Some(&params.filter.select(cfg)), gw, cfg).await?;
+    let tasks = state.tasks.flat_tree();
+    let candidates = tasks
+        .iter()
+        .map(|task| candidate(task, &state))
+        .collect::<Vec<_>>();
+    if params.candidates {
+        for candidate in candidates {
+            println!("{}", candidate);
+        }
+        return Ok(());
+    }
+    let chosen = run_picker(&cfg.picker, &candidates)?;
+    let id = chosen.split('\t').next().unwrap_or_default();
+    let task = tasks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| eyre!("the picker chose an unknown task {:?}", chosen))?;
+    match params.print {
+        Field::Id => println!("{}", task.id),
+        Field::Url => println!("{}", task.url),
+        Field::Content => println!("{}", task.content),
+    }
+    Ok(())
+}
+
+/// Formats the task as a line of tab separated ID, content, project and due date.
+fn candidate(task: &Task, state: &State) -> String {
+    let project = state
+        .projects
+        .get(&task.project_id)
+        .map(|p| format!("#{}", p.name))
+        .unwrap_or_default();
+    let due = match &task.due {
+        Some(due) => match &due.exact {
+            Some(exact) => exact
+                .datetime
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            None => due.date.to_string(),
+        },
+        None => String::new(),
+    };
+    format!(
+        "{}\t{}\t{}\t{}",
+        task.id,
+        task.content.replace(['\t', '\n'], " "),
+        project,
+        due
+    )
+}
+
+/// Runs the picker with the candidates on its input, and returns the line it chose.
+fn run_picker(picker: &[String], candidates: &[String]) -> Result<String> {
+    let picker = if picker.is_empty() {
+        DEFAULT_PICKER.iter().map(|p| p.to_string()).collect()
+    } else {
+        picker.to_vec()
+    };
+    let (program, args) = picker
+        .split_first()
+        .ok_or_else(|| eyre!("the picker command is empty"))?;
+    let mut child = process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .wrap_err_with(|| format!("unable to run the picker {}", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A picker may exit before reading all candidates, so a broken pipe is not an error.
+        let _ = stdin.write_all(candidates.join("\n").as_bytes());
+    }
+    let output = child
+        .wait_with_output()
+        .wrap_err_with(|| format!("unable to run the picker {}", program))?;
+    let chosen = String::from_utf8_lossy(&output.stdout);
+    match chosen.lines().next() {
+        Some(line) if output.status.success() && !line.is_empty() => Ok(line.to_string()),
+        _ => Err(eyre!("no task was picked")),
+    }
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        pick(self, &ctx.gw, &ctx.cfg).await
+    }
+}
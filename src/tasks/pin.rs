@@ -0,0 +1,130 @@
+//! Pinned tasks, which are kept in a small local list and always shown at the top of task lists.
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    api::{
+        rest::{Gateway, TaskID},
+        tree::TreeFlattenExt,
+    },
+    command::{Command, Context},
+    config::Config,
+    output,
+    store::Store,
+};
+
+use super::state::State;
+
+/// The file in the [`Store`] that lists the IDs of the pinned tasks.
+pub(crate) const PINS_FILE: &str = "pins.json";
+
+/// How many tasks can be pinned at once, so that pins stay a short list.
+const MAX_PINS: usize = 10;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Use `list` to find out what ID your task has.
+    id: TaskID,
+    /// Unpins the task instead.
+    #[arg(short = 'r', long = "remove")]
+    remove: bool,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct ListParams {}
+
+/// Returns the IDs of the pinned tasks, in the order they were pinned.
+pub(crate) fn pins(store: &Store) -> Result<Vec<TaskID>> {
+    store.load(PINS_FILE)
+}
+
+/// Fetches the pinned tasks that are still active, in the order they were pinned.
+pub(crate) async fn fetch<'a>(gw: &Gateway, cfg: &'a Config) -> Result<Option<State<'a>>> {
+    let pins = pins(&cfg.store()?)?;
+    if pins.is_empty() {
+        return Ok(None);
+    }
+    let mut state = State::fetch_ids(&pins, gw, cfg).await?;
+    state
+        .tasks
+        .sort_by_key(|t| pins.iter().position(|id| *id == t.id));
+    Ok(Some(state))
+}
+
+/// Renders the pinned tasks, one per line.
+pub(crate) fn render(state: &State) -> Vec<String> {
+    state
+        .tasks
+        .flat_tree()
+        .into_iter()
+        .map(|task| {
+            format!(
+                "{}{}",
+                output::symbol("[PIN] ", "📌 "),
+                state.table_task(task)
+            )
+        })
+        .collect()
+}
+
+/// Fetches and renders the pinned tasks for the top of a task list, if any are pinned. Failing to
+/// do so only warns, as the list itself can still be shown.
+pub(crate) async fn lines(gw: &Gateway, cfg: &Config) -> Vec<String> {
+    match fetch(gw, cfg).await {
+        Ok(state) => state.map(|state| render(&state)).unwrap_or_default(),
+        Err(e) => {
+            eprintln!("unable to show the pinned tasks: {:#}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Pins or unpins a task.
+pub async fn pin(params: Params, cfg: &Config) -> Result<()> {
+    let store = cfg.store()?;
+    let mut pins = pins(&store)?;
+    if params.remove {
+        pins.retain(|id| *id != params.id);
+        store.save(PINS_FILE, &pins)?;
+        println!("unpinned task {}", params.id);
+        return Ok(());
+    }
+    if pins.contains(&params.id) {
+        println!("task {} is already pinned", params.id);
+        return Ok(());
+    }
+    if pins.len() >= MAX_PINS {
+        return Err(eyre!(
+            "at most {} tasks can be pinned, unpin one with `doist pin --remove <id>` first",
+            MAX_PINS
+        ));
+    }
+    pins.push(params.id.clone());
+    store.save(PINS_FILE, &pins)?;
+    println!("pinned task {}", params.id);
+    Ok(())
+}
+
+/// Lists the pinned tasks.
+pub async fn list(_params: ListParams, gw: &Gateway, cfg: &Config) -> Result<()> {
+    match fetch(gw, cfg).await? {
+        Some(state) if !state.tasks.is_empty() => {
+            for line in render(&state) {
+                println!("{}", line);
+            }
+        }
+        _ => println!("no tasks are pinned"),
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        pin(self, &ctx.cfg).await
+    }
+}
+
+impl Command for ListParams {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,117 @@
+//! Pushes the due date of tasks back, like to tomorrow or by a few days.
+use color_eyre::Result;
+
+use crate::{
+    api,
+    command::{Command, Context},
+    holidays::Scheduling,
+    labels::LabelSelect,
+};
+
+use super::{edit, filter::TasksOrInteractive};
+
+/// The filter of the tasks that `--all-overdue` postpones.
+const OVERDUE: &str = "overdue";
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Several tasks can be postponed at once by
+    /// separating their IDs with commas. If omitted, will interactively select a task.
+    #[arg(value_delimiter = ',')]
+    ids: Vec<api::rest::TaskID>,
+    /// The new due date, either as Todoist understands it, like `next monday`, or as an offset
+    /// from today like `+2d`, `+1w` or `+1m`.
+    #[arg(short = 't', long = "to", default_value = "tomorrow")]
+    to: String,
+    /// Postpones all tasks that are overdue.
+    #[arg(long = "all-overdue", conflicts_with = "ids")]
+    all_overdue: bool,
+    #[clap(flatten)]
+    scheduling: Scheduling,
+}
+
+/// Turns offsets like `+2d` into due strings that Todoist understands. Days and weeks are left
+/// alone when scheduling around holidays, which resolves them locally.
+fn due_string(to: &str, scheduling: &Scheduling) -> String {
+    let offset = to.trim().strip_prefix('+').and_then(|offset| {
+        let unit = offset.chars().last()?;
+        let n = offset[..offset.len() - unit.len_utf8()]
+            .parse::<u32>()
+            .ok()?;
+        Some((n, unit))
+    });
+    let unit = match offset {
+        Some((_, 'd' | 'w')) if scheduling.business_days || scheduling.skip_holidays => None,
+        Some((n, 'd')) => Some((n, "day")),
+        Some((n, 'w')) => Some((n, "week")),
+        Some((n, 'm')) => Some((n, "month")),
+        _ => None,
+    };
+    match unit {
+        Some((1, unit)) => format!("in 1 {}", unit),
+        Some((n, unit)) => format!("in {} {}s", n, unit),
+        None => to.to_string(),
+    }
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        let ids = TasksOrInteractive::new(self.ids, self.all_overdue.then(|| OVERDUE.to_string()))
+            .task_ids(&ctx.gw, &ctx.cfg)
+            .await?;
+        // A new due date would replace the recurrence of recurring tasks, which end up due again
+        // by themselves anyway.
+        let (recurring, ids): (Vec<_>, Vec<_>) = ctx
+            .gw
+            .tasks_by_ids(&ids)
+            .await?
+            .into_iter()
+            .partition(|task| task.due.as_ref().is_some_and(|due| due.is_recurring));
+        for task in &recurring {
+            eprintln!(
+                "skipping task {}, postponing it would end its recurrence",
+                task.id
+            );
+        }
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let params = edit::Params {
+            ids: ids.into_iter().map(|task| task.id).collect(),
+            filter: None,
+            set: Vec::new(),
+            // Postponing all overdue tasks is what was asked for, so there is nothing to confirm.
+            yes: true,
+            name: None,
+            due: Some(due_string(&self.to, &self.scheduling)),
+            scheduling: self.scheduling,
+            desc: None,
+            priority: None,
+            labels: LabelSelect::default(),
+            assign: None,
+            quick: None,
+        };
+        edit::edit(params, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn translates_offsets() {
+        let none = Scheduling::default();
+        assert_eq!(due_string("+2d", &none), "in 2 days");
+        assert_eq!(due_string("+1w", &none), "in 1 week");
+        assert_eq!(due_string("+3m", &none), "in 3 months");
+        assert_eq!(due_string("next monday", &none), "next monday");
+        assert_eq!(due_string("+d", &none), "+d");
+        let business = Scheduling {
+            business_days: true,
+            ..Default::default()
+        };
+        assert_eq!(due_string("+2d", &business), "+2d");
+        assert_eq!(due_string("+1m", &business), "in 1 month");
+    }
+}
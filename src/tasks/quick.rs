@@ -0,0 +1,49 @@
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    api::{
+        rest::{Gateway, TableTask},
+        tree::Tree,
+    },
+    command::{Command, Context},
+    config::Config,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The whole task in one line, like in the quick add box of the Todoist apps. Todoist parses
+    /// the due date, `#project`, `@labels` and priority like `p2` out of it.
+    ///
+    /// Example: "Buy milk tomorrow #Groceries @errands p2"
+    text: String,
+}
+
+/// Sends the text to Todoist to be parsed into a task, and shows the task it became.
+pub async fn quick(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let task = Tree::new(gw.quick_add(&params.text).await?);
+    let (projects, sections, labels) = tokio::try_join!(gw.projects(), gw.sections(), gw.labels())?;
+    let mut table = TableTask::from_task(&task, cfg);
+    table.1 = projects.iter().find(|p| p.id == task.project_id);
+    table.2 = sections
+        .iter()
+        .find(|s| task.section_id.as_ref() == Some(&s.id));
+    table.3 = labels
+        .iter()
+        .filter(|l| task.labels.contains(&l.name))
+        .collect();
+    println!("created task: {}", table);
+    Ok(())
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.text.trim().is_empty() {
+            return Err(eyre!("task can not be empty"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        quick(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,59 @@
+use chrono::{Local, Utc};
+use color_eyre::{eyre::eyre, Result};
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::rest::{Gateway, TaskID},
+    command::{Command, Context},
+    config::Config,
+    duration::Since,
+    interactive,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The ID of the closed task. If omitted, a task that was completed recently can be selected.
+    id: Option<TaskID>,
+    /// How far back completed tasks are offered for selection, like `today`, `yesterday` or `2h`.
+    #[arg(long = "since", default_value = "today", conflicts_with = "id")]
+    since: Since,
+}
+
+/// Reopens a task that was closed by accident.
+pub async fn reopen(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let id = match params.id {
+        Some(id) => id,
+        None => {
+            let now = cfg.override_time.unwrap_or_else(Utc::now);
+            let since = params
+                .since
+                .resolve(now.with_timezone(&Local))
+                .with_timezone(&Utc);
+            let completed = gw.completed(&since).await?;
+            if completed.is_empty() {
+                return Err(eyre!("no tasks were completed since {}", since));
+            }
+            let items = completed.iter().map(|c| &c.content).collect::<Vec<_>>();
+            let index = interactive::select("Select task to reopen", &items)?
+                .ok_or_else(|| eyre!("no task selected for reopening"))?;
+            completed[index].task_id.clone()
+        }
+    };
+    reopen_task(&id, gw).await
+}
+
+/// Reopens the task and reports it.
+pub async fn reopen_task(id: &TaskID, gw: &Gateway) -> Result<()> {
+    gw.reopen(id).await?;
+    println!(
+        "reopened task {}",
+        id.if_supports_color(Stream::Stdout, |text| text.bright_green())
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        reopen(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,161 @@
+//! Searches the tasks for text, in their content through the Todoist filter and in their
+//! description locally, as filters can't search descriptions.
+use std::collections::HashSet;
+
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, Task},
+        tree::{Tree, TreeFlattenExt},
+    },
+    command::{Command, Context},
+    config::Config,
+    output,
+};
+
+use super::state::State;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The text to search for, ignoring case.
+    #[arg(required = true, num_args = 1..)]
+    text: Vec<String>,
+    #[clap(flatten)]
+    output: output::Json,
+}
+
+/// Escapes the characters that have a meaning in the Todoist filter language.
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut out, c| {
+            if matches!(c, '&' | '|' | '!' | '(' | ')' | ',' | '\\') {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        })
+}
+
+/// Returns the byte ranges of the text where the query matches, ignoring case.
+fn matches(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    if query.is_empty() {
+        return ranges;
+    }
+    let mut from = 0;
+    for (start, _) in text.char_indices() {
+        if start < from {
+            continue;
+        }
+        let mut rest = text[start..].char_indices();
+        let mut end = None;
+        let mut query_chars = query.chars();
+        loop {
+            let Some(q) = query_chars.next() else {
+                end = Some(rest.next().map_or(text.len(), |(i, _)| start + i));
+                break;
+            };
+            match rest.next() {
+                Some((_, c)) if c.to_lowercase().eq(q.to_lowercase()) => {}
+                _ => break,
+            }
+        }
+        if let Some(end) = end {
+            ranges.push((start, end));
+            from = end;
+        }
+    }
+    ranges
+}
+
+/// Highlights the parts of the text that match the query.
+fn highlight(text: &str, query: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in matches(text, query) {
+        let matched = &text[start..end];
+        out.push_str(&text[last..start]);
+        out.push_str(
+            &matched
+                .if_supports_color(Stream::Stdout, |t| t.black().on_yellow())
+                .to_string(),
+        );
+        last = end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Renders a task with its matching description lines under it.
+fn render(task: &Tree<Task>, state: &State, query: &str) -> String {
+    let mut out = format!(
+        "{} {} {}",
+        task.id
+            .if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+        task.priority,
+        highlight(&Content(&task.content).to_string(), query),
+    );
+    if let Some(project) = state.project(task) {
+        out.push_str(&format!(" [{}]", Content(&project.name)));
+    }
+    out.push('\n');
+    for line in Content(&task.description).to_string().lines() {
+        if !matches(line, query).is_empty() {
+            out.push_str(&format!("    {}\n", highlight(line.trim(), query)));
+        }
+    }
+    out
+}
+
+/// Searches the content of tasks with the Todoist filter and their descriptions locally.
+pub async fn search(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let query = params.text.join(" ");
+    let filter = format!("search: {}", escape(&query));
+    let (state, found) = tokio::try_join!(
+        State::fetch_tree(Some("all"), gw, cfg),
+        gw.tasks(Some(&filter))
+    )?;
+    let found = found.into_iter().map(|t| t.id).collect::<HashSet<_>>();
+    let tasks = state
+        .tasks
+        .flat_tree()
+        .into_iter()
+        .filter(|t| found.contains(&t.id) || !matches(&t.description, &query).is_empty())
+        .collect::<Vec<_>>();
+    if params.output.json {
+        return params
+            .output
+            .print(&tasks.iter().map(|t| &t.item).collect::<Vec<_>>());
+    }
+    if tasks.is_empty() {
+        println!("no tasks match \"{}\"", query);
+        return Ok(());
+    }
+    for task in tasks {
+        print!("{}", render(task, &state, &query));
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        search(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn finds_matches() {
+        assert_eq!(matches("Buy milk, MILK", "milk"), vec![(4, 8), (10, 14)]);
+        assert_eq!(matches("Grüße", "GRÜ"), vec![(0, 4)]);
+        assert_eq!(matches("aaa", "aa"), vec![(0, 2)]);
+        assert!(matches("Buy bread", "milk").is_empty());
+        assert_eq!(escape("a & (b)"), "a \\& \\(b\\)");
+    }
+}
@@ -0,0 +1,259 @@
+//! Recognizes due dates that are written into the name of a task, like the Todoist app does when
+//! typing "pay rent on the 1st".
+
+/// Words that introduce a due date, and are removed together with it.
+const CONNECTORS: [&str; 3] = ["on", "by", "due"];
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+const MONTHS: [&str; 24] = [
+    "january",
+    "jan",
+    "february",
+    "feb",
+    "march",
+    "mar",
+    "april",
+    "apr",
+    "may",
+    "june",
+    "jun",
+    "july",
+    "jul",
+    "august",
+    "aug",
+    "september",
+    "sep",
+    "sept",
+    "october",
+    "oct",
+    "november",
+    "nov",
+    "december",
+    "dec",
+];
+
+/// SmartDue is a due date that was found in the name of a task.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SmartDue {
+    /// The phrase that describes the due date, in a way the Todoist API understands.
+    pub due: String,
+    /// The name of the task without the due date.
+    pub content: String,
+}
+
+/// Word is a single whitespace separated word of the content, with its position.
+struct Word {
+    start: usize,
+    end: usize,
+    /// The word in lowercase, without punctuation around it.
+    norm: String,
+}
+
+/// Looks for the first phrase in the content that describes a due date. Returns nothing if there
+/// is none, or if the content would be empty without it.
+pub fn detect(content: &str) -> Option<SmartDue> {
+    let words = split(content);
+    let norms = words.iter().map(|w| w.norm.as_str()).collect::<Vec<_>>();
+    for i in 0..norms.len() {
+        let Some(len) = date(&norms[i..]) else {
+            continue;
+        };
+        // Without a connector, "the 2nd" is more likely to mean something else than a date.
+        if norms[i] == "the" && (i == 0 || !CONNECTORS.contains(&norms[i - 1])) {
+            continue;
+        }
+        let len = len + time(&norms[i + len..]);
+        let mut first = i;
+        while first > 0 && CONNECTORS.contains(&norms[first - 1]) {
+            first -= 1;
+        }
+        // "the" is needed to recognize "the 1st", but is not part of the date.
+        let due_start = if norms[i] == "the" { i + 1 } else { i };
+        let last = &words[i + len - 1];
+        let due = content[words[due_start].start..last.end]
+            .trim_end_matches(is_punctuation)
+            .to_string();
+        let rest = format!(
+            "{} {}",
+            content[..words[first].start].trim_end(),
+            content[last.end..].trim_start()
+        );
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return None;
+        }
+        return Some(SmartDue {
+            due,
+            content: rest.to_string(),
+        });
+    }
+    None
+}
+
+fn split(content: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in content.char_indices().chain([(content.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push(Word {
+                    start: s,
+                    end: i,
+                    norm: content[s..i].trim_matches(is_punctuation).to_lowercase(),
+                });
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    words
+}
+
+fn is_punctuation(c: char) -> bool {
+    matches!(c, ',' | '.' | ';' | ':' | '!' | '?' | '(' | ')')
+}
+
+/// Returns how many words at the start describe a date.
+fn date(words: &[&str]) -> Option<usize> {
+    let first = *words.first()?;
+    let second = words.get(1).copied().unwrap_or_default();
+    let third = words.get(2).copied().unwrap_or_default();
+    if ["today", "tonight", "tomorrow"].contains(&first)
+        || WEEKDAYS.contains(&first)
+        || chrono::NaiveDate::parse_from_str(first, "%Y-%m-%d").is_ok()
+    {
+        return Some(1);
+    }
+    if first == "next"
+        && (["week", "month", "year"].contains(&second) || WEEKDAYS.contains(&second))
+    {
+        return Some(2);
+    }
+    if first == "in"
+        && second.parse::<u32>().is_ok()
+        && ["day", "days", "week", "weeks", "month", "months"].contains(&third)
+    {
+        return Some(3);
+    }
+    if first == "the" && ordinal(second) {
+        if third == "of" && month(words.get(3).copied().unwrap_or_default()) {
+            return Some(4);
+        }
+        return Some(2);
+    }
+    if month(first) && day(second) {
+        return Some(2);
+    }
+    if day(first) && month(second) {
+        return Some(2);
+    }
+    None
+}
+
+/// Returns how many words at the start describe a time of the day, like `at 5pm`.
+fn time(words: &[&str]) -> usize {
+    match words {
+        ["at", t, ..] if clock(t) => 2,
+        [t, ..] if clock(t) && (t.ends_with("am") || t.ends_with("pm")) => 1,
+        _ => 0,
+    }
+}
+
+fn clock(word: &str) -> bool {
+    let word = word
+        .strip_suffix("am")
+        .or_else(|| word.strip_suffix("pm"))
+        .unwrap_or(word);
+    let (hours, minutes) = word.split_once(':').unwrap_or((word, "00"));
+    matches!(hours.parse::<u32>(), Ok(0..=23))
+        && minutes.len() == 2
+        && matches!(minutes.parse::<u32>(), Ok(0..=59))
+}
+
+fn month(word: &str) -> bool {
+    MONTHS.contains(&word)
+}
+
+fn day(word: &str) -> bool {
+    matches!(word.parse::<u32>(), Ok(1..=31)) || ordinal(word)
+}
+
+fn ordinal(word: &str) -> bool {
+    let Some(split) = word.find(|c: char| !c.is_ascii_digit()) else {
+        return false;
+    };
+    let (number, suffix) = word.split_at(split);
+    let Ok(number @ 1..=31) = number.parse::<u32>() else {
+        return false;
+    };
+    let expected = match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    suffix == expected
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn smart(due: &str, content: &str) -> Option<SmartDue> {
+        Some(SmartDue {
+            due: due.to_string(),
+            content: content.to_string(),
+        })
+    }
+
+    #[test]
+    fn detects_due_dates() {
+        assert_eq!(detect("Pay rent on the 1st"), smart("1st", "Pay rent"));
+        assert_eq!(
+            detect("Call mom tomorrow at 5pm, really"),
+            smart("tomorrow at 5pm", "Call mom really")
+        );
+        assert_eq!(
+            detect("Submit taxes by April 15th!"),
+            smart("April 15th", "Submit taxes")
+        );
+        assert_eq!(
+            detect("Renew passport in 3 weeks"),
+            smart("in 3 weeks", "Renew passport")
+        );
+        assert_eq!(
+            detect("next Monday 9am standup"),
+            smart("next Monday 9am", "standup")
+        );
+        assert_eq!(detect("Ship on 2022-09-01"), smart("2022-09-01", "Ship"));
+        assert_eq!(
+            detect("Party on the 3rd of September"),
+            smart("3rd of September", "Party")
+        );
+    }
+
+    #[test]
+    fn ignores_other_content() {
+        for content in [
+            "Buy some flowers",
+            "Read the 2nd chapter",
+            "Read chapter 1st",
+            "May the force be with you",
+            "Fix the 12rd bug",
+            "tomorrow",
+        ] {
+            assert_eq!(detect(content), None, "{}", content);
+        }
+    }
+}
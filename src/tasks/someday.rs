@@ -0,0 +1,181 @@
+//! Keeps a backlog of someday/maybe tasks without due dates from rotting, by regularly reviewing a
+//! random sample of it.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::{eyre::eyre, Result};
+use dialoguer::console::Term;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        rest::{Gateway, Project, Task, TaskDue, UpdateTask},
+        tree::TreeFlattenExt,
+    },
+    command::{Command, Context},
+    config::Config,
+    interactive,
+};
+
+use super::state::State;
+
+/// The file in the store that remembers when the backlog was last reviewed.
+pub(crate) const REVIEW_FILE: &str = "someday.json";
+
+/// How long the backlog can go without a review before it is pointed out.
+const REVIEW_INTERVAL: i64 = 7;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The label of the tasks in the backlog. Ignored if a project is given.
+    #[arg(short = 'l', long = "label", default_value = "someday")]
+    label: String,
+    /// Uses the tasks of this project as the backlog, instead of the ones with the label.
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    /// Reviews a random sample of the backlog, deciding for each task whether to promote it with
+    /// a due date, keep it, or delete it.
+    #[arg(short = 'r', long = "review")]
+    review: bool,
+    /// How many tasks are reviewed at once.
+    #[arg(long = "sample", default_value_t = 5, requires = "review")]
+    sample: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Review {
+    last: Option<DateTime<Utc>>,
+}
+
+/// The decisions that can be made for a task during a review, in the order they are offered.
+const DECISIONS: [&str; 3] = ["Keep", "Promote", "Delete"];
+
+/// Lists the tasks of the backlog, or reviews a sample of them.
+pub async fn someday(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params.project.optional(&projects)?;
+    let filter = match project {
+        Some(p) => format!("no date & #{}", p.name),
+        None => format!("no date & @{}", params.label),
+    };
+    let state = State::fetch_tree(Some(&filter), gw, cfg)
+        .await?
+        .filter(|t| {
+            t.due.is_none()
+                && match project {
+                    Some(p) => t.project_id == p.id,
+                    None => t.labels.contains(&params.label),
+                }
+        });
+    let store = cfg.store()?;
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let mut review: Review = store.load(REVIEW_FILE)?;
+    if !params.review {
+        for task in state.tasks.flat_tree() {
+            println!("{}", state.table_task(task));
+        }
+        match review.last {
+            Some(last) if now - last < Duration::days(REVIEW_INTERVAL) => {}
+            Some(last) => eprintln!(
+                "the backlog was last reviewed {} days ago, review it with --review",
+                (now - last).num_days()
+            ),
+            None => eprintln!("the backlog was never reviewed, review it with --review"),
+        }
+        return Ok(());
+    }
+    if !Term::stderr().is_term() {
+        return Err(eyre!("reviewing the backlog needs an interactive terminal"));
+    }
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    let (mut kept, mut promoted, mut deleted) = (0, 0, 0);
+    for task in sample(&state.tasks, params.sample, seed) {
+        let Some(decision) = interactive::select(&state.table_task(task).to_string(), &DECISIONS)?
+        else {
+            break;
+        };
+        match decision {
+            1 => {
+                let Some(due) = interactive::input_optional("Due", None)? else {
+                    kept += 1;
+                    continue;
+                };
+                promote(task, due, project.is_none().then_some(&params.label), gw).await?;
+                promoted += 1;
+            }
+            2 => {
+                gw.delete_task(&task.id).await?;
+                deleted += 1;
+            }
+            _ => kept += 1,
+        }
+    }
+    review.last = Some(now);
+    store.save(REVIEW_FILE, &review)?;
+    println!(
+        "promoted {}, kept {} and deleted {} tasks",
+        promoted, kept, deleted
+    );
+    Ok(())
+}
+
+/// Gives the task a due date, and takes it out of the backlog by removing the label, if any.
+async fn promote(task: &Task, due: String, label: Option<&String>, gw: &Gateway) -> Result<()> {
+    gw.update(
+        &task.id,
+        &UpdateTask {
+            due: Some(TaskDue::String(due)),
+            labels: label.map(|label| {
+                task.labels
+                    .iter()
+                    .filter(|l| *l != label)
+                    .cloned()
+                    .collect()
+            }),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Picks up to `n` random items, using a xorshift generator seeded with `seed`.
+fn sample<T>(items: &[T], n: usize, seed: u64) -> Vec<&T> {
+    let mut items = items.iter().collect::<Vec<_>>();
+    let mut state = seed | 1;
+    for i in (1..items.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        items.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+    items.truncate(n);
+    items
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        someday(self, &ctx.gw, &ctx.cfg).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn samples_items() {
+        let items = (0..20).collect::<Vec<_>>();
+        let picked = sample(&items, 5, 42);
+        assert_eq!(picked.len(), 5);
+        assert_eq!(picked.iter().collect::<HashSet<_>>().len(), 5);
+        assert_eq!(picked, sample(&items, 5, 42));
+        assert_ne!(picked, sample(&items, 5, 1234));
+        assert_eq!(sample(&items[..3], 5, 42).len(), 3);
+        assert!(sample::<u32>(&[], 5, 42).is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+//! Splits a big task into smaller ones, written line by line in the editor.
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+
+use crate::{
+    api::{
+        rest::{CreateTask, Gateway, Task},
+        tree::Tree,
+    },
+    command::{Command, Context},
+    config::Config,
+    editor,
+};
+
+use super::{close, filter};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    task: filter::TaskOrInteractive,
+    /// Creates the new tasks next to the task, in the same project, section and parent, instead of
+    /// as its subtasks.
+    #[arg(short = 's', long = "siblings")]
+    siblings: bool,
+    /// Closes the task once it is split. Only works with `--siblings`, as closing the task would
+    /// close its subtasks as well.
+    #[arg(short = 'c', long = "close", requires = "siblings")]
+    close: bool,
+}
+
+/// Returns the text the editor starts with, which is the task to rewrite into several lines.
+fn template(task: &Task, siblings: bool) -> String {
+    format!(
+        "{}\n\
+         # Write one task per line, each line becomes a {} of the task.\n\
+         # Lines starting with '#' are ignored, and an empty file cancels the split.\n",
+        task.content,
+        if siblings { "sibling" } else { "subtask" }
+    )
+}
+
+/// Returns the content of the tasks to create from the edited text.
+fn lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Splits the task into the tasks written in the editor.
+pub async fn split(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let (id, state) = params.task.task(gw, cfg).await?;
+    let task = match state.task(&id) {
+        Some(task) => task.item.clone(),
+        None => gw.task(&id).await?,
+    };
+    let contents = lines(&editor::edit(&template(&task, params.siblings))?);
+    if contents.is_empty() || contents == [task.content.clone()] {
+        return Err(eyre!("nothing to split the task into"));
+    }
+    for content in contents {
+        let create = match params.siblings {
+            true => CreateTask {
+                content,
+                project_id: Some(task.project_id.clone()),
+                section_id: task.section_id.clone(),
+                parent_id: task.parent_id.clone(),
+                ..Default::default()
+            },
+            false => CreateTask {
+                content,
+                parent_id: Some(task.id.clone()),
+                ..Default::default()
+            },
+        };
+        let created = gw
+            .create(&create)
+            .await
+            .wrap_err("unable to create the split task")?;
+        println!("created task: {}", state.table_task(&Tree::new(created)));
+    }
+    if params.close {
+        println!("{}", close::close_task(&task.id, gw).await?);
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        split(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -6,8 +6,8 @@ use owo_colors::OwoColorize;
 use crate::{
     api::{
         rest::{
-            FullTask, Gateway, Label, Project, ProjectID, Section, SectionID, TableTask, Task,
-            TaskID,
+            Collaborator, FullTask, Gateway, Label, Project, ProjectID, Section, SectionID,
+            TableTask, Task, TaskID, UserID,
         },
         tree::{Tree, TreeFlattenExt},
     },
@@ -21,6 +21,7 @@ pub struct State<'a> {
     pub projects: HashMap<ProjectID, Project>,
     pub sections: HashMap<SectionID, Section>,
     pub labels: HashMap<String, Label>,
+    pub collaborators: HashMap<UserID, Collaborator>,
     pub config: &'a Config,
 }
 
@@ -39,6 +40,44 @@ impl<'a> State<'a> {
     ) -> Result<State<'a>> {
         let (filtered_tasks, projects, sections, labels) =
             tokio::try_join!(gw.tasks(filter), gw.projects(), gw.sections(), gw.labels())?;
+        Self::build(filtered_tasks, projects, sections, labels, cfg)?
+            .with_collaborators(gw)
+            .await
+    }
+
+    /// Fetches the active tasks with the given IDs, as flat trees of just those tasks.
+    pub async fn fetch_ids(ids: &[TaskID], gw: &'_ Gateway, cfg: &'a Config) -> Result<State<'a>> {
+        let (tasks, projects, sections, labels) = tokio::try_join!(
+            gw.tasks_by_ids(ids),
+            gw.projects(),
+            gw.sections(),
+            gw.labels()
+        )?;
+        Self::build(tasks, projects, sections, labels, cfg)?
+            .with_collaborators(gw)
+            .await
+    }
+
+    /// Fetches a single task with the chain of its parents, whether it matches any filter or not.
+    pub async fn fetch_task(id: &TaskID, gw: &'_ Gateway, cfg: &'a Config) -> Result<State<'a>> {
+        let (task, projects, sections, labels) =
+            tokio::try_join!(gw.task(id), gw.projects(), gw.sections(), gw.labels())?;
+        let mut tasks = vec![task];
+        while let Some(parent) = tasks.last().and_then(|t| t.parent_id.clone()) {
+            tasks.push(gw.task(&parent).await?);
+        }
+        Self::build(tasks, projects, sections, labels, cfg)?
+            .with_collaborators(gw)
+            .await
+    }
+
+    fn build(
+        filtered_tasks: Vec<Task>,
+        projects: Vec<Project>,
+        sections: Vec<Section>,
+        labels: Vec<Label>,
+        cfg: &'a Config,
+    ) -> Result<State<'a>> {
         let projects = projects.into_iter().map(|p| (p.id.clone(), p)).collect();
         let sections = sections.into_iter().map(|s| (s.id.clone(), s)).collect();
         // We save by name so it works with the shared labels concept of todoist
@@ -49,9 +88,35 @@ impl<'a> State<'a> {
             projects,
             sections,
             labels,
+            collaborators: HashMap::new(),
             config: cfg,
         })
     }
+
+    /// Fetches the collaborators of the shared projects that have assigned tasks, so the
+    /// assignees can be shown by name.
+    async fn with_collaborators(mut self, gw: &'_ Gateway) -> Result<State<'a>> {
+        let mut shared = self
+            .tasks
+            .flat_tree()
+            .into_iter()
+            .filter(|t| t.assignee_id.is_some())
+            .filter_map(|t| self.projects.get(&t.project_id))
+            .filter(|p| p.is_shared)
+            .map(|p| &p.id)
+            .collect::<Vec<_>>();
+        shared.sort();
+        shared.dedup();
+        let collaborators =
+            futures::future::try_join_all(shared.into_iter().map(|id| gw.collaborators(id)))
+                .await?;
+        self.collaborators = collaborators
+            .into_iter()
+            .flatten()
+            .map(|c| (c.id.clone(), c))
+            .collect();
+        Ok(self)
+    }
     pub async fn fetch_full_tree(
         filter: Option<&'_ str>,
         gw: &'_ Gateway,
@@ -107,11 +172,12 @@ impl<'a> State<'a> {
             projects: self.projects,
             sections: self.sections,
             labels: self.labels,
+            collaborators: self.collaborators,
             config: self.config,
         }
     }
 
-    fn project<'s>(&'s self, task: &'s Tree<Task>) -> Option<&'s Project> {
+    pub fn project<'s>(&'s self, task: &'s Tree<Task>) -> Option<&'s Project> {
         self.projects.get(&task.project_id)
     }
 
@@ -128,12 +194,31 @@ impl<'a> State<'a> {
             .collect()
     }
 
+    fn assignee<'s>(&'s self, task: &'s Tree<Task>) -> Option<&'s Collaborator> {
+        task.assignee_id
+            .as_ref()
+            .and_then(|id| self.collaborators.get(id))
+    }
+
     pub fn table_task<'s>(&'s self, task: &'s Tree<Task>) -> TableTask<'s> {
         TableTask(
             task,
             self.project(task),
             self.section(task),
             self.labels(task),
+            self.assignee(task),
+            self.config,
+        )
+    }
+
+    /// Like [`State::table_task`], but leaves out the project, for lists that are grouped by it.
+    pub fn table_task_in_project<'s>(&'s self, task: &'s Tree<Task>) -> TableTask<'s> {
+        TableTask(
+            task,
+            None,
+            None,
+            self.labels(task),
+            self.assignee(task),
             self.config,
         )
     }
@@ -144,7 +229,19 @@ impl<'a> State<'a> {
             self.project(task),
             self.section(task),
             self.labels(task),
+            self.parents(task),
             self.config,
         )
     }
+
+    /// Returns the parents of the task, starting with the top one.
+    fn parents<'s>(&'s self, task: &'s Tree<Task>) -> Vec<&'s Task> {
+        let mut parents = Vec::new();
+        let mut parent = task.parent_id.as_ref().and_then(|id| self.task(id));
+        while let Some(task) = parent {
+            parents.insert(0, &task.item);
+            parent = task.parent_id.as_ref().and_then(|id| self.task(id));
+        }
+        parents
+    }
 }
@@ -0,0 +1,97 @@
+//! Fills in the templates of the config, so tasks like bug reports or meeting notes always have the
+//! same format.
+use chrono::{DateTime, Local};
+use color_eyre::{eyre::eyre, Result};
+
+use crate::config::Template;
+
+/// Expanded is the content and description of a task, after filling in a [`Template`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Expanded {
+    pub content: String,
+    pub description: Option<String>,
+}
+
+/// Fills in the placeholders of the template. Besides `{{name}}`, `{{date}}`, `{{time}}` and
+/// `{{weekday}}`, placeholders are taken from environment variables, like `{{branch}}` from
+/// `branch` or `BRANCH`.
+pub fn expand(template: &Template, name: &str, now: DateTime<Local>) -> Result<Expanded> {
+    expand_with(template, |placeholder| match placeholder {
+        "name" => Some(name.to_string()),
+        "date" => Some(now.format("%Y-%m-%d").to_string()),
+        "time" => Some(now.format("%H:%M").to_string()),
+        "weekday" => Some(now.format("%A").to_string()),
+        _ => std::env::var(placeholder)
+            .or_else(|_| std::env::var(placeholder.to_uppercase()))
+            .ok(),
+    })
+}
+
+fn expand_with<F: Fn(&str) -> Option<String>>(template: &Template, value: F) -> Result<Expanded> {
+    Ok(Expanded {
+        content: fill(template.content.as_deref().unwrap_or("{{name}}"), &value)?,
+        description: template
+            .description
+            .as_deref()
+            .map(|d| fill(d, &value))
+            .transpose()?,
+    })
+}
+
+fn fill<F: Fn(&str) -> Option<String>>(text: &str, value: &F) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| eyre!("unclosed placeholder in template {:?}", text))?;
+        let placeholder = rest[start + 2..start + end].trim();
+        out.push_str(&value(placeholder).ok_or_else(|| {
+            eyre!(
+                "unknown placeholder {{{{{}}}}} in template, it is not built in or set as an environment variable",
+                placeholder
+            )
+        })?);
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fills_placeholders() {
+        let template = Template {
+            content: Some("Bug: {{name}}".to_string()),
+            description: Some("Found on {{ date }} in {{branch}}.".to_string()),
+        };
+        let value = |p: &str| match p {
+            "name" => Some("crash on start".to_string()),
+            "date" => Some("2022-08-26".to_string()),
+            "branch" => Some("main".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_with(&template, value).unwrap(),
+            Expanded {
+                content: "Bug: crash on start".to_string(),
+                description: Some("Found on 2022-08-26 in main.".to_string()),
+            }
+        );
+        assert_eq!(
+            expand_with(&Template::default(), value).unwrap().content,
+            "crash on start"
+        );
+        for bad in ["{{missing}}", "{{name"] {
+            let template = Template {
+                content: Some(bad.to_string()),
+                description: None,
+            };
+            assert!(expand_with(&template, value).is_err(), "{}", bad);
+        }
+    }
+}
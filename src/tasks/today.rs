@@ -0,0 +1,122 @@
+//! Shows what is due today, grouped by project, with overdue tasks flagged.
+use chrono::{DateTime, Local, Utc};
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{content::Content, rest::Task, tree::Tree},
+    command::{Command, Context},
+    i18n::{self, t},
+    output,
+};
+
+use super::{capacity, defer, pin, state::State};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// The tasks that `today` shows, which the tasks are narrowed down to locally as well.
+const FILTER: &str = "today | overdue";
+
+/// Renders the tasks that are due today or earlier, grouped by project in the order of the
+/// projects.
+fn render(state: &State, now: &DateTime<Utc>) -> String {
+    let today = now.with_timezone(&Local).date_naive();
+    let mut tasks = state
+        .tasks
+        .iter()
+        .filter(|t| t.due.as_ref().is_some_and(|d| d.date <= today))
+        .collect::<Vec<_>>();
+    tasks.sort_by_key(|t| {
+        let project = state.project(t);
+        (
+            project.map(|p| !p.is_inbox_project),
+            project.map(|p| p.order),
+            t.project_id.clone(),
+        )
+    });
+    let overdue = tasks.iter().filter(|t| is_overdue(t, now)).count();
+    let mut out = format!(
+        "{}: {}",
+        i18n::date(today, &t!("today-date")).if_supports_color(Stream::Stdout, |t| t.bold()),
+        t!("tasks", count = tasks.len())
+    );
+    if overdue > 0 {
+        out.push_str(&format!(
+            ", {}",
+            t!("overdue-count", count = overdue)
+                .if_supports_color(Stream::Stdout, |t| t.bright_red())
+        ));
+    }
+    if let Some(load) = capacity::load(&tasks, state.config, true) {
+        out.push_str(&format!(", {}", load));
+    }
+    out.push('\n');
+    let count = capacity::total(&tasks);
+    let mut index = 0;
+    let mut project = None;
+    for task in tasks {
+        if project != Some(&task.project_id) {
+            project = Some(&task.project_id);
+            let name = state
+                .project(task)
+                .map(|p| Content(&p.name).to_string())
+                .unwrap_or_else(|| t!("unknown-project"));
+            out.push_str(&format!(
+                "\n{}\n",
+                name.if_supports_color(Stream::Stdout, |t| t.bold())
+            ));
+        }
+        render_tree(task, state, now, &mut index, count, &mut out);
+    }
+    out
+}
+
+/// Renders a task with its subtasks, marking the overdue ones with a red `!`.
+fn render_tree(
+    task: &Tree<Task>,
+    state: &State,
+    now: &DateTime<Utc>,
+    index: &mut usize,
+    count: usize,
+    out: &mut String,
+) {
+    let overdue = is_overdue(task, now);
+    let marker = match (overdue, output::a11y()) {
+        (true, true) => format!("{}: ", t!("overdue")),
+        (true, false) => "! "
+            .if_supports_color(Stream::Stdout, |t| t.bright_red())
+            .to_string(),
+        (false, true) => "".to_string(),
+        (false, false) => "  ".to_string(),
+    };
+    out.push_str(&format!(
+        "{}\n",
+        output::position(
+            *index,
+            count,
+            format!("{}{}", marker, state.table_task_in_project(task))
+        )
+    ));
+    *index += 1;
+    for subtask in &task.subitems {
+        render_tree(subtask, state, now, index, count, out);
+    }
+}
+
+fn is_overdue(task: &Task, now: &DateTime<Utc>) -> bool {
+    task.due.as_ref().is_some_and(|d| d.is_overdue(now))
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        let state = State::fetch_tree(Some(FILTER), &ctx.gw, &ctx.cfg).await?;
+        let state = defer::hide(state, &ctx.gw, &ctx.cfg).await?;
+        let now = ctx.cfg.override_time.unwrap_or_else(Utc::now);
+        for line in pin::lines(&ctx.gw, &ctx.cfg).await {
+            println!("{}", line);
+        }
+        print!("{}", render(&state, &now));
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+//! Remembers the last change that closed, deleted, edited or moved tasks, so `undo` can reverse
+//! it.
+use chrono::{DateTime, Local, Utc};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use owo_colors::{OwoColorize, Stream};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        rest::{CreateTask, Gateway, MoveTarget, Task, TaskDue, TaskID, UpdateTask},
+        tree::Tree,
+    },
+    command::{Command, Context},
+    config::Config,
+    history,
+};
+
+use super::bulk;
+
+/// The file in the [`Store`](crate::store::Store) that contains the last change.
+const UNDO_FILE: &str = "undo.json";
+
+/// Change is the last change that can be undone.
+#[derive(Debug, Serialize, Deserialize)]
+struct Change {
+    /// When the change was made.
+    timestamp: DateTime<Utc>,
+    /// The arguments of the command that made the change, without the name of the binary and with
+    /// secrets redacted.
+    args: Vec<String>,
+    operation: Operation,
+}
+
+/// Operation holds what is needed to reverse a change.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Operation {
+    /// The tasks that were closed, which are reopened.
+    Close { ids: Vec<TaskID> },
+    /// The tasks that were deleted, parents before their subtasks, which are created again.
+    Delete { tasks: Vec<Task> },
+    /// The tasks as they were before they were edited, whose fields are restored.
+    Edit { tasks: Vec<Task> },
+    /// The tasks as they were before they were moved, which are moved back.
+    Move { tasks: Vec<Task> },
+}
+
+/// Remembers the change as the one that `undo` reverses, replacing the one before. Failing to do
+/// so only warns, as the change itself matters more than undoing it.
+pub(crate) fn record(operation: Operation, cfg: &Config) {
+    let change = Change {
+        timestamp: Utc::now(),
+        args: history::redact_args(&std::env::args().skip(1).collect::<Vec<_>>()),
+        operation,
+    };
+    if let Err(e) = cfg.store().and_then(|s| s.save(UNDO_FILE, &Some(change))) {
+        eprintln!("unable to remember the change for undo: {:#}", e);
+    }
+}
+
+/// Fetches the tasks as they are before they change, so the change can be remembered once it is
+/// made. Warns if they can't be fetched, as the change can still be made.
+pub(crate) async fn snapshot(ids: &[TaskID], gw: &Gateway) -> Option<Vec<Task>> {
+    match gw.tasks_by_ids(ids).await {
+        Ok(tasks) => Some(tasks),
+        Err(e) => {
+            eprintln!("unable to remember the change for undo: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Remembers the tasks of the snapshot that were changed, if any were, so a change that failed
+/// for some tasks only reverses the others.
+pub(crate) fn record_snapshot(
+    snapshot: Option<Vec<Task>>,
+    changed: &[TaskID],
+    operation: fn(Vec<Task>) -> Operation,
+    cfg: &Config,
+) {
+    let Some(mut tasks) = snapshot else {
+        return;
+    };
+    tasks.retain(|t| changed.contains(&t.id));
+    if !tasks.is_empty() {
+        record(operation(tasks), cfg);
+    }
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Reverses the last change and forgets it, so it is only undone once.
+pub async fn undo(gw: &Gateway, cfg: &Config) -> Result<()> {
+    let store = cfg.store()?;
+    let change = store
+        .load::<Option<Change>>(UNDO_FILE)?
+        .ok_or_else(|| eyre!("there is nothing to undo"))?;
+    eprintln!(
+        "undoing {} from {}",
+        history::command_line(&change.args),
+        change
+            .timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+    );
+    // Undoing twice would reverse the change again, like recreating deleted tasks twice.
+    store.remove(UNDO_FILE)?;
+    match change.operation {
+        Operation::Close { ids } => reopen(&ids, gw).await,
+        Operation::Delete { tasks } => recreate(&tasks, gw).await,
+        Operation::Edit { tasks } => restore(&tasks, gw).await,
+        Operation::Move { tasks } => move_back(&tasks, gw).await,
+    }
+}
+
+/// Reopens the closed tasks. Recurring tasks stay open when they are closed, and only keep their
+/// next date.
+async fn reopen(ids: &[TaskID], gw: &Gateway) -> Result<()> {
+    let messages = bulk::run("reopen", ids, |id| async move {
+        let task = gw.task(id).await?;
+        if task.is_completed {
+            gw.reopen(id).await?;
+            return Ok(format!(
+                "reopened task {}",
+                id.if_supports_color(Stream::Stdout, |text| text.bright_green())
+            ));
+        }
+        Ok(match task.due.filter(|d| d.is_recurring) {
+            Some(due) => format!(
+                "task {} recurs and stays due on its next date {}",
+                id.if_supports_color(Stream::Stdout, |text| text.bright_yellow()),
+                due.date
+            ),
+            None => format!(
+                "task {} is open already",
+                id.if_supports_color(Stream::Stdout, |text| text.bright_yellow())
+            ),
+        })
+    })
+    .await?;
+    for message in messages {
+        println!("{}", message);
+    }
+    Ok(())
+}
+
+/// Returns the due date of the task in a form that sets it again.
+fn due(task: &Task) -> Option<TaskDue> {
+    let due = task.due.as_ref()?;
+    Some(match (&due.exact, due.is_recurring) {
+        (_, true) => TaskDue::String(due.string.clone()),
+        (Some(exact), false) => TaskDue::DateTime(exact.datetime.with_timezone(&Utc)),
+        (None, false) => TaskDue::Date(due.date.to_string()),
+    })
+}
+
+/// Creates the deleted tasks again. They get new IDs, and their subtasks are created under them.
+async fn recreate(tasks: &[Task], gw: &Gateway) -> Result<()> {
+    let mut ids: Vec<(&TaskID, TaskID)> = Vec::new();
+    for task in tasks {
+        let parent_id = task.parent_id.as_ref().map(|parent| {
+            ids.iter()
+                .find(|(old, _)| *old == parent)
+                .map(|(_, new)| new.clone())
+                .unwrap_or_else(|| parent.clone())
+        });
+        let created = gw
+            .create(&CreateTask {
+                content: task.content.clone(),
+                description: Some(task.description.clone()).filter(|d| !d.is_empty()),
+                project_id: Some(task.project_id.clone()),
+                section_id: task.section_id.clone(),
+                parent_id,
+                order: Some(task.order),
+                labels: task.labels.clone(),
+                priority: Some(task.priority),
+                due: due(task),
+                assignee: task.assignee_id.clone(),
+                ..Default::default()
+            })
+            .await
+            .wrap_err_with(|| format!("unable to recreate task {}", task.id))?;
+        println!(
+            "recreated task {} as {}",
+            task.id,
+            created
+                .id
+                .if_supports_color(Stream::Stdout, |text| text.bright_green())
+        );
+        ids.push((&task.id, created.id));
+    }
+    Ok(())
+}
+
+/// Sets the fields that `edit` changes back to what they were.
+async fn restore(tasks: &[Task], gw: &Gateway) -> Result<()> {
+    let ids = tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+    let restored = bulk::run("restore", &ids, |id| async move {
+        let task = tasks
+            .iter()
+            .find(|t| &t.id == id)
+            .expect("the task is among the tasks");
+        gw.update(
+            id,
+            &UpdateTask {
+                content: Some(task.content.clone()),
+                description: Some(task.description.clone()),
+                labels: Some(task.labels.clone()),
+                priority: Some(task.priority),
+                due: Some(due(task).unwrap_or_else(|| TaskDue::String("no date".to_string()))),
+                // Unassigns the tasks that were not assigned before.
+                assignee: Some(task.assignee_id.clone()),
+                ..Default::default()
+            },
+        )
+        .await?;
+        Ok(id)
+    })
+    .await?;
+    for id in restored {
+        println!(
+            "restored task {}",
+            id.if_supports_color(Stream::Stdout, |text| text.bright_green())
+        );
+    }
+    Ok(())
+}
+
+/// Moves the tasks back under their parent, or into their section or project.
+async fn move_back(tasks: &[Task], gw: &Gateway) -> Result<()> {
+    let ids = tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+    let moved = bulk::run("move", &ids, |id| async move {
+        let task = tasks
+            .iter()
+            .find(|t| &t.id == id)
+            .expect("the task is among the tasks");
+        let target = match (&task.parent_id, &task.section_id) {
+            (Some(parent), _) => MoveTarget::Parent(parent.clone()),
+            (None, Some(section)) => MoveTarget::Section(section.clone()),
+            (None, None) => MoveTarget::Project(task.project_id.clone()),
+        };
+        gw.move_task(id, &target).await?;
+        Ok(id)
+    })
+    .await?;
+    for id in moved {
+        println!(
+            "moved task {} back",
+            id.if_supports_color(Stream::Stdout, |text| text.bright_green())
+        );
+    }
+    Ok(())
+}
+
+/// Returns the task with all of its subtasks, parents first, from all tasks.
+pub(crate) fn subtree(id: &TaskID, tasks: Vec<Task>) -> Result<Vec<Task>> {
+    let trees = Tree::from_items(tasks)?;
+    let tree = trees
+        .iter()
+        .find_map(|t| t.find(id))
+        .ok_or_else(|| eyre!("task {} does not exist", id))?;
+    Ok(tree.flatten().into_iter().map(|t| t.item.clone()).collect())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        undo(&ctx.gw, &ctx.cfg).await
+    }
+}
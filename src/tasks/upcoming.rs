@@ -0,0 +1,122 @@
+//! Shows an agenda of the coming days, with the tasks that are due on each day and how much of
+//! the day they fill.
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{rest::Task, tree::Tree},
+    command::{Command, Context},
+    i18n::{self, t},
+    output,
+};
+
+use super::{capacity, state::State};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// How many days to show, starting with today.
+    #[arg(short = 'd', long = "days", default_value_t = 7, value_parser = clap::value_parser!(u64).range(1..))]
+    days: u64,
+}
+
+/// Renders the overdue tasks, and then every day with the tasks that are due on it.
+fn render(state: &State, now: &DateTime<Utc>, days: u64) -> String {
+    let today = now.with_timezone(&Local).date_naive();
+    let mut out = String::new();
+    let overdue = due_on(state, |date| date < today);
+    if !overdue.is_empty() {
+        // Overdue tasks have no capacity left to fill.
+        let mut name = format!("{}: {}", t!("overdue"), capacity::count(&overdue));
+        if let Some(load) = capacity::load(&overdue, state.config, false) {
+            name.push_str(&format!(", {}", load));
+        }
+        heading(&mut out, name, true);
+        render_tasks(&overdue, state, &mut out);
+    }
+    for day in (0..days).filter_map(|d| today.checked_add_days(Days::new(d))) {
+        let format = match (day - today).num_days() {
+            0 => t!("day-date-today"),
+            1 => t!("day-date-tomorrow"),
+            _ => t!("day-date"),
+        };
+        let name = i18n::date(day, &format);
+        let tasks = due_on(state, |date| date == day);
+        let load = capacity::load(&tasks, state.config, true);
+        let name = match (tasks.is_empty(), load) {
+            (true, None) => name,
+            (true, Some(load)) => format!("{}: {}", name, load),
+            (false, None) => format!("{}: {}", name, capacity::count(&tasks)),
+            (false, Some(load)) => format!("{}: {}, {}", name, capacity::count(&tasks), load),
+        };
+        heading(&mut out, name, false);
+        if tasks.is_empty() {
+            out.push_str(&format!(
+                "  {}\n",
+                t!("nothing-due").if_supports_color(Stream::Stdout, |t| t.dimmed())
+            ));
+        }
+        render_tasks(&tasks, state, &mut out);
+    }
+    out
+}
+
+/// Returns the top level tasks whose due date matches.
+fn due_on<'s, F: Fn(NaiveDate) -> bool>(state: &'s State, matches: F) -> Vec<&'s Tree<Task>> {
+    state
+        .tasks
+        .iter()
+        .filter(|t| t.due.as_ref().is_some_and(|d| matches(d.date)))
+        .collect()
+}
+
+fn heading(out: &mut String, name: String, overdue: bool) {
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    let name = match overdue {
+        true => name
+            .if_supports_color(Stream::Stdout, |t| t.bright_red())
+            .to_string(),
+        false => name
+            .if_supports_color(Stream::Stdout, |t| t.bold())
+            .to_string(),
+    };
+    out.push_str(&format!("{}\n", name));
+}
+
+/// Renders the tasks of a day with their subtasks, numbered within the day for screen readers.
+fn render_tasks(tasks: &[&Tree<Task>], state: &State, out: &mut String) {
+    let count = capacity::total(tasks);
+    let mut index = 0;
+    for task in tasks {
+        render_tree(task, state, &mut index, count, out);
+    }
+}
+
+fn render_tree(
+    task: &Tree<Task>,
+    state: &State,
+    index: &mut usize,
+    count: usize,
+    out: &mut String,
+) {
+    out.push_str(&format!(
+        "  {}\n",
+        output::position(*index, count, state.table_task(task))
+    ));
+    *index += 1;
+    for subtask in &task.subitems {
+        render_tree(subtask, state, index, count, out);
+    }
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        let filter = format!("overdue | next {} days", self.days);
+        let state = State::fetch_tree(Some(&filter), &ctx.gw, &ctx.cfg).await?;
+        let now = ctx.cfg.override_time.unwrap_or_else(Utc::now);
+        print!("{}", render(&state, &now, self.days));
+        Ok(())
+    }
+}
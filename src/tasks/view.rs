@@ -1,23 +1,74 @@
+use std::collections::HashSet;
+
 use color_eyre::{eyre::eyre, Result};
 
-use crate::{api::rest::Gateway, comments, config::Config};
+use crate::{
+    api::rest::Gateway,
+    command::{Command, Context},
+    comments,
+    config::Config,
+    pager, preview,
+};
 
-use super::filter::TaskOrInteractive;
+use super::{filter::TaskOrInteractive, note, state::State};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     #[clap(flatten)]
     task: TaskOrInteractive,
+    /// Prints everything directly instead of using a pager for long output.
+    #[arg(long = "nopager")]
+    nopager: bool,
+    /// Disables inline previews of attached or linked images in supported terminals.
+    #[arg(long = "noimages")]
+    noimages: bool,
 }
 
 /// Displays full information about a task.
 pub async fn view(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let (id, state) = params.task.task(gw, cfg).await?;
+    let (id, state) = match params.task.id() {
+        Some(id) => (id.clone(), State::fetch_task(id, gw, cfg).await?),
+        None => params.task.task(gw, cfg).await?,
+    };
     let task = state.full_task(state.task(&id).ok_or_else(|| eyre!("no valid task"))?);
-    println!("{}", task);
+    let mut output = task.to_string();
+    let mut images = preview::image_links(&task.0.description);
     if task.0.comment_count > 0 {
         let comments = gw.task_comments(&id).await?;
-        comments::list(&comments)
+        output.push('\n');
+        output.push_str(&comments::list(&comments));
+        for comment in &comments {
+            if let Some(url) = comment
+                .attachment
+                .as_ref()
+                .filter(|a| a.is_image())
+                .and_then(|a| a.file_url.clone())
+            {
+                images.push(url);
+            }
+            images.extend(preview::image_links(&comment.content));
+        }
+    }
+    let notes = note::notes(&cfg.store()?, &id)?;
+    if !notes.is_empty() {
+        output.push('\n');
+        output.push_str(&note::list(&notes));
+    }
+    if params.nopager {
+        println!("{}", output);
+    } else {
+        pager::page(&output)?;
+    }
+    if !params.noimages {
+        let mut seen = HashSet::new();
+        images.retain(|url| seen.insert(url.clone()));
+        preview::show(&images).await?;
     }
     Ok(())
 }
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        view(self, &ctx.gw, &ctx.cfg).await
+    }
+}
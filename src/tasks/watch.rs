@@ -0,0 +1,387 @@
+//! Keeps showing a list of tasks that refreshes on an interval, as a simple dashboard.
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use color_eyre::{eyre::WrapErr, Result};
+use dialoguer::console::Term;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{DueDate, Gateway, Task, TaskID},
+        tree::Tree,
+    },
+    cancel, duration,
+};
+
+use super::state::State;
+
+/// Options that turn a list into a continuously refreshing dashboard.
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    /// Keeps showing the list and refreshes it on an interval, highlighting the tasks that
+    /// changed since the last refresh. Stop it with Ctrl-C.
+    #[arg(long = "watch")]
+    pub watch: bool,
+    /// How often to refresh in watch mode, like `30s` or `5m`. Plain numbers are seconds.
+    #[arg(long = "interval", default_value = "60", value_parser = parse_interval)]
+    pub interval: Duration,
+}
+
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    let interval = duration::parse(s)?;
+    if interval < Duration::seconds(1) {
+        return Err("interval needs to be at least one second".to_string());
+    }
+    Ok(interval)
+}
+
+/// Snapshot remembers how tasks looked at the time of a refresh.
+#[derive(Debug, Default)]
+pub struct Snapshot(HashMap<TaskID, Task>);
+
+impl Snapshot {
+    /// Takes a snapshot of all tasks in the trees.
+    pub fn new(tasks: &[Tree<Task>]) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        snapshot.add(tasks);
+        snapshot
+    }
+
+    fn add(&mut self, tasks: &[Tree<Task>]) {
+        for task in tasks {
+            self.0.insert(task.id.clone(), task.item.clone());
+            self.add(&task.subitems);
+        }
+    }
+
+    /// Returns true if the task is new or different since the snapshot was taken.
+    pub fn changed(&self, task: &Task) -> bool {
+        self.0.get(&task.id) != Some(task)
+    }
+
+    /// Returns the IDs of the tasks that are no longer there in the next snapshot.
+    pub fn vanished<'a>(&'a self, next: &'a Snapshot) -> impl Iterator<Item = &'a TaskID> + 'a {
+        self.0.keys().filter(|id| !next.0.contains_key(*id))
+    }
+
+    /// Returns what changed between this snapshot and the next one, ordered by content. Tasks
+    /// that vanished were either among the `completed` ones, or removed from the list.
+    pub fn diff(&self, next: &Snapshot, completed: &HashSet<TaskID>) -> Vec<Change> {
+        let mut changes = next
+            .0
+            .values()
+            .filter_map(|task| match self.0.get(&task.id) {
+                None => Some(Change::New(task.content.clone())),
+                Some(old) if old.due != task.due => Some(Change::Rescheduled {
+                    content: task.content.clone(),
+                    from: due_text(&old.due),
+                    to: due_text(&task.due),
+                }),
+                Some(old) if old != task => Some(Change::Changed(task.content.clone())),
+                Some(_) => None,
+            })
+            .map(|change| (change.content().to_string(), change))
+            .chain(
+                self.0
+                    .values()
+                    .filter(|task| !next.0.contains_key(&task.id))
+                    .map(|task| {
+                        let content = task.content.clone();
+                        match completed.contains(&task.id) {
+                            true => (content.clone(), Change::Completed(content)),
+                            false => (content.clone(), Change::Removed(content)),
+                        }
+                    }),
+            )
+            .collect::<Vec<_>>();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        changes.into_iter().map(|(_, change)| change).collect()
+    }
+}
+
+/// Change describes how a task changed between two refreshes.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The task appeared in the list.
+    New(String),
+    /// The task was completed.
+    Completed(String),
+    /// The task left the list without being completed, because it was deleted or no longer
+    /// matches the filter.
+    Removed(String),
+    /// The due date of the task changed.
+    Rescheduled {
+        content: String,
+        from: String,
+        to: String,
+    },
+    /// Something else about the task changed.
+    Changed(String),
+}
+
+impl Change {
+    fn content(&self) -> &str {
+        match self {
+            Change::New(content)
+            | Change::Completed(content)
+            | Change::Removed(content)
+            | Change::Changed(content) => content,
+            Change::Rescheduled { content, .. } => content,
+        }
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::New(content) => write!(
+                f,
+                "{} {}",
+                "new".if_supports_color(Stream::Stdout, |t| t.bright_green()),
+                Content(content)
+            ),
+            Change::Completed(content) => write!(
+                f,
+                "{} {}",
+                "completed".if_supports_color(Stream::Stdout, |t| t.bright_blue()),
+                Content(content)
+            ),
+            Change::Removed(content) => write!(
+                f,
+                "{} {}",
+                "removed".if_supports_color(Stream::Stdout, |t| t.bright_red()),
+                Content(content)
+            ),
+            Change::Rescheduled { content, from, to } => write!(
+                f,
+                "{} {} ({} -> {})",
+                "rescheduled".if_supports_color(Stream::Stdout, |t| t.bright_yellow()),
+                Content(content),
+                from,
+                to
+            ),
+            Change::Changed(content) => write!(
+                f,
+                "{} {}",
+                "changed".if_supports_color(Stream::Stdout, |t| t.yellow()),
+                Content(content)
+            ),
+        }
+    }
+}
+
+fn due_text(due: &Option<DueDate>) -> String {
+    match due {
+        Some(DueDate {
+            exact: Some(exact), ..
+        }) => exact.to_string(),
+        Some(due) => due.date.to_string(),
+        None => "no date".to_string(),
+    }
+}
+
+/// How many changes the feed below the list shows at most.
+const FEED_SIZE: usize = 10;
+
+/// Feed keeps the most recent changes, newest first.
+#[derive(Debug, Default)]
+struct Feed(VecDeque<(DateTime<Local>, Change)>);
+
+impl Feed {
+    fn push(&mut self, at: DateTime<Local>, changes: Vec<Change>) {
+        for change in changes.into_iter().rev() {
+            self.0.push_front((at, change));
+        }
+        self.0.truncate(FEED_SIZE);
+    }
+
+    fn render(&self, out: &mut String) {
+        if self.0.is_empty() {
+            return;
+        }
+        out.push_str("\nChanges:\n");
+        for (at, change) in &self.0 {
+            out.push_str(&format!("{} {}\n", at.format("%H:%M:%S"), change));
+        }
+    }
+}
+
+/// Calls `fetch` on every interval and shows the tasks it returns, together with a feed of what
+/// changed between refreshes, until the user cancels.
+pub async fn watch<'a, F, Fut>(options: &Options, gw: &Gateway, mut fetch: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<State<'a>>>,
+{
+    let term = Term::stdout();
+    let interval = options
+        .interval
+        .to_std()
+        .wrap_err("invalid watch interval")?;
+    // The last snapshot, and when it was fetched.
+    let mut previous: Option<(Snapshot, DateTime<Utc>)> = None;
+    let mut feed = Feed::default();
+    while !cancel::is_cancelled() {
+        let fetched_at = Utc::now();
+        let refreshed = async {
+            let state = fetch().await?;
+            let snapshot = Snapshot::new(&state.tasks);
+            let changes = match &previous {
+                Some((previous, since)) => changes(previous, &snapshot, since, gw).await?,
+                None => Vec::new(),
+            };
+            Ok::<_, color_eyre::Report>((state, snapshot, changes))
+        };
+        let (state, snapshot, changes) = match refreshed.await {
+            Ok(refreshed) => refreshed,
+            // A failed refresh, like when the network is down for a moment, keeps showing the last
+            // one and is tried again on the next tick.
+            Err(e) => {
+                eprintln!("unable to refresh: {:#}", e);
+                tick(interval).await;
+                continue;
+            }
+        };
+        let now = Local::now();
+        feed.push(now, changes);
+        let mut output = format!(
+            "Refreshed at {}, every {}. Press Ctrl-C to stop.\n\n",
+            now.format("%H:%M:%S"),
+            duration::format(options.interval),
+        );
+        render(
+            &state.tasks,
+            &state,
+            previous.as_ref().map(|(p, _)| p),
+            &mut output,
+        );
+        feed.render(&mut output);
+        if term.is_term() {
+            term.clear_screen()?;
+        }
+        print!("{}", output);
+        previous = Some((snapshot, fetched_at));
+        tick(interval).await;
+    }
+    Ok(())
+}
+
+/// Returns what changed since the previous snapshot, which was fetched at `since`. The tasks that
+/// vanished are looked up among the ones that were completed since, to tell them apart from the
+/// ones that were removed.
+async fn changes(
+    previous: &Snapshot,
+    next: &Snapshot,
+    since: &DateTime<Utc>,
+    gw: &Gateway,
+) -> Result<Vec<Change>> {
+    let completed = match previous.vanished(next).next() {
+        Some(_) => gw
+            .completed(since)
+            .await?
+            .into_iter()
+            .map(|c| c.task_id)
+            .collect(),
+        None => HashSet::new(),
+    };
+    Ok(previous.diff(next, &completed))
+}
+
+/// Waits until the next refresh, or until the user cancels.
+async fn tick(interval: std::time::Duration) {
+    tokio::select! {
+        _ = tokio::time::sleep(interval) => {}
+        _ = cancel::cancelled() => {}
+    }
+}
+
+/// Renders the tasks like a non-interactive list, marking the ones that changed since the
+/// previous snapshot.
+fn render(tasks: &[Tree<Task>], state: &State, previous: Option<&Snapshot>, out: &mut String) {
+    let mut tasks = tasks.iter().collect::<Vec<_>>();
+    tasks.sort();
+    for task in tasks {
+        let marker = match previous {
+            Some(previous) if previous.changed(task) => "*",
+            _ => " ",
+        };
+        out.push_str(&format!(
+            "{} {}\n",
+            marker.if_supports_color(Stream::Stdout, |m| m.bright_yellow()),
+            state.table_task(task)
+        ));
+        render(&task.subitems, state, previous, out);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_changes() {
+        let one = Task::new("1", "one");
+        let tasks = Tree::from_items(vec![one.clone(), Task::new("2", "two")]).unwrap();
+        let snapshot = Snapshot::new(&tasks);
+        assert!(!snapshot.changed(&one));
+        assert!(snapshot.changed(&Task {
+            content: "changed".to_string(),
+            ..one
+        }));
+        assert!(snapshot.changed(&Task::new("3", "three")));
+    }
+
+    #[test]
+    fn diffs_snapshots() {
+        let due = |date: &str| {
+            Some(DueDate {
+                string: date.to_string(),
+                date: date.parse().unwrap(),
+                is_recurring: false,
+                exact: None,
+            })
+        };
+        let one = Task::new("1", "one");
+        let two = Task {
+            due: due("2022-08-24"),
+            ..Task::new("2", "two")
+        };
+        let three = Task::new("3", "three");
+        let five = Task::new("5", "five");
+        let before = Snapshot::new(
+            &Tree::from_items(vec![one.clone(), two.clone(), three.clone(), five]).unwrap(),
+        );
+        let after = Snapshot::new(
+            &Tree::from_items(vec![
+                Task {
+                    due: due("2022-08-25"),
+                    ..two
+                },
+                Task {
+                    priority: crate::api::rest::Priority::Urgent,
+                    ..three
+                },
+                Task::new("4", "four"),
+            ])
+            .unwrap(),
+        );
+        let completed = HashSet::from(["5".to_string()]);
+        assert_eq!(
+            before.diff(&after, &completed),
+            vec![
+                Change::Completed("five".to_string()),
+                Change::New("four".to_string()),
+                Change::Removed("one".to_string()),
+                Change::Changed("three".to_string()),
+                Change::Rescheduled {
+                    content: "two".to_string(),
+                    from: "2022-08-24".to_string(),
+                    to: "2022-08-25".to_string(),
+                },
+            ]
+        );
+        assert!(before.diff(&before, &completed).is_empty());
+    }
+}
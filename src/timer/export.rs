@@ -0,0 +1,109 @@
+use chrono::{Local, NaiveDate};
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+    csv,
+};
+
+use super::{format_elapsed, ledger, Timer};
+
+/// The formats the time ledger can be exported to.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// CSV that can be imported into Toggl Track.
+    Toggl,
+    /// Plain CSV with all recorded fields.
+    Csv,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The format to export to.
+    #[arg(value_enum)]
+    format: Format,
+    /// Only exports timers that started on or after this date, like `2022-08-01`.
+    #[arg(long = "since")]
+    since: Option<NaiveDate>,
+    /// The email of the Toggl Track user the time entries belong to.
+    #[arg(long = "email")]
+    email: Option<String>,
+}
+
+/// Prints the stopped timers of the ledger as CSV.
+pub fn export(params: Params, cfg: &Config) -> Result<()> {
+    let timers = ledger(&cfg.store()?)?
+        .into_iter()
+        .filter(|t| match params.since {
+            Some(since) => t.start.with_timezone(&Local).date_naive() >= since,
+            None => true,
+        })
+        .collect::<Vec<_>>();
+    let rows: Vec<Vec<String>> = match params.format {
+        Format::Toggl => {
+            let email = params.email.as_deref().unwrap_or_default();
+            std::iter::once(
+                [
+                    "Email",
+                    "Project",
+                    "Description",
+                    "Start date",
+                    "Start time",
+                    "Duration",
+                ]
+                .map(String::from)
+                .to_vec(),
+            )
+            .chain(timers.iter().map(|t| toggl_row(t, email)))
+            .collect()
+        }
+        Format::Csv => std::iter::once(
+            ["task_id", "task", "project", "start", "end", "seconds"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .chain(timers.iter().map(csv_row))
+        .collect(),
+    };
+    for row in rows {
+        println!("{}", csv::row(&row));
+    }
+    Ok(())
+}
+
+fn toggl_row(timer: &Timer, email: &str) -> Vec<String> {
+    let start = timer.start.with_timezone(&Local);
+    vec![
+        email.to_string(),
+        timer.project.clone(),
+        timer.content.clone(),
+        start.format("%Y-%m-%d").to_string(),
+        start.format("%H:%M:%S").to_string(),
+        format_elapsed(timer.elapsed(timer.start)),
+    ]
+}
+
+fn csv_row(timer: &Timer) -> Vec<String> {
+    vec![
+        timer.task_id.clone(),
+        timer.content.clone(),
+        timer.project.clone(),
+        timer.start.to_rfc3339(),
+        timer.end.map(|e| e.to_rfc3339()).unwrap_or_default(),
+        timer.elapsed(timer.start).num_seconds().to_string(),
+    ]
+}
+
+impl Command for Params {
+    fn validate(&self) -> Result<()> {
+        if self.format == Format::Toggl && self.email.is_none() {
+            return Err(eyre!("exporting to Toggl needs the --email of the user"));
+        }
+        Ok(())
+    }
+
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        export(self, &ctx.cfg)
+    }
+}
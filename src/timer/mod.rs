@@ -0,0 +1,71 @@
+//! Tracks the time spent on tasks with start/stop timers, recorded in a local time ledger.
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{api::rest::TaskID, store::Store};
+
+pub mod export;
+pub mod start;
+pub mod status;
+pub mod stop;
+
+/// The file in the [`Store`] that contains the currently running timer.
+const RUNNING_FILE: &str = "timer.json";
+/// The file in the [`Store`] that contains all stopped timers.
+pub(crate) const LEDGER_FILE: &str = "timers.jsonl";
+
+/// Timer describes the time spent on a task, which is still running if it has no end yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Timer {
+    /// The task that was worked on.
+    pub task_id: TaskID,
+    /// The content of the task at the time the timer started.
+    pub content: String,
+    /// The name of the project of the task at the time the timer started.
+    pub project: String,
+    /// When the timer was started.
+    pub start: DateTime<Utc>,
+    /// When the timer was stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl Timer {
+    /// Returns how long the timer ran, or has been running until `now`.
+    pub fn elapsed(&self, now: DateTime<Utc>) -> Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+/// Returns the timer that is currently running, if any.
+pub(crate) fn running(store: &Store) -> Result<Option<Timer>> {
+    store.load(RUNNING_FILE)
+}
+
+/// Stops the running timer at the given time and records it in the ledger.
+pub(crate) fn stop_running(store: &Store, now: DateTime<Utc>) -> Result<Option<Timer>> {
+    let Some(mut timer) = running(store)? else {
+        return Ok(None);
+    };
+    timer.end = Some(now);
+    store.append(LEDGER_FILE, &timer)?;
+    store.remove(RUNNING_FILE)?;
+    Ok(Some(timer))
+}
+
+/// Returns all stopped timers, oldest first.
+pub(crate) fn ledger(store: &Store) -> Result<Vec<Timer>> {
+    store.lines(LEDGER_FILE)
+}
+
+/// Formats a duration as `HH:MM:SS`, which is also what time tracking tools import.
+pub(crate) fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.num_seconds().max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
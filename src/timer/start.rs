@@ -0,0 +1,51 @@
+use chrono::Utc;
+use color_eyre::Result;
+
+use crate::{
+    api::rest::{Gateway, TaskID},
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{format_elapsed, stop_running, Timer, RUNNING_FILE};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Use `list` to find out what ID your task has.
+    id: TaskID,
+}
+
+/// Starts a timer for the task, stopping the timer that is currently running.
+pub async fn start(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let (task, projects) = tokio::try_join!(gw.task(&params.id), gw.projects())?;
+    let project = projects
+        .iter()
+        .find(|p| p.id == task.project_id)
+        .map(|p| p.name.clone())
+        .unwrap_or_default();
+    let store = cfg.store()?;
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    if let Some(stopped) = stop_running(&store, now)? {
+        println!(
+            "stopped timer of {} after {}",
+            stopped.content,
+            format_elapsed(stopped.elapsed(now))
+        );
+    }
+    let timer = Timer {
+        task_id: task.id,
+        content: task.content,
+        project,
+        start: now,
+        end: None,
+    };
+    store.save(RUNNING_FILE, &timer)?;
+    println!("started timer of {}", timer.content);
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        start(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::Utc;
+use color_eyre::Result;
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{format_elapsed, running};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Shows the timer that is currently running.
+pub fn status(_params: Params, cfg: &Config) -> Result<()> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    match running(&cfg.store()?)? {
+        Some(timer) => println!(
+            "{} ({}) running for {}",
+            timer.content,
+            timer.task_id,
+            format_elapsed(timer.elapsed(now))
+        ),
+        None => println!("no timer is running"),
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        status(self, &ctx.cfg)
+    }
+}
@@ -0,0 +1,30 @@
+use chrono::Utc;
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{format_elapsed, stop_running};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+/// Stops the running timer and records it in the ledger.
+pub fn stop(_params: Params, cfg: &Config) -> Result<()> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let timer = stop_running(&cfg.store()?, now)?.ok_or_else(|| eyre!("no timer is running"))?;
+    println!(
+        "stopped timer of {} after {}",
+        timer.content,
+        format_elapsed(timer.elapsed(now))
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        stop(self, &ctx.cfg)
+    }
+}
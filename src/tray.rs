@@ -0,0 +1,293 @@
+//! Shows an icon in the system tray with the number of tasks that are due today, for desktops
+//! that support StatusNotifierItem, like KDE Plasma or GNOME with the AppIndicator extension.
+//!
+//! The menu lists the tasks and opens them in Todoist, and can quick add new ones.
+use std::{
+    io::ErrorKind,
+    process::{self, Stdio},
+};
+
+use chrono::Duration;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use ksni::{menu::StandardItem, MenuItem, ToolTip, TrayMethods};
+use tokio::sync::mpsc;
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, Task},
+    },
+    cancel,
+    command::{Command, Context},
+    duration,
+};
+
+/// The tasks that are counted, the same as `doist today` shows.
+const FILTER: &str = "today | overdue";
+/// The most tasks that are listed in the menu, so that it still fits on the screen.
+const MENU_TASKS: usize = 15;
+/// The view of Todoist that clicking the icon opens.
+const TODAY_URL: &str = "https://todoist.com/app/today";
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// How often to refresh the tasks, like `30s` or `5m`. Plain numbers are seconds.
+    #[arg(long = "interval", default_value = "5m", value_parser = duration::parse)]
+    interval: Duration,
+}
+
+/// Action is what was picked in the menu that needs the [`Gateway`], which the tray itself
+/// doesn't have.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Refresh,
+    QuickAdd,
+    Quit,
+}
+
+/// Tray is what the icon and its menu show.
+struct Tray {
+    /// The content and URL of the tasks that are due today.
+    tasks: Vec<(String, String)>,
+    /// Why the last refresh failed, if it did.
+    error: Option<String>,
+    actions: mpsc::UnboundedSender<Action>,
+}
+
+impl Tray {
+    fn new(actions: mpsc::UnboundedSender<Action>) -> Tray {
+        Tray {
+            tasks: Vec::new(),
+            error: None,
+            actions,
+        }
+    }
+
+    /// Shows the tasks of a refresh, or why it failed.
+    fn set(&mut self, tasks: Result<Vec<Task>>) {
+        match tasks {
+            Ok(tasks) => {
+                self.tasks = tasks
+                    .iter()
+                    .map(|t| (Content(&t.content).to_string(), t.url.to_string()))
+                    .collect();
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Describes how many tasks are due, like `3 tasks due today`.
+    fn summary(&self) -> String {
+        if self.error.is_some() {
+            return "unable to refresh".to_string();
+        }
+        match self.tasks.len() {
+            1 => "1 task due today".to_string(),
+            n => format!("{} tasks due today", n),
+        }
+    }
+
+    /// Returns a menu item that sends the action.
+    fn action(&self, label: &str, action: fn() -> Action) -> MenuItem<Self> {
+        StandardItem {
+            label: label.to_string(),
+            activate: Box::new(move |tray: &mut Self| {
+                let _ = tray.actions.send(action());
+            }),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl ksni::Tray for Tray {
+    fn id(&self) -> String {
+        "doist".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("doist: {}", self.summary())
+    }
+
+    fn icon_name(&self) -> String {
+        match self.error {
+            Some(_) => "dialog-warning".to_string(),
+            None => "view-task".to_string(),
+        }
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: self.title(),
+            description: self.error.clone().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        open(TODAY_URL);
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let mut menu = vec![StandardItem {
+            label: self.summary(),
+            enabled: false,
+            ..Default::default()
+        }
+        .into()];
+        for (content, url) in self.tasks.iter().take(MENU_TASKS) {
+            let url = url.clone();
+            menu.push(
+                StandardItem {
+                    label: content.clone(),
+                    activate: Box::new(move |_: &mut Self| open(&url)),
+                    ..Default::default()
+                }
+                .into(),
+            );
+        }
+        if self.tasks.len() > MENU_TASKS {
+            menu.push(
+                StandardItem {
+                    label: format!("and {} more", self.tasks.len() - MENU_TASKS),
+                    activate: Box::new(|_: &mut Self| open(TODAY_URL)),
+                    ..Default::default()
+                }
+                .into(),
+            );
+        }
+        menu.extend([
+            MenuItem::Separator,
+            self.action("Quick add…", || Action::QuickAdd),
+            self.action("Refresh", || Action::Refresh),
+            self.action("Quit", || Action::Quit),
+        ]);
+        menu
+    }
+}
+
+/// Opens the URL in the default browser, or the Todoist app if it handles the URL.
+fn open(url: &str) {
+    let opened = process::Command::new("xdg-open")
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Err(e) = opened {
+        eprintln!("unable to open {}: {}", url, e);
+    }
+}
+
+/// Asks for a task in a small dialog, with whichever of the common dialog tools is installed.
+/// Returns nothing if the dialog was closed.
+fn prompt() -> Result<Option<String>> {
+    let dialogs: [(&str, &[&str]); 2] = [
+        (
+            "zenity",
+            &["--entry", "--title=doist", "--text=Quick add a task"],
+        ),
+        (
+            "kdialog",
+            &["--title", "doist", "--inputbox", "Quick add a task"],
+        ),
+    ];
+    for (program, args) in dialogs {
+        match process::Command::new(program).args(args).output() {
+            // Both exit with an error if the dialog was closed.
+            Ok(output) => {
+                let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                return Ok(output
+                    .status
+                    .success()
+                    .then_some(text)
+                    .filter(|t| !t.is_empty()));
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).wrap_err_with(|| format!("unable to run {}", program)),
+        }
+    }
+    Err(eyre!("quick add needs zenity or kdialog to be installed"))
+}
+
+/// Asks for a task and adds it like `doist quick` does.
+async fn quick_add(gw: &Gateway) -> Result<()> {
+    let text = tokio::task::spawn_blocking(prompt).await??;
+    if let Some(text) = text {
+        let task = gw.quick_add(&text).await?;
+        eprintln!("added {}", Content(&task.content));
+    }
+    Ok(())
+}
+
+/// Shows the tray icon until it is quit from its menu or with Ctrl-C.
+pub async fn tray(params: Params, gw: &Gateway) -> Result<()> {
+    let interval = params.interval.to_std()?;
+    let (actions, mut picked) = mpsc::unbounded_channel();
+    let handle = Tray::new(actions).spawn().await.wrap_err(
+        "unable to show the tray icon, the desktop needs to support StatusNotifierItem",
+    )?;
+    let mut refresh = true;
+    while !cancel::is_cancelled() {
+        if refresh {
+            // Tasks may have changed in the app since the last refresh.
+            gw.refresh().await;
+            let tasks = gw.tasks(Some(FILTER)).await;
+            handle.update(|tray| tray.set(tasks)).await;
+        }
+        refresh = tokio::select! {
+            _ = tokio::time::sleep(interval) => true,
+            _ = cancel::cancelled() => false,
+            action = picked.recv() => match action {
+                Some(Action::Refresh) => true,
+                Some(Action::QuickAdd) => {
+                    if let Err(e) = quick_add(gw).await {
+                        eprintln!("unable to quick add: {:#}", e);
+                    }
+                    true
+                }
+                Some(Action::Quit) | None => break,
+            },
+        };
+    }
+    handle.shutdown().await;
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        tray(self, &ctx.gw).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ksni::Tray as _;
+
+    use super::*;
+
+    #[test]
+    fn lists_tasks() {
+        let (actions, mut picked) = mpsc::unbounded_channel();
+        let mut tray = Tray::new(actions);
+        tray.set(Ok(vec![Task::new("1", "one")]));
+        assert_eq!(tray.title(), "doist: 1 task due today");
+        let menu = tray.menu();
+        // The summary, the task, a separator and the three actions.
+        assert_eq!(menu.len(), 6);
+        let MenuItem::Standard(quit) = &menu[5] else {
+            panic!("quit is not a standard item");
+        };
+        (quit.activate)(&mut tray);
+        assert_eq!(picked.try_recv(), Ok(Action::Quit));
+
+        tray.set(Err(eyre!("no network")));
+        assert_eq!(tray.title(), "doist: unable to refresh");
+        assert_eq!(tray.tool_tip().description, "no network");
+        // The tasks of the last refresh stay.
+        assert_eq!(tray.tasks.len(), 1);
+    }
+}
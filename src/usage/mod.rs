@@ -0,0 +1,227 @@
+//! Keeps local statistics of how the app is used, like which commands and flags are run and how
+//! long the API takes to answer. They are only written to disk and never leave the machine.
+use std::{collections::BTreeMap, sync::Mutex, time::Duration};
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+pub mod report;
+
+/// The file in the [`Store`] that contains one line per invocation.
+const USAGE_FILE: &str = "usage.jsonl";
+
+/// The API calls of the current invocation, if statistics are collected.
+static REQUESTS: Mutex<Option<BTreeMap<String, Latency>>> = Mutex::new(None);
+
+/// Invocation describes a single run of the app.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Invocation {
+    /// When the run ended.
+    pub timestamp: DateTime<Utc>,
+    /// The subcommands that were run, like `projects list`.
+    pub command: String,
+    /// The long names of the flags that were given, without their values.
+    #[serde(default)]
+    pub flags: Vec<String>,
+    /// The API calls that were made, by their method and endpoint.
+    #[serde(default)]
+    pub requests: BTreeMap<String, Latency>,
+}
+
+/// Latency adds up how often an API endpoint was called and how long it took in total.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Latency {
+    pub count: u64,
+    pub millis: u64,
+}
+
+impl Latency {
+    fn add(&mut self, other: Latency) {
+        self.count += other.count;
+        self.millis += other.millis;
+    }
+
+    /// Returns the average time a call took, in milliseconds.
+    pub fn average(&self) -> u64 {
+        self.millis.checked_div(self.count).unwrap_or_default()
+    }
+}
+
+/// Starts collecting the API calls of this invocation.
+pub fn install() {
+    *REQUESTS.lock().unwrap() = Some(BTreeMap::new());
+}
+
+/// Adds an API call to the statistics, if they are collected.
+pub(crate) fn record_request(method: &str, path: &str, elapsed: Duration) {
+    if let Some(requests) = REQUESTS.lock().unwrap().as_mut() {
+        let latency = Latency {
+            count: 1,
+            millis: elapsed.as_millis().try_into().unwrap_or(u64::MAX),
+        };
+        requests
+            .entry(format!("{} {}", method, endpoint(path)))
+            .or_default()
+            .add(latency);
+    }
+}
+
+/// Writes the statistics of this invocation with the given arguments. Failing to do so only
+/// warns, as the command already ran.
+pub(crate) fn save(store: &Store, cli: &clap::Command, args: &[String]) {
+    let (command, flags) = invocation(cli, args);
+    // Looking at the statistics is not counted, so that resetting them leaves them empty.
+    if command == "usage" {
+        return;
+    }
+    let invocation = Invocation {
+        timestamp: Utc::now(),
+        command,
+        flags,
+        requests: REQUESTS.lock().unwrap().take().unwrap_or_default(),
+    };
+    if let Err(e) = store.append(USAGE_FILE, &invocation) {
+        eprintln!("unable to write usage statistics: {:#}", e);
+    }
+}
+
+/// Returns all invocations that were recorded in the store, oldest first.
+pub(crate) fn invocations(store: &Store) -> Result<Vec<Invocation>> {
+    store.lines(USAGE_FILE)
+}
+
+/// Removes all recorded statistics.
+pub(crate) fn reset(store: &Store) -> Result<()> {
+    store.remove(USAGE_FILE)
+}
+
+/// Replaces the IDs in the path of an API call, so that calls to the same endpoint are counted
+/// together.
+fn endpoint(path: &str) -> String {
+    path.trim_start_matches('/')
+        .split('/')
+        .map(|segment| {
+            let version = segment
+                .strip_prefix('v')
+                .is_some_and(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit()));
+            match !version && segment.chars().any(|c| c.is_ascii_digit()) {
+                true => ":id",
+                false => segment,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Finds the subcommands and flags in the arguments, leaving out all values as they could be
+/// anything, like the names of tasks.
+fn invocation(cli: &clap::Command, args: &[String]) -> (String, Vec<String>) {
+    let mut cli = cli.clone();
+    // Makes the global flags known to all subcommands.
+    cli.build();
+    let mut cmd = &cli;
+    let mut names = Vec::new();
+    let mut flags = Vec::new();
+    let mut args = args.iter();
+    let takes_value = |arg: Option<&clap::Arg>| arg.is_some_and(|a| a.get_action().takes_values());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        let mut flag = |flag: String| {
+            if !flags.contains(&flag) {
+                flags.push(flag);
+            }
+        };
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, _)) => (name, true),
+                None => (long, false),
+            };
+            let found = cmd.get_arguments().find(|a| a.get_long() == Some(name));
+            flag(format!("--{}", name));
+            if !value && takes_value(found) {
+                args.next();
+            }
+        } else if let Some(shorts) = arg.strip_prefix('-').filter(|s| !s.is_empty()) {
+            for (i, c) in shorts.char_indices() {
+                let found = cmd.get_arguments().find(|a| a.get_short() == Some(c));
+                flag(match found.and_then(|a| a.get_long()) {
+                    Some(long) => format!("--{}", long),
+                    None => format!("-{}", c),
+                });
+                if takes_value(found) {
+                    // The value either follows right after the flag, or is the next argument.
+                    if i + c.len_utf8() == shorts.len() {
+                        args.next();
+                    }
+                    break;
+                }
+            }
+        } else if let Some(sub) = cmd.find_subcommand(arg) {
+            names.push(sub.get_name().to_string());
+            cmd = sub;
+        }
+    }
+    if names.is_empty() {
+        // Listing tasks is the default if no subcommand is given.
+        names.push("list".to_string());
+    }
+    (names.join(" "), flags)
+}
+
+#[cfg(test)]
+mod test {
+    use clap::CommandFactory;
+
+    use super::*;
+    use crate::Arguments;
+
+    fn parse(args: &[&str]) -> (String, Vec<String>) {
+        let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        invocation(&Arguments::command(), &args)
+    }
+
+    #[test]
+    fn finds_commands_and_flags() {
+        assert_eq!(parse(&[]), ("list".to_string(), vec![]));
+        assert_eq!(
+            parse(&["l", "-f", "today", "--expand"]),
+            (
+                "list".to_string(),
+                vec!["--filter".to_string(), "--expand".to_string()]
+            )
+        );
+        assert_eq!(
+            parse(&["--read-only", "projects", "list"]),
+            ("projects list".to_string(), vec!["--read-only".to_string()])
+        );
+        assert_eq!(
+            parse(&["add", "today", "-p1", "--due=tomorrow", "-P", "today"]),
+            (
+                "add".to_string(),
+                vec![
+                    "--priority".to_string(),
+                    "--due".to_string(),
+                    "--project".to_string()
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn counts_endpoints() {
+        assert_eq!(endpoint("rest/v2/tasks"), "rest/v2/tasks");
+        assert_eq!(
+            endpoint("/rest/v2/tasks/7000001/close"),
+            "rest/v2/tasks/:id/close"
+        );
+        assert_eq!(
+            endpoint("sync/v9/completed/get_all"),
+            "sync/v9/completed/get_all"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, Utc};
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+
+use crate::{
+    command::{Command, Context},
+    config::Config,
+    duration::Since,
+};
+
+use super::{Invocation, Latency};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Only counts the invocations since then, like `monday`, `7d` or `2022-08-01`.
+    #[arg(short = 's', long = "since")]
+    since: Option<Since>,
+    /// Removes all recorded statistics.
+    #[arg(long = "reset", conflicts_with = "since")]
+    reset: bool,
+}
+
+/// Shows how often commands and flags were used, and how long API calls took on average.
+pub fn report(params: Params, cfg: &Config) -> Result<()> {
+    let store = cfg.store()?;
+    if params.reset {
+        super::reset(&store)?;
+        println!("removed the usage statistics");
+        return Ok(());
+    }
+    let since = params.since.map(|since| {
+        let now = cfg.override_time.unwrap_or_else(Utc::now);
+        since.resolve(now.with_timezone(&Local)).with_timezone(&Utc)
+    });
+    let invocations = super::invocations(&store)?
+        .into_iter()
+        .filter(|i| since.is_none_or(|since| i.timestamp >= since))
+        .collect::<Vec<_>>();
+    if invocations.is_empty() {
+        match cfg.usage_stats {
+            true => println!("no usage was recorded yet"),
+            false => println!(
+                "usage statistics are off, set `usage_stats = true` in the config to collect them"
+            ),
+        }
+        return Ok(());
+    }
+    print!("{}", render(&invocations, since));
+    Ok(())
+}
+
+fn render(invocations: &[Invocation], since: Option<DateTime<Utc>>) -> String {
+    let mut commands = BTreeMap::<&str, u64>::new();
+    let mut flags = BTreeMap::<String, u64>::new();
+    let mut requests = BTreeMap::<&str, Latency>::new();
+    for invocation in invocations {
+        *commands.entry(&invocation.command).or_default() += 1;
+        for flag in &invocation.flags {
+            *flags
+                .entry(format!("{} {}", invocation.command, flag))
+                .or_default() += 1;
+        }
+        for (endpoint, latency) in &invocation.requests {
+            requests.entry(endpoint).or_default().add(*latency);
+        }
+    }
+    let since = since.unwrap_or(invocations[0].timestamp);
+    let mut out = format!(
+        "{} invocations since {}\n",
+        invocations.len(),
+        since.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+    );
+    out.push_str(&section(
+        "Commands",
+        commands
+            .into_iter()
+            .map(|(command, n)| (command.to_string(), n, n.to_string()))
+            .collect(),
+    ));
+    out.push_str(&section(
+        "Flags",
+        flags
+            .into_iter()
+            .map(|(flag, n)| (flag, n, n.to_string()))
+            .collect(),
+    ));
+    out.push_str(&section(
+        "API calls",
+        requests
+            .into_iter()
+            .map(|(endpoint, latency)| {
+                let calls = match latency.count {
+                    1 => "call",
+                    _ => "calls",
+                };
+                let value = format!(
+                    "{} {}, {}ms on average",
+                    latency.count,
+                    calls,
+                    latency.average()
+                );
+                (endpoint.to_string(), latency.count, value)
+            })
+            .collect(),
+    ));
+    out
+}
+
+/// Renders the rows of a section by their name, count and value, most used first and with the
+/// values aligned. Returns nothing if there are no rows.
+fn section(title: &str, mut rows: Vec<(String, u64, String)>) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let width = rows
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut out = format!(
+        "\n{}\n",
+        title.if_supports_color(Stream::Stdout, |t| t.bold())
+    );
+    for (name, _, value) in rows {
+        out.push_str(&format!("  {:width$}  {}\n", name, value, width = width));
+    }
+    out
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        report(self, &ctx.cfg)
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{Duration, Utc};
+use color_eyre::Result;
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, TaskID, UpdateTask},
+    },
+    command::{Command, Context},
+    config::Config,
+    duration,
+};
+
+use super::{load, save, Waiting, WAITING_LABEL};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Use `list` to find out what ID your task has.
+    id: TaskID,
+    /// What or who the task waits on, like `vendor reply`.
+    #[arg(long = "on")]
+    on: String,
+    /// How long to wait before nagging about the task, like `3d`.
+    #[arg(long = "nag", default_value = "3d", value_parser = duration::parse)]
+    nag: Duration,
+}
+
+/// Marks the task as waiting on something, adding the waiting label to it.
+pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let task = gw.task(&params.id).await?;
+    if !task.labels.iter().any(|l| l == WAITING_LABEL) {
+        let mut labels = task.labels.clone();
+        labels.push(WAITING_LABEL.to_string());
+        gw.update(
+            &task.id,
+            &UpdateTask {
+                labels: Some(labels),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+    let store = cfg.store()?;
+    let mut waiting = load(&store)?;
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    waiting.insert(
+        task.id,
+        Waiting {
+            content: task.content.clone(),
+            on: params.on.clone(),
+            since: now,
+            nag_seconds: params.nag.num_seconds(),
+            nag_at: now + params.nag,
+        },
+    );
+    save(&store, &waiting)?;
+    println!(
+        "{} is waiting on {}, nagging after {}",
+        Content(&task.content),
+        Content(&params.on),
+        duration::format(params.nag)
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        add(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,57 @@
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, TaskID, UpdateTask},
+    },
+    command::{Command, Context},
+    config::Config,
+};
+
+use super::{load, save, WAITING_LABEL};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The Task ID as provided from the Todoist API. Use `waiting --all` to see which tasks are
+    /// waited on.
+    id: TaskID,
+}
+
+/// Stops waiting on the task, removing the waiting label from it.
+pub async fn done(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let store = cfg.store()?;
+    let mut waiting = load(&store)?;
+    let item = waiting
+        .remove(&params.id)
+        .ok_or_else(|| eyre!("task {} is not waiting on anything", params.id))?;
+    let task = gw.task(&params.id).await?;
+    if task.labels.iter().any(|l| l == WAITING_LABEL) {
+        gw.update(
+            &task.id,
+            &UpdateTask {
+                labels: Some(
+                    task.labels
+                        .into_iter()
+                        .filter(|l| l != WAITING_LABEL)
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+    save(&store, &waiting)?;
+    println!(
+        "{} is no longer waiting on {}",
+        Content(&item.content),
+        Content(&item.on)
+    );
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        done(self, &ctx.gw, &ctx.cfg).await
+    }
+}
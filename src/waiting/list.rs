@@ -0,0 +1,97 @@
+use chrono::{Duration, Utc};
+use color_eyre::{eyre::eyre, Result};
+
+use crate::{
+    api::{
+        content::Content,
+        rest::{Gateway, TaskDue, UpdateTask},
+    },
+    command::{Command, Context},
+    config::Config,
+    duration,
+};
+
+use super::{load, save};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Lists all tasks that are waited on, not only the ones that are due for a nag.
+    #[arg(short = 'a', long = "all")]
+    all: bool,
+    /// Moves the due date of the tasks that are nagged about to today, and waits another nag
+    /// interval before nagging about them again.
+    #[arg(long = "bump")]
+    bump: bool,
+}
+
+/// Lists the tasks that waited for longer than their nag interval.
+pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let store = cfg.store()?;
+    let mut waiting = load(&store)?;
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let mut nagged = Vec::new();
+    for (id, item) in waiting.iter() {
+        let nag = item.nag_at <= now;
+        if !nag && !params.all {
+            continue;
+        }
+        // Minutes are precise enough for how long something waited.
+        let waited = Duration::minutes((now - item.since).num_minutes());
+        println!(
+            "{}{} {} (waiting on {} for {})",
+            if nag { "! " } else { "" },
+            id,
+            Content(&item.content),
+            Content(&item.on),
+            duration::format(waited)
+        );
+        if nag {
+            nagged.push(id.clone());
+        }
+    }
+    if !params.bump || nagged.is_empty() {
+        return Ok(());
+    }
+    // Closed and deleted tasks are not returned, so they are not reopened by moving them.
+    let tasks = gw.tasks_by_ids(&nagged).await?;
+    let (mut moved, mut failed) = (0, 0);
+    for id in &nagged {
+        let Some(task) = tasks.iter().find(|t| &t.id == id) else {
+            eprintln!("skipping task {}, it was closed or deleted", id);
+            continue;
+        };
+        // A new due date would replace the recurrence, so recurring tasks only wait another
+        // nag interval.
+        if !task.due.as_ref().is_some_and(|due| due.is_recurring) {
+            let update = UpdateTask {
+                due: Some(TaskDue::String("today".to_string())),
+                ..Default::default()
+            };
+            if let Err(e) = gw.update(id, &update).await {
+                eprintln!("unable to move task {} to today: {:#}", id, e);
+                failed += 1;
+                continue;
+            }
+            moved += 1;
+        }
+        if let Some(item) = waiting.get_mut(id) {
+            item.nag_at = now + item.nag();
+        }
+        // Saved after every task, so that the ones that were moved already are not moved again
+        // if a later one fails.
+        save(&store, &waiting)?;
+    }
+    if moved > 0 {
+        eprintln!("moved {} tasks to today", moved);
+    }
+    if failed > 0 {
+        return Err(eyre!("unable to move {} of {} tasks", failed, nagged.len()));
+    }
+    Ok(())
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        list(self, &ctx.gw, &ctx.cfg).await
+    }
+}
@@ -0,0 +1,50 @@
+//! Tracks tasks that were delegated and wait on someone else, and nags about them once they waited
+//! for too long.
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{api::rest::TaskID, store::Store};
+
+pub mod add;
+pub mod done;
+pub mod list;
+
+/// The file in the [`Store`] that contains the tasks that are waited on.
+pub(crate) const WAITING_FILE: &str = "waiting.json";
+/// The label that is added to tasks that are waited on, so they stand out in the app as well.
+const WAITING_LABEL: &str = "waiting";
+
+/// Waiting describes what a task waits on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Waiting {
+    /// The content of the task at the time it started waiting.
+    pub content: String,
+    /// What or who the task waits on.
+    pub on: String,
+    /// When the task started waiting.
+    pub since: DateTime<Utc>,
+    /// How many seconds to wait before nagging again.
+    pub nag_seconds: i64,
+    /// When to nag about the task.
+    pub nag_at: DateTime<Utc>,
+}
+
+impl Waiting {
+    /// Returns the interval after which the task is nagged about.
+    pub fn nag(&self) -> Duration {
+        Duration::seconds(self.nag_seconds)
+    }
+}
+
+/// Returns all tasks that are waited on, by their ID.
+pub(crate) fn load(store: &Store) -> Result<BTreeMap<TaskID, Waiting>> {
+    store.load(WAITING_FILE)
+}
+
+/// Saves the tasks that are waited on.
+pub(crate) fn save(store: &Store, waiting: &BTreeMap<TaskID, Waiting>) -> Result<()> {
+    store.save(WAITING_FILE, waiting)
+}
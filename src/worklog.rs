@@ -0,0 +1,177 @@
+//! Keeps tasks up to date with the commits that worked on them, by commenting on every task that
+//! commits link to with a `Todoist: <id>` trailer.
+use std::{collections::BTreeMap, process};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use dialoguer::console::Term;
+
+use crate::{
+    api::rest::{CreateComment, Gateway, TaskID, ThreadID},
+    command::{Command, Context},
+    interactive,
+};
+
+/// The trailer of commit messages that links them to a task.
+const TRAILER: &str = "todoist";
+
+/// Separates the fields of a commit in the log output.
+const FIELD: char = '\x1f';
+/// Separates the commits in the log output.
+const RECORD: char = '\x1e';
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The commits to log, as a git revision range like `main..HEAD` or `v1.2.0..`.
+    range: String,
+    /// Posts the comments without asking for confirmation.
+    #[arg(short = 'y', long = "yes")]
+    yes: bool,
+}
+
+/// A commit that links to tasks.
+#[derive(Debug, PartialEq, Eq)]
+struct Commit {
+    hash: String,
+    subject: String,
+    tasks: Vec<TaskID>,
+}
+
+/// Comments on every task that is linked from a commit in the range, with a list of those commits.
+pub async fn worklog(params: Params, gw: &Gateway) -> Result<()> {
+    let format = format!(
+        "--format=%h{f}%s{f}%(trailers:key={},valueonly,separator=%x2C){r}",
+        TRAILER,
+        f = "%x1f",
+        r = "%x1e"
+    );
+    let output = process::Command::new("git")
+        .args(["log", "--reverse", &format, &params.range, "--"])
+        .output()
+        .wrap_err("unable to run git")?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "unable to read the commits of {}: {}",
+            params.range,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let comments = comments(
+        &params.range,
+        &parse_log(&String::from_utf8_lossy(&output.stdout)),
+    );
+    if comments.is_empty() {
+        println!(
+            "no commits in {} link to a task with a \"Todoist: <id>\" trailer",
+            params.range
+        );
+        return Ok(());
+    }
+    for (task, comment) in &comments {
+        println!("Comment on {}:\n{}\n", task, comment);
+    }
+    let post = if params.yes {
+        true
+    } else if Term::stderr().is_term() {
+        interactive::confirm(&format!("Post these {} comments?", comments.len()))?
+    } else {
+        eprintln!("run again with --yes to post the comments");
+        false
+    };
+    if !post {
+        return Ok(());
+    }
+    for (task, content) in comments {
+        gw.create_comment(&CreateComment {
+            thread: ThreadID::Task { task_id: task },
+            content,
+            attachment: None,
+        })
+        .await?;
+    }
+    println!("posted the worklog");
+    Ok(())
+}
+
+/// Parses the commits out of the log output, leaving out the ones without tasks.
+fn parse_log(log: &str) -> Vec<Commit> {
+    log.split(RECORD)
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, FIELD);
+            let (hash, subject, trailers) = (fields.next()?, fields.next()?, fields.next()?);
+            let tasks = trailers
+                .split([',', '\n'])
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            if tasks.is_empty() {
+                return None;
+            }
+            Some(Commit {
+                hash: hash.to_string(),
+                subject: subject.to_string(),
+                tasks,
+            })
+        })
+        .collect()
+}
+
+/// Writes a comment for each task, listing the commits that link to it in order.
+fn comments(range: &str, commits: &[Commit]) -> BTreeMap<TaskID, String> {
+    let mut comments = BTreeMap::new();
+    for commit in commits {
+        for task in &commit.tasks {
+            let comment = comments
+                .entry(task.clone())
+                .or_insert_with(|| format!("Worklog of `{}`:\n", range));
+            comment.push_str(&format!("\n- `{}` {}", commit.hash, commit.subject));
+        }
+    }
+    comments
+}
+
+impl Command for Params {
+    async fn execute(self, ctx: &Context) -> Result<()> {
+        worklog(self, &ctx.gw).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_log() {
+        let log = "a1b2c3d\x1fFix the parser\x1f7000001\x1e\n\
+                   d4e5f6a\x1fUpdate the readme\x1f\x1e\n\
+                   b7c8d9e\x1fHandle tabs, too\x1f7000001,7000002\x1e\n";
+        let commits = parse_log(log);
+        assert_eq!(
+            commits,
+            [
+                Commit {
+                    hash: "a1b2c3d".to_string(),
+                    subject: "Fix the parser".to_string(),
+                    tasks: vec!["7000001".to_string()],
+                },
+                Commit {
+                    hash: "b7c8d9e".to_string(),
+                    subject: "Handle tabs, too".to_string(),
+                    tasks: vec!["7000001".to_string(), "7000002".to_string()],
+                },
+            ]
+        );
+        let comments = comments("main..HEAD", &commits);
+        assert_eq!(
+            comments["7000001"],
+            "Worklog of `main..HEAD`:\n\n- `a1b2c3d` Fix the parser\n- `b7c8d9e` Handle tabs, too"
+        );
+        assert_eq!(
+            comments["7000002"],
+            "Worklog of `main..HEAD`:\n\n- `b7c8d9e` Handle tabs, too"
+        );
+    }
+}
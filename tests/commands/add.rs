@@ -0,0 +1,364 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::matchers;
+
+#[tokio::test]
+async fn add() -> Result<()> {
+    for test in &[vec!["add"], vec!["a"]] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_projects(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+        mocks::mock_labels(&cmd, 1).await;
+        mocks::mock_create_task(&cmd, 1, |mb| {
+            mb.and(matchers::body_partial_json(serde_json::json!({
+                "content": "Buy some flowers",
+                "due_string": "tomorrow",
+                "priority": 3,
+                "labels": ["two"],
+                "project_id": "1000002",
+            })))
+        })
+        .await;
+
+        let mut command = cmd.cmd()?;
+        for arg in test {
+            command.arg(arg);
+        }
+        command
+            .args(["Buy some flowers", "-d", "tomorrow", "-p", "2"])
+            .args(["-P", "Project One", "-L", "two"])
+            .assert()
+            .success()
+            .stdout(predicate::eq(
+                "created task: 7000100 p2 Buy some flowers tomorrow @two [Project One]\n",
+            ));
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn german() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.language = Some("de".to_string());
+    cmd.cfg.save()?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Blumen kaufen",
+            "due_string": "morgen",
+            "due_lang": "de",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["add", "Blumen kaufen", "-d", "morgen"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Aufgabe erstellt: 7000100 "));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_name() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["add", " "])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("task name can not be empty"));
+    assert!(cmd.mock.received_requests().await.unwrap().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_only() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+
+    // Creating the task is not mocked, so sending it would fail with a different error.
+    cmd.cmd()?
+        .args(["--read-only", "add", "Buy some flowers"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "refusing to send POST rest/v2/tasks in read-only mode",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rerun_after_failure() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.retry.max_retries = Some(0);
+    cmd.cfg.save()?;
+
+    mocks::mock_projects(&cmd, 3).await;
+    mocks::mock_sections(&cmd, 3).await;
+    mocks::mock_labels(&cmd, 3).await;
+    mocks::mock_error(&cmd, "POST", "/rest/v2/tasks", 500, 1).await;
+    mocks::mock_create_task(&cmd, 2, |mb| mb).await;
+
+    cmd.cmd()?
+        .args(["add", "Buy some flowers"])
+        .assert()
+        .failure();
+    for _ in 0..2 {
+        cmd.cmd()?
+            .args(["add", "Buy some flowers"])
+            .assert()
+            .success();
+    }
+    let keys = cmd
+        .mock
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.method.as_str() == "POST")
+        .map(|r| r.headers["X-Request-Id"].to_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    // Running the command again continues the failed one, after that it adds another task.
+    assert_eq!(keys.len(), 3);
+    assert_eq!(keys[0], keys[1]);
+    assert_ne!(keys[1], keys[2]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn auto_label() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.suggest_labels = true;
+    cmd.cfg.label_rules = vec![doist::config::LabelRule {
+        keyword: "invoice".to_string(),
+        label: "one".to_string(),
+    }];
+    cmd.cfg.save()?;
+
+    mocks::mock_projects(&cmd, 4).await;
+    mocks::mock_sections(&cmd, 4).await;
+    mocks::mock_labels(&cmd, 4).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Send the invoice",
+            "labels": ["one"],
+        })))
+    })
+    .await;
+    mocks::mock_create_task(&cmd, 2, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Call the dentist",
+            "labels": ["two"],
+        })))
+    })
+    .await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Dentist appointment",
+            "labels": ["two"],
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["add", "Send the invoice", "--auto-label"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("adding suggested labels @one"));
+    // Labels given to earlier tasks are learned.
+    for _ in 0..2 {
+        cmd.cmd()?
+            .args(["add", "Call the dentist", "-L", "two"])
+            .assert()
+            .success();
+    }
+    cmd.cmd()?
+        .args(["add", "Dentist appointment", "--auto-label"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("adding suggested labels @two"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn smart_due() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Pay rent",
+            "due_string": "1st",
+        })))
+    })
+    .await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Read on the 1st floor",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["add", "Pay rent on the 1st"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "using \"1st\" from the task name as due date",
+        ));
+    cmd.cmd()?
+        .args(["add", "Read on the 1st floor", "--nosmartdue"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn template() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.templates.insert(
+        "bug".to_string(),
+        doist::config::Template {
+            content: Some("Bug: {{name}}".to_string()),
+            description: Some("Found on {{date}} in {{branch}}".to_string()),
+        },
+    );
+    cmd.cfg.save()?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Bug: crash on start",
+            "description": "Found on 2022-08-26 in main",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .env("BRANCH", "main")
+        .args(["add", "crash on start", "--template", "bug"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .args(["add", "crash on start", "-t", "feature"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "there is no template named feature in the config",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn here() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg
+        .repos
+        .insert("website".to_string(), "Project One".to_string());
+    cmd.cfg.save()?;
+    let repo = cmd.tmp.path().join("website");
+    std::fs::create_dir(&repo)?;
+    std::process::Command::new("git")
+        .args(["init", "--quiet", "--initial-branch=main"])
+        .current_dir(&repo)
+        .assert()
+        .success();
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Fix the parser",
+            "description": "It breaks on tabs\n\nFrom website on main at src/parse.rs:42",
+            "project_id": "1000002",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .current_dir(&repo)
+        .args(["add", "Fix the parser", "-D", "It breaks on tabs", "--here"])
+        .args(["--at", "src/parse.rs:42"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .current_dir(cmd.tmp.path())
+        .args(["add", "Fix the parser", "--here"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "only works inside of a git repository",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn parent() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Write tests",
+            "parent_id": "7000001",
+            "project_id": null,
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["add", "Write tests", "--parent", "7000001"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .args([
+            "add",
+            "Write tests",
+            "--parent",
+            "7000001",
+            "-P",
+            "Project One",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn agenda() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_create_comment(&cmd, 4, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "discussed on 2022-08-26",
+        })))
+    })
+    .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path_regex(r"^/rest/v2/tasks/\d+$"))
+        .and(matchers::body_json(serde_json::json!({ "labels": [] })))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(4)
+        .mount(&cmd.mock)
+        .await;
+
+    let agenda = "# Agenda for @two on 2022-08-26\n\n\
+                  - [ ] Shake it\n\
+                  - [ ] Test\n  > Testing\n\
+                  - [ ] Bla bla\n\
+                  - [ ] Write it down\n";
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["agenda", "two"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(agenda));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["agenda", "@two", "--discussed"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(agenda))
+        .stderr(predicate::str::contains("marked 4 tasks as discussed"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redact() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["--redact", "agenda", "two"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("# Agenda for @")
+                .and(predicate::str::contains("@two").not())
+                .and(predicate::str::contains("Shake it").not())
+                .and(predicate::str::contains("Testing").not()),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn audit() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_error(&cmd, "DELETE", "/rest/v2/labels/1999991", 400, 1).await;
+
+    cmd.cmd()?.args(["close", "7000001"]).assert().success();
+    cmd.cmd()?
+        .args(["labels", "delete", "-L", "one"])
+        .assert()
+        .failure();
+    cmd.mock.verify().await;
+
+    cmd.cmd()?
+        .arg("audit")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            " 204 POST rest/v2/tasks/7000001/close\n",
+        ))
+        .stdout(predicate::str::contains("close 7000001\n"))
+        .stdout(predicate::str::contains(
+            " 400 DELETE rest/v2/labels/1999991\n",
+        ));
+    cmd.cmd()?
+        .args(["audit", "--failed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rest/v2/tasks/7000001/close").not())
+        .stdout(predicate::str::contains("DELETE rest/v2/labels/1999991"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn usage() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .arg("usage")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("usage statistics are off"));
+
+    cmd.cfg.usage_stats = true;
+    cmd.cfg.save()?;
+    mocks::mock_close_task(&cmd, 2).await;
+    mocks::mock_task(&cmd, 2).await;
+    mocks::mock_task_by_id(&cmd, "7000008", 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_update_task(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?.args(["close", "7000001"]).assert().success();
+    cmd.cmd()?.args(["c", "7000001"]).assert().success();
+    cmd.cmd()?
+        .args(["--read-only", "edit", "7000001", "-p1"])
+        .assert()
+        .failure();
+
+    cmd.cmd()?
+        .arg("usage")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("3 invocations since "))
+        .stdout(predicate::str::contains("  close  2\n  edit   1\n"))
+        .stdout(predicate::str::contains(
+            "  edit --config_prefix   1\n  edit --priority        1\n  edit --read-only       1\n",
+        ))
+        .stdout(predicate::str::contains(
+            "  GET rest/v2/tasks/:id         2 calls, ",
+        ))
+        .stdout(predicate::str::contains(
+            "  POST rest/v2/tasks/:id/close  2 calls, ",
+        ));
+    cmd.cmd()?.args(["usage", "--reset"]).assert().success();
+    cmd.cmd()?
+        .arg("usage")
+        .assert()
+        .success()
+        .stdout(predicate::eq("no usage was recorded yet\n"));
+
+    Ok(())
+}
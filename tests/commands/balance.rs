@@ -0,0 +1,38 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn balance() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["balance", "--days", "21", "--max", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Sat 08-27       3      3  overloaded\n",
+        ))
+        .stdout(predicate::str::contains(
+            "Tue 09-13       3      2  overloaded\n",
+        ))
+        .stdout(predicate::str::contains(
+            "Move \"Coffee\" from Thu 09-15 to Sun 09-11\n",
+        ))
+        .stderr(predicate::str::contains("run again with --yes"));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["balance", "--days", "21", "--max", "2", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("moved 2 tasks\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn prewarm() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .arg("prewarm")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("the cache is off"));
+
+    cmd.cfg.cache.max_age = Some("1h".to_string());
+    cmd.cfg.save()?;
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["prewarm", "--foreground"])
+        .assert()
+        .success();
+    // Everything is fresh, so nothing is fetched again.
+    cmd.cmd()?.arg("prewarm").assert().success();
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::TASK_OUTPUT));
+    // Changes make the tasks stale, but keep the other lists.
+    cmd.cmd()?.args(["close", "7000001"]).assert().success();
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::TASK_OUTPUT));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+/// Commands that are answered from the cache never reach the API, which is what makes them start
+/// fast.
+#[tokio::test]
+async fn startup() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.cache.max_age = Some("1h".to_string());
+    cmd.cfg.save()?;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["prewarm", "--foreground"])
+        .assert()
+        .success();
+    let prewarmed = cmd.mock.received_requests().await.unwrap().len();
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success();
+    assert_eq!(cmd.mock.received_requests().await.unwrap().len(), prewarmed);
+
+    Ok(())
+}
+
+/// Commands that are answered from the cache should start about as fast as a shell prompt.
+const STARTUP_BUDGET: Duration = Duration::from_millis(30);
+
+/// Measures the time it takes, which only means something for a release build on an idle
+/// machine. CI runs it like that with `cargo test --release -- --ignored`.
+#[tokio::test]
+#[ignore]
+async fn startup_time() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.cache.max_age = Some("1h".to_string());
+    cmd.cfg.save()?;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["prewarm", "--foreground"])
+        .assert()
+        .success();
+    // Takes the fastest of a few runs, so that a busy machine doesn't fail the test.
+    let mut fastest = Duration::MAX;
+    for _ in 0..5 {
+        let mut list = cmd.cmd()?;
+        list.args(["list", "--nointeractive"]);
+        let started = Instant::now();
+        list.assert().success();
+        fastest = fastest.min(started.elapsed());
+    }
+    assert!(
+        fastest < STARTUP_BUDGET,
+        "listing cached tasks took {:?}, more than {:?}",
+        fastest,
+        STARTUP_BUDGET
+    );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
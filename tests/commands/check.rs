@@ -0,0 +1,42 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn check() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 3).await;
+    mocks::mock_labels(&cmd, 3).await;
+    mocks::mock_projects(&cmd, 3).await;
+    mocks::mock_sections(&cmd, 3).await;
+
+    // One task is overdue and two are due today.
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["check", "--due-within", "2h"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Bla bla").and(predicate::str::contains("Big crash")))
+        .stderr(predicate::str::contains(
+            "3 tasks are due within 2h, more than the allowed 0",
+        ));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["check", "--due-within", "2h", "--max", "3", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    // Tasks without a time count once their day starts.
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["check", "--due-within", "5h", "--max", "3", "-q"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("4 tasks are due within 5h"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
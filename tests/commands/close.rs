@@ -0,0 +1,127 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn close() -> Result<()> {
+    for test in &[vec!["close"], vec!["c"]] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_tasks(&cmd, 1).await;
+        mocks::mock_projects(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+        mocks::mock_labels(&cmd, 1).await;
+        mocks::mock_close_task(&cmd, 1).await;
+        mocks::mock_task(&cmd, 1).await;
+
+        let mut command = cmd.cmd()?;
+        for arg in test {
+            command.arg(arg);
+        }
+        command
+            .arg("7000001")
+            .assert()
+            .success()
+            .stdout(predicate::eq(
+                "closed task 7000001\nnext due date: 2022-08-27\n",
+            ));
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_many() -> Result<()> {
+    for args in [
+        vec!["close", "7000008", "7000006"],
+        vec!["close", "7000008,7000006"],
+        vec!["close", "--filter", "#Project Three"],
+    ] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_tasks_partial(&cmd, 1).await;
+        for id in ["7000008", "7000006"] {
+            Mock::given(matchers::method("POST"))
+                .and(matchers::path(format!("/rest/v2/tasks/{}/close", id)))
+                .respond_with(ResponseTemplate::new(204))
+                .expect(1)
+                .mount(&cmd.mock)
+                .await;
+            mocks::mock_task_by_id(&cmd, id, 1).await;
+        }
+
+        cmd.cmd()?
+            .args(args)
+            .assert()
+            .success()
+            .stdout(predicate::eq("closed task 7000008\nclosed task 7000006\n"));
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reopen() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_reopen_task(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["reopen", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("reopened task 7000001\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn done_recurring() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 2).await;
+
+    cmd.cmd()?
+        .args(["done", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "task 7000001 recurs every day, so it advances to its next occurrence instead of \
+             being completed. Use `--stop-recurring` to end the recurrence.\n\
+             closed task 7000001\nnext due date: 2022-08-27\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn done_stop_recurring() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(
+            serde_json::json!({"due_date": "2022-08-27"}),
+        ))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["done", "7000001", "--stop-recurring"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "stopped the recurrence of task 7000001, it stays due on 2022-08-27\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+/// A task in the shared Project Five that is assigned to Ann.
+fn assigned_task() -> serde_json::Value {
+    serde_json::json!({
+        "id": "7000200",
+        "project_id": "1000006",
+        "order": 1,
+        "content": "Water the plants",
+        "description": "",
+        "is_completed": false,
+        "labels": [],
+        "priority": 1,
+        "comment_count": 0,
+        "creator_id": "2671362",
+        "assignee_id": "1111111111",
+        "created_at": "2022-04-28T03:09:47Z",
+        "url": "https://todoist.com/showTask?id=7000200"
+    })
+}
+
+#[tokio::test]
+async fn list_collaborators() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_collaborators(&cmd, "1000006", 1).await;
+
+    cmd.cmd()?
+        .args(["projects", "collaborators", "-P", "Project Five"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "1111111111 +Ann Lee <ann@example.com>\n2671362 +Bob Stone <bob@example.com>\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn not_shared() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["projects", "collaborators", "-P", "Project One"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "project Project One is not shared",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_assigned() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_collaborators(&cmd, "1000006", 1).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Buy some flowers",
+            "project_id": "1000006",
+            "assignee_id": "2671362",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args([
+            "add",
+            "Buy some flowers",
+            "-P",
+            "Project Five",
+            "--assign",
+            "bob",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "created task: 7000100 p2 Buy some flowers tomorrow +Bob Stone [Project Five]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_needs_shared_project() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args([
+            "add",
+            "Buy some flowers",
+            "-P",
+            "Project One",
+            "--assign",
+            "bob",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--assign needs a shared project to be chosen",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn edit_assigned() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_collaborators(&cmd, "1000006", 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000200"))
+        .respond_with(ResponseTemplate::new(200).set_body_json([assigned_task()]))
+        // Once more to remember the task for undo.
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks/7000200"))
+        .and(matchers::body_json(
+            serde_json::json!({"assignee_id": "2671362"}),
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["edit", "7000200", "--assign", "bob@example.com"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_assignee() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_collaborators(&cmd, "1000006", 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json([assigned_task()]))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "7000200 p4 Water the plants +Ann Lee [Project Five]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
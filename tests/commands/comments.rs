@@ -0,0 +1,106 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn task() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_comments(&cmd, ("task_id", "7000001"), 1).await;
+
+    cmd.cmd()?
+        .args(["comments", "7000001"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("Comments:\n-----\nID: 2992679862\n")
+                .and(predicate::str::contains("discussed on 2022-08-26"))
+                .and(predicate::str::contains("follow up next week")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn project() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_comments(&cmd, ("project_id", "1000002"), 1).await;
+
+    cmd.cmd()?
+        .args(["projects", "comments", "--project_id", "1000002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("follow up next week"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn attach() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("report.pdf");
+    file.write_str("%PDF-1.4 quarterly numbers")?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/sync/v9/uploads/add"))
+        .and(matchers::header_regex(
+            "content-type",
+            "^multipart/form-data; boundary=",
+        ))
+        .and(matchers::body_string_contains(
+            "Content-Disposition: form-data; name=\"file\"; filename=\"report.pdf\"\r\n\
+             Content-Type: application/pdf\r\n\r\n\
+             %PDF-1.4 quarterly numbers\r\n",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "file_name": "report.pdf",
+            "file_size": 26,
+            "file_type": "application/pdf",
+            "file_url": "https://files.todoist.com/report.pdf",
+            "resource_type": "file",
+            "upload_state": "completed",
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_create_comment(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "task_id": "7000001",
+            "content": "see attached",
+            "attachment": {
+                "file_name": "report.pdf",
+                "file_size": 26,
+                "file_type": "application/pdf",
+                "file_url": "https://files.todoist.com/report.pdf",
+                "resource_type": "file",
+            },
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["comment", "see attached", "7000001", "--file"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("created comment: "));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn completed() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_completed(&cmd, ("since", "2022-08-19T19:33:20"), 1).await;
+
+    cmd.cmd()?
+        .arg("completed")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Thursday 25 August\n  08:12 Pay rent [Inbox]\n\n\
+             Tuesday 23 August\n  17:45 Water the plants [Project One] @two\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn completed_in_project() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/sync/v9/completed/get_all"))
+        .and(matchers::query_param("project_id", "1000002"))
+        .and(matchers::query_param("since", "2022-08-26T00:00:00"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"items": []}"#))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["completed", "--since", "today", "-P", "Project One"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "no tasks were completed since 2022-08-26 00:00:00",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::io::Read;
+
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn bundle() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 1).await;
+    cmd.cmd()?.args(["close", "7000001"]).assert().success();
+
+    let output = cmd.tmp.child("bundle.zip");
+    cmd.cmd()?
+        .args(["debug", "bundle", "--output"])
+        .arg(output.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("wrote debug bundle to "));
+
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(output.path())?)?;
+    let mut read = |name: &str| -> Result<String> {
+        let mut content = String::new();
+        zip.by_name(name)?.read_to_string(&mut content)?;
+        Ok(content)
+    };
+    assert!(read("version.txt")?.starts_with("doist "));
+    let config = read("config.toml")?;
+    assert!(config.contains("token = \"<redacted>\""));
+    assert!(!config.contains("AUTH_KEY"));
+    assert!(read("state.txt")?.contains("audit.jsonl"));
+    assert!(read("audit.jsonl")?.contains("\"path\":\"rest/v2/tasks/7000001/close\""));
+
+    Ok(())
+}
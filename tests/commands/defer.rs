@@ -0,0 +1,104 @@
+use super::setup::Tool;
+use super::{fixtures, mocks};
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn defer() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_task_by_id(&cmd, "7000008", 1).await;
+    mocks::mock_task(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["defer", "7000008", "--until-done", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "deferred task 7000008 until 7000001 Bla bla is done\n",
+        ));
+    cmd.cmd()?
+        .arg("defer")
+        .assert()
+        .success()
+        .stdout(predicate::eq("7000008 Nope waits for 7000001 Bla bla\n"));
+    cmd.mock.verify().await;
+
+    // Hidden from lists while the other task is open.
+    cmd.mock.reset().await;
+    mocks::mock_tasks_by_ids(&cmd, "7000001", 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(fixtures::TASK_OUTPUT.replace(
+            "  ⌞ 7000008 p4 Nope [Project Three/Section Five]\n",
+            "",
+        )));
+    cmd.mock.verify().await;
+
+    // Shown again once the other task is done.
+    cmd.mock.reset().await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000001"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(fixtures::TASK_OUTPUT))
+        .stderr(predicate::eq(
+            "task 7000008 is back, as 7000001 Bla bla is done\n",
+        ));
+    cmd.mock.verify().await;
+    cmd.cmd()?
+        .arg("defer")
+        .assert()
+        .success()
+        .stdout(predicate::eq("no tasks are deferred\n"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn remove() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_task_by_id(&cmd, "7000008", 1).await;
+    mocks::mock_task(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["defer", "7000008", "-u", "7000001"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .args(["defer", "7000008", "--remove"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("task 7000008 is shown again\n"));
+    cmd.cmd()?
+        .args(["defer", "7000008", "--remove"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("task 7000008 is not deferred"));
+    cmd.cmd()?
+        .args(["defer", "7000008", "-u", "7000008"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("a task can not wait for itself"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn delete() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 2).await;
+    Mock::given(matchers::method("DELETE"))
+        .and(matchers::path("/rest/v2/tasks/7000001"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    // Without a terminal to confirm on, nothing is deleted.
+    cmd.cmd()?
+        .args(["delete", "7000001"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "run again with --yes to delete the task without asking",
+        ));
+    cmd.cmd()?
+        .args(["delete", "7000001", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("deleted task 7000001\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
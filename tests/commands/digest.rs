@@ -0,0 +1,72 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn digest() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_completed(&cmd, ("since", "2022-08-22T00:00:00"), 2).await;
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["digest", "--upcoming", "2d"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "# Digest from 2022-08-22 to 2022-08-26\n\n\
+             ## Completed\n\n\
+             ### Inbox\n\n\
+             - Pay rent\n\n\
+             ### Project One\n\n\
+             - Water the plants\n\n\
+             ## Added\n\n\
+             ### Inbox\n\n\
+             - Sign it\n\
+             - Check comments\n\
+             - Open data\n\n\
+             ## Upcoming\n\n\
+             ### Project One\n\n",
+        ))
+        .stdout(predicate::str::contains("- Big crash (2022-08-26)\n"))
+        .stdout(predicate::str::contains("- Woah").not());
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["digest", "--since", "monday", "--format", "html"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "<h2>Completed</h2>\n<h3>Inbox</h3>\n<ul>\n<li>Pay rent</li>\n</ul>\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redact() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_completed(&cmd, ("since", "2022-08-22T00:00:00"), 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["--redact", "digest", "--upcoming", "2d"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("## Completed\n")
+                .and(predicate::str::contains("Pay rent").not())
+                .and(predicate::str::contains("Project One").not())
+                .and(predicate::str::contains("Big crash").not()),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use super::setup::Tool;
+use super::{fixtures, mocks};
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn edit() -> Result<()> {
+    for test in &[vec!["edit"], vec!["e"]] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_tasks(&cmd, 1).await;
+        mocks::mock_projects(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+        mocks::mock_labels(&cmd, 1).await;
+        mocks::mock_update_task(&cmd, 1, |mb| {
+            mb.and(matchers::body_json(serde_json::json!({
+                "content": "New name",
+                "due_string": "next week",
+            })))
+        })
+        .await;
+
+        let mut command = cmd.cmd()?;
+        for arg in test {
+            command.arg(arg);
+        }
+        command
+            .args(["7000001", "-n", "New name", "-d", "next week"])
+            .assert()
+            .success();
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn edit_many() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    for id in ["7000008", "7000006"] {
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path(format!("/rest/v2/tasks/{}", id)))
+            .and(matchers::body_json(serde_json::json!({"priority": 4})))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .args(["edit", "7000008,7000006", "-p", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("edited 2 tasks\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn edit_filter() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let args = [
+        "edit",
+        "--filter",
+        "#Project Three",
+        "--set",
+        "due:today",
+        "-s",
+        "priority:2",
+    ];
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    cmd.cmd()?
+        .args(args)
+        .assert()
+        .failure()
+        .stdout(predicate::eq(
+            "set due to \"today\" and priority to 2 on 2 tasks:\n  \
+             7000006 p4 Darn [Project Three/Section Five]\n  \
+             7000008 p4 Nope [Project Three/Section Five]\n",
+        ))
+        .stderr(predicate::str::contains("run again with --yes"));
+    cmd.mock.verify().await;
+    cmd.mock.reset().await;
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    for id in ["7000008", "7000006"] {
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path(format!("/rest/v2/tasks/{}", id)))
+            .and(matchers::body_json(serde_json::json!({
+                "priority": 3,
+                "due_string": "today",
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+    cmd.cmd()?
+        .args(args)
+        .arg("--yes")
+        .assert()
+        .success()
+        .stdout(predicate::eq("edited 2 tasks\n"));
+    cmd.mock.verify().await;
+
+    cmd.cmd()?
+        .args(["edit", "7000001", "-d", "today", "--set", "due:tomorrow"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("due was given more than once"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quick_edit() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "content": "Call mom",
+            "priority": 4,
+            "due_string": "next monday",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["edit", "7000001", r#"Call mom !!p1 due:"next monday""#])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn business_days() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.holidays.ics = Some(format!("{}/holidays.ics", cmd.mock.uri()));
+    cmd.cfg.save()?;
+
+    // Downloaded only once, and cached after that.
+    mocks::mock_holidays(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_update_task(&cmd, 2, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "due_date": "2022-08-30",
+        })))
+    })
+    .await;
+
+    // Friday plus one business day skips the weekend and the holiday on Monday.
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["edit", "7000001", "-d", "+1d", "--business-days"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["edit", "7000001", "due:+3d", "--skip-holidays"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn postpone() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let mut task: serde_json::Value = serde_json::from_str(fixtures::TASK)?;
+    task["due"]["is_recurring"] = false.into();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vec![task]))
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "due_string": "in 2 days",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["postpone", "7000001", "--to", "+2d"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn postpone_all_overdue() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let tasks: Vec<serde_json::Value> = serde_json::from_str(fixtures::TASKS)?;
+    let overdue = tasks
+        .into_iter()
+        .filter(|t| t["id"] == "7000008" || t["id"] == "7000006")
+        .collect::<Vec<_>>();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("filter", "overdue"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&overdue))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000006,7000008"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&overdue))
+        .mount(&cmd.mock)
+        .await;
+    for id in ["7000008", "7000006"] {
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path(format!("/rest/v2/tasks/{}", id)))
+            .and(matchers::body_json(
+                serde_json::json!({"due_string": "tomorrow"}),
+            ))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&cmd.mock)
+            .await;
+    }
+
+    cmd.cmd()?
+        .args(["postpone", "--all-overdue"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("edited 2 tasks\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn postpone_skips_recurring() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    let tasks: Vec<serde_json::Value> = serde_json::from_str(fixtures::TASKS)?;
+    let overdue = tasks
+        .into_iter()
+        .filter(|t| t["id"] == "7000001" || t["id"] == "7000006")
+        .collect::<Vec<_>>();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("filter", "overdue"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&overdue))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000001,7000006"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&overdue))
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks/7000006"))
+        .and(matchers::body_json(
+            serde_json::json!({"due_string": "tomorrow"}),
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks/7000001"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(0)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["postpone", "--all-overdue"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "skipping task 7000001, postponing it would end its recurrence",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,245 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use std::time::{Duration, Instant};
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn unauthorized() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_error(&cmd, "GET", "/rest/v2/labels", 401, 1).await;
+
+    cmd.cmd()?
+        .args(["labels", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("401"));
+    assert_eq!(cmd.mock.received_requests().await.unwrap().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn explains_unauthorized() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_error(&cmd, "GET", "/rest/v2/labels", 401, 1).await;
+
+    cmd.cmd()?
+        .args(["labels", "list"])
+        .assert()
+        .code(77)
+        .stderr(predicate::str::contains(
+            "Todoist did not accept the API token",
+        ))
+        .stderr(predicate::str::contains("doist auth <TOKEN>"))
+        .stderr(predicate::str::contains("Location").not());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_server_error() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_error(&cmd, "GET", "/rest/v2/labels", 500, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["labels", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::LABELS_OUTPUT));
+    assert_eq!(cmd.mock.received_requests().await.unwrap().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_rate_limit() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_error(&cmd, "GET", "/rest/v2/projects", 429, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["projects", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::PROJECTS_OUTPUT));
+    assert_eq!(cmd.mock.received_requests().await.unwrap().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn honors_retry_after() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/projects"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "2"))
+        .up_to_n_times(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_projects(&cmd, 1).await;
+
+    let started = Instant::now();
+    cmd.cmd()?
+        .args(["projects", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::PROJECTS_OUTPUT));
+    assert!(started.elapsed() >= Duration::from_secs(2));
+    assert_eq!(cmd.mock.received_requests().await.unwrap().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tells_when_rate_limited() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.retry.max_delay = Some("10s".to_string());
+    cmd.cfg.save()?;
+
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/projects"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_projects(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["projects", "list"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_match(
+            r"rate limited, retrying at \d{2}:\d{2}",
+        )?);
+
+    cmd.mock.reset().await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/projects"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args(["projects", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("within 15 minutes. Try again at"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn gives_up_on_long_retry_after() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.retry.max_delay = Some("10s".to_string());
+    cmd.cfg.save()?;
+
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/projects"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .mount(&cmd.mock)
+        .await;
+
+    let started = Instant::now();
+    cmd.cmd()?
+        .args(["projects", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("429"));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(cmd.mock.received_requests().await.unwrap().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_can_be_turned_off() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.retry.max_retries = Some(0);
+    cmd.cfg.save()?;
+
+    mocks::mock_error(&cmd, "GET", "/rest/v2/labels", 500, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["labels", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("500"));
+    assert_eq!(cmd.mock.received_requests().await.unwrap().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bad_request_on_create() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_error(&cmd, "POST", "/rest/v2/tasks", 400, 1).await;
+
+    cmd.cmd()?
+        .args(["add", "Buy some flowers"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unable to create task"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn explains_invalid_due() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("Date is invalid"))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["add", "Buy some flowers", "--due", "whenever"])
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains("Todoist answered 400 Bad Request: Date is invalid")
+                .and(predicate::str::contains("--due 2022-09-01")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deadline() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_slow(&cmd, "GET", "/rest/v2/labels", Duration::from_secs(10)).await;
+
+    let started = Instant::now();
+    cmd.cmd()?
+        .args(["labels", "list", "--deadline", "1s"])
+        .assert()
+        .code(124)
+        .stderr(predicate::eq(
+            "the command did not finish within its deadline of 1s\n",
+        ));
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn export() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_activity(&cmd, "2022-08-26T00:00:00", 1).await;
+    mocks::mock_activity(&cmd, "2022-08-26T12:00:00", 1).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["events", "export"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "[\n  {\n    \"id\": \"955333384\",\n    \"type\": \"comment.added\",\n",
+        ))
+        .stdout(predicate::str::contains("\"type\": \"task.completed\""));
+    cmd.cmd()?
+        .args(["events", "export", "--since", "2022-08-26T12:00:00Z"])
+        .args(["--format", "jsonl"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "{\"id\":\"955333385\",\"type\":\"task.completed\",\
+             \"occurred_at\":\"2022-08-26T18:00:00Z\",\"object_id\":\"7000001\",\
+             \"project_id\":\"1000002\",\"task_id\":\"7000001\",\"title\":\"Bla bla\"}\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn log() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_activity(&cmd, "2022-08-26T00:00:00", 2).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .arg("log")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Fri 26 Aug 18:00 you completed task Bla bla [Project One]\n\
+             Fri 26 Aug 14:24 user 2671362 added comment Looks good [Project One]\n",
+        ));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["log", "--type", "comment", "-P", "one"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Fri 26 Aug 14:24 user 2671362 added comment Looks good [Project One]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn notion() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.notion.token = Some("secret".to_string());
+    cmd.cfg.notion.properties.id = Some("Todoist ID".to_string());
+    cmd.cfg.notion.properties.project = Some("Project".to_string());
+    cmd.cfg.save()?;
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/notion/databases/db1/query"))
+        .and(matchers::bearer_token("secret"))
+        .and(matchers::header("Notion-Version", "2022-06-28"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{
+                "id": "page1",
+                "properties": {"Todoist ID": {"rich_text": [{"plain_text": "7000008"}]}},
+            }],
+            "has_more": false,
+            "next_cursor": null,
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("PATCH"))
+        .and(matchers::path("/notion/pages/page1"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "properties": {"Name": {"title": [{"text": {"content": "Nope"}}]}},
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"id": "page1"})))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/notion/pages"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "parent": {"database_id": "db1"},
+            "properties": {
+                "Name": {"title": [{"text": {"content": "Darn"}}]},
+                "Todoist ID": {"rich_text": [{"text": {"content": "7000006"}}]},
+            },
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"id": "page2"})))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["export", "notion", "--database", "db1"])
+        .arg(format!("--api-url={}/notion/", cmd.mock.uri()))
+        .assert()
+        .success()
+        .stdout(predicate::eq("added 1 and updated 1 pages in Notion\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn notion_without_token() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["export", "notion", "--database", "db1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no Notion token"));
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use super::setup::Tool;
+use super::{fixtures, mocks};
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::matchers;
+
+#[tokio::test]
+async fn list() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_filters(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["filters", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("Everything all\nWork @work & today\n"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_edit_delete() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_filters(&cmd, 3).await;
+    mocks::mock_sync(&cmd, 1, |mb| {
+        mb.and(matchers::body_string_contains("filter_add"))
+            .and(matchers::body_string_contains("Someday"))
+    })
+    .await;
+    mocks::mock_sync(&cmd, 1, |mb| {
+        mb.and(matchers::body_string_contains("filter_update"))
+            .and(matchers::body_string_contains("4638878"))
+    })
+    .await;
+    mocks::mock_sync(&cmd, 1, |mb| {
+        mb.and(matchers::body_string_contains("filter_delete"))
+            .and(matchers::body_string_contains("4638879"))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["filter", "add", "Someday", "no date"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("created filter: Someday\n"));
+    cmd.cmd()?
+        .args(["filter", "edit", "work", "--query", "@work"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("updated filter: Work\n"));
+    cmd.cmd()?
+        .args(["filter", "delete", "everything"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("deleted filter: Everything\n"));
+    cmd.cmd()?
+        .args(["filter", "delete", "old"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no saved filter is named old"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_saved() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_filters(&cmd, 1).await;
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["list", "--saved", "Everything", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(fixtures::TASK_OUTPUT));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -8,3 +8,16 @@ pub const TASK_OUTPUT: &str = include_str!("./task_output");
 pub const TASK_EXPAND_OUTPUT: &str = include_str!("./task_expand_output");
 pub const PROJECTS_OUTPUT: &str = include_str!("./projects_output");
 pub const LABELS_OUTPUT: &str = include_str!("./labels_output");
+pub const TASK: &str = include_str!("./task.json");
+pub const CREATED_TASK: &str = include_str!("./created_task.json");
+#[cfg(feature = "importers")]
+pub const CREATED_PROJECT: &str = include_str!("./created_project.json");
+#[cfg(feature = "importers")]
+pub const CREATED_SECTION: &str = include_str!("./created_section.json");
+pub const CREATED_COMMENT: &str = include_str!("./created_comment.json");
+pub const COMMENTS: &str = include_str!("./comments.json");
+pub const COMPLETED: &str = include_str!("./completed.json");
+pub const HOLIDAYS: &str = include_str!("./holidays.ics");
+pub const ACTIVITY: &str = include_str!("./activity.json");
+pub const FILTERS: &str = include_str!("./filters.json");
+pub const COLLABORATORS: &str = include_str!("./collaborators.json");
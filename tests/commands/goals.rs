@@ -0,0 +1,57 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use doist::config::{Goal, Period};
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn goals() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.goals = vec![
+        Goal {
+            name: "Plants".to_string(),
+            label: Some("two".to_string()),
+            project: None,
+            count: 1,
+            period: Period::Week,
+        },
+        Goal {
+            name: "Chores".to_string(),
+            label: None,
+            project: Some("inbox".to_string()),
+            count: 2,
+            period: Period::Week,
+        },
+    ];
+    cmd.cfg.save()?;
+
+    mocks::mock_completed(&cmd, ("since", "2022-06-06T00:00:00"), 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .arg("goals")
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "Plants: 1/1 this week [##########] streak of 1 week, best 1\n\
+             Chores: 1/2 this week [#####-----] streak of 0 weeks, best 0\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_goals() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .arg("goals")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no goals are set in the config"));
+
+    Ok(())
+}
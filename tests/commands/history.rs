@@ -0,0 +1,54 @@
+use super::setup::Tool;
+use super::{fixtures, mocks};
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn history() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("set history = true in the config"));
+
+    cmd.cfg.history = true;
+    cmd.cfg.save()?;
+    mocks::mock_labels(&cmd, 1).await;
+    cmd.cmd()?.args(["labels", "list"]).assert().success();
+    cmd.cmd()?
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(
+            r"^    1  \S+ \S+  doist --config_prefix=\S+ labels list\n$",
+        )?);
+
+    cmd.mock.reset().await;
+    mocks::mock_labels(&cmd, 1).await;
+    cmd.cmd()?
+        .arg("rerun")
+        .assert()
+        .success()
+        .stderr(predicate::str::ends_with("labels list\n"))
+        .stdout(predicate::eq(fixtures::LABELS_OUTPUT));
+    cmd.mock.verify().await;
+
+    // The command that ran again is recorded, instead of the rerun.
+    cmd.cmd()?
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("    2  ").and(predicate::str::contains("rerun").not()));
+    cmd.cmd()?
+        .args(["rerun", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "there is no command 5, the history has 2",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use doist::config::{Hook, HookKind};
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn run() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.hooks = vec![
+        Hook {
+            url: format!("{}/slack", cmd.mock.uri()),
+            kind: HookKind::Slack,
+            label: Some("two".to_string()),
+            ..Default::default()
+        },
+        Hook {
+            url: format!("{}/generic", cmd.mock.uri()),
+            ..Default::default()
+        },
+    ];
+    cmd.cfg.save()?;
+
+    mocks::mock_completed(&cmd, ("since", "2022-08-26T19:33:20"), 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/slack"))
+        .and(matchers::body_json(serde_json::json!({
+            "text": "Completed: Water the plants [Project One]",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/generic"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "event": "completed",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["hooks", "run"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "posting tasks that are completed from now on\n",
+        ));
+    cmd.cmd()?
+        .args(["hooks", "run"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("posted 3 messages\n"));
+    // Completions are only posted once.
+    cmd.cmd()?
+        .args(["hooks", "run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,463 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+const SPEC: &str = r#"{
+    "projects": [{"name": "Garden", "sections": ["Flowers"]}],
+    "tasks": [
+        {"key": "buy", "content": "Buy some flowers", "project": "Garden", "section": "Flowers"},
+        {"content": "Plant them", "parent": "buy", "priority": 2, "due": "tomorrow"}
+    ]
+}"#;
+
+#[tokio::test]
+async fn import_rest() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("import.json");
+    file.write_str(SPEC)?;
+
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_create_project(&cmd, 1).await;
+    mocks::mock_create_section(&cmd, 1).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Buy some flowers",
+            "project_id": "1000100",
+            "section_id": "1100100",
+        })))
+    })
+    .await;
+    // The second task fails the first time, so the import has to be resumed.
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("Something went wrong"))
+        .up_to_n_times(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Plant them",
+            "parent_id": "7000100",
+            "priority": 3,
+            "due_string": "tomorrow",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["import", "--nobatch"])
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "import stopped after 3 of 4 items, run the same import again to resume",
+        ));
+    cmd.cmd()?
+        .args(["import", "--nobatch"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "imported 0 projects, 0 sections and 1 tasks\n",
+        ))
+        .stderr(predicate::str::contains(
+            "resuming import, 3 of 4 items were already created",
+        ));
+    cmd.mock.verify().await;
+
+    let created = cmd
+        .mock
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| r.method.as_str() == "POST")
+        .count();
+    assert_eq!(created, 5);
+
+    Ok(())
+}
+
+/// Responds to Sync API requests by accepting all commands and mapping the temporary IDs.
+#[tokio::test]
+async fn import_batch() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("import.json");
+    file.write_str(SPEC)?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/sync/v9/sync"))
+        .respond_with(mocks::SyncResponder)
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .arg("import")
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "imported 1 projects, 1 sections and 2 tasks\n",
+        ));
+    cmd.mock.verify().await;
+
+    let request = &cmd.mock.received_requests().await.unwrap()[3];
+    let (_, commands) = url::form_urlencoded::parse(&request.body)
+        .find(|(key, _)| key == "commands")
+        .unwrap();
+    let commands: serde_json::Value = serde_json::from_str(&commands)?;
+    let types = commands
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["type"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        ["project_add", "section_add", "item_add", "item_add"]
+    );
+    // Items reference items of the same batch through their temporary IDs.
+    assert_eq!(commands[1]["args"]["project_id"], commands[0]["temp_id"]);
+    assert_eq!(commands[2]["args"]["section_id"], commands[1]["temp_id"]);
+    assert_eq!(commands[3]["args"]["parent_id"], commands[2]["temp_id"]);
+    assert_eq!(commands[3]["args"]["priority"], 3);
+    assert_eq!(commands[3]["args"]["due"]["string"], "tomorrow");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_completed() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("import.json");
+    file.write_str(r#"{"tasks": [{"content": "Done already", "completed": true}]}"#)?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .arg("import")
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "imported 0 projects, 0 sections and 1 tasks, 1 of them completed\n",
+        ));
+    cmd.mock.verify().await;
+
+    let request = &cmd.mock.received_requests().await.unwrap()[3];
+    let (_, commands) = url::form_urlencoded::parse(&request.body)
+        .find(|(key, _)| key == "commands")
+        .unwrap();
+    let commands: serde_json::Value = serde_json::from_str(&commands)?;
+    assert_eq!(commands[1]["type"], "item_close");
+    assert_eq!(commands[1]["args"]["id"], commands[0]["temp_id"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_existing() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("import.json");
+    file.write_str(
+        r#"{
+            "projects": [{"name": "Project One", "sections": ["Flowers"]}],
+            "tasks": [
+                {"key": "bla", "content": "Bla bla", "project": "Project One", "section": "Flowers"},
+                {"content": "Woah", "project": "Project One"},
+                {"content": "Subtask", "parent": "bla"}
+            ]
+        }"#,
+    )?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_create_section(&cmd, 1).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Bla bla",
+            "project_id": "1000002",
+            "section_id": "1100100",
+        })))
+    })
+    .await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Subtask",
+            "parent_id": "7000100",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["import", "--nobatch", "--existing-tasks", "skip"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "imported 0 projects, 1 sections and 2 tasks\nskipped 1 items that exist already\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_gtasks() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let credentials = cmd.tmp.child("credentials.json");
+    credentials.write_str(&serde_json::to_string(&serde_json::json!({
+        "type": "authorized_user",
+        "client_id": "client",
+        "client_secret": "secret",
+        "refresh_token": "refresh",
+        "token_uri": format!("{}/token", cmd.mock.uri()),
+    }))?)?;
+
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/token"))
+        .and(matchers::body_string_contains("refresh_token=refresh"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"access_token": "google"})),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/tasks/v1/users/@me/lists"))
+        .and(matchers::bearer_token("google"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{"id": "L1", "title": "Travel"}, {"id": "L2", "title": "Other"}],
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    // The tasks come in two pages.
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/tasks/v1/lists/L1/tasks"))
+        .and(matchers::query_param("pageToken", "next"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{"id": "b", "title": "Pack", "status": "completed", "parent": "a", "position": "0"}],
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/tasks/v1/lists/L1/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{"id": "a", "title": "Trip", "notes": "To Rome", "status": "needsAction",
+                       "due": "2022-09-01T00:00:00.000Z", "position": "0"}],
+            "nextPageToken": "next",
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .args(["import", "gtasks", "--list", "travel", "--completed"])
+        .arg("--credentials")
+        .arg(credentials.path())
+        .arg(format!("--api-url={}/tasks/v1/", cmd.mock.uri()))
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "imported 1 projects, 0 sections and 2 tasks, 1 of them completed\n",
+        ));
+    cmd.mock.verify().await;
+
+    let request = cmd
+        .mock
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|r| r.url.path() == "/sync/v9/sync")
+        .unwrap();
+    let (_, commands) = url::form_urlencoded::parse(&request.body)
+        .find(|(key, _)| key == "commands")
+        .unwrap();
+    let commands: serde_json::Value = serde_json::from_str(&commands)?;
+    let types = commands
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["type"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(types, ["project_add", "item_add", "item_add", "item_close"]);
+    assert_eq!(commands[1]["args"]["description"], "To Rome");
+    assert_eq!(commands[1]["args"]["due"]["string"], "2022-09-01");
+    assert_eq!(commands[2]["args"]["parent_id"], commands[1]["temp_id"]);
+    assert_eq!(commands[3]["args"]["id"], commands[2]["temp_id"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_wunderlist() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("Tasks.json");
+    file.write_str(&serde_json::to_string(&serde_json::json!([
+        {"title": "Travel", "tasks": [
+            {"id": 1, "title": "Trip", "starred": true, "dueDate": "2022-09-01",
+             "notes": [{"content": "To Rome"}], "subtasks": [{"title": "Pack"}]},
+        ]},
+    ]))?)?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .args(["import", "wunderlist"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "imported 1 projects, 0 sections and 2 tasks\n",
+        ));
+    cmd.mock.verify().await;
+
+    let request = &cmd.mock.received_requests().await.unwrap()[3];
+    let (_, commands) = url::form_urlencoded::parse(&request.body)
+        .find(|(key, _)| key == "commands")
+        .unwrap();
+    let commands: serde_json::Value = serde_json::from_str(&commands)?;
+    assert_eq!(commands[1]["args"]["description"], "To Rome");
+    assert_eq!(commands[1]["args"]["priority"], 4);
+    assert_eq!(commands[2]["args"]["parent_id"], commands[1]["temp_id"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_rtm_missing_list() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("rtm.json");
+    file.write_str(r#"{"lists": [{"id": "1", "name": "Inbox"}], "tasks": []}"#)?;
+
+    cmd.cmd()?
+        .args(["import", "rtm", "--list", "Work"])
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "there is no list \"Work\" in Remember The Milk",
+        ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn demo_seed() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .args(["demo", "seed", "--tasks", "20", "--projects", "2"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("imported 2 projects, ")
+                .and(predicate::str::ends_with("and 20 tasks\n")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn demo_anonymize() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("import.json");
+    file.write_str(SPEC)?;
+    let output = cmd.tmp.child("anonymized.json");
+
+    cmd.cmd()?
+        .args(["demo", "anonymize"])
+        .arg(file.path())
+        .arg("--output")
+        .arg(output.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    output.assert(
+        predicate::str::contains("\"Project 1\"")
+            .and(predicate::str::contains("Garden").not())
+            .and(predicate::str::contains("flowers").not()),
+    );
+    // Nothing is sent to the API.
+    assert!(cmd.mock.received_requests().await.unwrap().is_empty());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn cancel_saves_progress() -> Result<()> {
+    use std::process::{Command, Stdio};
+
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("import.json");
+    file.write_str(SPEC)?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_create_project(&cmd, 1).await;
+    mocks::mock_slow(
+        &cmd,
+        "POST",
+        "/rest/v2/sections",
+        std::time::Duration::from_secs(30),
+    )
+    .await;
+
+    let child = Command::cargo_bin("doist")?
+        .env("LC_ALL", "C")
+        .arg(format!("--config_prefix={}", cmd.tmp.path().display()))
+        .args(["import", "--nobatch"])
+        .arg(file.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+
+    // The import stops while the section is created, but knows the project was created.
+    assert_eq!(output.status.code(), Some(130));
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("import stopped after 1 of 4 items"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.ends_with("cancelled\n"), "{}", stderr);
+
+    Ok(())
+}
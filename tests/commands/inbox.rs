@@ -0,0 +1,32 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn inbox() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 2).await;
+
+    cmd.cmd()?.arg("inbox").assert().success().stdout(
+        predicate::str::starts_with("7000021 p4 Bits n bytes")
+            .and(predicate::str::contains("7000027 p4 Open data"))
+            .and(predicate::str::contains("Bla bla").not())
+            .and(predicate::function(|out: &str| out.lines().count() == 7)),
+    );
+    // Triaging asks for every task, so it needs a terminal.
+    cmd.cmd()?
+        .args(["inbox", "-i"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "triaging the Inbox needs an interactive terminal",
+        ));
+
+    Ok(())
+}
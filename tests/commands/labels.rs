@@ -3,6 +3,7 @@ use super::setup::Tool;
 use assert_cmd::prelude::*;
 use color_eyre::Result;
 use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
 
 #[tokio::test]
 async fn list() -> Result<()> {
@@ -29,3 +30,101 @@ async fn list() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn update() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/labels/1999991"))
+        .and(matchers::body_json(
+            serde_json::json!({ "name": "uno", "color": "red" }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "1999991",
+            "name": "uno",
+            "order": 1,
+            "color": "red",
+            "is_favorite": false
+        })))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args([
+            "labels",
+            "update",
+            "--label_id",
+            "1999991",
+            "--name",
+            "uno",
+            "--color",
+            "red",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq("updated label: @uno\n"));
+    cmd.mock.verify().await;
+
+    cmd.cmd()?
+        .args(["labels", "update", "--label_id", "1999991"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nothing to update"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn shared() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/labels/shared"))
+        .and(matchers::query_param("omit_personal", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(["team"]))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args(["labels", "list", "--shared"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("@team\n"));
+    cmd.mock.verify().await;
+
+    cmd.mock.reset().await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/labels/shared"))
+        .and(matchers::query_param("omit_personal", "false"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(["team", "one"]))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/labels/shared/rename"))
+        .and(matchers::body_json(
+            serde_json::json!({ "name": "team", "new_name": "crew" }),
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args(["label", "rename-shared", "@team", "crew"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("renamed shared label @team to @crew\n"));
+    cmd.cmd()?
+        .args(["labels", "remove-shared", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no task carries the shared label @nope",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
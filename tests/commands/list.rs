@@ -53,3 +53,305 @@ async fn expand() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn sanitize() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("--nointeractive")
+        .arg("--sanitize")
+        .arg("strip-emoji,collapse-whitespace,ascii")
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::TASK_OUTPUT));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redact() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["--redact", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("7000002 p4 Amet [REPEAT] every day @sit [Eiusmod Sed/")
+                .and(predicate::str::contains("Project One").not()),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn watch() -> Result<()> {
+    use std::process::{Command, Stdio};
+    use wiremock::{matchers, Mock, ResponseTemplate};
+
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_labels(&cmd, 10).await;
+    mocks::mock_sections(&cmd, 10).await;
+    // The task that is gone on the next refresh was completed.
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/sync/v9/completed/get_all"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{
+                "id": "1",
+                "task_id": "7000001",
+                "content": "Bla bla",
+                "project_id": "1000002",
+                "completed_at": "2022-08-26T12:00:00Z",
+            }],
+        })))
+        .mount(&cmd.mock)
+        .await;
+    // Projects are fetched again on every refresh, instead of remembered from the first one.
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/projects"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(super::fixtures::PROJECTS, "application/json"),
+        )
+        .expect(2..)
+        .mount(&cmd.mock)
+        .await;
+
+    let child = Command::cargo_bin("doist")?
+        .env("LC_ALL", "C")
+        .arg(format!("--config_prefix={}", cmd.tmp.path().display()))
+        .args(["list", "--watch", "--interval", "1"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains(" completed Bla bla\n"), "{}", stdout);
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn watch_failed_refresh() -> Result<()> {
+    use std::process::{Command, Stdio};
+    use wiremock::{matchers, Mock, ResponseTemplate};
+
+    let cmd = Tool::init().await?;
+
+    // The second refresh fails, the third one shows the task as removed.
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_error(&cmd, "GET", "/rest/v2/tasks", 400, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_labels(&cmd, 10).await;
+    mocks::mock_projects(&cmd, 10).await;
+    mocks::mock_sections(&cmd, 10).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/sync/v9/completed/get_all"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": [] })))
+        .mount(&cmd.mock)
+        .await;
+
+    let child = Command::cargo_bin("doist")?
+        .env("LC_ALL", "C")
+        .arg(format!("--config_prefix={}", cmd.tmp.path().display()))
+        .args(["list", "--watch", "--interval", "1"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    tokio::time::sleep(std::time::Duration::from_millis(3500)).await;
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("unable to refresh: "), "{}", stderr);
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains(" removed Bla bla\n"), "{}", stdout);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn watch_redact() -> Result<()> {
+    use std::process::{Command, Stdio};
+    use wiremock::{matchers, Mock, ResponseTemplate};
+
+    let cmd = Tool::init().await?;
+
+    // The task is gone on the next refresh, which shows in the feed of changes.
+    mocks::mock_tasks(&cmd, 1).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_labels(&cmd, 10).await;
+    mocks::mock_projects(&cmd, 10).await;
+    mocks::mock_sections(&cmd, 10).await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/sync/v9/completed/get_all"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": [] })))
+        .mount(&cmd.mock)
+        .await;
+
+    let child = Command::cargo_bin("doist")?
+        .env("LC_ALL", "C")
+        .arg(format!("--config_prefix={}", cmd.tmp.path().display()))
+        .args(["--redact", "list", "--watch", "--interval", "1"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("\nChanges:\n"), "{}", stdout);
+    assert!(!stdout.contains("Bla bla"), "{}", stdout);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn json_fields() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let output = cmd
+        .cmd()?
+        .args(["list", "--json", "--fields", "id,due.date"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    cmd.mock.verify().await;
+
+    let tasks: Vec<serde_json::Value> = serde_json::from_slice(&output)?;
+    let fixture: Vec<serde_json::Value> = serde_json::from_str(super::fixtures::TASKS)?;
+    assert_eq!(tasks.len(), fixture.len());
+    for task in &tasks {
+        let keys = task.as_object().unwrap().keys().collect::<Vec<_>>();
+        assert_eq!(keys, ["due", "id"]);
+    }
+    let task = tasks.iter().find(|t| t["id"] == "7000001").unwrap();
+    assert_eq!(
+        *task,
+        serde_json::json!({"id": "7000001", "due": {"date": "2022-08-24"}})
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn null_delimited() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["list", "-0"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            super::fixtures::TASK_OUTPUT.replace('\n', "\0"),
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn aging() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.theme.aging = vec![30, 90];
+    cmd.cfg.save()?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "7000001 p4 Bla bla [REPEAT] every day @two 120d old [Project One/Section Three]\n",
+        ))
+        .stdout(predicate::str::contains(
+            "7000027 p4 Open data 13 Sep [Inbox]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn a11y() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let count = super::fixtures::TASK_OUTPUT.lines().count();
+    cmd.cmd()?
+        .args(["list", "--nointeractive", "--a11y"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!(
+            "Task 1 of {count}: 7000004 p3 Unheard of [REPEAT] 2022-08-27 08:00:00 EEST [Project One]\n"
+        )))
+        .stdout(predicate::str::contains(format!(
+            "Task 6 of {count}: subtask: 7000008 p4 Nope [Project Three/Section Five]\n"
+        )))
+        .stdout(predicate::str::contains("⌞").not());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
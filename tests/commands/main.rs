@@ -1,7 +1,64 @@
+mod add;
+mod agenda;
+mod audit;
 mod auth;
+mod balance;
+mod cache;
+mod check;
+mod close;
+mod collaborators;
+mod comments;
+mod completed;
+mod debug;
+mod defer;
+mod delete;
+mod digest;
+mod edit;
+mod errors;
+mod events;
+#[cfg(feature = "integrations")]
+mod export;
+mod filters;
 mod fixtures;
+mod goals;
+mod history;
+#[cfg(feature = "integrations")]
+mod homeassistant;
+#[cfg(feature = "integrations")]
+mod hooks;
+#[cfg(feature = "importers")]
+mod import;
+mod inbox;
 mod labels;
 mod list;
+mod merge;
 mod mocks;
+mod move_task;
+mod note;
+mod notify;
+#[cfg(feature = "integrations")]
+mod obsidian;
+mod pick;
+mod pin;
 mod projects;
+mod quick;
+mod recurrence;
+mod report;
+mod search;
+mod sections;
+#[cfg(feature = "integrations")]
+mod serve;
 mod setup;
+mod someday;
+mod split;
+#[cfg(feature = "integrations")]
+mod sync;
+#[cfg(feature = "importers")]
+mod template;
+mod timer;
+mod today;
+mod undo;
+mod view;
+mod waiting;
+#[cfg(feature = "integrations")]
+mod worklog;
@@ -0,0 +1,91 @@
+use super::setup::Tool;
+use super::{fixtures, mocks};
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn merge() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let tasks: Vec<serde_json::Value> = serde_json::from_str(fixtures::TASKS)?;
+    let mut tasks = tasks
+        .into_iter()
+        .filter(|t| t["id"] == "7000001" || t["id"] == "7000002")
+        .collect::<Vec<_>>();
+    for task in tasks.iter_mut().filter(|t| t["id"] == "7000002") {
+        task["comment_count"] = 2.into();
+    }
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000001,7000002"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tasks))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    // The kept task gets the description of the duplicate, but is due earlier already.
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "description": "Testing",
+        })))
+    })
+    .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/comments"))
+        .and(matchers::query_param("task_id", "7000002"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(fixtures::COMMENTS, "application/json"),
+        )
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_create_comment(&cmd, 2, |mb| {
+        mb.and(matchers::body_partial_json(
+            serde_json::json!({"task_id": "7000001"}),
+        ))
+        .and(matchers::body_string_contains("From merged task"))
+    })
+    .await;
+    mocks::mock_create_comment(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(
+            serde_json::json!({"task_id": "7000002"}),
+        ))
+        .and(matchers::body_string_contains("Merged into"))
+    })
+    .await;
+    // The duplicate is recurring, so it is completed instead of moved to its next date.
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks/7000002"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks/7000002/close"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_task(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["merge", "7000001", "7000002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "closed task 7000002\nmerged into: 7000001 p4 Bla bla",
+        ));
+    cmd.mock.verify().await;
+
+    cmd.cmd()?
+        .args(["merge", "7000001"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("2 values required"));
+
+    Ok(())
+}
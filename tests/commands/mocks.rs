@@ -1,4 +1,4 @@
-use wiremock::{matchers, Mock, MockBuilder, ResponseTemplate};
+use wiremock::{matchers, Mock, MockBuilder, Request, Respond, ResponseTemplate};
 
 use crate::setup::Tool;
 
@@ -27,6 +27,21 @@ pub async fn mock_tasks_all(tool: &Tool, times: u64) {
     .await
 }
 
+/// Mocks fetching tasks by their IDs, which responds with the single task of the fixtures.
+/// Needs to be mounted before other task mocks, as they would match the request as well.
+pub async fn mock_tasks_by_ids(tool: &Tool, ids: &str, times: u64) {
+    mock_http_with_builder(
+        tool,
+        "GET",
+        "/rest/v2/tasks",
+        200,
+        &format!("[{}]", super::fixtures::TASK),
+        times,
+        |mb| mb.and(matchers::query_param("ids", ids)),
+    )
+    .await
+}
+
 pub async fn mock_tasks_partial(tool: &Tool, times: u64) {
     mock_http(
         tool,
@@ -75,6 +90,282 @@ pub async fn mock_sections(tool: &Tool, times: u64) {
     .await
 }
 
+pub async fn mock_task(tool: &Tool, times: u64) {
+    mock_http(
+        tool,
+        "GET",
+        "/rest/v2/tasks/7000001",
+        200,
+        super::fixtures::TASK,
+        times,
+    )
+    .await
+}
+
+/// Mocks fetching a single task of the task list fixtures by its ID.
+pub async fn mock_task_by_id(tool: &Tool, id: &str, times: u64) {
+    let tasks: Vec<serde_json::Value> = serde_json::from_str(super::fixtures::TASKS).unwrap();
+    let task = tasks.into_iter().find(|t| t["id"] == id).unwrap();
+    mock_http(
+        tool,
+        "GET",
+        &format!("/rest/v2/tasks/{}", id),
+        200,
+        &task.to_string(),
+        times,
+    )
+    .await
+}
+
+pub async fn mock_create_task<F: Fn(MockBuilder) -> MockBuilder>(
+    tool: &Tool,
+    times: u64,
+    matchers: F,
+) {
+    mock_http_with_builder(
+        tool,
+        "POST",
+        "/rest/v2/tasks",
+        200,
+        super::fixtures::CREATED_TASK,
+        times,
+        matchers,
+    )
+    .await
+}
+
+pub async fn mock_quick_add<F: Fn(MockBuilder) -> MockBuilder>(
+    tool: &Tool,
+    times: u64,
+    matchers: F,
+) {
+    mock_http_with_builder(
+        tool,
+        "POST",
+        "/sync/v9/quick/add",
+        200,
+        r#"{"id": "7000100", "content": "Buy some flowers", "checked": false}"#,
+        times,
+        matchers,
+    )
+    .await;
+    mock_http(
+        tool,
+        "GET",
+        "/rest/v2/tasks/7000100",
+        200,
+        super::fixtures::CREATED_TASK,
+        times,
+    )
+    .await
+}
+
+pub async fn mock_update_task<F: Fn(MockBuilder) -> MockBuilder>(
+    tool: &Tool,
+    times: u64,
+    matchers: F,
+) {
+    mock_http_with_builder(
+        tool,
+        "POST",
+        "/rest/v2/tasks/7000001",
+        204,
+        "",
+        times,
+        matchers,
+    )
+    .await
+}
+
+pub async fn mock_close_task(tool: &Tool, times: u64) {
+    mock_http(tool, "POST", "/rest/v2/tasks/7000001/close", 204, "", times).await
+}
+
+pub async fn mock_reopen_task(tool: &Tool, times: u64) {
+    mock_http(
+        tool,
+        "POST",
+        "/rest/v2/tasks/7000001/reopen",
+        204,
+        "",
+        times,
+    )
+    .await
+}
+
+#[cfg(feature = "importers")]
+pub async fn mock_create_project(tool: &Tool, times: u64) {
+    mock_http(
+        tool,
+        "POST",
+        "/rest/v2/projects",
+        200,
+        super::fixtures::CREATED_PROJECT,
+        times,
+    )
+    .await
+}
+
+#[cfg(feature = "importers")]
+pub async fn mock_create_section(tool: &Tool, times: u64) {
+    mock_http(
+        tool,
+        "POST",
+        "/rest/v2/sections",
+        200,
+        super::fixtures::CREATED_SECTION,
+        times,
+    )
+    .await
+}
+
+pub async fn mock_create_comment<F: Fn(MockBuilder) -> MockBuilder>(
+    tool: &Tool,
+    times: u64,
+    matchers: F,
+) {
+    mock_http_with_builder(
+        tool,
+        "POST",
+        "/rest/v2/comments",
+        200,
+        super::fixtures::CREATED_COMMENT,
+        times,
+        matchers,
+    )
+    .await
+}
+
+/// Mocks fetching the comments of the task or project given as query parameter.
+pub async fn mock_comments(tool: &Tool, param: (&str, &str), times: u64) {
+    mock_http_with_builder(
+        tool,
+        "GET",
+        "/rest/v2/comments",
+        200,
+        super::fixtures::COMMENTS,
+        times,
+        |mb| mb.and(matchers::query_param(param.0, param.1)),
+    )
+    .await
+}
+
+/// Mocks fetching the completed tasks that match the given query parameter, which fit on a single
+/// page.
+pub async fn mock_completed(tool: &Tool, param: (&str, &str), times: u64) {
+    mock_http_with_builder(
+        tool,
+        "GET",
+        "/sync/v9/completed/get_all",
+        200,
+        super::fixtures::COMPLETED,
+        times,
+        |mb| mb.and(matchers::query_param(param.0, param.1)),
+    )
+    .await
+}
+
+/// Mocks fetching the activity log since the given time, which fits on a single page.
+pub async fn mock_activity(tool: &Tool, since: &str, times: u64) {
+    mock_http_with_builder(
+        tool,
+        "GET",
+        "/sync/v9/activity/get",
+        200,
+        super::fixtures::ACTIVITY,
+        times,
+        |mb| mb.and(matchers::query_param("since", since)),
+    )
+    .await
+}
+
+/// Mocks reading the saved filters from the Sync API.
+pub async fn mock_filters(tool: &Tool, times: u64) {
+    mock_http_with_builder(
+        tool,
+        "GET",
+        "/sync/v9/sync",
+        200,
+        super::fixtures::FILTERS,
+        times,
+        |mb| mb.and(matchers::query_param("resource_types", r#"["filters"]"#)),
+    )
+    .await
+}
+
+pub async fn mock_collaborators(tool: &Tool, project: &str, times: u64) {
+    mock_http(
+        tool,
+        "GET",
+        &format!("/rest/v2/projects/{}/collaborators", project),
+        200,
+        super::fixtures::COLLABORATORS,
+        times,
+    )
+    .await
+}
+
+pub async fn mock_holidays(tool: &Tool, times: u64) {
+    mock_http(
+        tool,
+        "GET",
+        "/holidays.ics",
+        200,
+        super::fixtures::HOLIDAYS,
+        times,
+    )
+    .await
+}
+
+/// Responds to Sync API requests by reporting every command as successful, and mapping every
+/// temporary ID to the index of its command.
+pub struct SyncResponder;
+
+impl Respond for SyncResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let commands = url::form_urlencoded::parse(&request.body)
+            .find(|(key, _)| key == "commands")
+            .map(|(_, commands)| serde_json::from_str::<serde_json::Value>(&commands).unwrap())
+            .unwrap();
+        let commands = commands.as_array().unwrap();
+        let status = commands
+            .iter()
+            .map(|c| (c["uuid"].as_str().unwrap().to_string(), "ok".into()))
+            .collect::<serde_json::Map<_, _>>();
+        let mapping = commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| Some((c["temp_id"].as_str()?.to_string(), i.to_string().into())))
+            .collect::<serde_json::Map<_, _>>();
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sync_status": status,
+            "temp_id_mapping": mapping,
+        }))
+    }
+}
+
+pub async fn mock_sync<F: Fn(MockBuilder) -> MockBuilder>(tool: &Tool, times: u64, matchers: F) {
+    matchers(Mock::given(matchers::method("POST")).and(matchers::path("/sync/v9/sync")))
+        .respond_with(SyncResponder)
+        .expect(times)
+        .mount(&tool.mock)
+        .await;
+}
+
+/// Mocks an endpoint that responds with an error status code for the given amount of times.
+pub async fn mock_error(tool: &Tool, method: &str, path: &str, code: u16, times: u64) {
+    mock_http(tool, method, path, code, "Something went wrong", times).await
+}
+
+/// Mocks an endpoint that takes the given time to answer, like on a slow network.
+pub async fn mock_slow(tool: &Tool, method: &str, path: &str, delay: std::time::Duration) {
+    Mock::given(matchers::method(method))
+        .and(matchers::path(path))
+        .respond_with(ResponseTemplate::new(200).set_delay(delay))
+        .mount(&tool.mock)
+        .await
+}
+
 async fn mock_http_with_builder<F: Fn(MockBuilder) -> MockBuilder>(
     tool: &Tool,
     method: &str,
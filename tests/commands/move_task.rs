@@ -0,0 +1,74 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+/// Returns the commands that were sent to the Sync API.
+async fn sync_commands(cmd: &Tool) -> Result<serde_json::Value> {
+    let request = cmd
+        .mock
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|r| r.url.path() == "/sync/v9/sync")
+        .unwrap();
+    let (_, commands) = url::form_urlencoded::parse(&request.body)
+        .find(|(key, _)| key == "commands")
+        .unwrap();
+    Ok(serde_json::from_str(&commands)?)
+}
+
+#[tokio::test]
+async fn move_to_section() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .args(["move", "7000001", "--project", "Three", "--section", "Five"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "moved task 7000001 to Project Three/Section Five\n",
+        ));
+    cmd.mock.verify().await;
+    let commands = sync_commands(&cmd).await?;
+    assert_eq!(commands[0]["type"], "item_move");
+    assert_eq!(
+        commands[0]["args"],
+        serde_json::json!({"id": "7000001", "section_id": "1100005"})
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_under_parent() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .args(["move", "7000006", "--parent", "7000005"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("moved task 7000006 under task 7000005\n"));
+    cmd.mock.verify().await;
+    let commands = sync_commands(&cmd).await?;
+    assert_eq!(
+        commands[0]["args"],
+        serde_json::json!({"id": "7000006", "parent_id": "7000005"})
+    );
+
+    cmd.cmd()?
+        .args(["move", "7000006", "--parent", "7000006"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("can not be moved under itself"));
+
+    Ok(())
+}
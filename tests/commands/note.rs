@@ -0,0 +1,99 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn note() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["note", "7000001", "call back after lunch"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("added note to task 7000001\n"));
+    cmd.cmd()?
+        .args(["note", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("call back after lunch"));
+
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["view", "--nopager", "7000001"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Notes:")
+                .and(predicate::str::contains("call back after lunch")),
+        );
+    cmd.mock.verify().await;
+    // Notes are never sent to the API.
+    let requests = cmd.mock.received_requests().await.unwrap_or_default();
+    assert!(requests.iter().all(|r| r.method.as_str() == "GET"));
+
+    cmd.cmd()?
+        .args(["note", "7000001", "--clear"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .args(["note", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("task 7000001 has no notes\n"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn without_token() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.token = None;
+    cmd.cfg.save()?;
+
+    cmd.cmd()?
+        .args(["note", "7000001", "call back after lunch"])
+        .assert()
+        .success();
+    // Notes are sanitized like everything else that is displayed.
+    cmd.cmd()?
+        .args(["--redact", "note", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Notes:").and(predicate::str::contains("lunch").not()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sqlite_storage() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.storage = doist::config::StorageBackend::Sqlite;
+    cmd.cfg.save()?;
+
+    #[cfg(not(feature = "sqlite"))]
+    cmd.cmd()?
+        .args(["note", "7000001", "call back after lunch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("the `sqlite` feature"));
+    #[cfg(feature = "sqlite")]
+    {
+        cmd.cmd()?
+            .args(["note", "7000001", "call back after lunch"])
+            .assert()
+            .success();
+        cmd.cmd()?
+            .args(["note", "7000001"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("call back after lunch"));
+        assert!(cmd.tmp.path().join("state.sqlite3").exists());
+    }
+
+    Ok(())
+}
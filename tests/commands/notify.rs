@@ -0,0 +1,140 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use doist::config::NotificationRule;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn notify() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.notifications.before = Some("10h".to_string());
+    cmd.cfg.notifications.rules = vec![
+        NotificationRule {
+            label: Some("two".to_string()),
+            before: Some("1d".to_string()),
+            ..Default::default()
+        },
+        NotificationRule {
+            project: Some("Project One".to_string()),
+            never: true,
+            ..Default::default()
+        },
+    ];
+    cmd.cfg.save()?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+
+    // "Unheard of" would be due for a notification, if its project didn't mute it.
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .arg("notify")
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "Shake it is due at 2022-08-27 19:00\n",
+        ));
+    // Every task is only notified about once.
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .arg("notify")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn snooze() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.notifications.before = Some("1d".to_string());
+    cmd.cfg.save()?;
+
+    mocks::mock_tasks(&cmd, 3).await;
+    mocks::mock_projects(&cmd, 3).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["notify", "--snooze", "2h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "notifications are snoozed until 2022-08-26 21:33\n",
+        ));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .arg("notify")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    // Once the snooze is over, the queued notifications are delivered.
+    cmd.cfg.override_time = Some("2022-08-26T22:00:00Z".parse()?);
+    cmd.cfg.save()?;
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .arg("notify")
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "Unheard of is due at 2022-08-27 05:00\n\
+             Shake it is due at 2022-08-27 19:00\n",
+        ));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .arg("notify")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn daemon_keeps_going() -> Result<()> {
+    use std::process::{Command, Stdio};
+    use wiremock::{matchers, Mock, ResponseTemplate};
+
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.notifications.before = Some("1d".to_string());
+    cmd.cfg.save()?;
+
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .respond_with(ResponseTemplate::new(400))
+        .up_to_n_times(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_tasks(&cmd, 10).await;
+    mocks::mock_projects(&cmd, 10).await;
+
+    let child = Command::cargo_bin("doist")?
+        .env("LC_ALL", "C")
+        .env("TZ", "UTC")
+        .arg(format!("--config_prefix={}", cmd.tmp.path().display()))
+        .args(["notify", "--daemon", "--interval", "1s"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("unable to check for notifications"),
+        "{}",
+        stderr
+    );
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("Shake it is due at"), "{}", stdout);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn sync() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let note = cmd.tmp.child("vault/Daily/daily.md");
+    note.write_str(
+        "# Today\n\n## Tasks\n<!-- doist:start -->\n- [x] Nope <!-- doist:7000008 -->\n- [ ] Gone <!-- doist:7000042 -->\nCall back\n<!-- doist:end -->\n\nNotes\n",
+    )?;
+
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/tasks/7000008/close"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_tasks_partial(&cmd, 1).await;
+
+    let vault = cmd.tmp.child("vault");
+    cmd.cmd()?
+        .args([
+            "obsidian",
+            "sync",
+            "--folder",
+            "Daily",
+            "--date-format",
+            "daily",
+        ])
+        .arg("--vault")
+        .arg(vault.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "closed 1 tasks and wrote 1 tasks to",
+        ));
+    cmd.mock.verify().await;
+    note.assert(
+        "# Today\n\n## Tasks\n<!-- doist:start -->\n- [ ] Darn <!-- doist:7000006 -->\n- [x] Nope\nCall back\n<!-- doist:end -->\n\nNotes\n",
+    );
+
+    Ok(())
+}
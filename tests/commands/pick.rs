@@ -0,0 +1,79 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn candidates() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["pick", "--candidates"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "7000015\tShake it\t#Project One\t2022-08-27 19:00\n",
+        ))
+        .stdout(predicate::str::contains("7000001\tBla bla\t"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pick() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.picker = vec!["grep".to_string(), "Shake it".to_string()];
+    cmd.cfg.save()?;
+
+    mocks::mock_tasks(&cmd, 3).await;
+    mocks::mock_labels(&cmd, 3).await;
+    mocks::mock_projects(&cmd, 3).await;
+    mocks::mock_sections(&cmd, 3).await;
+
+    cmd.cmd()?
+        .arg("pick")
+        .assert()
+        .success()
+        .stdout(predicate::eq("7000015\n"));
+    cmd.cmd()?
+        .args(["pick", "--print", "content"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("Shake it\n"));
+    cmd.cmd()?
+        .args(["pick", "--print", "url"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("https://"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nothing_picked() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.picker = vec!["false".to_string()];
+    cmd.cfg.save()?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    cmd.cmd()?
+        .arg("pick")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no task was picked"));
+
+    Ok(())
+}
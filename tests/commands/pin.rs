@@ -0,0 +1,81 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn pin() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["pin", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("pinned task 7000001\n"));
+
+    mocks::mock_tasks_by_ids(&cmd, "7000001", 2).await;
+    mocks::mock_tasks(&cmd, 3).await;
+    mocks::mock_labels(&cmd, 3).await;
+    mocks::mock_projects(&cmd, 3).await;
+    mocks::mock_sections(&cmd, 3).await;
+
+    // Records only contain the tasks of the list.
+    cmd.cmd()?
+        .args(["list", "--nointeractive"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::TASK_OUTPUT));
+    // The pinned task is shown on top of the list to pick a task from. The numbered list of `--a11y` is shown
+    // without a terminal, only picking from it needs one.
+    cmd.cmd()?
+        .args(["list", "--a11y"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::starts_with("[PIN] 7000001 "))
+        .stderr(predicate::str::starts_with("Select task\n"));
+    // And on top of what is due today.
+    cmd.cmd()?
+        .arg("today")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("[PIN] 7000001 "));
+    cmd.mock.verify().await;
+
+    cmd.cmd()?
+        .args(["pin", "--remove", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("unpinned task 7000001\n"));
+    cmd.cmd()?
+        .arg("pins")
+        .assert()
+        .success()
+        .stdout(predicate::eq("no tasks are pinned\n"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_pins() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?.args(["pin", "7000001"]).assert().success();
+
+    // Only what is due today is mocked, fetching the pinned task fails.
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+
+    cmd.cmd()?
+        .arg("today")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[PIN]").not())
+        .stderr(predicate::str::starts_with(
+            "unable to show the pinned tasks: ",
+        ));
+
+    Ok(())
+}
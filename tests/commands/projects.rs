@@ -3,6 +3,7 @@ use super::setup::Tool;
 use assert_cmd::prelude::*;
 use color_eyre::Result;
 use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
 
 #[tokio::test]
 async fn list() -> Result<()> {
@@ -29,3 +30,151 @@ async fn list() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn milestones() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_completed(&cmd, ("project_id", "1000002"), 2).await;
+
+    cmd.cmd()?
+        .args(["milestones", "--project_id", "1000002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Section Three  [######--------------]  33% (1/3)  due 2022-08-24 to 2022-08-26\n\
+             \n\
+             Roadmap from 2022-08-24 to 2022-08-26:\n\
+             Section Three  |====================|\n",
+        ));
+    cmd.cmd()?
+        .args([
+            "milestones",
+            "-P",
+            "One",
+            "--json",
+            "--fields",
+            "name,percent,latest_due",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "[\n  {\n    \"latest_due\": \"2022-08-26\",\n    \"name\": \"Section Three\",\n    \"percent\": 33\n  }\n]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn milestones_redact() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_completed(&cmd, ("project_id", "1000002"), 1).await;
+
+    cmd.cmd()?
+        .args(["--redact", "milestones", "--project_id", "1000002"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("33% (1/3)")
+                .and(predicate::str::contains("Section Three").not()),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rename() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/projects/1000002"))
+        .and(matchers::body_json(
+            serde_json::json!({ "name": "Project Uno" }),
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args([
+            "projects",
+            "rename",
+            "--project_id",
+            "1000002",
+            "Project Uno",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "renamed project Project One to Project Uno\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn archive() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| {
+        mb.and(matchers::body_string_contains("project_archive"))
+            .and(matchers::body_string_contains("1000002"))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["projects", "archive", "-P", "Project One"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("archived project: "));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unarchive() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/sync/v9/projects/get_archived"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"id": "1000010", "parent_id": null, "name": "Old Stuff", "color": "grey"},
+            {"id": "1000011", "parent_id": null, "name": "Vacation 2021", "color": "blue"},
+        ])))
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_sync(&cmd, 1, |mb| {
+        mb.and(matchers::body_string_contains("project_unarchive"))
+            .and(matchers::body_string_contains("1000011"))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args(["projects", "list", "--archived"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("1000010 Old Stuff\n1000011 Vacation 2021\n"));
+    cmd.cmd()?
+        .args(["projects", "unarchive", "-P", "vacation"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("unarchived project: 1000011 Vacation 2021\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
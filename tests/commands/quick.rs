@@ -0,0 +1,48 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::matchers;
+
+#[tokio::test]
+async fn quick() -> Result<()> {
+    for test in &[vec!["quick"], vec!["q"]] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_projects(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+        mocks::mock_labels(&cmd, 1).await;
+        mocks::mock_quick_add(&cmd, 1, |mb| {
+            mb.and(matchers::body_json(serde_json::json!({
+                "text": "Buy some flowers tomorrow #Project One @two p2",
+            })))
+        })
+        .await;
+
+        cmd.cmd()?
+            .args(test)
+            .arg("Buy some flowers tomorrow #Project One @two p2")
+            .assert()
+            .success()
+            .stdout(predicate::eq(
+                "created task: 7000100 p2 Buy some flowers tomorrow @two [Project One]\n",
+            ));
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["quick", ""])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("task can not be empty"));
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::matchers;
+
+#[tokio::test]
+async fn fix() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_task(&cmd, 2).await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "due_string": "every other week",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args([
+            "recurrence",
+            "fix",
+            "7000001",
+            "--to",
+            "every other week",
+            "-n",
+            "3",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "Current recurrence of \"Bla bla\": every day\n  \
+             Fri 2022-08-26\n  \
+             Sat 2022-08-27\n  \
+             Sun 2022-08-28\n\
+             New recurrence: every other week\n  \
+             Fri 2022-08-26\n  \
+             Fri 2022-09-09\n  \
+             Fri 2022-09-23\n\
+             \"Bla bla\" now repeats every other week\n",
+        ));
+    cmd.cmd()?
+        .args(["recurrence", "fix", "7000001", "--to", "every blue moon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot preview"))
+        .stderr(predicate::str::contains("could not be previewed"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
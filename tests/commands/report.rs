@@ -0,0 +1,48 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn accuracy() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .args(["report", "accuracy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("no time was tracked since"));
+
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    cmd.cmd()?
+        .args(["timer", "start", "7000001"])
+        .assert()
+        .success();
+    let start = cmd.cfg.override_time.unwrap();
+    cmd.cfg.override_time = Some(start + chrono::Duration::minutes(90));
+    cmd.cfg.save()?;
+    cmd.cmd()?.args(["timer", "stop"]).assert().success();
+    cmd.mock.verify().await;
+
+    mocks::mock_tasks_by_ids(&cmd, "7000001", 2).await;
+    mocks::mock_completed(&cmd, ("since", "2022-07-27T21:03:20"), 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    cmd.cmd()?
+        .args(["report", "accuracy"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Project One    1 task   estimated      1h  tracked   1h30m  +50%\n\
+             Total          1 task   estimated      1h  tracked   1h30m  +50%\n",
+        ));
+    cmd.cmd()?
+        .args(["report", "accuracy", "--by", "label", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""name": "@two""#))
+        .stdout(predicate::str::contains(r#""estimated_minutes": 60"#))
+        .stdout(predicate::str::contains(r#""tracked_minutes": 90"#));
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use super::setup::Tool;
+use super::{fixtures, mocks};
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn search() -> Result<()> {
+    let cmd = Tool::init().await?;
+    // The search filter only finds the single task fixture, the description of 7000002 matches
+    // locally.
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("filter", "search: test"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(format!("[{}]", fixtures::TASK), "application/json"),
+        )
+        .expect(2)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_tasks_all(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 2).await;
+
+    cmd.cmd()?
+        .args(["search", "test"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "7000002 p4 Test [Project One]\n    \
+             Testing\n\
+             7000001 p4 Bla bla [Project One]\n",
+        ));
+    cmd.cmd()?
+        .args(["search", "test", "--json", "--fields", "id"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("7000002"));
+    cmd.mock.verify().await;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn rename() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_sections(&cmd, 1).await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/rest/v2/sections/1100002"))
+        .and(matchers::body_json(serde_json::json!({ "name": "Later" })))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args([
+            "projects",
+            "sections",
+            "rename",
+            "-S",
+            "Section Two",
+            "Later",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq("renamed section Section Two to Later\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use super::mocks;
+use super::setup::Tool;
+use color_eyre::Result;
+use serde_json::{json, Value};
+use wiremock::matchers;
+
+#[tokio::test]
+async fn serve() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(json!({
+            "content": "Buy some flowers",
+            "description": "The red ones",
+            "due_string": "tomorrow",
+            "project_id": "1000002",
+        })))
+    })
+    .await;
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_close_task(&cmd, 1).await;
+
+    let mut input = Vec::new();
+    for request in [
+        json!([0, 1, "list", []]),
+        json!([0, 2, "add", ["\nBuy some flowers\nThe red ones\n", {"due": "tomorrow", "project": "project one"}]]),
+        json!([0, 3, "toggle", ["7000001"]]),
+        json!([2, "toggle", []]),
+        json!([0, 4, "remove", []]),
+    ] {
+        rmp_serde::encode::write(&mut input, &request)?;
+    }
+    let mut command = cmd.cmd()?;
+    command.env("TZ", "UTC").args(["serve", "--msgpack-rpc"]);
+    let output = assert_cmd::Command::from_std(command)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let mut output = output.as_slice();
+    let mut responses = Vec::new();
+    while !output.is_empty() {
+        responses.push(rmp_serde::from_read::<_, Value>(&mut output)?);
+    }
+    assert_eq!(responses.len(), 4);
+
+    let tasks = responses[0][3].as_array().unwrap();
+    let task = tasks.iter().find(|t| t["id"] == "7000015").unwrap();
+    assert_eq!(task["text"], "Shake it #Project One (2022-08-27 19:00)");
+    assert_eq!(task["project"], "Project One");
+    assert_eq!(
+        responses[1],
+        json!([1, 2, null, {
+            "id": "7000100",
+            "content": "Buy some flowers",
+            "url": "https://todoist.com/showTask?id=7000100",
+        }])
+    );
+    assert_eq!(
+        responses[2],
+        json!([1, 3, null, {"id": "7000001", "done": true}])
+    );
+    assert_eq!(
+        responses[3],
+        json!([1, 4, "unknown method \"remove\"", null])
+    );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
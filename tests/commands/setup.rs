@@ -32,7 +32,9 @@ impl Tool {
 
     pub fn cmd(&self) -> Result<Command> {
         let mut cmd = Command::cargo_bin("doist")?;
+        // Messages are expected in English, whatever the language of the machine is.
         cmd.env("RUST_BACKTRACE", "1")
+            .env("LC_ALL", "C")
             .arg(format!("--config_prefix={}", self.tmp.path().display()));
         Ok(cmd)
     }
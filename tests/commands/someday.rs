@@ -0,0 +1,44 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn someday() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 3).await;
+    mocks::mock_labels(&cmd, 3).await;
+    mocks::mock_projects(&cmd, 3).await;
+    mocks::mock_sections(&cmd, 3).await;
+
+    cmd.cmd()?
+        .args(["someday", "--project_id", "1000004"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Impossible")
+                .and(predicate::str::contains("Nope"))
+                .and(predicate::str::contains("Do the thing").not()),
+        )
+        .stderr(predicate::str::contains(
+            "the backlog was never reviewed, review it with --review",
+        ));
+    // None of the tasks with the label are without a due date.
+    cmd.cmd()?
+        .args(["someday", "--label", "two"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd.cmd()?
+        .args(["someday", "--review"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "reviewing the backlog needs an interactive terminal",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
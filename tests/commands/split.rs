@@ -0,0 +1,93 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use std::path::PathBuf;
+use wiremock::matchers;
+
+/// Writes an editor script that replaces the edited file with the given text.
+fn editor(cmd: &Tool, name: &str, text: &str) -> Result<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = cmd.tmp.path().join(name);
+    std::fs::write(
+        &path,
+        format!(
+            "#!/bin/sh\nprintf '{}' > \"$1\"\n",
+            text.replace('\n', "\\n")
+        ),
+    )?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    Ok(path)
+}
+
+#[tokio::test]
+async fn split() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let split = editor(&cmd, "split.sh", "Outline\n\n# ignored\nWrite intro\n")?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_create_task(&cmd, 2, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "parent_id": "7000001",
+            "project_id": null,
+        })))
+    })
+    .await;
+    cmd.cmd()?
+        .env("EDITOR", &split)
+        .env_remove("VISUAL")
+        .args(["split", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("created task: "))
+        .stdout(predicate::function(|out: &str| out.lines().count() == 2));
+    cmd.mock.verify().await;
+    cmd.mock.reset().await;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_create_task(&cmd, 2, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "project_id": "1000002",
+            "section_id": "1100003",
+            "parent_id": null,
+        })))
+    })
+    .await;
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 1).await;
+    cmd.cmd()?
+        .env("EDITOR", &split)
+        .env_remove("VISUAL")
+        .args(["split", "7000001", "--siblings", "--close"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("closed task 7000001"));
+    cmd.mock.verify().await;
+
+    let empty = editor(&cmd, "empty.sh", "# nothing\n")?;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    cmd.cmd()?
+        .env("EDITOR", &empty)
+        .env_remove("VISUAL")
+        .args(["split", "7000001"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nothing to split the task into"));
+    cmd.cmd()?
+        .args(["split", "7000001", "--close"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--siblings"));
+
+    Ok(())
+}
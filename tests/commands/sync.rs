@@ -0,0 +1,341 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use doist::config::{RemoteKind, SyncRemote, Template};
+use predicates::prelude::*;
+use std::path::Path;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+/// Sets up a machine that syncs with the given remote.
+async fn machine(kind: RemoteKind, location: &str) -> Result<Tool> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.sync = Some(SyncRemote {
+        kind,
+        location: location.to_string(),
+        ..Default::default()
+    });
+    cmd.cfg.save()?;
+    Ok(cmd)
+}
+
+#[tokio::test]
+async fn no_remote() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .arg("sync")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "there is no sync location in the config",
+        ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dir_remote() -> Result<()> {
+    let remote = assert_fs::TempDir::new()?;
+    let location = remote.path().display().to_string();
+    let mut first = machine(RemoteKind::Dir, &location).await?;
+    let second = machine(RemoteKind::Dir, &location).await?;
+    first.cfg.templates.insert(
+        "standup".to_string(),
+        Template {
+            content: Some("Standup {{date}}".to_string()),
+            description: None,
+        },
+    );
+    first.cfg.save()?;
+
+    first
+        .cmd()?
+        .args(["note", "7000001", "call back after lunch"])
+        .assert()
+        .success();
+    first
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pushed notes.json\npushed templates\n"));
+    second
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pulled notes.json\npulled templates\n"));
+    second
+        .cmd()?
+        .args(["note", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("call back after lunch"));
+    let synced = doist::config::Config::load_prefix(second.tmp.path())?;
+    assert!(synced.templates.contains_key("standup"));
+    // Flags of the run are not written into the config.
+    assert!(!synced.read_only);
+    second
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("everything is in sync\n"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deletes_templates() -> Result<()> {
+    let remote = assert_fs::TempDir::new()?;
+    let location = remote.path().display().to_string();
+    let mut first = machine(RemoteKind::Dir, &location).await?;
+    let second = machine(RemoteKind::Dir, &location).await?;
+    first.cfg.templates.insert(
+        "standup".to_string(),
+        Template {
+            content: Some("Standup {{date}}".to_string()),
+            description: None,
+        },
+    );
+    first.cfg.save()?;
+    first.cmd()?.arg("sync").assert().success();
+    second.cmd()?.arg("sync").assert().success();
+
+    let mut cfg = doist::config::Config::load_prefix(first.tmp.path())?;
+    cfg.templates.remove("standup");
+    cfg.save()?;
+    first
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pushed templates\n"));
+    second
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pulled templates\n"));
+    let synced = doist::config::Config::load_prefix(second.tmp.path())?;
+    assert!(!synced.templates.contains_key("standup"));
+    // The deleted template doesn't come back from the machine that had it.
+    first
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("everything is in sync\n"));
+    let cfg = doist::config::Config::load_prefix(first.tmp.path())?;
+    assert!(!cfg.templates.contains_key("standup"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn merges_journals() -> Result<()> {
+    let remote = assert_fs::TempDir::new()?;
+    let location = remote.path().display().to_string();
+    let mut first = machine(RemoteKind::Dir, &location).await?;
+    let mut second = machine(RemoteKind::Dir, &location).await?;
+    // The second machine tracks its time a day later.
+    second.cfg.override_time = Some(second.cfg.override_time.unwrap() + chrono::Duration::days(1));
+    second.cfg.save()?;
+
+    for cmd in [&mut first, &mut second] {
+        mocks::mock_task(cmd, 1).await;
+        mocks::mock_projects(cmd, 1).await;
+        cmd.cmd()?
+            .args(["timer", "start", "7000001"])
+            .assert()
+            .success();
+        cmd.cfg.override_time = Some(cmd.cfg.override_time.unwrap() + chrono::Duration::hours(1));
+        cmd.cfg.save()?;
+        cmd.cmd()?.args(["timer", "stop"]).assert().success();
+    }
+
+    // Both machines tracked time, which ends up on both of them.
+    for cmd in [&first, &second, &first] {
+        cmd.cmd()?.arg("sync").assert().success();
+    }
+    for cmd in [&first, &second] {
+        cmd.cmd()?
+            .args(["timer", "export", "csv"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(",2022-08-26T19:33:20+00:00,"))
+            .stdout(predicate::str::contains(",2022-08-27T19:33:20+00:00,"));
+    }
+    // The audit log stays on each machine.
+    assert!(!remote.path().join("audit.jsonl").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn conflict() -> Result<()> {
+    let remote = assert_fs::TempDir::new()?;
+    let location = remote.path().display().to_string();
+    let first = machine(RemoteKind::Dir, &location).await?;
+    let second = machine(RemoteKind::Dir, &location).await?;
+
+    first.cmd()?.args(["pin", "7000001"]).assert().success();
+    first.cmd()?.arg("sync").assert().success();
+    second.cmd()?.args(["pin", "7000002"]).assert().success();
+    second
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pushed pins.json\n"))
+        .stderr(predicate::eq(
+            "pins.json changed here and on the remote since the last sync, kept the one from here\n",
+        ));
+    first
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pulled pins.json\n"));
+
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()?;
+    assert!(output.status.success(), "git {:?} failed", args);
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[tokio::test]
+async fn git_remote() -> Result<()> {
+    let tmp = assert_fs::TempDir::new()?;
+    let origin = tmp.path().join("origin.git");
+    let clone = tmp.path().join("clone");
+    git(tmp.path(), &["init", "--quiet", "--bare", "origin.git"])?;
+    git(tmp.path(), &["clone", "--quiet", "origin.git", "clone"])?;
+    let cmd = machine(RemoteKind::Git, &clone.display().to_string()).await?;
+
+    cmd.cmd()?
+        .args(["note", "7000001", "call back after lunch"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .arg("sync")
+        .env("GIT_AUTHOR_NAME", "doist")
+        .env("GIT_AUTHOR_EMAIL", "doist@example.com")
+        .env("GIT_COMMITTER_NAME", "doist")
+        .env("GIT_COMMITTER_EMAIL", "doist@example.com")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pushed notes.json\n"));
+    assert_eq!(git(&origin, &["log", "--format=%s"])?, "Sync doist state\n");
+    assert!(git(&origin, &["show", "HEAD:notes.json"])?.contains("call back after lunch"));
+
+    // Another machine with a clone of its own pulls before syncing.
+    let other = tmp.path().join("other");
+    git(tmp.path(), &["init", "--quiet", "other"])?;
+    git(
+        &other,
+        &["remote", "add", "origin", &origin.display().to_string()],
+    )?;
+    let second = machine(RemoteKind::Git, &other.display().to_string()).await?;
+    second
+        .cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pulled notes.json\n"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn webdav_remote() -> Result<()> {
+    let mut cmd = machine(RemoteKind::WebDav, "").await?;
+    let location = format!("{}/dav/doist", cmd.mock.uri());
+    cmd.cfg.sync = Some(SyncRemote {
+        kind: RemoteKind::WebDav,
+        location,
+        username: Some("me".to_string()),
+        password: Some("secret".to_string()),
+        ..Default::default()
+    });
+    cmd.cfg.save()?;
+
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path_regex("^/dav/doist/"))
+        .and(matchers::header("authorization", "Basic bWU6c2VjcmV0"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(8)
+        .mount(&cmd.mock)
+        .await;
+    Mock::given(matchers::method("PUT"))
+        .and(matchers::path("/dav/doist/notes.json"))
+        .and(matchers::body_string_contains("call back after lunch"))
+        .respond_with(ResponseTemplate::new(201))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+
+    cmd.cmd()?
+        .args(["note", "7000001", "call back after lunch"])
+        .assert()
+        .success();
+    cmd.cmd()?
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::eq("pushed notes.json\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn cancel_slow_remote() -> Result<()> {
+    use std::process::{Command, Stdio};
+
+    let mut cmd = machine(RemoteKind::WebDav, "").await?;
+    cmd.cfg.sync = Some(SyncRemote {
+        kind: RemoteKind::WebDav,
+        location: format!("{}/dav/doist", cmd.mock.uri()),
+        ..Default::default()
+    });
+    cmd.cfg.save()?;
+    mocks::mock_slow(
+        &cmd,
+        "GET",
+        "/dav/doist/timers.jsonl",
+        std::time::Duration::from_secs(30),
+    )
+    .await;
+
+    // Requests to the remote don't look at cancellation, so the first Ctrl-C stops the sync
+    // after the grace period.
+    let started = std::time::Instant::now();
+    let child = Command::cargo_bin("doist")?
+        .env("LC_ALL", "C")
+        .arg(format!("--config_prefix={}", cmd.tmp.path().display()))
+        .arg("sync")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    let output = child.wait_with_output()?;
+
+    assert_eq!(output.status.code(), Some(130));
+    assert_eq!(String::from_utf8(output.stderr)?, "cancelled\n");
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn export() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+
+    let file = cmd.tmp.child("three.csv");
+    cmd.cmd()?
+        .args(["template", "export", "three"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "exported 4 tasks of Project Three to ",
+        ));
+    cmd.mock.verify().await;
+    file.assert(
+        "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE,DATE_LANG,TIMEZONE,DURATION,DURATION_UNIT\n\
+         section,Section Four,,,,,,,,,,\n\
+         section,Section Five,,,,,,,,,,\n\
+         task,Impossible,,4,1,,,,,,,\n\
+         task,Nope,,4,2,,,,,,,\n\
+         task,Darn,,4,1,,,,,,,\n\
+         task,Sweet,,4,1,,,,,,,\n",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("kickoff.csv");
+    file.write_str(
+        "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,DATE\n\
+         task,Kick off @two,,1,1,tomorrow\n\
+         note,\"Invite everyone, early\",,,,\n\
+         \n\
+         section,Later,,,,\n\
+         task,Retro,Look back,4,1,\n\
+         task,Write it up,,4,2,\n",
+    )?;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+
+    cmd.cmd()?
+        .args(["template", "import", "--into", "Project Three"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "imported 0 projects, 1 sections and 3 tasks with 1 comments\n",
+        ));
+    cmd.mock.verify().await;
+
+    let request = &cmd.mock.received_requests().await.unwrap()[3];
+    let (_, commands) = url::form_urlencoded::parse(&request.body)
+        .find(|(key, _)| key == "commands")
+        .unwrap();
+    let commands: serde_json::Value = serde_json::from_str(&commands)?;
+    assert_eq!(commands[0]["type"], "section_add");
+    assert_eq!(commands[0]["args"]["project_id"], "1000004");
+    assert_eq!(commands[1]["args"]["content"], "Kick off");
+    assert_eq!(commands[1]["args"]["labels"], serde_json::json!(["two"]));
+    assert_eq!(commands[1]["args"]["priority"], 4);
+    assert_eq!(commands[2]["type"], "note_add");
+    assert_eq!(commands[2]["args"]["item_id"], commands[1]["temp_id"]);
+    assert_eq!(commands[2]["args"]["content"], "Invite everyone, early");
+    assert_eq!(commands[4]["args"]["parent_id"], commands[3]["temp_id"]);
+    assert_eq!(commands[4]["args"]["section_id"], commands[0]["temp_id"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn import_again() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let file = cmd.tmp.child("kickoff.csv");
+    file.write_str("TYPE,CONTENT\ntask,Kick off\n")?;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_sync(&cmd, 2, |mb| mb).await;
+
+    for _ in 0..2 {
+        cmd.cmd()?
+            .args(["template", "import", "--into", "Project Three"])
+            .arg(file.path())
+            .assert()
+            .success();
+    }
+    cmd.mock.verify().await;
+
+    // A finished import that runs again creates everything again, instead of its commands being
+    // ignored as already handled.
+    let uuids = cmd
+        .mock
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/sync/v9/sync")
+        .map(|r| {
+            let (_, commands) = url::form_urlencoded::parse(&r.body)
+                .find(|(key, _)| key == "commands")
+                .unwrap();
+            let commands: serde_json::Value = serde_json::from_str(&commands).unwrap();
+            commands[0]["uuid"].clone()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(uuids.len(), 2);
+    assert_ne!(uuids[0], uuids[1]);
+
+    Ok(())
+}
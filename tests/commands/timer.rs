@@ -0,0 +1,67 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn timer() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["timer", "start", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("started timer of Bla bla\n"));
+    cmd.mock.verify().await;
+
+    // Time passes.
+    let start = cmd.cfg.override_time.unwrap();
+    cmd.cfg.override_time = Some(start + chrono::Duration::minutes(90));
+    cmd.cfg.save()?;
+
+    cmd.cmd()?
+        .arg("timer")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Bla bla (7000001) running for 01:30:00\n"));
+    cmd.cmd()?
+        .args(["timer", "stop"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("stopped timer of Bla bla after 01:30:00\n"));
+    cmd.cmd()?
+        .args(["timer", "stop"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no timer is running"));
+
+    cmd.cmd()?
+        .args(["timer", "export", "csv"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "task_id,task,project,start,end,seconds\n\
+             7000001,Bla bla,Project One,2022-08-26T19:33:20+00:00,2022-08-26T21:03:20+00:00,5400\n",
+        ));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["timer", "export", "toggl", "--email", "me@example.com"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Email,Project,Description,Start date,Start time,Duration\n\
+             me@example.com,Project One,Bla bla,2022-08-26,19:33:20,01:30:00\n",
+        ));
+    cmd.cmd()?
+        .env("TZ", "UTC")
+        .args(["timer", "export", "csv", "--since", "2022-08-27"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("task_id,task,project,start,end,seconds\n"));
+
+    Ok(())
+}
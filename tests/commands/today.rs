@@ -0,0 +1,195 @@
+use super::setup::Tool;
+use super::{fixtures, mocks};
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+async fn mock_filtered_tasks(cmd: &Tool, filter: &str) {
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("filter", filter))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(fixtures::TASKS, "application/json"))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    mocks::mock_projects(cmd, 1).await;
+    mocks::mock_sections(cmd, 1).await;
+    mocks::mock_labels(cmd, 1).await;
+}
+
+#[tokio::test]
+async fn today() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mock_filtered_tasks(&cmd, "today | overdue").await;
+
+    let today = "Today, Friday 26 August: 3 tasks, 1 overdue\n\
+                 \n\
+                 Project One\n  \
+                 7000002 p4 Test [REPEAT] every day @two\n\
+                 ! 7000001 p4 Bla bla [REPEAT] every day @two\n  \
+                 7000010 p4 Big crash [REPEAT] every other day\n";
+    cmd.cmd()?
+        .arg("today")
+        .assert()
+        .success()
+        .stdout(predicate::eq(today));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn upcoming() -> Result<()> {
+    let cmd = Tool::init().await?;
+    mock_filtered_tasks(&cmd, "overdue | next 4 days").await;
+
+    cmd.cmd()?
+        .args(["upcoming", "--days", "4"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with(
+                "Overdue: 1 task\n  7000001 p4 Bla bla [REPEAT] every day @two [Project One/Section Three]\n\n\
+                 Fri 26 Aug (today): 2 tasks\n",
+            )
+            .and(predicate::str::contains(
+                "Sun 28 Aug\n  nothing due\n\nMon 29 Aug: 1 task\n  7000014 p4 Switch it up",
+            ))
+            // Tasks after the last day are left out.
+            .and(predicate::str::contains("Woah").not()),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn capacity() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.work_hours = Some(8.0);
+    cmd.cfg.save()?;
+    mock_filtered_tasks(&cmd, "today | overdue").await;
+
+    // Bars are only drawn with blocks if the locale is UTF-8.
+    cmd.cmd()?
+        .env("LC_ALL", "C.UTF-8")
+        .arg("today")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Today, Friday 26 August: 3 tasks, 1 overdue, [░░░░░░░░░░] 8h free\n",
+        ));
+    cmd.mock.verify().await;
+
+    cmd.mock.reset().await;
+    mock_filtered_tasks(&cmd, "overdue | next 4 days").await;
+    cmd.cmd()?
+        .env("LC_ALL", "C.UTF-8")
+        .args(["upcoming", "--days", "4"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("Overdue: 1 task\n")
+                .and(predicate::str::contains(
+                    "Fri 26 Aug (today): 2 tasks, [░░░░░░░░░░] 8h free\n",
+                ))
+                .and(predicate::str::contains(
+                    "Sun 28 Aug: [░░░░░░░░░░] 8h free\n  nothing due\n",
+                )),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ascii() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.work_hours = Some(4.0);
+    cmd.cfg.save()?;
+    mock_filtered_tasks(&cmd, "today | overdue").await;
+
+    // Tests run in the C locale, which has no block characters.
+    cmd.cmd()?
+        .arg("today")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Today, Friday 26 August: 3 tasks, 1 overdue, [----------] 4h free\n",
+        ));
+    cmd.mock.verify().await;
+
+    cmd.mock.reset().await;
+    mock_filtered_tasks(&cmd, "today | overdue").await;
+    cmd.cmd()?
+        .env("LC_ALL", "C.UTF-8")
+        .args(["today", "--ascii"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Today, Friday 26 August: 3 tasks, 1 overdue, [----------] 4h free\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn a11y() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.work_hours = Some(8.0);
+    cmd.cfg.save()?;
+    mock_filtered_tasks(&cmd, "today | overdue").await;
+
+    let today = "Today, Friday 26 August: 3 tasks, 1 overdue, 0% used 8h free\n\
+                 \n\
+                 Project One\n\
+                 Task 1 of 3: 7000002 p4 Test [REPEAT] every day @two\n\
+                 Task 2 of 3: Overdue: 7000001 p4 Bla bla [REPEAT] every day @two\n\
+                 Task 3 of 3: 7000010 p4 Big crash [REPEAT] every other day\n";
+    cmd.cmd()?
+        .args(["today", "--a11y"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(today));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn german() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    mock_filtered_tasks(&cmd, "today | overdue").await;
+
+    // The locale of the system picks the language, if none is configured.
+    cmd.cmd()?
+        .env("LC_ALL", "de_DE.UTF-8")
+        .arg("today")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Heute, Freitag, 26. August: 3 Aufgaben, 1 überfällig\n\nProject One\n",
+        ));
+    cmd.mock.verify().await;
+
+    cmd.mock.reset().await;
+    cmd.cfg.language = Some("de".to_string());
+    cmd.cfg.save()?;
+    mock_filtered_tasks(&cmd, "overdue | next 4 days").await;
+    cmd.cmd()?
+        .args(["upcoming", "--days", "4"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("Überfällig: 1 Aufgabe\n")
+                .and(predicate::str::contains(
+                    "Fr, 26. Aug (heute): 2 Aufgaben\n",
+                ))
+                .and(predicate::str::contains("So, 28. Aug\n  nichts fällig\n")),
+        );
+    cmd.mock.verify().await;
+
+    Ok(())
+}
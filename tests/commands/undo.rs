@@ -0,0 +1,224 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn nothing_to_undo() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("there is nothing to undo"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 2).await;
+    cmd.cmd()?.args(["close", "7000001"]).assert().success();
+
+    // The task recurs, so closing it only moved it to its next date.
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(" close 7000001 from "))
+        .stdout(predicate::eq(
+            "task 7000001 recurs and stays due on its next date 2022-08-27\n",
+        ));
+    cmd.mock.verify().await;
+
+    cmd.mock.reset().await;
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_reopen_task(&cmd, 1).await;
+    cmd.cmd()?.args(["close", "7000001"]).assert().success();
+    let mut completed: serde_json::Value = serde_json::from_str(super::fixtures::TASK)?;
+    completed["is_completed"] = serde_json::json!(true);
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks/7000001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completed))
+        .with_priority(1)
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .success()
+        .stdout(predicate::eq("reopened task 7000001\n"));
+    cmd.mock.verify().await;
+
+    // A change is only undone once.
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("there is nothing to undo"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_close() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_close_task(&cmd, 1).await;
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_error(&cmd, "POST", "/rest/v2/tasks/7000002/close", 400, 1).await;
+    cmd.cmd()?
+        .args(["close", "7000001", "7000002"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unable to close 1 of 2 tasks"));
+    cmd.mock.verify().await;
+
+    // Only the task that was closed is reopened.
+    cmd.mock.reset().await;
+    mocks::mock_task(&cmd, 1).await;
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "task 7000001 recurs and stays due on its next date 2022-08-27\n",
+        ));
+    cmd.mock.verify().await;
+
+    // Nothing is remembered when no task was closed.
+    cmd.mock.reset().await;
+    mocks::mock_error(&cmd, "POST", "/rest/v2/tasks/7000001/close", 400, 1).await;
+    cmd.cmd()?.args(["close", "7000001"]).assert().failure();
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("there is nothing to undo"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    Mock::given(matchers::method("DELETE"))
+        .and(matchers::path("/rest/v2/tasks/7000005"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&cmd.mock)
+        .await;
+    cmd.cmd()?
+        .args(["delete", "7000005", "--yes"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    // The subtask is created under the new task.
+    cmd.mock.reset().await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Impossible",
+            "project_id": "1000004",
+            "section_id": "1100005",
+        })))
+    })
+    .await;
+    mocks::mock_create_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "content": "Nope",
+            "parent_id": "7000100",
+        })))
+    })
+    .await;
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "recreated task 7000005 as 7000100\nrecreated task 7000008 as 7000100\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn edit() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_tasks_by_ids(&cmd, "7000001", 1).await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(
+            serde_json::json!({"content": "New name"}),
+        ))
+    })
+    .await;
+    cmd.cmd()?
+        .args(["edit", "7000001", "-n", "New name"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    cmd.mock.reset().await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "content": "Bla bla",
+            "description": "",
+            "labels": ["two"],
+            "priority": 1,
+            "due_string": "every day",
+            "assignee_id": null,
+        })))
+    })
+    .await;
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .success()
+        .stdout(predicate::eq("restored task 7000001\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_back() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_by_ids(&cmd, "7000001", 1).await;
+    mocks::mock_sync(&cmd, 1, |mb| mb).await;
+    cmd.cmd()?
+        .args(["move", "7000001", "--parent", "7000002"])
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    cmd.mock.reset().await;
+    mocks::mock_sync(&cmd, 1, |mb| {
+        mb.and(matchers::body_string_contains("section_id%22%3A%221100003"))
+    })
+    .await;
+    cmd.cmd()?
+        .arg("undo")
+        .assert()
+        .success()
+        .stdout(predicate::eq("moved task 7000001 back\n"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
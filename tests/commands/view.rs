@@ -0,0 +1,58 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn view() -> Result<()> {
+    for test in &[vec!["view"], vec!["v"], vec!["view", "--nopager"]] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_task(&cmd, 1).await;
+        mocks::mock_projects(&cmd, 1).await;
+        mocks::mock_sections(&cmd, 1).await;
+        mocks::mock_labels(&cmd, 1).await;
+
+        let mut command = cmd.cmd()?;
+        for arg in test {
+            command.arg(arg);
+        }
+        // Output is not a terminal, so it's never paged.
+        command
+            .arg("7000001")
+            .env("PAGER", "false")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("ID: 7000001"));
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn view_subtask() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_task_by_id(&cmd, "7000008", 1).await;
+    mocks::mock_task_by_id(&cmd, "7000005", 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+
+    cmd.cmd()?
+        .args(["view", "7000008", "--nopager"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Content: Nope\nParents: Impossible\n",
+        ))
+        .stdout(predicate::str::contains(
+            "URL: https://todoist.com/showTask?id=7000008\n",
+        ))
+        .stdout(predicate::str::contains("Created: "));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use chrono::Duration;
+use color_eyre::Result;
+use predicates::prelude::*;
+use wiremock::{matchers, Mock, ResponseTemplate};
+
+/// Mocks fetching the task waited on by its ID, either recurring like the fixture or not.
+async fn mock_waiting_task(cmd: &Tool, recurring: bool) -> Result<()> {
+    let mut task: serde_json::Value = serde_json::from_str(super::fixtures::TASK)?;
+    task["due"]["is_recurring"] = recurring.into();
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(vec![task]))
+        .mount(&cmd.mock)
+        .await;
+    Ok(())
+}
+
+/// Starts waiting on task 7000001 with a nag interval of an hour, and lets two hours pass.
+async fn wait_two_hours(cmd: &mut Tool) -> Result<()> {
+    cmd.cmd()?
+        .args([
+            "waiting",
+            "add",
+            "7000001",
+            "--on",
+            "vendor reply",
+            "--nag",
+            "1h",
+        ])
+        .assert()
+        .success();
+    cmd.cfg.override_time = cmd.cfg.override_time.map(|t| t + Duration::hours(2));
+    cmd.cfg.save()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn waiting() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    mocks::mock_task(&cmd, 2).await;
+    mock_waiting_task(&cmd, false).await?;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "labels": ["two", "waiting"],
+        })))
+    })
+    .await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "due_string": "today",
+        })))
+    })
+    .await;
+
+    cmd.cmd()?
+        .args([
+            "waiting",
+            "add",
+            "7000001",
+            "--on",
+            "vendor reply",
+            "--nag",
+            "1h",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Bla bla is waiting on vendor reply, nagging after 1h\n",
+        ));
+    cmd.cmd()?
+        .arg("waiting")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    cmd.cfg.override_time = cmd.cfg.override_time.map(|t| t + Duration::hours(2));
+    cmd.cfg.save()?;
+    cmd.cmd()?
+        .args(["waiting", "--bump"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "! 7000001 Bla bla (waiting on vendor reply for 2h)\n",
+        ))
+        .stderr(predicate::str::contains("moved 1 tasks to today"));
+    cmd.cmd()?
+        .arg("waiting")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd.cmd()?
+        .args(["waiting", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "7000001 Bla bla (waiting on vendor reply for 2h)\n",
+        ));
+
+    cmd.cmd()?
+        .args(["waiting", "done", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Bla bla is no longer waiting on vendor reply\n",
+        ));
+    cmd.cmd()?
+        .args(["waiting", "done", "7000001"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "task 7000001 is not waiting on anything",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bump_keeps_recurrence() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    mocks::mock_task(&cmd, 1).await;
+    mock_waiting_task(&cmd, true).await?;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "labels": ["two", "waiting"],
+        })))
+    })
+    .await;
+
+    wait_two_hours(&mut cmd).await?;
+    cmd.cmd()?
+        .args(["waiting", "--bump"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("moved").not());
+    // Still nagged again after another interval.
+    cmd.cmd()?
+        .arg("waiting")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bump_skips_closed() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+
+    mocks::mock_task(&cmd, 1).await;
+    mocks::mock_update_task(&cmd, 1, |mb| {
+        mb.and(matchers::body_json(serde_json::json!({
+            "labels": ["two", "waiting"],
+        })))
+    })
+    .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/rest/v2/tasks"))
+        .and(matchers::query_param("ids", "7000001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&cmd.mock)
+        .await;
+
+    wait_two_hours(&mut cmd).await?;
+    cmd.cmd()?
+        .args(["waiting", "--bump"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "skipping task 7000001, it was closed or deleted",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+/// Matches output that shows neither the task nor who it waits on.
+fn hidden() -> impl Predicate<str> {
+    predicate::str::contains("Bla bla")
+        .not()
+        .and(predicate::str::contains("vendor reply").not())
+}
+
+#[tokio::test]
+async fn redact() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_task(&cmd, 2).await;
+    mocks::mock_update_task(&cmd, 2, |mb| mb).await;
+
+    cmd.cmd()?
+        .args([
+            "--redact",
+            "waiting",
+            "add",
+            "7000001",
+            "--on",
+            "vendor reply",
+        ])
+        .assert()
+        .success()
+        .stdout(hidden());
+    cmd.cmd()?
+        .args(["--redact", "waiting", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("7000001 ").and(hidden()));
+    cmd.cmd()?
+        .args(["--redact", "waiting", "done", "7000001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("is no longer waiting on").and(hidden()));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+use std::{path::Path, process::Command};
+use wiremock::matchers;
+
+fn commit(repo: &Path, message: &[&str]) {
+    let mut git = Command::new("git");
+    git.args([
+        "-c",
+        "user.name=Doist",
+        "-c",
+        "user.email=doist@example.com",
+    ])
+    .args(["commit", "--quiet", "--allow-empty"])
+    .current_dir(repo);
+    for paragraph in message {
+        git.args(["-m", paragraph]);
+    }
+    git.assert().success();
+}
+
+#[tokio::test]
+async fn worklog() -> Result<()> {
+    let cmd = Tool::init().await?;
+    let repo = cmd.tmp.path().join("repo");
+    std::fs::create_dir(&repo)?;
+    Command::new("git")
+        .args(["init", "--quiet"])
+        .current_dir(&repo)
+        .assert()
+        .success();
+    commit(&repo, &["Start", "Todoist: 7000009"]);
+    commit(&repo, &["Fix the parser", "Todoist: 7000001"]);
+    commit(&repo, &["Update the readme"]);
+
+    mocks::mock_create_comment(&cmd, 1, |mb| {
+        mb.and(matchers::body_partial_json(serde_json::json!({
+            "task_id": "7000001",
+        })))
+        .and(matchers::body_string_contains("Fix the parser"))
+    })
+    .await;
+
+    cmd.cmd()?
+        .current_dir(&repo)
+        .args(["worklog", "HEAD~2..HEAD"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Comment on 7000001:\nWorklog of `HEAD~2..HEAD`:\n\n- `",
+        ))
+        .stdout(predicate::str::contains("7000009").not())
+        .stderr(predicate::str::contains("run again with --yes"));
+    cmd.cmd()?
+        .current_dir(&repo)
+        .args(["worklog", "HEAD~2..HEAD", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("posted the worklog\n"));
+    cmd.cmd()?
+        .current_dir(&repo)
+        .args(["worklog", "HEAD~1..HEAD"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("no commits in HEAD~1..HEAD"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}